@@ -0,0 +1,42 @@
+//! Sets `CUPRIC_BUILD_ID` to a hash of the compiler sources, which build manifests record so that
+//! packages are compiled again after the compiler changes, even if its version number doesn't.
+
+use std::path::{Path, PathBuf};
+
+/// Hash `bytes` with 64-bit FNV-1a, continuing from `hash`.
+fn hash_bytes(hash: u64, bytes: &[u8]) -> u64 {
+    const PRIME: u64 = 0x0100_0000_01B3;
+
+    bytes.iter().fold(hash, |hash, &byte| (hash ^ byte as u64).wrapping_mul(PRIME))
+}
+
+fn collect_files(dir: &Path, paths: &mut Vec<PathBuf>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_files(&path, paths);
+        }
+        else {
+            paths.push(path);
+        }
+    }
+}
+
+fn main() {
+    const OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+
+    println!("cargo:rerun-if-changed=src");
+
+    let mut paths = Vec::new();
+    collect_files(Path::new("src"), &mut paths);
+    paths.sort();
+
+    // Each file's path is hashed along with its contents, so moving one changes the build id
+    let build_id = paths.iter().fold(OFFSET_BASIS, |hash, path| {
+        let hash = hash_bytes(hash, path.display().to_string().replace('\\', "/").as_bytes());
+        let hash = hash_bytes(hash, &[0]);
+        let hash = hash_bytes(hash, &std::fs::read(path).unwrap());
+        hash_bytes(hash, &[0])
+    });
+    println!("cargo:rustc-env=CUPRIC_BUILD_ID={build_id:016x}");
+}
@@ -1,5 +1,6 @@
 pub mod parse;
 
+use crate::ir::{CallingConvention, InlineHint, ParameterAttributes};
 use crate::ir::value::GlobalRegister;
 use crate::sema::{NamespaceHandle, PointerSemantics, TypeHandle};
 use crate::token::*;
@@ -384,6 +385,22 @@ impl TypeNode {
     pub fn kind(&self) -> &TypeNodeKind {
         &self.kind
     }
+
+    /// Count the number of nodes in the tree rooted at this node, including this node.
+    pub fn node_count(&self) -> usize {
+        1 + match self.kind() {
+            TypeNodeKind::Path { .. } => 0,
+            TypeNodeKind::Pointer { pointee_type, .. } => pointee_type.node_count(),
+            TypeNodeKind::Array { item_type, length } => {
+                item_type.node_count() + length.as_ref().map_or(0, |length| length.node_count())
+            }
+            TypeNodeKind::Tuple { item_types } => item_types.iter().map(TypeNode::node_count).sum(),
+            TypeNodeKind::Function { parameter_types, return_type, .. } => {
+                parameter_types.iter().map(TypeNode::node_count).sum::<usize>() + return_type.node_count()
+            }
+            TypeNodeKind::Grouping { content } => content.node_count(),
+        }
+    }
 }
 
 impl std::fmt::Display for TypeNode {
@@ -611,6 +628,69 @@ impl LocalNode {
         &mut self.kind
     }
 
+    /// Count the number of nodes in the tree rooted at this node, including this node.
+    pub fn node_count(&self) -> usize {
+        fn optional_count(node: &Option<Box<LocalNode>>) -> usize {
+            node.as_ref().map_or(0, |node| node.node_count())
+        }
+
+        1 + match self.kind() {
+            LocalNodeKind::Literal(..) | LocalNodeKind::Path { .. } | LocalNodeKind::Continue => 0,
+            LocalNodeKind::Type(type_node) => type_node.node_count(),
+            LocalNodeKind::Unary { operand, .. } => operand.node_count(),
+            LocalNodeKind::Binary { lhs, rhs, .. } => lhs.node_count() + rhs.node_count(),
+            LocalNodeKind::Call { callee, arguments } => {
+                callee.node_count() + arguments.iter().map(LocalNode::node_count).sum::<usize>()
+            }
+            LocalNodeKind::ArrayLiteral { items } | LocalNodeKind::TupleLiteral { items } => {
+                items.iter().map(LocalNode::node_count).sum()
+            }
+            LocalNodeKind::StructureLiteral { structure_type, members } => {
+                structure_type.node_count() + members.iter().map(|(_, member)| member.node_count()).sum::<usize>()
+            }
+            LocalNodeKind::Grouping { content } => content.node_count(),
+            LocalNodeKind::Scope { statements, tail } => {
+                statements.iter().map(LocalNode::node_count).sum::<usize>() + optional_count(tail)
+            }
+            LocalNodeKind::Conditional { condition, consequent, alternative }
+            | LocalNodeKind::While { condition, consequent, alternative } => {
+                condition.node_count() + consequent.node_count() + optional_count(alternative)
+            }
+            LocalNodeKind::Break { value } | LocalNodeKind::Return { value } => optional_count(value),
+            LocalNodeKind::Let { value_type, value, .. } => {
+                value_type.as_ref().map_or(0, |value_type| value_type.node_count()) + optional_count(value)
+            }
+        }
+    }
+
+    /// Whether the tree rooted at this node contains a call operation.
+    pub fn contains_call(&self) -> bool {
+        fn optional_contains(node: &Option<Box<LocalNode>>) -> bool {
+            node.as_ref().is_some_and(|node| node.contains_call())
+        }
+
+        match self.kind() {
+            LocalNodeKind::Call { .. } => true,
+            LocalNodeKind::Literal(..) | LocalNodeKind::Type(..) | LocalNodeKind::Path { .. } | LocalNodeKind::Continue => false,
+            LocalNodeKind::Unary { operand, .. } => operand.contains_call(),
+            LocalNodeKind::Binary { lhs, rhs, .. } => lhs.contains_call() || rhs.contains_call(),
+            LocalNodeKind::ArrayLiteral { items } | LocalNodeKind::TupleLiteral { items } => {
+                items.iter().any(LocalNode::contains_call)
+            }
+            LocalNodeKind::StructureLiteral { members, .. } => members.iter().any(|(_, member)| member.contains_call()),
+            LocalNodeKind::Grouping { content } => content.contains_call(),
+            LocalNodeKind::Scope { statements, tail } => {
+                statements.iter().any(LocalNode::contains_call) || optional_contains(tail)
+            }
+            LocalNodeKind::Conditional { condition, consequent, alternative }
+            | LocalNodeKind::While { condition, consequent, alternative } => {
+                condition.contains_call() || consequent.contains_call() || optional_contains(alternative)
+            }
+            LocalNodeKind::Break { value } | LocalNodeKind::Return { value } => optional_contains(value),
+            LocalNodeKind::Let { value, .. } => optional_contains(value),
+        }
+    }
+
     pub fn as_name(&self) -> crate::Result<&str> {
         match self.kind() {
             LocalNodeKind::Literal(Literal::Name(name)) => {
@@ -690,15 +770,44 @@ pub struct FunctionParameterNode {
     pub name: Box<str>,
     pub is_mutable: bool,
     pub type_node: Box<TypeNode>,
+    pub attributes: ParameterAttributes,
+}
+
+impl std::fmt::Display for FunctionParameterNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.attributes.is_noalias {
+            write!(f, "noalias ")?;
+        }
+        if self.attributes.is_nonnull {
+            write!(f, "nonnull ")?;
+        }
+        if self.attributes.is_readonly {
+            write!(f, "readonly ")?;
+        }
+        if self.is_mutable {
+            write!(f, "mut ")?;
+        }
+        write!(f, "{}: {}", self.name, self.type_node)
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct StructureMemberNode {
     pub span: crate::Span,
+    pub is_public: bool,
     pub name: Box<str>,
     pub type_node: Box<TypeNode>,
 }
 
+impl std::fmt::Display for StructureMemberNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_public {
+            write!(f, "pub ")?;
+        }
+        write!(f, "{}: {}", self.name, self.type_node)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum GlobalNodeKind {
     Let {
@@ -716,6 +825,11 @@ pub enum GlobalNodeKind {
         is_variadic: bool,
         return_type: Box<TypeNode>,
         body: Option<Box<LocalNode>>,
+        inline_hint: InlineHint,
+        calling_convention: CallingConvention,
+        /// The function was declared `nounwind`, which is only allowed for `foreign` functions and
+        /// in interface headers.
+        is_nounwind: bool,
         register: Option<GlobalRegister>,
     },
     Structure {
@@ -764,23 +878,27 @@ impl std::fmt::Display for GlobalNodeKind {
                     write!(f, ";")
                 }
             }
-            Self::Function { name, parameters, is_variadic, return_type, body, symbol_name, .. } => {
+            Self::Function { name, parameters, is_variadic, return_type, body, symbol_name, inline_hint, calling_convention, is_nounwind, .. } => {
                 if let Some(symbol_name) = symbol_name {
                     write!(f, " foreign(\"{}\")", String::from_utf8_lossy(symbol_name))?;
                 }
+                if *calling_convention != CallingConvention::C {
+                    write!(f, " callconv(\"{}\")", calling_convention.name())?;
+                }
+                if *is_nounwind {
+                    write!(f, " nounwind")?;
+                }
+                match inline_hint {
+                    InlineHint::Default => {}
+                    InlineHint::Always => write!(f, " inline")?,
+                    InlineHint::Never => write!(f, " noinline")?,
+                }
                 write!(f, " function {name}(")?;
                 let mut parameters_iter = parameters.iter();
                 if let Some(parameter) = parameters_iter.next() {
-                    if parameter.is_mutable {
-                        write!(f, "mut ")?;
-                    }
-                    write!(f, "{}: {}", parameter.name, parameter.type_node)?;
+                    write!(f, "{parameter}")?;
                     for parameter in parameters_iter {
-                        write!(f, ", ")?;
-                        if parameter.is_mutable {
-                            write!(f, "mut ")?;
-                        }
-                        write!(f, ", {}: {}", parameter.name, parameter.type_node)?;
+                        write!(f, ", {parameter}")?;
                     }
                     if *is_variadic {
                         write!(f, ", ..")?;
@@ -801,9 +919,9 @@ impl std::fmt::Display for GlobalNodeKind {
                     write!(f, " struct {name} {{")?;
                     let mut members_iter = members.iter();
                     if let Some(member) = members_iter.next() {
-                        write!(f, " {}: {}", member.name, member.type_node)?;
+                        write!(f, " {member}")?;
                         for member in members_iter {
-                            write!(f, ", {}: {}", member.name, member.type_node)?;
+                            write!(f, ", {member}")?;
                         }
                         write!(f, " ")?;
                     }
@@ -849,6 +967,7 @@ impl std::fmt::Display for GlobalNodeKind {
 #[derive(Clone, Debug)]
 pub struct GlobalNode {
     span: crate::Span,
+    is_public: bool,
     kind: GlobalNodeKind,
 }
 
@@ -856,14 +975,24 @@ impl GlobalNode {
     pub fn new(span: crate::Span, kind: GlobalNodeKind) -> Self {
         Self {
             span,
+            is_public: false,
             kind,
         }
     }
 
+    pub fn set_public(&mut self, is_public: bool) {
+        self.is_public = is_public;
+    }
+
     pub fn span(&self) -> crate::Span {
         self.span
     }
 
+    /// Whether this statement was marked `pub`, making it accessible outside of its module.
+    pub fn is_public(&self) -> bool {
+        self.is_public
+    }
+
     pub fn kind(&self) -> &GlobalNodeKind {
         &self.kind
     }
@@ -871,10 +1000,35 @@ impl GlobalNode {
     pub fn kind_mut(&mut self) -> &mut GlobalNodeKind {
         &mut self.kind
     }
+
+    /// Count the number of nodes in the tree rooted at this node, including this node.
+    pub fn node_count(&self) -> usize {
+        1 + match self.kind() {
+            GlobalNodeKind::Let { value_type, value, .. } => {
+                value_type.node_count() + value.as_ref().map_or(0, |value| value.node_count())
+            }
+            GlobalNodeKind::Function { parameters, return_type, body, .. } => {
+                parameters.iter().map(|parameter| parameter.type_node.node_count()).sum::<usize>()
+                    + return_type.node_count()
+                    + body.as_ref().map_or(0, |body| body.node_count())
+            }
+            GlobalNodeKind::Structure { members, .. } => members.as_ref().map_or(0, |members| {
+                members.iter().map(|member| member.type_node.node_count()).sum()
+            }),
+            GlobalNodeKind::Implement { self_type, statements } => {
+                self_type.node_count() + statements.iter().map(GlobalNode::node_count).sum::<usize>()
+            }
+            GlobalNodeKind::Module { statements, .. } => statements.iter().map(GlobalNode::node_count).sum(),
+            GlobalNodeKind::ModuleFile { .. } | GlobalNodeKind::Import { .. } | GlobalNodeKind::GlobImport { .. } => 0,
+        }
+    }
 }
 
 impl std::fmt::Display for GlobalNode {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.is_public {
+            write!(f, " pub")?;
+        }
         write!(f, "{}", self.kind)
     }
 }
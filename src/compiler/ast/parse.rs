@@ -1,11 +1,11 @@
 use super::*;
 
-use std::io::BufRead;
 use crate::sema::{GlobalContext, PrimitiveType, Symbol, SymbolKind};
 use crate::token::scan::Scanner;
 
-pub fn parse_module<T: BufRead>(scanner: &mut Scanner<T>, context: &mut GlobalContext, namespace: NamespaceHandle) -> crate::Result<ParsedModule> {
+pub fn parse_module(scanner: &mut Scanner, context: &mut GlobalContext, namespace: NamespaceHandle) -> crate::Result<ParsedModule> {
     let mut parser = Parser::new(scanner)?;
+    parser.is_interface = context.package().interface_path().is_some();
 
     let previous_module = context.replace_current_module(namespace);
 
@@ -41,19 +41,23 @@ impl ParsedModule {
     }
 }
 
-pub struct Parser<'a, T: BufRead> {
-    scanner: &'a mut Scanner<T>,
+pub struct Parser<'a> {
+    scanner: &'a mut Scanner,
     current_span: crate::Span,
     current_token: Option<Token>,
+    /// Whether an interface header is being parsed, in which case functions and variables may
+    /// be declared without being defined.
+    is_interface: bool,
 }
 
-impl<'a, T: BufRead> Parser<'a, T> {
-    pub fn new(scanner: &'a mut Scanner<T>) -> crate::Result<Self> {
+impl<'a> Parser<'a> {
+    pub fn new(scanner: &'a mut Scanner) -> crate::Result<Self> {
         let current_span = scanner.create_span(scanner.next_index(), scanner.next_index());
         let mut new_instance = Self {
             scanner,
             current_span,
             current_token: None,
+            is_interface: false,
         };
         new_instance.scan_token()?;
         Ok(new_instance)
@@ -138,6 +142,7 @@ impl<'a, T: BufRead> Parser<'a, T> {
                 }
                 Token::Colon2 if segments.is_empty() => {
                     segments.push(PathSegment::RootModule);
+                    self.scan_token()?;
                     continue;
                 }
                 Token::Super => {
@@ -194,17 +199,16 @@ impl<'a, T: BufRead> Parser<'a, T> {
 
         if let Some(operation) = UnaryOperation::from_prefix_token(token) {
             self.scan_token()?;
-            let operand;
-            match operation {
+            let operand = match operation {
                 UnaryOperation::GetSize | UnaryOperation::GetAlign => {
                     self.expect_token(&[Token::ParenLeft])?;
                     self.scan_token()?;
                     let type_node = self.parse_type(Some(&[Token::ParenRight]))?;
                     self.scan_token()?;
-                    operand = Box::new(LocalNode::new(type_node.span(), LocalNodeKind::Type(type_node)));
+                    Box::new(LocalNode::new(type_node.span(), LocalNodeKind::Type(type_node)))
                 }
                 _ => {
-                    operand = self.parse_expression(Some(Precedence::Prefix), allowed_ends, strict_ends)?;
+                    self.parse_expression(Some(Precedence::Prefix), allowed_ends, strict_ends)?
                 }
             };
 
@@ -939,8 +943,8 @@ impl<'a, T: BufRead> Parser<'a, T> {
         self.scan_token()?;
 
         let allowed = [Token::Equal, Token::Semicolon];
-        // Semicolon is not allowed if the variable is not foreign
-        let allowed = &allowed[..(allowed.len() - symbol_name.is_none() as usize)];
+        // Semicolon is not allowed if the variable is not foreign (outside of interface headers)
+        let allowed = &allowed[..(allowed.len() - (symbol_name.is_none() && !self.is_interface) as usize)];
         let value_type = self.parse_type(Some(allowed))?;
         let mut end_span = value_type.span();
 
@@ -967,7 +971,60 @@ impl<'a, T: BufRead> Parser<'a, T> {
         )))
     }
 
-    fn parse_function_definition(&mut self, start_span: crate::Span, is_foreign: bool, mut symbol_name: Option<Box<[u8]>>) -> crate::Result<Box<GlobalNode>> {
+    /// Parse an optional `callconv("name")` modifier following `foreign`.
+    fn parse_calling_convention(&mut self) -> crate::Result<Option<CallingConvention>> {
+        let Some(Token::CallConv) = self.current_token() else {
+            return Ok(None);
+        };
+        self.scan_token()?;
+        self.expect_token(&[Token::ParenLeft])?;
+        self.scan_token()?;
+        let name_span = self.current_span();
+        let name = self.expect_string()?;
+        let calling_convention = std::str::from_utf8(&name).ok()
+            .and_then(CallingConvention::from_name)
+            .ok_or_else(|| Box::new(crate::Error::new(
+                Some(name_span),
+                crate::ErrorKind::UnknownCallingConvention {
+                    name: String::from_utf8_lossy(&name).into_owned(),
+                    known_names: CallingConvention::NAMES.iter().map(|&(name, _)| name).collect(),
+                },
+            )))?;
+        self.scan_token()?;
+        self.expect_token(&[Token::ParenRight])?;
+        self.scan_token()?;
+
+        Ok(Some(calling_convention))
+    }
+
+    /// Parse an optional `nounwind` modifier following `foreign` and `callconv`.
+    fn parse_nounwind(&mut self) -> crate::Result<bool> {
+        let Some(Token::NoUnwind) = self.current_token() else {
+            return Ok(false);
+        };
+        self.scan_token()?;
+
+        Ok(true)
+    }
+
+    /// Parse an optional `inline` or `noinline` modifier along with the `function` keyword which
+    /// must follow it.
+    fn parse_inline_hint(&mut self) -> crate::Result<InlineHint> {
+        let inline_hint = match self.current_token() {
+            Some(Token::Inline) => InlineHint::Always,
+            Some(Token::NoInline) => InlineHint::Never,
+            _ => InlineHint::Default,
+        };
+        if inline_hint != InlineHint::Default {
+            self.scan_token()?;
+        }
+        self.expect_token(&[Token::Function])?;
+        self.scan_token()?;
+
+        Ok(inline_hint)
+    }
+
+    fn parse_function_definition(&mut self, start_span: crate::Span, is_foreign: bool, mut symbol_name: Option<Box<[u8]>>, calling_convention: CallingConvention, is_nounwind: bool, inline_hint: InlineHint) -> crate::Result<Box<GlobalNode>> {
         let name = self.expect_identifier()?;
         if is_foreign && symbol_name.is_none() {
             symbol_name = Some(name.as_bytes().into());
@@ -988,6 +1045,17 @@ impl<'a, T: BufRead> Parser<'a, T> {
             }
             let parameter_start_span = self.current_span();
 
+            let mut attributes = ParameterAttributes::default();
+            loop {
+                match self.current_token() {
+                    Some(Token::NoAlias) => attributes.is_noalias = true,
+                    Some(Token::NonNull) => attributes.is_nonnull = true,
+                    Some(Token::ReadOnly) => attributes.is_readonly = true,
+                    _ => break,
+                }
+                self.scan_token()?;
+            }
+
             let is_mutable = if let Some(Token::Mut) = self.current_token() {
                 self.scan_token()?;
                 true
@@ -1006,6 +1074,7 @@ impl<'a, T: BufRead> Parser<'a, T> {
                 name: parameter_name,
                 type_node: parameter_type,
                 is_mutable,
+                attributes,
             });
 
             if let Some(Token::Comma) = self.current_token() {
@@ -1018,8 +1087,8 @@ impl<'a, T: BufRead> Parser<'a, T> {
         // The function body must be enclosed by a scope, so expect a '{' or ';' token following
         // the return type (if present)
         let allowed = [Token::RightArrow, Token::CurlyLeft, Token::Semicolon];
-        // Semicolon is not allowed if the function is not foreign
-        let allowed = &allowed[..(allowed.len() - symbol_name.is_none() as usize)];
+        // Semicolon is not allowed if the function is not foreign (outside of interface headers)
+        let allowed = &allowed[..(allowed.len() - (symbol_name.is_none() && !self.is_interface) as usize)];
         self.expect_token(allowed)?;
         let return_type = if let Some(Token::RightArrow) = self.current_token() {
             self.scan_token()?;
@@ -1058,15 +1127,18 @@ impl<'a, T: BufRead> Parser<'a, T> {
                 is_variadic,
                 return_type,
                 body,
+                inline_hint,
+                calling_convention,
+                is_nounwind,
                 register: None,
             },
         )))
     }
 
-    fn parse_structure_definition(&mut self, context: &mut GlobalContext, start_span: crate::Span) -> crate::Result<Box<GlobalNode>> {
+    fn parse_structure_definition(&mut self, context: &mut GlobalContext, start_span: crate::Span, is_public: bool) -> crate::Result<Box<GlobalNode>> {
         let name = self.expect_identifier()?;
 
-        let self_type = context.outline_structure_type(name.clone())?;
+        let self_type = context.outline_structure_type(name.clone(), self.current_span(), is_public)?;
         context.set_self_type(self_type);
 
         self.scan_token()?;
@@ -1079,8 +1151,15 @@ impl<'a, T: BufRead> Parser<'a, T> {
         } else {
             let mut members = Vec::new();
             while !matches!(self.current_token(), Some(Token::CurlyRight)) {
-                let member_name = self.expect_identifier()?;
                 let member_start_span = self.current_span();
+                let is_public = if let Some(Token::Pub) = self.current_token() {
+                    self.scan_token()?;
+                    true
+                }
+                else {
+                    false
+                };
+                let member_name = self.expect_identifier()?;
                 self.scan_token()?;
                 self.expect_token(&[Token::Colon])?;
                 self.scan_token()?;
@@ -1088,6 +1167,7 @@ impl<'a, T: BufRead> Parser<'a, T> {
 
                 members.push(StructureMemberNode {
                     span: member_start_span.expand_to(member_type.span()),
+                    is_public,
                     name: member_name,
                     type_node: member_type,
                 });
@@ -1116,9 +1196,26 @@ impl<'a, T: BufRead> Parser<'a, T> {
     pub fn parse_global_statement(&mut self, global_context: &mut GlobalContext, is_implementation: bool, allow_empty: bool) -> crate::Result<Option<Box<GlobalNode>>> {
         let start_span = self.current_span();
 
+        let is_public = if let Some(Token::Pub) = self.current_token() {
+            self.scan_token()?;
+            true
+        }
+        else {
+            false
+        };
+
+        let mut statement = self.parse_global_statement_kind(global_context, start_span, is_public, is_implementation, allow_empty)?;
+        if let Some(statement) = &mut statement {
+            statement.set_public(is_public);
+        }
+
+        Ok(statement)
+    }
+
+    fn parse_global_statement_kind(&mut self, global_context: &mut GlobalContext, start_span: crate::Span, is_public: bool, is_implementation: bool, allow_empty: bool) -> crate::Result<Option<Box<GlobalNode>>> {
         // Most statement types can be detected simply by the first token
         match self.current_token() {
-            Some(Token::Semicolon) if allow_empty => {
+            Some(Token::Semicolon) if allow_empty && !is_public => {
                 self.scan_token()?;
                 // Returning None would imply that the end of the file was reached,
                 // so recursively try to parse a statement instead
@@ -1128,9 +1225,15 @@ impl<'a, T: BufRead> Parser<'a, T> {
                 self.scan_token()?;
                 self.parse_global_variable(start_span, false, None).map(Some)
             }
-            Some(Token::Function) => {
+            Some(Token::Function | Token::Inline | Token::NoInline) => {
+                let inline_hint = self.parse_inline_hint()?;
+                self.parse_function_definition(start_span, false, None, CallingConvention::C, false, inline_hint).map(Some)
+            }
+            Some(Token::NoUnwind) if self.is_interface => {
+                // Interface headers record which functions were inferred not to unwind
                 self.scan_token()?;
-                self.parse_function_definition(start_span, false, None).map(Some)
+                let inline_hint = self.parse_inline_hint()?;
+                self.parse_function_definition(start_span, false, None, CallingConvention::C, true, inline_hint).map(Some)
             }
             Some(Token::Foreign) => {
                 self.scan_token()?;
@@ -1144,18 +1247,22 @@ impl<'a, T: BufRead> Parser<'a, T> {
                     self.scan_token()?;
                 }
 
+                let calling_convention = self.parse_calling_convention()?;
+                let is_nounwind = self.parse_nounwind()?;
+                let is_function_only = calling_convention.is_some() || is_nounwind;
+
                 match self.get_token()? {
-                    Token::Function => {
-                        self.scan_token()?;
-                        self.parse_function_definition(start_span, true, symbol_name).map(Some)
+                    Token::Function | Token::Inline | Token::NoInline => {
+                        let inline_hint = self.parse_inline_hint()?;
+                        self.parse_function_definition(start_span, true, symbol_name, calling_convention.unwrap_or_default(), is_nounwind, inline_hint).map(Some)
                     }
-                    Token::Let if !is_implementation => {
+                    Token::Let if !is_implementation && !is_function_only => {
                         self.scan_token()?;
                         self.parse_global_variable(start_span, true, symbol_name).map(Some)
                     }
                     got_token => {
                         let mut allowed_tokens = vec![Token::Function];
-                        if !is_implementation {
+                        if !is_implementation && !is_function_only {
                             allowed_tokens.push(Token::Let);
                         }
                         Err(Box::new(crate::Error::new(
@@ -1170,9 +1277,9 @@ impl<'a, T: BufRead> Parser<'a, T> {
             }
             Some(Token::Struct) if !is_implementation => {
                 self.scan_token()?;
-                self.parse_structure_definition(global_context, start_span).map(Some)
+                self.parse_structure_definition(global_context, start_span, is_public).map(Some)
             }
-            Some(Token::Implement) if !is_implementation => {
+            Some(Token::Implement) if !is_implementation && !is_public => {
                 self.scan_token()?;
                 let self_type = self.parse_type(Some(&[Token::CurlyLeft]))?;
                 self.scan_token()?;
@@ -1227,6 +1334,7 @@ impl<'a, T: BufRead> Parser<'a, T> {
                 if let Token::Semicolon = self.expect_token(&[Token::Semicolon, Token::CurlyLeft])? {
                     self.scan_token()?;
 
+                    global_context.get_or_create_module(global_context.current_module(), &name, is_public)?;
                     global_context.queue_module_file(name.clone());
 
                     return Ok(Some(Box::new(GlobalNode::new(
@@ -1241,6 +1349,7 @@ impl<'a, T: BufRead> Parser<'a, T> {
                 let namespace = global_context.get_or_create_module(
                     global_context.current_module(),
                     &name,
+                    is_public,
                 )?;
                 let parent_module = global_context.replace_current_module(namespace);
 
@@ -1297,7 +1406,8 @@ impl<'a, T: BufRead> Parser<'a, T> {
                     self.scan_token()?;
 
                     let path = global_context.get_absolute_path(path_span, &segments)?;
-                    global_context.current_module_info_mut().add_glob_import(path);
+                    let visibility = global_context.declared_visibility(is_public);
+                    global_context.current_module_info_mut().add_glob_import(path, visibility);
 
                     Ok(Some(Box::new(GlobalNode::new(
                         start_span.expand_to(path_span),
@@ -1340,10 +1450,10 @@ impl<'a, T: BufRead> Parser<'a, T> {
                         }
                     };
                     // Establish an alias symbol in the current module corresponding to this import
-                    global_context.current_module_info_mut().define(
-                        import_name,
-                        Symbol::new(SymbolKind::Alias(path.clone())),
-                    )?;
+                    let symbol = Symbol::new(SymbolKind::Alias(path.clone()))
+                        .with_span(start_span.expand_to(end_span))
+                        .with_visibility(global_context.declared_visibility(is_public));
+                    global_context.current_module_info_mut().define(import_name, symbol)?;
 
                     Ok(Some(Box::new(GlobalNode::new(
                         start_span.expand_to(end_span),
@@ -1355,23 +1465,31 @@ impl<'a, T: BufRead> Parser<'a, T> {
                 }
             }
             Some(got_token) => {
+                // Semicolon is technically allowed, but like... why would you do that
+                let mut allowed_tokens = vec![Token::Let, Token::Function];
+                if !is_implementation {
+                    allowed_tokens.push(Token::Struct);
+                    if !is_public {
+                        allowed_tokens.push(Token::Implement);
+                    }
+                    allowed_tokens.extend([Token::Module, Token::Import]);
+                }
+                allowed_tokens.push(Token::Foreign);
+                if !is_public {
+                    allowed_tokens.insert(0, Token::Pub);
+                }
                 Err(Box::new(crate::Error::new(
                     Some(self.current_span()),
                     crate::ErrorKind::ExpectedTokenFromList {
                         got_token: got_token.clone(),
-                        // Semicolon is technically allowed, but like... why would you do that
-                        allowed_tokens: vec![
-                            Token::Let,
-                            Token::Function,
-                            Token::Struct,
-                            Token::Implement,
-                            Token::Module,
-                            Token::Import,
-                            Token::Foreign,
-                        ],
+                        allowed_tokens,
                     },
                 )))
             }
+            None if is_public => Err(Box::new(crate::Error::new(
+                Some(self.current_span()),
+                crate::ErrorKind::ExpectedToken,
+            ))),
             None => Ok(None),
         }
     }
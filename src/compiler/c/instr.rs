@@ -0,0 +1,379 @@
+use super::*;
+use crate::ir::instr::Instruction;
+use crate::ir::value::{Constant, LocalRegister};
+use crate::sema::ConversionOperation;
+
+/// Writes the name of the temporary which receives the input to a phi instruction before a jump.
+pub struct PhiInputDisplay<'a>(pub &'a LocalRegister);
+
+impl std::fmt::Display for PhiInputDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "p_{}", EscapedIdentifierDisplay(self.0.identifier()))
+    }
+}
+
+/// Writes the name of the local variable backing a stack allocation.
+pub struct StackStorageDisplay<'a>(pub &'a LocalRegister);
+
+impl std::fmt::Display for StackStorageDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "a_{}", EscapedIdentifierDisplay(self.0.identifier()))
+    }
+}
+
+fn is_unsigned_integer(context: &GlobalContext, value_type: TypeHandle) -> bool {
+    matches!(value_type.repr(context), TypeRepr::Integer { signed: false, .. })
+}
+
+fn is_signed_integer(context: &GlobalContext, value_type: TypeHandle) -> bool {
+    matches!(value_type.repr(context), TypeRepr::Integer { signed: true, .. })
+}
+
+/// Whether `operator` can overflow, which wraps around in the IR but is undefined behaviour for
+/// signed integers in C.
+fn is_wrapping_operator(operator: &str) -> bool {
+    matches!(operator, "+" | "-" | "*" | "<<")
+}
+
+pub fn fmt_negate(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    result: &LocalRegister,
+    operand: &Value,
+) -> std::fmt::Result {
+    let result_type = result.get_type();
+    let result_type = result_type.c(context);
+    if let TypeRepr::Integer { .. } = operand.get_type().repr(context) {
+        // Integers are negated unsigned at full width, so that signed integers wrap like in the
+        // IR instead of overflowing
+        write!(f, "{} = ({result_type})(0 - (uint64_t){});", result.c(context), operand.c(context))
+    }
+    else {
+        write!(f, "{} = ({result_type})-{};", result.c(context), operand.c(context))
+    }
+}
+
+pub fn fmt_binary_operation(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    result: &LocalRegister,
+    lhs: &Value,
+    operator: &str,
+    rhs: &Value,
+) -> std::fmt::Result {
+    let result_type = result.get_type();
+    let result_type = result_type.c(context);
+    let is_wrapping = is_signed_integer(context, lhs.get_type()) && is_wrapping_operator(operator);
+    if is_unsigned_integer(context, lhs.get_type()) || is_wrapping {
+        // Unsigned arithmetic is done at full width to avoid promotion to a signed type, which
+        // also makes signed arithmetic wrap like in the IR instead of overflowing
+        write!(f, "{} = ({result_type})((uint64_t){} {operator} (uint64_t){});", result.c(context), lhs.c(context), rhs.c(context))
+    }
+    else {
+        write!(f, "{} = ({result_type})({} {operator} {});", result.c(context), lhs.c(context), rhs.c(context))
+    }
+}
+
+pub fn fmt_remainder(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    result: &LocalRegister,
+    lhs: &Value,
+    rhs: &Value,
+) -> std::fmt::Result {
+    match lhs.get_type().repr(context) {
+        TypeRepr::Float32 => {
+            write!(f, "{} = fmodf({}, {});", result.c(context), lhs.c(context), rhs.c(context))
+        }
+        TypeRepr::Float64 => {
+            write!(f, "{} = fmod({}, {});", result.c(context), lhs.c(context), rhs.c(context))
+        }
+        _ => {
+            fmt_binary_operation(f, context, result, lhs, "%", rhs)
+        }
+    }
+}
+
+pub fn fmt_not(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    result: &LocalRegister,
+    operand: &Value,
+) -> std::fmt::Result {
+    match operand.get_type().repr(context) {
+        TypeRepr::Boolean => {
+            write!(f, "{} = !{};", result.c(context), operand.c(context))
+        }
+        _ => {
+            write!(f, "{} = ({})~{};", result.c(context), result.get_type().c(context), operand.c(context))
+        }
+    }
+}
+
+pub fn fmt_compare(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    result: &LocalRegister,
+    lhs: &Value,
+    operator: &str,
+    rhs: &Value,
+) -> std::fmt::Result {
+    match lhs.get_type().repr(context) {
+        TypeRepr::Pointer { .. } | TypeRepr::Function { .. } => {
+            // Pointers are compared as addresses, since their types may differ in C
+            write!(f, "{} = (uintptr_t){} {operator} (uintptr_t){};", result.c(context), lhs.c(context), rhs.c(context))
+        }
+        _ => {
+            write!(f, "{} = {} {operator} {};", result.c(context), lhs.c(context), rhs.c(context))
+        }
+    }
+}
+
+/// Write the access path into an aggregate of type `aggregate_type` described by `indices`, as
+/// used by `extractvalue` and `getelementptr` in LLVM.
+pub fn fmt_member_path(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    aggregate_type: TypeHandle,
+    indices: &[Value],
+) -> std::fmt::Result {
+    let mut current_type = aggregate_type;
+    for index in indices {
+        let repr = current_type.repr(context);
+        match *repr {
+            TypeRepr::Array { item_type, .. } => {
+                write!(f, ".items[{}]", index.c(context))?;
+                current_type = item_type;
+            }
+            TypeRepr::Tuple { ref item_types } => {
+                let member_index = constant_index(index);
+                write!(f, ".{}", types::MemberNameDisplay(repr, member_index))?;
+                current_type = item_types[member_index];
+            }
+            TypeRepr::Structure { ref members, .. } => {
+                let member_index = constant_index(index);
+                write!(f, ".{}", types::MemberNameDisplay(repr, member_index))?;
+                current_type = members[member_index].member_type;
+            }
+            _ => panic!("cannot index into type '{}'", current_type.path(context))
+        }
+    }
+
+    Ok(())
+}
+
+fn constant_index(index: &Value) -> usize {
+    match index {
+        Value::Constant(Constant::Integer(integer)) => integer.raw() as usize,
+        _ => panic!("member index must be an integer constant"),
+    }
+}
+
+/// Write the address of the element of `pointer` selected by `indices`, which follow the rules of
+/// `getelementptr` in LLVM.
+pub fn fmt_element_address(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    pointee_type: TypeHandle,
+    pointer: &Value,
+    indices: &[Value],
+) -> std::fmt::Result {
+    // Unsized arrays are pointed to by pointers to their first item
+    let pointee_type = match *pointee_type.repr(context) {
+        TypeRepr::Array { item_type, length: None } => item_type,
+        _ => pointee_type,
+    };
+    let [first_index, indices @ ..] = indices else {
+        panic!("element pointer requires at least one index");
+    };
+
+    write!(f, "&({})[{}]", pointer.c(context), first_index.c(context))?;
+    fmt_member_path(f, context, pointee_type, indices)
+}
+
+pub fn fmt_get_element_pointer(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    result: &LocalRegister,
+    pointer: &Value,
+    indices: &[Value],
+) -> std::fmt::Result {
+    let &TypeRepr::Pointer { pointee_type, .. } = pointer.get_type().repr(context) else {
+        panic!("{} is not a pointer", pointer.c(context));
+    };
+
+    write!(f, "{} = ({})", result.c(context), result.get_type().c(context))?;
+    fmt_element_address(f, context, pointee_type, pointer, indices)?;
+    write!(f, ";")
+}
+
+/// Write an expression converting `operand` from `from_type` to `to_type`.
+pub fn fmt_conversion(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    operation: ConversionOperation,
+    from_type: TypeHandle,
+    to_type: TypeHandle,
+    operand: &dyn std::fmt::Display,
+) -> std::fmt::Result {
+    let to_type_c = to_type.c(context);
+    let from_size = match *from_type.repr(context) {
+        TypeRepr::Integer { size, .. } => size * 8,
+        _ => 0,
+    };
+
+    match operation {
+        // The intermediate cast selects the kind of extension regardless of the source signedness
+        ConversionOperation::ZeroExtend if from_size > 0 => {
+            write!(f, "(({to_type_c})(uint{from_size}_t){operand})")
+        }
+        ConversionOperation::SignExtend if from_size > 0 => {
+            write!(f, "(({to_type_c})(int{from_size}_t){operand})")
+        }
+        ConversionOperation::PointerToInteger | ConversionOperation::IntegerToPointer => {
+            write!(f, "(({to_type_c})(uintptr_t){operand})")
+        }
+        _ => {
+            write!(f, "(({to_type_c}){operand})")
+        }
+    }
+}
+
+pub fn fmt_convert(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    operation: ConversionOperation,
+    result: &LocalRegister,
+    value: &Value,
+) -> std::fmt::Result {
+    let is_pointer = |value_type: TypeHandle| matches!(value_type.repr(context), TypeRepr::Pointer { .. } | TypeRepr::Function { .. });
+
+    if operation == ConversionOperation::BitwiseCast && !(is_pointer(value.get_type()) && is_pointer(result.get_type())) {
+        // Reinterpreting the bits of a non-pointer value requires a union in C
+        write!(
+            f,
+            "{{ union {{ {} from; {} to; }} cast; cast.from = {}; {} = cast.to; }}",
+            value.get_type().c(context),
+            result.get_type().c(context),
+            value.c(context),
+            result.c(context),
+        )
+    }
+    else {
+        write!(f, "{} = ", result.c(context))?;
+        fmt_conversion(f, context, operation, value.get_type(), result.get_type(), &value.c(context))?;
+        write!(f, ";")
+    }
+}
+
+pub fn fmt_call(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    result: Option<&LocalRegister>,
+    callee: &Value,
+    arguments: &[Value],
+) -> std::fmt::Result {
+    if let Some(result) = result.filter(|result| !matches!(result.get_type(), TypeHandle::VOID | TypeHandle::NEVER)) {
+        write!(f, "{} = ", result.c(context))?;
+    }
+    write!(f, "{}(", callee.c(context))?;
+
+    let mut arguments_iter = arguments.iter();
+    if let Some(argument) = arguments_iter.next() {
+        write!(f, "{}", argument.c(context))?;
+        for argument in arguments_iter {
+            write!(f, ", {}", argument.c(context))?;
+        }
+    }
+
+    write!(f, ");")
+}
+
+impl CDisplay for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        match self {
+            Self::Negate { result, operand } => {
+                fmt_negate(f, context, result, operand)
+            }
+            Self::Add { result, lhs, rhs } => {
+                fmt_binary_operation(f, context, result, lhs, "+", rhs)
+            }
+            Self::Subtract { result, lhs, rhs } => {
+                fmt_binary_operation(f, context, result, lhs, "-", rhs)
+            }
+            Self::Multiply { result, lhs, rhs } => {
+                fmt_binary_operation(f, context, result, lhs, "*", rhs)
+            }
+            Self::Divide { result, lhs, rhs } => {
+                fmt_binary_operation(f, context, result, lhs, "/", rhs)
+            }
+            Self::Remainder { result, lhs, rhs } => {
+                fmt_remainder(f, context, result, lhs, rhs)
+            }
+            Self::ShiftLeft { result, lhs, rhs } => {
+                fmt_binary_operation(f, context, result, lhs, "<<", rhs)
+            }
+            Self::ShiftRight { result, lhs, rhs } => {
+                fmt_binary_operation(f, context, result, lhs, ">>", rhs)
+            }
+            Self::Not { result, operand } => {
+                fmt_not(f, context, result, operand)
+            }
+            Self::And { result, lhs, rhs } => {
+                fmt_binary_operation(f, context, result, lhs, "&", rhs)
+            }
+            Self::Or { result, lhs, rhs } => {
+                fmt_binary_operation(f, context, result, lhs, "|", rhs)
+            }
+            Self::Xor { result, lhs, rhs } => {
+                fmt_binary_operation(f, context, result, lhs, "^", rhs)
+            }
+            Self::ExtractValue { result, aggregate, indices } => {
+                write!(f, "{} = ({})", result.c(context), aggregate.c(context))?;
+                fmt_member_path(f, context, aggregate.get_type(), indices)?;
+                write!(f, ";")
+            }
+            Self::InsertValue { result, aggregate, value, indices } => {
+                write!(f, "{} = {}; {}", result.c(context), aggregate.c(context), result.c(context))?;
+                fmt_member_path(f, context, aggregate.get_type(), indices)?;
+                write!(f, " = {};", value.c(context))
+            }
+            Self::StackAllocate { result } => {
+                write!(f, "{} = &{};", result.c(context), StackStorageDisplay(result))
+            }
+            Self::Load { result, pointer } => {
+                write!(f, "{} = *{};", result.c(context), pointer.c(context))
+            }
+            Self::Store { value, pointer } => {
+                write!(f, "*{} = {};", pointer.c(context), value.c(context))
+            }
+            Self::GetElementPointer { result, pointer, indices } => {
+                fmt_get_element_pointer(f, context, result, pointer, indices)
+            }
+            Self::Convert { operation, result, value } => {
+                fmt_convert(f, context, *operation, result, value)
+            }
+            Self::CompareEqual { result, lhs, rhs } => {
+                fmt_compare(f, context, result, lhs, "==", rhs)
+            }
+            Self::CompareNotEqual { result, lhs, rhs } => {
+                fmt_compare(f, context, result, lhs, "!=", rhs)
+            }
+            Self::CompareLessThan { result, lhs, rhs } => {
+                fmt_compare(f, context, result, lhs, "<", rhs)
+            }
+            Self::CompareLessEqual { result, lhs, rhs } => {
+                fmt_compare(f, context, result, lhs, "<=", rhs)
+            }
+            Self::CompareGreaterThan { result, lhs, rhs } => {
+                fmt_compare(f, context, result, lhs, ">", rhs)
+            }
+            Self::CompareGreaterEqual { result, lhs, rhs } => {
+                fmt_compare(f, context, result, lhs, ">=", rhs)
+            }
+            Self::Call { result, callee, arguments } => {
+                fmt_call(f, context, result.as_ref(), callee, arguments)
+            }
+        }
+    }
+}
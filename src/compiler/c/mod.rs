@@ -0,0 +1,388 @@
+use crate::ir::instr::{BasicBlock, TerminatorInstruction};
+use crate::ir::value::{BlockLabel, Value};
+use crate::sema::{GlobalContext, TypeHandle, TypeRepr};
+
+mod instr;
+mod value;
+mod types;
+
+/// Definitions included at the top of every generated C file.
+const PRELUDE: &str = "\
+#include <stdbool.h>
+#include <stdint.h>
+#include <math.h>
+
+#if defined(__GNUC__)
+#define CUPRIC_UNREACHABLE() __builtin_unreachable()
+#else
+#define CUPRIC_UNREACHABLE() for (;;) {}
+#endif
+";
+
+const C_KEYWORDS: &[&str] = &[
+    "auto", "break", "case", "char", "const", "continue", "default", "do", "double", "else", "enum",
+    "extern", "float", "for", "goto", "if", "inline", "int", "long", "register", "restrict",
+    "return", "short", "signed", "sizeof", "static", "struct", "switch", "typedef", "union",
+    "unsigned", "void", "volatile", "while", "_Bool", "_Complex", "_Imaginary",
+];
+
+/// Writes an arbitrary identifier as a valid C identifier fragment. Letters and digits are kept
+/// as-is and every other byte is written as an escape sequence beginning with `_`, so distinct
+/// identifiers always produce distinct output.
+pub struct EscapedIdentifierDisplay<T: AsRef<[u8]>>(pub T);
+
+impl<T: AsRef<[u8]>> std::fmt::Display for EscapedIdentifierDisplay<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for &ch in self.0.as_ref() {
+            match ch {
+                b'0'..=b'9' | b'A'..=b'Z' | b'a'..=b'z' => write!(f, "{}", ch as char)?,
+                b'_' => write!(f, "_u")?,
+                b'.' => write!(f, "_d")?,
+                b':' => write!(f, "_c")?,
+                b'-' => write!(f, "_h")?,
+                _ => write!(f, "_x{ch:02X}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes the C name of a global symbol. Names which are already valid C identifiers are kept
+/// so that they link against C code, and any others are escaped.
+pub struct GlobalIdentifierDisplay<T: AsRef<[u8]>>(pub T);
+
+impl<T: AsRef<[u8]>> std::fmt::Display for GlobalIdentifierDisplay<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let identifier = self.0.as_ref();
+        let is_valid = identifier.first().is_some_and(|&ch| ch == b'_' || ch.is_ascii_alphabetic())
+            && identifier.iter().all(|&ch| ch == b'_' || ch.is_ascii_alphanumeric())
+            && !identifier.starts_with(b"cupric_")
+            && !C_KEYWORDS.iter().any(|keyword| keyword.as_bytes() == identifier);
+
+        if is_valid {
+            // SAFETY: We just validated every byte in the identifier against an ASCII-only list.
+            unsafe {
+                write!(f, "{}", str::from_utf8_unchecked(identifier))
+            }
+        }
+        else {
+            write!(f, "cupric_{}", EscapedIdentifierDisplay(identifier))
+        }
+    }
+}
+
+pub struct StringLiteralDisplay<T: AsRef<[u8]>>(pub T);
+
+impl<T: AsRef<[u8]>> std::fmt::Display for StringLiteralDisplay<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"")?;
+        for &ch in self.0.as_ref() {
+            match ch {
+                b'"' | b'\\' | b'?' => write!(f, "\\{}", ch as char)?,
+                b' ' => write!(f, " ")?,
+                // Always use three octal digits so a following digit is not absorbed
+                _ if !ch.is_ascii_graphic() => write!(f, "\\{ch:03o}")?,
+                _ => write!(f, "{}", ch as char)?,
+            }
+        }
+        write!(f, "\"")
+    }
+}
+
+pub trait CDisplay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result;
+
+    fn c<'a>(&'a self, context: &'a GlobalContext) -> CDisplayWrapper<'a, Self>
+    where
+        Self: Sized,
+    {
+        CDisplayWrapper {
+            inner: self,
+            context,
+        }
+    }
+}
+
+pub struct CDisplayWrapper<'a, T: CDisplay> {
+    pub inner: &'a T,
+    pub context: &'a GlobalContext,
+}
+
+impl<'a, T: CDisplay> std::fmt::Display for CDisplayWrapper<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f, self.context)
+    }
+}
+
+impl CDisplay for BlockLabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        let _ = context;
+        write!(f, "b{}", EscapedIdentifierDisplay(self.identifier()))
+    }
+}
+
+impl CDisplay for crate::ir::ExternalGlobalVariable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        write!(
+            f,
+            "extern {} {};",
+            self.value_type().c(context),
+            GlobalIdentifierDisplay(self.register().identifier()),
+        )
+    }
+}
+
+impl CDisplay for crate::ir::ExternalFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        let TypeRepr::Function { signature } = self.register().get_type().repr(context) else {
+            panic!("'{}' is not a function type", self.register().get_type().path(context));
+        };
+
+        // LLVM intrinsics have no C equivalent to link against, so the common ones are defined here
+        if let Some(operator) = intrinsic_operator(self.register().identifier()) {
+            let &[lhs_type, rhs_type] = signature.parameter_types() else {
+                panic!("invalid intrinsic signature");
+            };
+            return write!(
+                f,
+                "static {return_type} {name}({} lhs, {} rhs) {{ return lhs {operator} rhs ? lhs : rhs; }}",
+                lhs_type.c(context),
+                rhs_type.c(context),
+                return_type = signature.return_type().c(context),
+                name = GlobalIdentifierDisplay(self.register().identifier()),
+            );
+        }
+
+        write!(
+            f,
+            "{} {}",
+            signature.return_type().c(context),
+            GlobalIdentifierDisplay(self.register().identifier()),
+        )?;
+        types::fmt_parameter_types(f, context, signature.parameter_types(), signature.is_variadic())?;
+        write!(f, ";")
+    }
+}
+
+/// Get the comparison operator implementing an LLVM minimum/maximum intrinsic, if `identifier`
+/// names one.
+fn intrinsic_operator(identifier: &[u8]) -> Option<&'static str> {
+    let name = identifier.strip_prefix(b"llvm.")?;
+    let operation = &name[..name.iter().position(|&ch| ch == b'.')?];
+    match operation {
+        b"smax" | b"umax" => Some(">"),
+        b"smin" | b"umin" => Some("<"),
+        _ => None
+    }
+}
+
+impl CDisplay for crate::ir::GlobalVariable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{} {} = ",
+            linkage_prefix(self.kind()),
+            self.value().get_type().c(context),
+            GlobalIdentifierDisplay(self.register().identifier()),
+        )?;
+        value::fmt_initializer(f, context, self.value())?;
+        write!(f, ";")
+    }
+}
+
+fn linkage_prefix(kind: crate::ir::GlobalVariableKind) -> &'static str {
+    match kind {
+        crate::ir::GlobalVariableKind::AnonymousConstant => "static ",
+        crate::ir::GlobalVariableKind::Constant | crate::ir::GlobalVariableKind::Mutable => "",
+    }
+}
+
+fn fmt_function_prototype(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    function: &crate::ir::FunctionDefinition,
+    with_parameter_names: bool,
+) -> std::fmt::Result {
+    let TypeRepr::Function { signature } = function.register().get_type().repr(context) else {
+        panic!("'{}' is not a function type", function.register().get_type().path(context));
+    };
+
+    write!(
+        f,
+        "{} {}",
+        signature.return_type().c(context),
+        GlobalIdentifierDisplay(function.register().identifier()),
+    )?;
+
+    if !with_parameter_names {
+        return types::fmt_parameter_types(f, context, signature.parameter_types(), signature.is_variadic());
+    }
+
+    write!(f, "(")?;
+    let mut parameters_iter = function.parameter_registers().iter();
+    if let Some(parameter) = parameters_iter.next() {
+        write!(f, "{} {}", parameter.get_type().c(context), parameter.c(context))?;
+        for parameter in parameters_iter {
+            write!(f, ", {} {}", parameter.get_type().c(context), parameter.c(context))?;
+        }
+        if signature.is_variadic() {
+            write!(f, ", ...")?;
+        }
+    }
+    else if !signature.is_variadic() {
+        write!(f, "void")?;
+    }
+    write!(f, ")")
+}
+
+impl CDisplay for crate::ir::FunctionDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        fmt_function_prototype(f, context, self, true)?;
+        writeln!(f, " {{")?;
+
+        // Every register is declared up front since blocks can be jumped between freely
+        let mut phi_results = Vec::new();
+        for block in self.blocks() {
+            for phi in block.phis() {
+                writeln!(f, "\t{} {};", phi.result.get_type().c(context), phi.result.c(context))?;
+                writeln!(f, "\t{} {};", phi.result.get_type().c(context), instr::PhiInputDisplay(&phi.result))?;
+                phi_results.push(&phi.result);
+            }
+            for instruction in block.body() {
+                if let crate::ir::instr::Instruction::StackAllocate { result } = instruction {
+                    let &TypeRepr::Pointer { pointee_type, .. } = result.get_type().repr(context) else {
+                        panic!("{} is not a pointer", result.c(context));
+                    };
+                    writeln!(f, "\t{} {};", pointee_type.c(context), instr::StackStorageDisplay(result))?;
+                }
+                if let Some(result) = instruction.result() {
+                    if !matches!(result.get_type(), TypeHandle::VOID | TypeHandle::NEVER) {
+                        writeln!(f, "\t{} {};", result.get_type().c(context), result.c(context))?;
+                    }
+                }
+            }
+        }
+
+        for block in self.blocks() {
+            fmt_basic_block(f, context, self, block)?;
+        }
+
+        write!(f, "}}")
+    }
+}
+
+fn fmt_basic_block(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    function: &crate::ir::FunctionDefinition,
+    block: &BasicBlock,
+) -> std::fmt::Result {
+    writeln!(f, "{}:;", block.label().c(context))?;
+    for phi in block.phis() {
+        writeln!(f, "\t{} = {};", phi.result.c(context), instr::PhiInputDisplay(&phi.result))?;
+    }
+    for instruction in block.body() {
+        writeln!(f, "\t{}", instruction.c(context))?;
+    }
+
+    match block.terminator() {
+        TerminatorInstruction::Return { value } => {
+            if matches!(value.get_type(), TypeHandle::VOID | TypeHandle::NEVER) {
+                writeln!(f, "\treturn;")
+            }
+            else {
+                writeln!(f, "\treturn {};", value.c(context))
+            }
+        }
+        TerminatorInstruction::Branch { to_label } => {
+            fmt_jump(f, context, function, block.label(), to_label, "\t")
+        }
+        TerminatorInstruction::ConditionalBranch { condition, consequent_label, alternative_label } => {
+            writeln!(f, "\tif ({}) {{", condition.c(context))?;
+            fmt_jump(f, context, function, block.label(), consequent_label, "\t\t")?;
+            writeln!(f, "\t}}")?;
+            writeln!(f, "\telse {{")?;
+            fmt_jump(f, context, function, block.label(), alternative_label, "\t\t")?;
+            writeln!(f, "\t}}")
+        }
+        TerminatorInstruction::Unreachable => {
+            writeln!(f, "\tCUPRIC_UNREACHABLE();")
+        }
+    }
+}
+
+/// Write a `goto` from block `from_label` to block `to_label`, first assigning the inputs for
+/// any phi instructions in the destination block.
+fn fmt_jump(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    function: &crate::ir::FunctionDefinition,
+    from_label: &BlockLabel,
+    to_label: &BlockLabel,
+    indent: &str,
+) -> std::fmt::Result {
+    let to_block = function.blocks().iter()
+        .find(|block| block.label() == to_label)
+        .expect("branch to nonexistent block");
+
+    for phi in to_block.phis() {
+        let (value, _) = phi.inputs.iter()
+            .find(|(_, label)| label == from_label)
+            .expect("phi has no input for predecessor block");
+        if !matches!(value, Value::Void | Value::Never | Value::Break | Value::Continue) {
+            writeln!(f, "{indent}{} = {};", instr::PhiInputDisplay(&phi.result), value.c(context))?;
+        }
+    }
+
+    writeln!(f, "{indent}goto {};", to_label.c(context))
+}
+
+impl CDisplay for crate::ir::CompilationUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        writeln!(f, "/* Generated from '{}' */", self.main_path().display())?;
+        writeln!(f)?;
+        write!(f, "{PRELUDE}")?;
+        writeln!(f)?;
+
+        types::fmt_type_declarations(f, context)?;
+
+        for variable in self.external_global_variables() {
+            writeln!(f, "{}", variable.c(context))?;
+        }
+        for function in self.external_functions() {
+            writeln!(f, "{}", function.c(context))?;
+        }
+        writeln!(f)?;
+
+        // Forward declarations allow globals and functions to refer to each other in any order
+        for variable in self.global_variables() {
+            let declaration_prefix = match variable.kind() {
+                crate::ir::GlobalVariableKind::AnonymousConstant => "static ",
+                crate::ir::GlobalVariableKind::Constant | crate::ir::GlobalVariableKind::Mutable => "extern ",
+            };
+            writeln!(
+                f,
+                "{declaration_prefix}{} {};",
+                variable.value().get_type().c(context),
+                GlobalIdentifierDisplay(variable.register().identifier()),
+            )?;
+        }
+        for function in self.function_definitions() {
+            fmt_function_prototype(f, context, function, false)?;
+            writeln!(f, ";")?;
+        }
+        writeln!(f)?;
+
+        for variable in self.global_variables() {
+            writeln!(f, "{}", variable.c(context))?;
+        }
+        writeln!(f)?;
+
+        for function in self.function_definitions() {
+            writeln!(f, "{}", function.c(context))?;
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,292 @@
+use std::collections::HashSet;
+use super::*;
+use crate::sema::StructureMember;
+
+impl CDisplay for TypeHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        match self.repr(context) {
+            TypeRepr::Unresolved => {
+                write!(f, "<ERROR unresolved type>")
+            }
+            TypeRepr::Meta => {
+                write!(f, "<ERROR meta type>")
+            }
+            TypeRepr::Never | TypeRepr::Void => {
+                write!(f, "void")
+            }
+            TypeRepr::Boolean => {
+                write!(f, "bool")
+            }
+            &TypeRepr::Integer { size, signed } => {
+                let prefix = if signed { "" } else { "u" };
+                write!(f, "{prefix}int{}_t", size * 8)
+            }
+            TypeRepr::PointerSizedInteger { .. } => {
+                panic!("unresolved pointer sized integer")
+            }
+            TypeRepr::Float32 => {
+                write!(f, "float")
+            }
+            TypeRepr::Float64 => {
+                write!(f, "double")
+            }
+            &TypeRepr::Pointer { pointee_type, .. } => {
+                write!(f, "{}*", pointee_type.c(context))
+            }
+            &TypeRepr::Array { item_type, length: None } => {
+                // Unsized arrays are only used behind pointers, which point to the first item
+                item_type.fmt(f, context)
+            }
+            TypeRepr::Array { .. } | TypeRepr::Tuple { .. } | TypeRepr::Structure { .. } | TypeRepr::OpaqueStructure { .. } | TypeRepr::Function { .. } => {
+                write!(f, "cupric_{}", MangledTypeDisplay(*self, context))
+            }
+        }
+    }
+}
+
+/// Writes a name for a type which is derived from its structure, so that types which are
+/// identical in C share the same name.
+struct MangledTypeDisplay<'a>(TypeHandle, &'a GlobalContext);
+
+impl std::fmt::Display for MangledTypeDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let context = self.1;
+        match self.0.repr(context) {
+            TypeRepr::Unresolved | TypeRepr::Meta | TypeRepr::Never | TypeRepr::Void => {
+                write!(f, "v")
+            }
+            TypeRepr::Boolean => {
+                write!(f, "b")
+            }
+            &TypeRepr::Integer { size, signed } => {
+                write!(f, "{}{}", if signed { 'i' } else { 'u' }, size * 8)
+            }
+            TypeRepr::PointerSizedInteger { .. } => {
+                panic!("unresolved pointer sized integer")
+            }
+            TypeRepr::Float32 => {
+                write!(f, "f32")
+            }
+            TypeRepr::Float64 => {
+                write!(f, "f64")
+            }
+            &TypeRepr::Pointer { pointee_type, .. } => {
+                write!(f, "P{}", MangledTypeDisplay(pointee_type, context))
+            }
+            &TypeRepr::Array { item_type, length } => match length {
+                Some(length) => write!(f, "A{length}_{}", MangledTypeDisplay(item_type, context)),
+                None => MangledTypeDisplay(item_type, context).fmt(f),
+            }
+            TypeRepr::Tuple { item_types } => {
+                write!(f, "T{}_", item_types.len())?;
+                for &item_type in item_types {
+                    MangledTypeDisplay(item_type, context).fmt(f)?;
+                }
+                Ok(())
+            }
+            TypeRepr::Structure { .. } | TypeRepr::OpaqueStructure { .. } => {
+                let name = EscapedIdentifierDisplay(self.0.path(context).to_string()).to_string();
+                write!(f, "S{}_{name}", name.len())
+            }
+            TypeRepr::Function { signature } => {
+                write!(f, "F{}_{}_", MangledTypeDisplay(signature.return_type(), context), signature.parameter_types().len())?;
+                for &parameter_type in signature.parameter_types() {
+                    MangledTypeDisplay(parameter_type, context).fmt(f)?;
+                }
+                if signature.is_variadic() {
+                    write!(f, "V")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Writes the name of the structure member at `index` in an aggregate.
+pub struct MemberNameDisplay<'a>(pub &'a TypeRepr, pub usize);
+
+impl std::fmt::Display for MemberNameDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            TypeRepr::Structure { members, .. } => {
+                write!(f, "m_{}", EscapedIdentifierDisplay(members[self.1].name.as_bytes()))
+            }
+            TypeRepr::Array { .. } => {
+                write!(f, "items")
+            }
+            _ => {
+                write!(f, "m{}", self.1)
+            }
+        }
+    }
+}
+
+pub fn fmt_parameter_types(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    parameter_types: &[TypeHandle],
+    is_variadic: bool,
+) -> std::fmt::Result {
+    write!(f, "(")?;
+    let mut parameters_iter = parameter_types.iter();
+    if let Some(&parameter_type) = parameters_iter.next() {
+        write!(f, "{}", parameter_type.c(context))?;
+        for &parameter_type in parameters_iter {
+            write!(f, ", {}", parameter_type.c(context))?;
+        }
+        if is_variadic {
+            write!(f, ", ...")?;
+        }
+    }
+    else if !is_variadic {
+        write!(f, "void")?;
+    }
+    write!(f, ")")
+}
+
+/// Whether `handle` can be written in C at all.
+fn is_representable(context: &GlobalContext, handle: TypeHandle) -> bool {
+    match handle.repr(context) {
+        TypeRepr::Unresolved | TypeRepr::Meta | TypeRepr::PointerSizedInteger { .. } => false,
+        TypeRepr::Never | TypeRepr::Void | TypeRepr::Boolean | TypeRepr::Integer { .. } |
+        TypeRepr::Float32 | TypeRepr::Float64 | TypeRepr::OpaqueStructure { .. } => true,
+        &TypeRepr::Pointer { pointee_type, .. } => match pointee_type.repr(context) {
+            // Recursive types always pass through a pointer to a structure, which is checked separately
+            TypeRepr::Structure { .. } => true,
+            _ => is_representable(context, pointee_type),
+        }
+        &TypeRepr::Array { item_type, .. } => is_representable(context, item_type),
+        TypeRepr::Tuple { item_types } => item_types.iter().all(|&item_type| is_representable(context, item_type)),
+        TypeRepr::Structure { members, .. } => members.iter().all(|member| is_representable(context, member.member_type)),
+        TypeRepr::Function { signature } => {
+            is_representable(context, signature.return_type())
+                && signature.parameter_types().iter().all(|&parameter_type| is_representable(context, parameter_type))
+        }
+    }
+}
+
+/// Whether `handle` is written as a C structure.
+fn is_aggregate(context: &GlobalContext, handle: TypeHandle) -> bool {
+    matches!(
+        handle.repr(context),
+        TypeRepr::Array { length: Some(_), .. } | TypeRepr::Tuple { .. } | TypeRepr::Structure { .. } | TypeRepr::OpaqueStructure { .. }
+    )
+}
+
+/// Emits type definitions such that every type is defined before it is needed.
+struct TypeDeclarationWriter<'a, 'f, 'g> {
+    f: &'a mut std::fmt::Formatter<'f>,
+    context: &'g GlobalContext,
+    /// Names of the types which have been completely defined so far.
+    defined_names: HashSet<String>,
+}
+
+impl TypeDeclarationWriter<'_, '_, '_> {
+    /// Ensure the name of `handle` can be used, though the type may still be incomplete.
+    fn declare(&mut self, handle: TypeHandle) -> std::fmt::Result {
+        match *handle.repr(self.context) {
+            TypeRepr::Pointer { pointee_type, .. } | TypeRepr::Array { item_type: pointee_type, length: None } => {
+                self.declare(pointee_type)
+            }
+            TypeRepr::Function { .. } => {
+                self.define(handle)
+            }
+            _ => {
+                // Aggregates are all forward-declared up front
+                Ok(())
+            }
+        }
+    }
+
+    /// Ensure `handle` is a complete type, so that it can be used by value.
+    fn define(&mut self, handle: TypeHandle) -> std::fmt::Result {
+        let context = self.context;
+        let repr = handle.repr(context);
+        if !is_aggregate(context, handle) && !matches!(repr, TypeRepr::Function { .. }) {
+            return self.declare(handle);
+        }
+        if matches!(repr, TypeRepr::OpaqueStructure { .. }) || !self.defined_names.insert(handle.c(context).to_string()) {
+            return Ok(());
+        }
+
+        match repr {
+            &TypeRepr::Array { item_type, length: Some(length) } => {
+                self.define(item_type)?;
+                // Zero-length arrays are not allowed in C, so they take a single unused slot
+                writeln!(self.f, "struct {} {{ {} items[{}]; }};", handle.c(context), item_type.c(context), length.max(1))?;
+            }
+            TypeRepr::Tuple { item_types } => {
+                let members: Vec<StructureMember> = item_types.iter()
+                    .map(|&member_type| StructureMember {
+                        name: Box::from(""),
+                        is_public: true,
+                        member_type,
+                    })
+                    .collect();
+                self.define_structure(handle, repr, &members)?;
+            }
+            TypeRepr::Structure { members, .. } => {
+                self.define_structure(handle, repr, members)?;
+            }
+            TypeRepr::Function { signature } => {
+                self.declare(signature.return_type())?;
+                for &parameter_type in signature.parameter_types() {
+                    self.declare(parameter_type)?;
+                }
+                write!(self.f, "typedef {} (*{})", signature.return_type().c(context), handle.c(context))?;
+                fmt_parameter_types(self.f, context, signature.parameter_types(), signature.is_variadic())?;
+                writeln!(self.f, ";")?;
+            }
+            _ => unreachable!()
+        }
+
+        Ok(())
+    }
+
+    fn define_structure(&mut self, handle: TypeHandle, repr: &TypeRepr, members: &[StructureMember]) -> std::fmt::Result {
+        let context = self.context;
+        for member in members {
+            self.define(member.member_type)?;
+        }
+
+        write!(self.f, "struct {} {{", handle.c(context))?;
+        if members.is_empty() {
+            // Empty structures are not allowed in C
+            write!(self.f, " char unused;")?;
+        }
+        for (index, member) in members.iter().enumerate() {
+            write!(self.f, " {} {};", member.member_type.c(context), MemberNameDisplay(repr, index))?;
+        }
+        writeln!(self.f, " }};")
+    }
+}
+
+/// Write declarations for every type known so far, ordered so that each type is complete
+/// before it is used by value.
+pub fn fmt_type_declarations(f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+    let handles: Vec<TypeHandle> = (0..context.type_registry().type_count())
+        .map(TypeHandle::new)
+        .filter(|&handle| is_representable(context, handle))
+        .collect();
+
+    let mut forward_declared = HashSet::new();
+    for &handle in &handles {
+        if is_aggregate(context, handle) {
+            let name = handle.c(context).to_string();
+            if forward_declared.insert(name.clone()) {
+                writeln!(f, "typedef struct {name} {name};")?;
+            }
+        }
+    }
+    writeln!(f)?;
+
+    let mut writer = TypeDeclarationWriter {
+        f,
+        context,
+        defined_names: HashSet::new(),
+    };
+    for handle in handles {
+        writer.define(handle)?;
+    }
+    writeln!(writer.f)
+}
@@ -0,0 +1,197 @@
+use super::*;
+use crate::ir::value::{Constant, FloatType, FloatValue, GlobalRegister, IntegerValue, LocalRegister};
+
+impl CDisplay for IntegerValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        let size = self.integer_type().size(context.target());
+        let raw = self.raw();
+        match (size, self.integer_type().is_signed()) {
+            // The minimum values cannot be written directly since the literal would overflow
+            (8, true) if raw == i64::MIN as i128 => write!(f, "INT64_MIN"),
+            (8, true) => write!(f, "INT64_C({raw})"),
+            (8, false) => write!(f, "UINT64_C({raw})"),
+            (_, true) if raw == i32::MIN as i128 => write!(f, "(-2147483647 - 1)"),
+            (_, true) if raw < 0 => write!(f, "({raw})"),
+            (_, true) => write!(f, "{raw}"),
+            (_, false) => write!(f, "{raw}u"),
+        }
+    }
+}
+
+impl CDisplay for FloatValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        let _ = context;
+        let raw = self.raw();
+        if raw.is_nan() {
+            write!(f, "NAN")
+        }
+        else if raw.is_infinite() {
+            write!(f, "{}INFINITY", if raw < 0.0 { "-" } else { "" })
+        }
+        else {
+            // Debug formatting gives the shortest representation that round-trips exactly
+            let (open, close) = if raw.is_sign_negative() { ("(", ")") } else { ("", "") };
+            match self.float_type() {
+                FloatType::F32 => write!(f, "{open}{:?}f{close}", raw as f32),
+                FloatType::F64 => write!(f, "{open}{raw:?}{close}"),
+            }
+        }
+    }
+}
+
+impl CDisplay for LocalRegister {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        let _ = context;
+        write!(f, "l_{}", EscapedIdentifierDisplay(self.identifier()))
+    }
+}
+
+impl CDisplay for GlobalRegister {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        // Global registers for variables are pointers to the variable
+        match self.get_type().repr(context) {
+            TypeRepr::Function { .. } => write!(f, "{}", GlobalIdentifierDisplay(self.identifier())),
+            _ => write!(f, "(&{})", GlobalIdentifierDisplay(self.identifier())),
+        }
+    }
+}
+
+fn is_aggregate_constant(context: &GlobalContext, value_type: TypeHandle) -> bool {
+    matches!(
+        value_type.repr(context),
+        TypeRepr::Array { .. } | TypeRepr::Tuple { .. } | TypeRepr::Structure { .. } | TypeRepr::OpaqueStructure { .. }
+    )
+}
+
+/// Write `constant` in the form of an initializer, which can only be used when declaring a
+/// variable or inside another initializer.
+pub fn fmt_initializer(f: &mut std::fmt::Formatter<'_>, context: &GlobalContext, constant: &Constant) -> std::fmt::Result {
+    fn fmt_items(f: &mut std::fmt::Formatter<'_>, context: &GlobalContext, items: &[Constant]) -> std::fmt::Result {
+        let mut items_iter = items.iter();
+        if let Some(item) = items_iter.next() {
+            write!(f, "{{ ")?;
+            fmt_initializer(f, context, item)?;
+            for item in items_iter {
+                write!(f, ", ")?;
+                fmt_initializer(f, context, item)?;
+            }
+            write!(f, " }}")
+        }
+        else {
+            write!(f, "{{0}}")
+        }
+    }
+
+    match constant {
+        Constant::Undefined(value_type) | Constant::Poison(value_type) | Constant::ZeroInitializer(value_type)
+            if is_aggregate_constant(context, *value_type) =>
+        {
+            match value_type.repr(context) {
+                // Arrays are wrapped in a structure, so the array itself needs another set of braces
+                TypeRepr::Array { .. } => write!(f, "{{ {{0}} }}"),
+                _ => write!(f, "{{0}}"),
+            }
+        }
+        Constant::String { array_type, value } => {
+            let &TypeRepr::Array { length, .. } = array_type.repr(context) else {
+                panic!("string constant with non-array type");
+            };
+            // A string literal implicitly ends with a null byte, which is left off if it would
+            // not fit in the array
+            let bytes = match value.bytes().last() {
+                Some(0) if length.is_none_or(|length| value.len() as u64 <= length) => value.raw_bytes(),
+                _ => value.bytes(),
+            };
+            write!(f, "{{ {} }}", StringLiteralDisplay(bytes))
+        }
+        Constant::Array { items, .. } => {
+            write!(f, "{{ ")?;
+            fmt_items(f, context, items)?;
+            write!(f, " }}")
+        }
+        Constant::Tuple { items, .. } => {
+            fmt_items(f, context, items)
+        }
+        Constant::Structure { members, .. } => {
+            fmt_items(f, context, members)
+        }
+        _ => {
+            constant.fmt(f, context)
+        }
+    }
+}
+
+impl CDisplay for Constant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        match self {
+            Self::Undefined(value_type) | Self::Poison(value_type) | Self::ZeroInitializer(value_type) | Self::NullPointer(value_type) => {
+                if is_aggregate_constant(context, *value_type) {
+                    write!(f, "({}){{0}}", value_type.c(context))
+                }
+                else if *value_type == TypeHandle::BOOL {
+                    write!(f, "false")
+                }
+                else {
+                    write!(f, "(({})0)", value_type.c(context))
+                }
+            }
+            Self::Boolean(value) => {
+                write!(f, "{value}")
+            }
+            Self::Integer(value) => {
+                value.fmt(f, context)
+            }
+            Self::Float(value) => {
+                value.fmt(f, context)
+            }
+            Self::String { .. } | Self::Array { .. } | Self::Tuple { .. } | Self::Structure { .. } => {
+                // Compound literal
+                write!(f, "({})", self.get_type().c(context))?;
+                fmt_initializer(f, context, self)
+            }
+            Self::Register(register) => {
+                register.fmt(f, context)
+            }
+            Self::Indirect { pointer, .. } => {
+                write!(f, "<ERROR indirect constant: {}>", pointer.c(context))
+            }
+            Self::Convert { operation, value, result_type } => {
+                instr::fmt_conversion(f, context, *operation, value.get_type(), *result_type, &value.c(context))
+            }
+            Self::GetElementPointer { result_type, aggregate_type, pointer, indices } => {
+                let indices: Vec<Value> = indices.iter().cloned().map(Value::Constant).collect();
+                write!(f, "(({})", result_type.c(context))?;
+                instr::fmt_element_address(f, context, *aggregate_type, &Value::Constant(*pointer.clone()), &indices)?;
+                write!(f, ")")
+            }
+            Self::Type(..) | Self::Module(..) => {
+                write!(f, "<ERROR meta constant>")
+            }
+        }
+    }
+}
+
+impl CDisplay for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        match self {
+            Self::Never | Self::Break | Self::Continue => {
+                write!(f, "<ERROR never value>")
+            }
+            Self::Void => {
+                write!(f, "<ERROR void value>")
+            }
+            Self::Constant(constant) => {
+                constant.fmt(f, context)
+            }
+            Self::Register(register) => {
+                register.fmt(f, context)
+            }
+            Self::Indirect { pointer, .. } => {
+                write!(f, "<ERROR indirect value: {}>", pointer.c(context))
+            }
+            Self::BoundFunction { function_value, .. } => {
+                function_value.fmt(f, context)
+            }
+        }
+    }
+}
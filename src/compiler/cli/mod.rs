@@ -1,101 +1,635 @@
-use std::io::Write;
+use std::collections::HashSet;
+use std::io::{BufRead, Write};
+use std::time::Instant;
 use std::path::{Path, PathBuf};
-use clap::Parser as ClapParser;
-use crate::ast::parse::parse_module;
+use crate::package::SourceMap;
+use clap::{Parser as ClapParser, Subcommand, ValueEnum};
+use crate::ast::GlobalNode;
+use crate::ast::parse::{ParsedModule, parse_module};
+use crate::c::CDisplay;
 use crate::gen::Generator;
+use crate::wasm::WatDisplay;
+use crate::ir::{CompilationUnit, FunctionDefinition, IRDisplay, OptimizationLevel};
 use crate::llvm::LLVMDisplay;
 use crate::sema::GlobalContext;
-use crate::target::TargetInfo;
+use crate::package::{BuildManifest, PackageKind, write_interfaces};
+use crate::target::{LLVMPointerStyle, TargetInfo};
 use crate::token::scan::Scanner;
 
+mod report;
+pub use report::*;
+mod stats;
+pub use stats::*;
+mod toolchain;
+pub use toolchain::*;
+
 #[derive(ClapParser, Debug)]
-#[command(author, version, about)]
+#[command(author, version, about, args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct CompilerArgs {
+    #[command(subcommand)]
+    command: Option<Command>,
     #[doc = "Compile the package inside directory <package_path>"]
+    #[arg(value_name = "package_path", required = true)]
+    package: Option<PathBuf>,
+    #[doc = "The kind of output to produce"]
+    #[arg(long, value_name = "kind", value_enum, default_value_t = EmitKind::Source)]
+    emit: EmitKind,
+    #[doc = "The language to generate code in (defaults to 'wasm' for wasm32 targets, otherwise 'llvm')"]
+    #[arg(long, value_name = "backend", value_enum)]
+    backend: Option<Backend>,
+    #[doc = "The external programs used to produce objects and executables"]
+    #[arg(long, value_name = "toolchain", value_enum, default_value_t = ToolchainKind::Auto)]
+    toolchain: ToolchainKind,
+    #[doc = "Path to the clang executable"]
+    #[arg(long, value_name = "path", default_value = "clang")]
+    clang: PathBuf,
+    #[doc = "Path to the llc executable"]
+    #[arg(long, value_name = "path", default_value = "llc")]
+    llc: PathBuf,
+    #[doc = "Path to the C compiler used for linking with the llc toolchain"]
+    #[arg(long, value_name = "path", default_value = "cc")]
+    cc: PathBuf,
+    #[doc = "Write output files to <out_dir> instead of the 'out' directory of each package"]
+    #[arg(long, value_name = "out_dir")]
+    out_dir: Option<PathBuf>,
+    #[doc = "Only print errors"]
+    #[arg(short, long, conflicts_with = "verbose")]
+    quiet: bool,
+    #[doc = "Print additional details about each phase of compilation"]
+    #[arg(short, long)]
+    verbose: bool,
+    #[doc = "Report the time spent in each compilation phase along with statistics"]
+    #[arg(long)]
+    time_passes: bool,
+    #[doc = "Compile for the target with triple <triple> (defaults to the host)"]
+    #[arg(long, value_name = "triple")]
+    target: Option<String>,
+    #[doc = "Pointer style for LLVM IR output (use 'typed' for LLVM 14 and earlier)"]
+    #[arg(long, value_name = "style", value_enum, default_value_t = LLVMPointerStyle::Opaque)]
+    llvm_pointers: LLVMPointerStyle,
+    #[doc = "Include debug info in the output"]
+    #[arg(short = 'g')]
+    debug_info: bool,
+    #[doc = "The level of optimization to apply to generated code"]
+    #[arg(short = 'O', value_name = "level", value_enum, default_value_t = OptimizationLevel::None)]
+    optimization_level: OptimizationLevel,
+    #[doc = "Control whether output is colored"]
+    #[arg(long, value_name = "when", value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+}
+
+impl CompilerArgs {
+    pub fn command(&self) -> Option<&Command> {
+        self.command.as_ref()
+    }
+
+    pub fn package_path(&self) -> &Path {
+        self.package.as_deref().expect("package path is required without a subcommand")
+    }
+
+    pub fn emit(&self) -> EmitKind {
+        self.emit
+    }
+
+    pub fn backend(&self) -> Option<Backend> {
+        self.backend
+    }
+
+    pub fn out_dir(&self) -> Option<&Path> {
+        self.out_dir.as_deref()
+    }
+
+    pub fn verbosity(&self) -> Verbosity {
+        if self.quiet {
+            Verbosity::Quiet
+        }
+        else if self.verbose {
+            Verbosity::Verbose
+        }
+        else {
+            Verbosity::Normal
+        }
+    }
+
+    pub fn color(&self) -> ColorChoice {
+        self.color
+    }
+
+    pub fn target_triple(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    pub fn llvm_pointer_style(&self) -> LLVMPointerStyle {
+        self.llvm_pointers
+    }
+
+    pub fn time_passes(&self) -> bool {
+        self.time_passes
+    }
+
+    pub fn debug_info(&self) -> bool {
+        self.debug_info
+    }
+
+    pub fn optimization_level(&self) -> OptimizationLevel {
+        self.optimization_level
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    #[doc = "Compile a package and run it with the built-in interpreter"]
+    Run(RunArgs),
+    #[doc = "Convert mangled symbol names into readable paths"]
+    Demangle(DemangleArgs),
+}
+
+#[derive(ClapParser, Debug)]
+pub struct RunArgs {
+    #[doc = "Run the package inside directory <package_path>"]
     #[arg(value_name = "package_path")]
     package: PathBuf,
+    #[doc = "Print progress while compiling the package"]
+    #[arg(short, long)]
+    verbose: bool,
+    #[doc = "Compile for the target with triple <triple> (defaults to the host)"]
+    #[arg(long, value_name = "triple")]
+    target: Option<String>,
+    #[doc = "The level of optimization to apply to generated code"]
+    #[arg(short = 'O', value_name = "level", value_enum, default_value_t = OptimizationLevel::None)]
+    optimization_level: OptimizationLevel,
+    #[doc = "Control whether output is colored"]
+    #[arg(long, value_name = "when", value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+    #[doc = "Arguments passed to the program"]
+    #[arg(value_name = "args", trailing_var_arg = true, allow_hyphen_values = true)]
+    arguments: Vec<String>,
 }
 
-impl CompilerArgs {
+impl RunArgs {
     pub fn package_path(&self) -> &Path {
         &self.package
     }
+
+    pub fn verbosity(&self) -> Verbosity {
+        if self.verbose {
+            Verbosity::Verbose
+        }
+        else {
+            Verbosity::Quiet
+        }
+    }
+
+    pub fn color(&self) -> ColorChoice {
+        self.color
+    }
+
+    pub fn target_triple(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    pub fn optimization_level(&self) -> OptimizationLevel {
+        self.optimization_level
+    }
+
+    pub fn arguments(&self) -> &[String] {
+        &self.arguments
+    }
+}
+
+#[derive(ClapParser, Debug)]
+pub struct DemangleArgs {
+    #[doc = "Symbols to demangle (if none are given, mangled symbols in standard input are replaced)"]
+    #[arg(value_name = "symbols")]
+    symbols: Vec<String>,
+}
+
+impl DemangleArgs {
+    pub fn symbols(&self) -> &[String] {
+        &self.symbols
+    }
 }
 
 pub fn parse_command_line_args() -> CompilerArgs {
     CompilerArgs::parse()
 }
 
-pub fn invoke(args: &CompilerArgs) -> Result<(), Box<(crate::Error, Vec<PathBuf>)>> {
+fn resolve_target(triple: Option<&str>) -> Result<TargetInfo, Box<(crate::Error, SourceMap)>> {
+    match triple {
+        Some(triple) => TargetInfo::from_triple(triple).ok_or_else(|| Box::new((
+            crate::Error::new(None, crate::ErrorKind::UnknownTarget {
+                triple: triple.to_owned(),
+                known_triples: TargetInfo::known_triples().collect(),
+            }),
+            SourceMap::new(),
+        ))),
+        None => Ok(TargetInfo::host()),
+    }
+}
+
+/// Parse, check, and generate IR for the package currently being compiled by `context`.
+fn compile_package(context: &mut GlobalContext, reporter: &mut dyn Reporter, stats: &mut PackageStatistics) -> Result<(), Box<(crate::Error, SourceMap)>> {
+    let parsed_modules = analyze_package(context, reporter, stats)?;
+    generate_package(context, reporter, stats, &parsed_modules)
+}
+
+/// Parse and check the package currently being compiled by `context`, completing the fill phase.
+fn analyze_package(context: &mut GlobalContext, reporter: &mut dyn Reporter, stats: &mut PackageStatistics) -> Result<Vec<ParsedModule>, Box<(crate::Error, SourceMap)>> {
     let error = |context: &GlobalContext| {
-        let source_paths = context.package().source_paths().to_vec();
-        move |error: Box<crate::Error>| Box::new((*error, source_paths))
+        let source_map = context.package().source_map().clone();
+        move |error: Box<crate::Error>| Box::new((*error, source_map))
     };
 
-    let package_path = args.package_path();
+    let mut parsed_modules = Vec::new();
+    loop {
+        let start_time = Instant::now();
+        let next_source = context.prepare_next_source().map_err(error(context))?;
+        stats.scan_time += start_time.elapsed();
+        let Some((source_id, namespace)) = next_source else {
+            break;
+        };
+
+        // Scanning, parsing, and outline pass simultaneously
+        let source_map = context.package().source_map();
+        reporter.status(&format!("Parsing '{}'...", source_map.file(source_id).path().display()));
+
+        let start_time = Instant::now();
+        let mut scanner = Scanner::from_source_map(source_map, source_id);
+        scanner.set_timed(stats.is_enabled);
+
+        let parsed_module = parse_module(&mut scanner, context, namespace)
+            .map_err(error(context))?;
+
+        stats.scan_time += scanner.scan_time();
+        stats.parse_time += start_time.elapsed().saturating_sub(scanner.scan_time());
+        stats.token_count += scanner.token_count();
+        stats.node_count += parsed_module.statements().iter().map(GlobalNode::node_count).sum::<usize>();
+
+        reporter.detail(&format!(
+            "Parsed {} global statements into module '{}'",
+            parsed_module.statements().len(),
+            context.namespace_info(namespace).path(),
+        ));
+
+        parsed_modules.push(parsed_module);
+    }
+
+    // Fill pass (must be done after outline pass is complete for all files)
+    reporter.status("Processing definitions...");
+    let start_time = Instant::now();
+    context.fill_package(&mut parsed_modules).map_err(error(context))?;
+    stats.fill_time = start_time.elapsed();
+
+    // Calculating type sizes and alignments
+    let start_time = Instant::now();
+    context.complete_fill_phase().map_err(error(context))?;
+    stats.layout_time = start_time.elapsed();
+
+    Ok(parsed_modules)
+}
+
+/// Generate IR for the package currently being compiled by `context` once it has been analyzed.
+fn generate_package(context: &mut GlobalContext, reporter: &mut dyn Reporter, stats: &mut PackageStatistics, parsed_modules: &[ParsedModule]) -> Result<(), Box<(crate::Error, SourceMap)>> {
+    let source_map = context.package().source_map().clone();
+    reporter.status("Compiling output...");
+    let start_time = Instant::now();
+    Generator::new(context).generate_package(parsed_modules)
+        .map_err(|error| Box::new((*error, source_map)))?;
+    stats.generate_time = start_time.elapsed();
+
+    Ok(())
+}
 
+pub fn invoke(args: &CompilerArgs) -> Result<(), Box<(crate::Error, SourceMap)>> {
+    invoke_with_reporter(args, &mut ConsoleReporter::new(args.verbosity()))
+}
+
+pub fn invoke_with_reporter(args: &CompilerArgs, reporter: &mut dyn Reporter) -> Result<(), Box<(crate::Error, SourceMap)>> {
     // Set up the global context for compilation
-    let target = TargetInfo::new(size_of::<&()>() as u64);
-    let mut context = GlobalContext::new(package_path, target)
-        .map_err(|error| Box::new((*error, Vec::new())))?;
+    let target = resolve_target(args.target_triple())?;
+    let target = target.with_llvm_pointer_style(args.llvm_pointer_style());
+    let backend = args.backend().unwrap_or(if target.is_wasm32() { Backend::Wasm } else { Backend::Llvm });
+    let backend_name = || backend.to_possible_value().unwrap().get_name().to_owned();
+
+    // WebAssembly modules are written as text for the embedder to assemble and link
+    if backend == Backend::Wasm && !target.is_wasm32() {
+        return Err(Box::new((
+            crate::Error::new(None, crate::ErrorKind::UnsupportedBackendTarget {
+                backend: backend_name(),
+                triple: target.triple().unwrap_or("unknown").to_owned(),
+            }),
+            SourceMap::new(),
+        )));
+    }
+    if backend == Backend::Wasm && matches!(args.emit(), EmitKind::Obj | EmitKind::Exe) {
+        return Err(Box::new((
+            crate::Error::new(None, crate::ErrorKind::UnsupportedBackendOutput {
+                backend: backend_name(),
+                emit: args.emit().to_possible_value().unwrap().get_name().to_owned(),
+            }),
+            SourceMap::new(),
+        )));
+    }
+
+    // Find the external toolchain up front so a missing program is reported before compiling
+    let toolchain = match args.emit() {
+        EmitKind::CupricIr | EmitKind::Source => None,
+        EmitKind::Obj | EmitKind::Exe => Some(Toolchain::resolve(
+            backend,
+            args.toolchain,
+            args.clang.clone(),
+            args.llc.clone(),
+            args.cc.clone(),
+            &target,
+            args.debug_info(),
+        ).map_err(|error| Box::new((*error, SourceMap::new())))?),
+    };
+    if let Some(toolchain) = toolchain.as_ref().filter(|_| args.emit() == EmitKind::Exe) {
+        toolchain.check_can_link().map_err(|error| Box::new((*error, SourceMap::new())))?;
+    }
+
+    // Packages loaded from their interface headers have no function bodies. The dependencies
+    // needed at compile time are found from the build manifests before compiling, but a package
+    // can start calling into one since its last build, in which case everything is compiled again
+    // from source
+    match build_packages(args, reporter, backend, toolchain.as_ref(), target.clone(), false) {
+        Err(error) => {
+            let crate::ErrorKind::InterfaceFunctionCall { package_name, .. } = error.0.kind() else {
+                return Err(error);
+            };
+            reporter.status(&format!("Package '{package_name}' is needed at compile time, building every package from source..."));
+            build_packages(args, reporter, backend, toolchain.as_ref(), target, true)
+        }
+        Ok(()) => Ok(()),
+    }
+}
+
+/// Compile every package needed for the package given by `args`, dependencies first. If
+/// `from_source` is set, every package is compiled from source, even if its build is current.
+fn build_packages(args: &CompilerArgs, reporter: &mut dyn Reporter, backend: Backend, toolchain: Option<&Toolchain>, target: TargetInfo, from_source: bool) -> Result<(), Box<(crate::Error, SourceMap)>> {
+    let error = |context: &GlobalContext| {
+        let source_map = context.package().source_map().clone();
+        move |error: Box<crate::Error>| Box::new((*error, source_map))
+    };
+
+    let mut object_paths = Vec::new();
+    let output_extension = match args.emit() {
+        EmitKind::CupricIr => "cir",
+        _ => backend.output_extension(),
+    };
+    // Everything which affects the output of a package
+    let build_options = format!(
+        "output={output_extension} target={} llvm-pointers={} debug-info={} optimization={}",
+        target.triple().unwrap_or("host"),
+        target.llvm_pointer_style().to_possible_value().unwrap().get_name(),
+        args.debug_info(),
+        args.optimization_level().to_possible_value().unwrap().get_name(),
+    );
+    let mut context = GlobalContext::new(args.package_path(), target)
+        .map_err(|error| Box::new((*error, SourceMap::new())))?;
+    context.set_debug_info(args.debug_info());
+    context.set_optimization_level(args.optimization_level());
+    let source_packages = find_source_packages(&context, args.out_dir(), output_extension, &build_options);
+
+    loop {
+        let info = context.package().info();
+        reporter.status(&format!("--- Compiling package '{}' ---", info.name()));
+        reporter.detail(&format!("Package directory: '{}'", info.path().display()));
+        let mut stats = PackageStatistics::new(info.name());
+        stats.is_enabled = args.time_passes();
+        let initial_type_count = context.type_registry().type_count();
+        let initial_symbol_count = context.namespace_registry().symbol_count();
+
+        let output_path = info.get_output_path_in(args.out_dir(), output_extension);
+        let headers_path = info.get_headers_path(args.out_dir());
+        let manifest = BuildManifest::new(
+            info,
+            &context.package_manager().get_all_dependencies(info),
+            args.out_dir(),
+            output_extension,
+            build_options.clone(),
+        );
+
+        // Packages which haven't changed since their output was written are only loaded from
+        // their interface headers, which is all that packages depending on them need
+        let mut is_build_current = !from_source
+            && context.package_manager().is_build_current(info, args.out_dir(), &output_path, &manifest);
+        if is_build_current && source_packages.contains(info.name()) {
+            reporter.status(&format!("Package '{}' is needed at compile time, building it from source...", info.name()));
+            is_build_current = false;
+        }
+        if is_build_current {
+            reporter.status(&format!("Loading interface from '{}'...", headers_path.display()));
+            context.package_mut().load_from_interface(headers_path);
+            analyze_package(&mut context, reporter, &mut stats)?;
+            reporter.status(&format!("Reusing '{}'.", output_path.display()));
+        }
+        else {
+            let parsed_modules = analyze_package(&mut context, reporter, &mut stats)?;
+            generate_package(&mut context, reporter, &mut stats, &parsed_modules)?;
+
+            reporter.detail(&format!("Writing interface headers to '{}'...", headers_path.display()));
+            write_interfaces(&mut context, &parsed_modules, &headers_path).map_err(error(&context))?;
+            write_package_output(args, backend, &context, reporter, &output_path, &mut stats)?;
+
+            let manifest = manifest.with_constant_calls(context.package().has_constant_calls());
+            let manifest_path = context.package().info().get_manifest_path(args.out_dir());
+            reporter.detail(&format!("Writing build manifest to '{}'...", manifest_path.display()));
+            manifest.write(&manifest_path).map_err(error(&context))?;
+        }
+
+        if let Some(toolchain) = toolchain {
+            let object_path = output_path.with_extension("o");
+            reporter.status(&format!("Compiling object file '{}'...", object_path.display()));
+            toolchain.compile_object(&output_path, &object_path).map_err(error(&context))?;
+            object_paths.push(object_path);
+
+            // Dependencies are always compiled first, so all objects needed are available
+            let info = context.package().info();
+            if args.emit() == EmitKind::Exe && info.kind() == PackageKind::Executable {
+                let executable_path = output_path.with_extension(std::env::consts::EXE_EXTENSION);
+                reporter.status(&format!("Linking executable '{}'...", executable_path.display()));
+                toolchain.link_executable(&object_paths, &executable_path).map_err(error(&context))?;
+            }
+        }
+
+        reporter.status("Finished.");
+
+        if args.time_passes() {
+            let function_definitions = context.package().output().function_definitions();
+            stats.type_count = context.type_registry().type_count() - initial_type_count;
+            stats.symbol_count = context.namespace_registry().symbol_count() - initial_symbol_count;
+            stats.function_count = function_definitions.len();
+            stats.instruction_count = function_definitions.iter().map(FunctionDefinition::instruction_count).sum();
+            reporter.report(Verbosity::Quiet, &stats.to_string());
+        }
+
+        if !context.start_next_package() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Find the packages which must be compiled from source even if their build is current, because a
+/// package compiled after them which evaluated calls at compile time in its last build will be
+/// compiled again. Any package depending on one which is compiled again is compiled again too.
+fn find_source_packages(context: &GlobalContext, out_dir: Option<&Path>, output_extension: &str, build_options: &str) -> HashSet<Box<str>> {
+    let package_manager = context.package_manager();
+    let packages: Vec<_> = std::iter::once(context.package().info())
+        .chain(package_manager.get_remaining_to_compile().map(AsRef::as_ref))
+        .map(|info| {
+            let dependencies = package_manager.get_all_dependencies(info);
+            let output_path = info.get_output_path_in(out_dir, output_extension);
+            let manifest = BuildManifest::new(info, &dependencies, out_dir, output_extension, build_options.to_owned());
+            let is_build_current = package_manager.is_build_current(info, out_dir, &output_path, &manifest);
+            let has_constant_calls = BuildManifest::read(&info.get_manifest_path(out_dir))
+                .is_some_and(|previous| previous.has_constant_calls());
+            (info, dependencies, is_build_current, has_constant_calls)
+        })
+        .collect();
+
+    // Building a dependency from source can cause packages after it to be compiled again, so this
+    // is repeated until nothing more needs to be built from source
+    let mut source_packages = HashSet::new();
+    loop {
+        let mut compiled_packages: HashSet<&str> = HashSet::new();
+        let mut is_changed = false;
+        for (info, dependencies, is_build_current, has_constant_calls) in &packages {
+            let is_compiled = !is_build_current
+                || source_packages.contains(info.name())
+                || dependencies.iter().any(|dependency| compiled_packages.contains(dependency.name()));
+            if !is_compiled {
+                continue;
+            }
+            compiled_packages.insert(info.name());
+            if *has_constant_calls {
+                for dependency in dependencies {
+                    is_changed |= source_packages.insert(Box::from(dependency.name()));
+                }
+            }
+        }
+        if !is_changed {
+            break source_packages;
+        }
+    }
+}
+
+/// Write the LLVM IR, C source, WebAssembly text, or Cupric IR generated for the package currently
+/// being compiled by `context` to `output_path`.
+fn write_package_output(args: &CompilerArgs, backend: Backend, context: &GlobalContext, reporter: &mut dyn Reporter, output_path: &Path, stats: &mut PackageStatistics) -> Result<(), Box<(crate::Error, SourceMap)>> {
+    let error = |context: &GlobalContext| {
+        let source_map = context.package().source_map().clone();
+        move |error: Box<crate::Error>| Box::new((*error, source_map))
+    };
+
+    reporter.status(&format!(
+        "Writing {} to '{}'...",
+        match (args.emit(), backend) {
+            (EmitKind::CupricIr, _) => "Cupric IR",
+            (_, Backend::Llvm) => "LLVM IR",
+            (_, Backend::C) => "C source",
+            (_, Backend::Wasm) => "WebAssembly text",
+        },
+        output_path.display(),
+    ));
+    let start_time = Instant::now();
+    let mut output = output_path.parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::File::create(output_path))
+        .map_err(|cause| error(context)(Box::new(crate::Error::new(
+            None,
+            crate::ErrorKind::OutputFileOpen {
+                filename: output_path.display().to_string(),
+                cause,
+            },
+        ))))?;
+    let write_result = match (args.emit(), backend) {
+        (EmitKind::CupricIr, _) => write!(output, "{}", context.package().output().ir(context)),
+        (_, Backend::Llvm) => write!(output, "{}", context.package().output().llvm(context)),
+        (_, Backend::C) => write!(output, "{}", context.package().output().c(context)),
+        (_, Backend::Wasm) => write!(output, "{}", context.package().output().wat(context)),
+    };
+    write_result
+        .map_err(|cause| error(context)(Box::new(crate::Error::new(
+            None,
+            crate::ErrorKind::OutputFileWrite {
+                filename: output_path.display().to_string(),
+                cause,
+            },
+        ))))?;
+    stats.write_time = start_time.elapsed();
+
+    Ok(())
+}
+
+pub fn run(args: &RunArgs) -> Result<i32, Box<(crate::Error, SourceMap)>> {
+    run_with_reporter(args, &mut ConsoleReporter::new(args.verbosity()), &mut std::io::stdout())
+}
+
+/// Compile the package given by `args` and run it with the interpreter, writing anything it
+/// prints to `output`. Returns the exit status of the program.
+pub fn run_with_reporter(args: &RunArgs, reporter: &mut dyn Reporter, output: &mut dyn Write) -> Result<i32, Box<(crate::Error, SourceMap)>> {
+    let target = resolve_target(args.target_triple())?;
+    let mut context = GlobalContext::new(args.package_path(), target)
+        .map_err(|error| Box::new((*error, SourceMap::new())))?;
+    context.set_optimization_level(args.optimization_level());
 
     loop {
-        println!("--- Compiling package '{}' ---", context.package().info().name());
-
-        let mut parsed_modules = Vec::new();
-        while let Some((source_id, namespace)) = context
-            .prepare_next_source()
-            .map_err(error(&context))?
-        {
-            // Scanning, parsing, and outline pass simultaneously
-            let source_path = &context.package().source_paths()[source_id];
-            println!("Parsing '{}'...", source_path.display());
-
-            let mut scanner = Scanner::from_path(source_id, source_path)
-                .map_err(error(&context))?;
-
-            let parsed_module = parse_module(&mut scanner, &mut context, namespace)
-                .map_err(error(&context))?;
-
-            parsed_modules.push(parsed_module);
-        }
-
-        // Fill pass (must be done after outline pass is complete for all files)
-        println!("Processing definitions...");
-        context.process_package(&mut parsed_modules).map_err(error(&context))?;
-
-        // Generating IR
-        let source_paths = context.package().source_paths().to_vec();
-        println!("Compiling output...");
-        Generator::new(&mut context).generate_package(&parsed_modules)
-            .map_err(|error| Box::new((*error, source_paths)))?;
-
-        // Writing LLVM IR to file
-        let output_path = context.package().info().get_output_path();
-        println!("Writing LLVM IR to '{}'...", output_path.display());
-        let mut output = std::fs::File::create(&output_path)
-            .map_err(|cause| error(&context)(Box::new(crate::Error::new(
-                None,
-                crate::ErrorKind::OutputFileOpen {
-                    filename: output_path.display().to_string(),
-                    cause,
-                },
-            ))))?;
-        write!(output, "{}", context.package().output().llvm(&context))
-            .map_err(|cause| error(&context)(Box::new(crate::Error::new(
-                None,
-                crate::ErrorKind::OutputFileWrite {
-                    filename: output_path.display().to_string(),
-                    cause,
-                },
-            ))))?;
-
-        println!("Finished.");
+        let info = context.package().info();
+        reporter.status(&format!("--- Compiling package '{}' ---", info.name()));
+        reporter.detail(&format!("Package directory: '{}'", info.path().display()));
+        let mut stats = PackageStatistics::new(info.name());
+        compile_package(&mut context, reporter, &mut stats)?;
 
         if !context.start_next_package() {
             break;
         }
     }
 
+    let info = context.package().info();
+    let source_map = context.package().source_map().clone();
+    if info.kind() != PackageKind::Executable {
+        return Err(Box::new((
+            crate::Error::new(None, crate::ErrorKind::NonExecutablePackage {
+                package_name: info.name().to_owned(),
+            }),
+            source_map,
+        )));
+    }
+
+    reporter.status(&format!("Running '{}'...", info.name()));
+    let arguments: Vec<String> = std::iter::once(info.name().to_owned())
+        .chain(args.arguments().iter().cloned())
+        .collect();
+    // The program needs the output of all of its dependencies, which the context keeps around
+    let units: Vec<&CompilationUnit> = context.finished_units().iter()
+        .chain([context.package().output()])
+        .collect();
+    crate::interp::run_program(&context, &units, &arguments, output)
+        .map_err(|error| Box::new((*error, source_map)))
+}
+
+/// Write the readable form of each symbol given by `args`, one per line. If no symbols were given,
+/// copy `input` to `output` with every mangled symbol replaced instead. Symbols which are not
+/// mangled are written unchanged.
+pub fn demangle(args: &DemangleArgs, input: &mut dyn BufRead, output: &mut dyn Write) -> std::io::Result<()> {
+    if args.symbols().is_empty() {
+        let mut line = String::new();
+        while input.read_line(&mut line)? != 0 {
+            write!(output, "{}", crate::sema::demangle_text(&line))?;
+            line.clear();
+        }
+    }
+    else {
+        for symbol in args.symbols() {
+            match crate::sema::demangle(symbol) {
+                Some(demangled) => writeln!(output, "{demangled}")?,
+                None => writeln!(output, "{symbol}")?,
+            }
+        }
+    }
+
     Ok(())
 }
@@ -0,0 +1,89 @@
+use std::io::IsTerminal;
+use clap::ValueEnum;
+
+/// How much progress information the compiler should report.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Verbosity {
+    /// Only report messages which are always relevant, like errors.
+    Quiet,
+    /// Report each phase of compilation as it begins.
+    Normal,
+    /// Additionally report details about inputs and outputs of each phase.
+    Verbose,
+}
+
+/// When to use ANSI escape sequences to color terminal output.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, ValueEnum)]
+pub enum ColorChoice {
+    /// Use colors only if standard output is a terminal and `NO_COLOR` is not set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn is_enabled(self) -> bool {
+        match self {
+            Self::Auto => std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal(),
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+
+    /// Wrap `text` in the ANSI escape sequence `start` if colors are enabled, resetting with
+    /// `end` afterward.
+    pub fn paint(self, text: &str, start: &str, end: &str) -> String {
+        if self.is_enabled() {
+            format!("\x1b[{start}m{text}\x1b[{end}m")
+        }
+        else {
+            text.to_owned()
+        }
+    }
+}
+
+/// Receives progress messages produced by [`invoke_with_reporter`](super::invoke_with_reporter).
+pub trait Reporter {
+    /// Handle a progress message. `level` is the lowest verbosity at which the message should
+    /// be shown.
+    fn report(&mut self, level: Verbosity, message: &str);
+
+    fn status(&mut self, message: &str) {
+        self.report(Verbosity::Normal, message);
+    }
+
+    fn detail(&mut self, message: &str) {
+        self.report(Verbosity::Verbose, message);
+    }
+}
+
+/// Prints progress messages to standard output, filtered by verbosity.
+#[derive(Clone, Debug)]
+pub struct ConsoleReporter {
+    verbosity: Verbosity,
+}
+
+impl ConsoleReporter {
+    pub fn new(verbosity: Verbosity) -> Self {
+        Self {
+            verbosity,
+        }
+    }
+}
+
+impl Reporter for ConsoleReporter {
+    fn report(&mut self, level: Verbosity, message: &str) {
+        if level <= self.verbosity {
+            println!("{message}");
+        }
+    }
+}
+
+/// Discards all progress messages.
+#[derive(Clone, Debug, Default)]
+pub struct SilentReporter;
+
+impl Reporter for SilentReporter {
+    fn report(&mut self, _level: Verbosity, _message: &str) {}
+}
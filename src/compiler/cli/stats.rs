@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+/// Wall time spent in each phase of compiling a single package, along with counts of what each
+/// phase produced. Collected when `--time-passes` is given.
+#[derive(Clone, Debug, Default)]
+pub struct PackageStatistics {
+    pub package_name: Box<str>,
+    /// Whether the statistics will be reported. Scanning is only timed separately from parsing
+    /// when they are, since that reads the clock for every token.
+    pub is_enabled: bool,
+    pub scan_time: Duration,
+    pub parse_time: Duration,
+    pub fill_time: Duration,
+    pub layout_time: Duration,
+    pub generate_time: Duration,
+    pub write_time: Duration,
+    pub token_count: usize,
+    pub node_count: usize,
+    pub type_count: usize,
+    pub symbol_count: usize,
+    pub function_count: usize,
+    pub instruction_count: usize,
+}
+
+impl PackageStatistics {
+    pub fn new(package_name: impl Into<Box<str>>) -> Self {
+        Self {
+            package_name: package_name.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.scan_time + self.parse_time + self.fill_time + self.layout_time + self.generate_time + self.write_time
+    }
+}
+
+impl std::fmt::Display for PackageStatistics {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fn milliseconds(duration: Duration) -> f64 {
+            duration.as_secs_f64() * 1000.0
+        }
+
+        write!(f, "--- Pass statistics for package '{}' ---", self.package_name)?;
+        let times = [
+            ("scanning", self.scan_time),
+            ("parsing/outline", self.parse_time),
+            ("fill", self.fill_time),
+            ("type layout", self.layout_time),
+            ("generation", self.generate_time),
+            ("LLVM writing", self.write_time),
+            ("total", self.total_time()),
+        ];
+        for (name, duration) in times {
+            write!(f, "\n  {name:<18}{:>10.3} ms", milliseconds(duration))?;
+        }
+        let counts = [
+            ("tokens", self.token_count),
+            ("AST nodes", self.node_count),
+            ("types", self.type_count),
+            ("symbols", self.symbol_count),
+            ("functions", self.function_count),
+            ("IR instructions", self.instruction_count),
+        ];
+        for (name, count) in counts {
+            write!(f, "\n  {name:<18}{count:>10}")?;
+        }
+        Ok(())
+    }
+}
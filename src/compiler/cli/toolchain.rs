@@ -0,0 +1,255 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use clap::ValueEnum;
+use crate::target::{LLVMPointerStyle, TargetInfo};
+
+/// The kind of output the compiler should produce.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Default, ValueEnum)]
+pub enum EmitKind {
+    /// Write the compiler's own IR (`.cir`) for each package instead of any backend output.
+    CupricIr,
+    /// Write the backend's source output (`.ll`, `.c`, or `.wat`) for each package.
+    #[default]
+    #[value(alias = "llvm-ir")]
+    Source,
+    /// Additionally compile each package to an object file (`.o`).
+    Obj,
+    /// Additionally link the object files of an executable package and all of its dependencies.
+    Exe,
+}
+
+/// The language the compiler generates code in.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, ValueEnum)]
+pub enum Backend {
+    /// Generate LLVM IR (`.ll`).
+    #[default]
+    Llvm,
+    /// Generate C99 source code (`.c`), compiled and linked with `cc`.
+    C,
+    /// Generate WebAssembly text format (`.wat`), the default for `wasm32` targets.
+    Wasm,
+}
+
+impl Backend {
+    /// The file extension for source output from this backend.
+    pub fn output_extension(self) -> &'static str {
+        match self {
+            Self::Llvm => "ll",
+            Self::C => "c",
+            Self::Wasm => "wat",
+        }
+    }
+}
+
+/// Which external programs are used to turn LLVM IR into objects and executables.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, ValueEnum)]
+pub enum ToolchainKind {
+    /// Use `clang` if it can be found, otherwise fall back to `llc` and `cc`.
+    #[default]
+    Auto,
+    /// Use `clang` for both compiling and linking.
+    Clang,
+    /// Use `llc` for compiling and `cc` for linking.
+    Llc,
+}
+
+/// A resolved set of external programs used for compiling generated code and linking.
+#[derive(Clone, Debug)]
+pub struct Toolchain {
+    backend: Backend,
+    kind: ToolchainKind,
+    clang: PathBuf,
+    llc: PathBuf,
+    cc: PathBuf,
+    /// The triple of the target to compile and link for, if it is a known target.
+    target_triple: Option<&'static str>,
+    /// Whether the LLVM version in use requires opaque pointers to be enabled explicitly.
+    needs_opaque_pointers_flag: bool,
+    /// Whether to ask the C compiler for debug info when compiling C source.
+    debug_info: bool,
+}
+
+impl Toolchain {
+    /// Determine which toolchain to use for compiling for `target`, checking that the required
+    /// programs can be run and can compile for the target. Only `clang` and `llc` can be told which
+    /// target to build for, so `cc` can only be used for the host.
+    pub fn resolve(
+        backend: Backend,
+        kind: ToolchainKind,
+        clang: PathBuf,
+        llc: PathBuf,
+        cc: PathBuf,
+        target: &TargetInfo,
+        debug_info: bool,
+    ) -> crate::Result<Self> {
+        let target_triple = target.triple();
+        if backend == Backend::C {
+            // C output needs only a C compiler, regardless of which LLVM tools are installed
+            if !is_host_triple(target_triple) {
+                return Err(host_only_error(&cc, target_triple));
+            }
+            llvm_major_version(&cc)?;
+            return Ok(Self {
+                backend,
+                kind,
+                clang,
+                llc,
+                cc,
+                target_triple,
+                needs_opaque_pointers_flag: false,
+                debug_info,
+            });
+        }
+
+        let (kind, llvm_version) = match kind {
+            ToolchainKind::Auto => match llvm_major_version(&clang) {
+                Ok(version) => (ToolchainKind::Clang, version),
+                Err(..) => (ToolchainKind::Llc, llvm_major_version(&llc)?),
+            },
+            ToolchainKind::Clang => (ToolchainKind::Clang, llvm_major_version(&clang)?),
+            ToolchainKind::Llc => (ToolchainKind::Llc, llvm_major_version(&llc)?),
+        };
+        // LLVM 15 enabled opaque pointers by default; earlier versions need to be asked
+        let needs_opaque_pointers_flag = target.llvm_pointer_style() == LLVMPointerStyle::Opaque
+            && llvm_version.is_some_and(|version| version < 15);
+
+        Ok(Self {
+            backend,
+            kind,
+            clang,
+            llc,
+            cc,
+            target_triple,
+            needs_opaque_pointers_flag,
+            debug_info,
+        })
+    }
+
+    /// Compile the backend output file at `ir_path` to an object file at `object_path`.
+    pub fn compile_object(&self, ir_path: &Path, object_path: &Path) -> crate::Result<()> {
+        let mut command;
+        if self.backend == Backend::C {
+            command = Command::new(&self.cc);
+            command.args(["-std=c99", "-fPIC"]);
+            if self.debug_info {
+                command.arg("-g");
+            }
+            command.arg("-c").arg(ir_path).arg("-o").arg(object_path);
+        }
+        else if self.kind == ToolchainKind::Clang {
+            command = Command::new(&self.clang);
+            if let Some(target_triple) = self.target_triple {
+                command.args(["-target", target_triple]);
+            }
+            if self.needs_opaque_pointers_flag {
+                command.args(["-Xclang", "-opaque-pointers"]);
+            }
+            command.arg("-c").arg(ir_path).arg("-o").arg(object_path);
+        }
+        else {
+            command = Command::new(&self.llc);
+            if let Some(target_triple) = self.target_triple {
+                command.arg(format!("-mtriple={target_triple}"));
+            }
+            if self.needs_opaque_pointers_flag {
+                command.arg("-opaque-pointers");
+            }
+            command.args(["-filetype=obj", "-relocation-model=pic"]).arg(ir_path).arg("-o").arg(object_path);
+        }
+
+        run_command(command)
+    }
+
+    /// Check that executables can be linked for the target, which `cc` can only do for the host.
+    pub fn check_can_link(&self) -> crate::Result<()> {
+        let uses_cc = self.backend == Backend::C || self.kind == ToolchainKind::Llc;
+        if uses_cc && !is_host_triple(self.target_triple) {
+            return Err(host_only_error(&self.cc, self.target_triple));
+        }
+
+        Ok(())
+    }
+
+    /// Link `object_paths` into an executable at `executable_path`.
+    pub fn link_executable(&self, object_paths: &[PathBuf], executable_path: &Path) -> crate::Result<()> {
+        let mut command = if self.backend == Backend::Llvm && self.kind == ToolchainKind::Clang {
+            let mut command = Command::new(&self.clang);
+            if let Some(target_triple) = self.target_triple {
+                command.args(["-target", target_triple]);
+            }
+            command
+        }
+        else {
+            Command::new(&self.cc)
+        };
+        command.args(object_paths).arg("-o").arg(executable_path);
+        if self.backend == Backend::C {
+            // Floating-point remainders are computed with `fmod` from the math library
+            command.arg("-lm");
+        }
+
+        run_command(command)
+    }
+}
+
+fn is_host_triple(triple: Option<&str>) -> bool {
+    triple == TargetInfo::host().triple()
+}
+
+fn host_only_error(program: &Path, triple: Option<&str>) -> Box<crate::Error> {
+    Box::new(crate::Error::new(
+        None,
+        crate::ErrorKind::UnsupportedToolchainTarget {
+            program: program.display().to_string(),
+            triple: triple.unwrap_or("unknown").to_owned(),
+        },
+    ))
+}
+
+/// Run `program --version` and extract the LLVM major version number, if one is reported.
+fn llvm_major_version(program: &Path) -> crate::Result<Option<u32>> {
+    let mut command = Command::new(program);
+    command.arg("--version");
+    let output = command.output()
+        .map_err(|cause| Box::new(crate::Error::new(
+            None,
+            crate::ErrorKind::MissingToolchain {
+                program: program.display().to_string(),
+                cause,
+            },
+        )))?;
+
+    let version_text = String::from_utf8_lossy(&output.stdout);
+    let version = version_text
+        .split_once("version ")
+        .and_then(|(_, rest)| rest.split(|ch: char| !ch.is_ascii_digit()).next())
+        .and_then(|major| major.parse().ok());
+
+    Ok(version)
+}
+
+fn run_command(mut command: Command) -> crate::Result<()> {
+    let command_line = format!("{command:?}");
+    let output = command.output()
+        .map_err(|cause| Box::new(crate::Error::new(
+            None,
+            crate::ErrorKind::MissingToolchain {
+                program: command.get_program().to_string_lossy().into_owned(),
+                cause,
+            },
+        )))?;
+
+    if output.status.success() {
+        Ok(())
+    }
+    else {
+        Err(Box::new(crate::Error::new(
+            None,
+            crate::ErrorKind::ToolchainFailed {
+                command: command_line,
+                status: output.status.to_string(),
+                stderr: String::from_utf8_lossy(&output.stderr).trim_end().to_owned(),
+            },
+        )))
+    }
+}
@@ -1,10 +1,8 @@
 use super::*;
 
-use std::io::{BufRead, BufReader};
-use std::fs::File;
-use std::path::{Path, PathBuf};
+use crate::package::SourceMap;
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct Span {
     pub source_id: usize,
     pub start_index: usize,
@@ -27,64 +25,13 @@ impl Span {
             .expect("end span comes before start span");
         self
     }
-
-    pub fn context_to_string(&self, path: impl AsRef<Path>) -> std::io::Result<(usize, usize, String)> {
-        let mut reader = BufReader::new(File::open(path)?);
-        let mut line_number = 0;
-        let mut column_number = 0;
-        let mut line_start_index = 0;
-        let mut context = String::new();
-        let mut line = String::new();
-
-        while reader.read_line(&mut line)? > 0 {
-            let line_end_index = line_start_index + line.len();
-
-            if line_end_index > self.start_index {
-                if column_number == 0 {
-                    column_number = self.start_index - line_start_index + 1;
-                }
-
-                if line_start_index < self.start_index + self.length {
-                    let line_trim = line.trim_end();
-
-                    // Write the line from the source file
-                    context.push('\t');
-                    context.push_str(line_trim);
-                    context.push('\n');
-
-                    // Write the span markers on the line below
-                    context.push('\t');
-                    context.extend(std::iter::repeat_n(
-                        ' ',
-                        self.start_index.saturating_sub(line_start_index),
-                    ));
-                    if self.length == 0 {
-                        context.push('^');
-                    }
-                    else {
-                        context.extend(std::iter::repeat_n(
-                            '~',
-                            line_end_index.min(self.start_index + self.length)
-                                .saturating_sub(line_start_index.max(self.start_index))
-                        ));
-                    }
-                    context.push('\n');
-                }
-                else {
-                    break;
-                }
-            }
-
-            line_number += 1;
-            line_start_index = line_end_index;
-            line.clear();
-        }
-
-        Ok((line_number, column_number, context))
-    }
 }
 
 pub enum ErrorKind {
+    UnknownTarget {
+        triple: String,
+        known_triples: Vec<&'static str>,
+    },
     PackageFile {
         cause: String,
     },
@@ -102,6 +49,40 @@ pub enum ErrorKind {
         filename: String,
         cause: std::io::Error,
     },
+    MissingToolchain {
+        program: String,
+        cause: std::io::Error,
+    },
+    ToolchainFailed {
+        command: String,
+        status: String,
+        stderr: String,
+    },
+    UnsupportedBackendTarget {
+        backend: String,
+        triple: String,
+    },
+    UnsupportedBackendOutput {
+        backend: String,
+        emit: String,
+    },
+    UnsupportedToolchainTarget {
+        program: String,
+        triple: String,
+    },
+    InvalidIR {
+        function_path: String,
+        message: String,
+    },
+    NonExecutablePackage {
+        package_name: String,
+    },
+    MissingMainFunction,
+    UnsupportedMainSignature,
+    RuntimeError {
+        function_name: String,
+        message: String,
+    },
     InvalidToken,
     InvalidLiteralSuffix,
     NonAsciiCharacter {
@@ -158,6 +139,10 @@ pub enum ErrorKind {
         namespace: String,
         name: String,
     },
+    PrivateSymbol {
+        name: String,
+        module: String,
+    },
     GlobalSymbolConflict {
         namespace: String,
         name: String,
@@ -234,6 +219,10 @@ pub enum ErrorKind {
         member_name: String,
         type_name: String,
     },
+    PrivateMember {
+        member_name: String,
+        type_name: String,
+    },
     ExpectedPointer {
         type_name: String,
     },
@@ -253,7 +242,27 @@ pub enum ErrorKind {
         expected_count: usize,
         got_count: usize,
     },
+    UnknownCallingConvention {
+        name: String,
+        known_names: Vec<&'static str>,
+    },
     UnsupportedConstantExpression,
+    ConstantEvaluationFailed {
+        message: String,
+    },
+    UninitializedGlobal {
+        name: String,
+    },
+    UngeneratedFunction {
+        name: String,
+    },
+    UnresolvedTypeLayout {
+        type_name: String,
+    },
+    InterfaceFunctionCall {
+        name: String,
+        package_name: String,
+    },
     NoSelfType,
     ExpectedSelfParameter,
     ImportAliasRequired {
@@ -271,14 +280,126 @@ pub enum ErrorKind {
     },
 }
 
+impl ErrorKind {
+    /// The name of this kind of error, matching the name of the variant (e.g.
+    /// `"IncompatibleTypes"`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::UnknownTarget { .. } => "UnknownTarget",
+            Self::PackageFile { .. } => "PackageFile",
+            Self::SourceFileOpen { .. } => "SourceFileOpen",
+            Self::SourceFileRead { .. } => "SourceFileRead",
+            Self::OutputFileOpen { .. } => "OutputFileOpen",
+            Self::OutputFileWrite { .. } => "OutputFileWrite",
+            Self::MissingToolchain { .. } => "MissingToolchain",
+            Self::ToolchainFailed { .. } => "ToolchainFailed",
+            Self::UnsupportedBackendTarget { .. } => "UnsupportedBackendTarget",
+            Self::UnsupportedBackendOutput { .. } => "UnsupportedBackendOutput",
+            Self::UnsupportedToolchainTarget { .. } => "UnsupportedToolchainTarget",
+            Self::InvalidIR { .. } => "InvalidIR",
+            Self::NonExecutablePackage { .. } => "NonExecutablePackage",
+            Self::MissingMainFunction => "MissingMainFunction",
+            Self::UnsupportedMainSignature => "UnsupportedMainSignature",
+            Self::RuntimeError { .. } => "RuntimeError",
+            Self::InvalidToken => "InvalidToken",
+            Self::InvalidLiteralSuffix => "InvalidLiteralSuffix",
+            Self::NonAsciiCharacter { .. } => "NonAsciiCharacter",
+            Self::InvalidEscape { .. } => "InvalidEscape",
+            Self::InvalidHexEscapeDigit { .. } => "InvalidHexEscapeDigit",
+            Self::UnclosedString => "UnclosedString",
+            Self::UnclosedCharacter => "UnclosedCharacter",
+            Self::UnclosedComment => "UnclosedComment",
+            Self::ExpectedToken => "ExpectedToken",
+            Self::ExpectedTokenFromList { .. } => "ExpectedTokenFromList",
+            Self::ExpectedIdentifier => "ExpectedIdentifier",
+            Self::ExpectedString => "ExpectedString",
+            Self::ExpectedTupleMember => "ExpectedTupleMember",
+            Self::TupleMemberOutOfRange { .. } => "TupleMemberOutOfRange",
+            Self::ExpectedOperand { .. } => "ExpectedOperand",
+            Self::ExpectedOperation { .. } => "ExpectedOperation",
+            Self::ExpectedType { .. } => "ExpectedType",
+            Self::UnexpectedQualifier { .. } => "UnexpectedQualifier",
+            Self::ExpectedClosingBracket { .. } => "ExpectedClosingBracket",
+            Self::ExpectedStatement => "ExpectedStatement",
+            Self::UnexpectedElse => "UnexpectedElse",
+            Self::UnexpectedNoBreak => "UnexpectedNoBreak",
+            Self::InvalidGlobPath => "InvalidGlobPath",
+            Self::CannotMutateValue { .. } => "CannotMutateValue",
+            Self::ExpectedLValue => "ExpectedLValue",
+            Self::UndefinedSymbol { .. } => "UndefinedSymbol",
+            Self::UndefinedGlobalSymbol { .. } => "UndefinedGlobalSymbol",
+            Self::PrivateSymbol { .. } => "PrivateSymbol",
+            Self::GlobalSymbolConflict { .. } => "GlobalSymbolConflict",
+            Self::NonTypeSymbol { .. } => "NonTypeSymbol",
+            Self::InvalidSuper { .. } => "InvalidSuper",
+            Self::ExpectedNamespace { .. } => "ExpectedNamespace",
+            Self::RecursiveTypeDefinition { .. } => "RecursiveTypeDefinition",
+            Self::UnknownTypeSize { .. } => "UnknownTypeSize",
+            Self::UnknownTypeAlignment { .. } => "UnknownTypeAlignment",
+            Self::NonConstantArrayLength => "NonConstantArrayLength",
+            Self::IncompatibleTypes { .. } => "IncompatibleTypes",
+            Self::InconvertibleTypes { .. } => "InconvertibleTypes",
+            Self::UnexpectedExpression => "UnexpectedExpression",
+            Self::InvalidBreak => "InvalidBreak",
+            Self::InvalidContinue => "InvalidContinue",
+            Self::ExpectedReturnValue { .. } => "ExpectedReturnValue",
+            Self::UnexpectedReturnValue { .. } => "UnexpectedReturnValue",
+            Self::NonValueSymbol { .. } => "NonValueSymbol",
+            Self::NonConstantSymbol { .. } => "NonConstantSymbol",
+            Self::IncompatibleValueType { .. } => "IncompatibleValueType",
+            Self::UnknownArrayType => "UnknownArrayType",
+            Self::UnknownTupleType => "UnknownTupleType",
+            Self::CannotInferType => "CannotInferType",
+            Self::NoSuchMethod { .. } => "NoSuchMethod",
+            Self::InvalidStructIdentifier => "InvalidStructIdentifier",
+            Self::NonStructSymbol { .. } => "NonStructSymbol",
+            Self::NonStructType { .. } => "NonStructType",
+            Self::MissingStructMembers { .. } => "MissingStructMembers",
+            Self::ExtraStructMembers { .. } => "ExtraStructMembers",
+            Self::UndefinedMember { .. } => "UndefinedMember",
+            Self::PrivateMember { .. } => "PrivateMember",
+            Self::ExpectedPointer { .. } => "ExpectedPointer",
+            Self::ExpectedInteger { .. } => "ExpectedInteger",
+            Self::ExpectedArray { .. } => "ExpectedArray",
+            Self::InvalidMemberAccess { .. } => "InvalidMemberAccess",
+            Self::ExpectedFunction { .. } => "ExpectedFunction",
+            Self::WrongFunctionArgumentCount { .. } => "WrongFunctionArgumentCount",
+            Self::UnknownCallingConvention { .. } => "UnknownCallingConvention",
+            Self::UnsupportedConstantExpression => "UnsupportedConstantExpression",
+            Self::ConstantEvaluationFailed { .. } => "ConstantEvaluationFailed",
+            Self::UninitializedGlobal { .. } => "UninitializedGlobal",
+            Self::UngeneratedFunction { .. } => "UngeneratedFunction",
+            Self::UnresolvedTypeLayout { .. } => "UnresolvedTypeLayout",
+            Self::InterfaceFunctionCall { .. } => "InterfaceFunctionCall",
+            Self::NoSelfType => "NoSelfType",
+            Self::ExpectedSelfParameter => "ExpectedSelfParameter",
+            Self::ImportAliasRequired { .. } => "ImportAliasRequired",
+            Self::AmbiguousSymbol { .. } => "AmbiguousSymbol",
+            Self::MustSpecifyTypeForGlobal { .. } => "MustSpecifyTypeForGlobal",
+            Self::MustSpecifyTypeForUninitialized { .. } => "MustSpecifyTypeForUninitialized",
+        }
+    }
+}
+
 impl std::fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Self::UnknownTarget { triple, known_triples } => write!(f, "unknown target '{triple}' (known targets: {})", known_triples.join(", ")),
             Self::PackageFile { cause } => write!(f, "error reading package: {cause}"),
             Self::SourceFileOpen { cause, .. } => write!(f, "unable to open file: {cause}"),
             Self::SourceFileRead { cause, .. } => write!(f, "error while reading file: {cause}"),
             Self::OutputFileOpen { cause, .. } => write!(f, "unable to create file: {cause}"),
             Self::OutputFileWrite { cause, .. } => write!(f, "error while writing file: {cause}"),
+            Self::MissingToolchain { program, cause } => write!(f, "unable to run '{program}' (is it installed?): {cause}"),
+            Self::ToolchainFailed { command, status, stderr } => write!(f, "command {command} failed with {status}:\n{stderr}"),
+            Self::UnsupportedBackendTarget { backend, triple } => write!(f, "the '{backend}' backend cannot compile for target '{triple}'"),
+            Self::UnsupportedBackendOutput { backend, emit } => write!(f, "the '{backend}' backend cannot produce '{emit}' output"),
+            Self::UnsupportedToolchainTarget { program, triple } => write!(f, "'{program}' can only build for the host, not target '{triple}' (try '--toolchain clang')"),
+            Self::InvalidIR { function_path, message } => write!(f, "internal compiler error: invalid IR generated for '{function_path}': {message}"),
+            Self::NonExecutablePackage { package_name } => write!(f, "package '{package_name}' is not an executable and cannot be run"),
+            Self::MissingMainFunction => write!(f, "no 'main' function is defined"),
+            Self::UnsupportedMainSignature => write!(f, "'main' must take either no parameters or 'argc' and 'argv'"),
+            Self::RuntimeError { function_name, message } => write!(f, "runtime error in '{function_name}': {message}"),
             Self::InvalidToken => write!(f, "unrecognized token"),
             Self::InvalidLiteralSuffix => write!(f, "unsupported literal suffix"),
             Self::NonAsciiCharacter { what } => write!(f, "non-ASCII character '{what}' in literal"),
@@ -312,6 +433,7 @@ impl std::fmt::Display for ErrorKind {
             Self::ExpectedLValue => write!(f, "expected an lvalue"),
             Self::UndefinedSymbol { name } => write!(f, "symbol '{name}' is not defined"),
             Self::UndefinedGlobalSymbol { namespace, name } => write!(f, "symbol '{name}' is not defined in namespace '{namespace}'"),
+            Self::PrivateSymbol { name, module } => write!(f, "'{name}' is private and cannot be accessed from '{module}'"),
             Self::GlobalSymbolConflict { namespace, name } => write!(f, "symbol '{name}' is defined multiple times in namespace '{namespace}'"),
             Self::NonTypeSymbol { name } => write!(f, "'{name}' does not represent a type"),
             Self::InvalidSuper { namespace } => write!(f, "namespace '{namespace}' has no 'super'"),
@@ -352,6 +474,7 @@ impl std::fmt::Display for ErrorKind {
                 Ok(())
             }
             Self::UndefinedMember { member_name, type_name } => write!(f, "type '{type_name}' has no member '{member_name}'"),
+            Self::PrivateMember { member_name, type_name } => write!(f, "member '{member_name}' of type '{type_name}' is private"),
             Self::ExpectedPointer { type_name } => write!(f, "expected a pointer, got value of type '{type_name}'"),
             Self::ExpectedInteger { type_name } => write!(f, "expected an integer, got value of type '{type_name}'"),
             Self::ExpectedArray { type_name } => write!(f, "expected an array, got value of type '{type_name}'"),
@@ -360,7 +483,13 @@ impl std::fmt::Display for ErrorKind {
             Self::WrongFunctionArgumentCount { expected_count, got_count } => {
                 write!(f, "too {} arguments for function (expected {expected_count}, got {got_count})", if got_count < expected_count { "few" } else { "many" })
             }
+            Self::UnknownCallingConvention { name, known_names } => write!(f, "unknown calling convention '{name}' (known calling conventions: {})", known_names.join(", ")),
             Self::UnsupportedConstantExpression => write!(f, "unsupported feature in constant expression"),
+            Self::ConstantEvaluationFailed { message } => write!(f, "failed to evaluate constant expression: {message}"),
+            Self::UninitializedGlobal { name } => write!(f, "global '{name}' is used by a constant expression before its own value is known"),
+            Self::UngeneratedFunction { name } => write!(f, "function '{name}' is called by a constant expression before it has been generated"),
+            Self::UnresolvedTypeLayout { type_name } => write!(f, "type '{type_name}' is used by a constant expression before the lengths of its arrays are known"),
+            Self::InterfaceFunctionCall { name, package_name } => write!(f, "function '{name}' is called by a constant expression, but package '{package_name}' was loaded from its interface header"),
             Self::NoSelfType => write!(f, "keyword 'Self' can only be used inside 'implement' blocks and 'struct' definitions"),
             Self::ExpectedSelfParameter => write!(f, "expected a first parameter of type 'Self', '*Self', or '*mut Self'"),
             Self::ImportAliasRequired { path } => write!(f, "import '{path}' must be renamed using the syntax 'import _ as <name>'"),
@@ -383,9 +512,19 @@ impl std::fmt::Debug for ErrorKind {
     }
 }
 
+/// A secondary location attached to an [`Error`], rendered below the primary location along
+/// with a short message explaining its relevance (e.g. "previously defined here").
+#[derive(Clone, Debug)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
 pub struct Error {
     span: Option<Span>,
     kind: ErrorKind,
+    labels: Vec<Label>,
+    notes: Vec<String>,
 }
 
 pub type Result<T> = std::result::Result<T, Box<Error>>;
@@ -395,9 +534,32 @@ impl Error {
         Self {
             span,
             kind,
+            labels: Vec::new(),
+            notes: Vec::new(),
         }
     }
 
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.add_label(span, message);
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.add_note(note);
+        self
+    }
+
+    pub fn add_label(&mut self, span: Span, message: impl Into<String>) {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+        });
+    }
+
+    pub fn add_note(&mut self, note: impl Into<String>) {
+        self.notes.push(note.into());
+    }
+
     pub fn span(&self) -> Option<Span> {
         self.span
     }
@@ -410,20 +572,43 @@ impl Error {
         &mut self.kind
     }
 
-    pub fn to_string_with_context(&self, paths: &[PathBuf]) -> String {
-        if let Some(span) = self.span() {
-            let path = &paths[span.source_id];
-            let path_display = path.display();
-            if let Ok((line_number, column_number, context)) = span.context_to_string(path) {
-                format!("Error in '{path_display}':\nline {line_number}:{column_number}: {self}\n\n{context}")
-            }
-            else {
-                format!("Error in '{path_display}':\n{self}")
-            }
+    pub fn labels(&self) -> &[Label] {
+        &self.labels
+    }
+
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+
+    pub fn to_string_with_context(&self, source_map: &SourceMap) -> String {
+        let mut output = if let Some(span) = self.span() {
+            let file = source_map.file(span.source_id);
+            let path_display = file.path().display();
+            let (line_number, column_number, context) = file.context_to_string(span);
+            format!("Error in '{path_display}':\nline {line_number}:{column_number}: {self}\n\n{context}")
         }
         else {
-            format!("Error:\n{self}")
+            format!("Error:\n{self}\n")
+        };
+
+        // Secondary locations, which may be in different files than the primary one
+        for label in self.labels() {
+            let file = source_map.file(label.span.source_id);
+            let path_display = file.path().display();
+            let message = &label.message;
+            let (line_number, column_number, context) = file.context_to_string(label.span);
+            output.push_str(&format!("\n --> '{path_display}' line {line_number}:{column_number}: {message}\n\n{context}"));
         }
+
+        if !self.notes().is_empty() {
+            output.push('\n');
+            for note in self.notes() {
+                output.push_str(&format!(" = note: {note}\n"));
+            }
+        }
+
+        output.truncate(output.trim_end().len());
+        output
     }
 }
 
@@ -1,21 +1,47 @@
 use crate::ast::*;
 use crate::ast::parse::ParsedModule;
-use crate::ir::{FunctionDefinition, GlobalVariable, GlobalVariableKind};
+use crate::ir::{CompilationUnit, DebugVariable, FunctionDefinition, GlobalVariable, GlobalVariableKind, Linkage};
 use crate::ir::instr::{Instruction, PhiInstruction, TerminatorInstruction};
 use crate::ir::value::*;
 use crate::sema::*;
 use crate::token::Literal;
 
+/// A global `let` whose value calls functions, so it can't be generated until every function in
+/// the package has been.
+struct DeferredGlobalLet {
+    module: NamespaceHandle,
+    self_type: Option<TypeHandle>,
+    value: LocalNode,
+    register: GlobalRegister,
+    linkage: Linkage,
+}
+
+/// A function whose body uses a type with an array length calling functions which hadn't been
+/// generated yet, so it must be generated again once they have been.
+struct DeferredFunction {
+    module: NamespaceHandle,
+    self_type: Option<TypeHandle>,
+    node: GlobalNode,
+}
+
+/// Whether `error` was caused by a constant expression using a global or function which hasn't
+/// been generated yet, in which case generation can be attempted again later.
+fn is_ungenerated_symbol_error(error: &crate::Error) -> bool {
+    matches!(error.kind(), crate::ErrorKind::UninitializedGlobal { .. } | crate::ErrorKind::UngeneratedFunction { .. })
+}
+
 pub struct Generator<'ctx> {
     context: &'ctx mut GlobalContext,
-    next_anonymous_constant_id: usize,
+    deferred_global_lets: Vec<DeferredGlobalLet>,
+    deferred_functions: Vec<DeferredFunction>,
 }
 
 impl<'ctx> Generator<'ctx> {
     pub fn new(context: &'ctx mut GlobalContext) -> Self {
         Self {
             context,
-            next_anonymous_constant_id: 0,
+            deferred_global_lets: Vec::new(),
+            deferred_functions: Vec::new(),
         }
     }
 
@@ -36,9 +62,106 @@ impl<'ctx> Generator<'ctx> {
             self.context.replace_current_module(parent_module);
         }
 
+        let deferred_functions = std::mem::take(&mut self.deferred_functions);
+        self.generate_in_dependency_order(deferred_functions, |generator, deferred| {
+            generator.generate_in_scope(deferred.module, deferred.self_type, |generator| {
+                generator.try_generate_function_definition(&deferred.node)
+            })
+        })?;
+
+        let mut output = self.context.package_mut().take_output();
+        let inline_result = crate::ir::inline_functions(self.context, &mut output, self.context.optimization_level());
+        *self.context.package_mut().output_mut() = output;
+        inline_result?;
+
+        let deferred_global_lets = std::mem::take(&mut self.deferred_global_lets);
+        self.generate_in_dependency_order(deferred_global_lets, |generator, deferred| {
+            generator.generate_in_scope(deferred.module, deferred.self_type, |generator| {
+                generator.generate_global_let_statement(&deferred.value, &deferred.register, deferred.linkage)
+            })
+        })?;
+
+        for identifier in crate::ir::find_nounwind_functions(self.context, self.context.package().output()) {
+            self.context.mark_nounwind(&identifier);
+        }
+
+        Ok(())
+    }
+
+    /// Generate each of the `pending` items with `generate` in dependency order. An item whose
+    /// constant expressions use a global or function which hasn't been generated yet is postponed
+    /// until it has been, and a round in which nothing could be generated means the remaining
+    /// items depend on each other in a cycle.
+    fn generate_in_dependency_order<T>(&mut self, mut pending: Vec<T>, generate: impl Fn(&mut Self, &T) -> crate::Result<Value>) -> crate::Result<()> {
+        while !pending.is_empty() {
+            let pending_count = pending.len();
+            let mut postponed = Vec::new();
+            let mut first_error = None;
+            for item in pending {
+                match generate(self, &item) {
+                    Ok(_) => {}
+                    Err(error) if is_ungenerated_symbol_error(&error) => {
+                        first_error.get_or_insert(error);
+                        postponed.push(item);
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
+
+            if postponed.len() == pending_count {
+                let error = first_error.expect("a postponed item should have an error");
+                return Err(Box::new(error.with_note("constant expressions cannot depend on each other in a cycle")));
+            }
+            pending = postponed;
+        }
+
+        Ok(())
+    }
+
+    /// Run `generate` with `module` as the current module and `self_type` as the `Self` type.
+    fn generate_in_scope(&mut self, module: NamespaceHandle, self_type: Option<TypeHandle>, generate: impl FnOnce(&mut Self) -> crate::Result<Value>) -> crate::Result<Value> {
+        let parent_module = self.context.replace_current_module(module);
+        if let Some(self_type) = self_type {
+            self.context.set_self_type(self_type);
+        }
+
+        let result = generate(self);
+
+        if self_type.is_some() {
+            self.context.unset_self_type();
+        }
+        self.context.replace_current_module(parent_module);
+        result
+    }
+
+    /// Generate the function definition `node` in `module` with `self_type` as the `Self` type,
+    /// ahead of the rest of the package because an array length outside of any function body
+    /// calls it. The function is skipped when the rest of the package is generated.
+    pub fn generate_function_early(&mut self, module: NamespaceHandle, self_type: Option<TypeHandle>, node: &GlobalNode) -> crate::Result<()> {
+        let GlobalNodeKind::Function { register, .. } = node.kind() else {
+            panic!("node should be a function definition");
+        };
+        let register = register.as_ref().expect("register should be valid after fill phase");
+
+        self.generate_in_scope(module, self_type, |generator| generator.try_generate_function_definition(node))?;
+        self.context.package_mut().record_early_function(register.identifier());
+
         Ok(())
     }
 
+    /// Generate the function definition `node`, removing any anonymous constants it added to the
+    /// output if it fails so it can be generated again later.
+    fn try_generate_function_definition(&mut self, node: &GlobalNode) -> crate::Result<Value> {
+        let global_variable_count = self.context.package().output().global_variables().len();
+        let next_anonymous_constant_id = self.context.package().next_anonymous_constant_id();
+
+        let result = self.generate_function_definition(node);
+        result.inspect_err(|_| {
+            self.context.package_mut().output_mut().truncate_global_variables(global_variable_count);
+            self.context.package_mut().set_next_anonymous_constant_id(next_anonymous_constant_id);
+        })
+    }
+
     pub fn generate_global_statements<'a>(&mut self, global_statements: impl IntoIterator<Item = &'a GlobalNode>) -> crate::Result<()> {
         for global_statement in global_statements {
             self.generate_global_statement(global_statement)?;
@@ -49,20 +172,46 @@ impl<'ctx> Generator<'ctx> {
 
     pub fn generate_global_statement(&mut self, node: &GlobalNode) -> crate::Result<Value> {
         match node.kind() {
-            GlobalNodeKind::Let { value, register, .. } => {
+            GlobalNodeKind::Let { symbol_name, value, register, .. } => {
                 let register = register.as_ref().expect("register should be valid after fill phase");
-                if let Some(value) = value {
-                    self.generate_global_let_statement(value, register)
+                let linkage = self.context.global_linkage(symbol_name.is_some(), node.is_public());
+                if let Some(value) = value.as_ref().filter(|value| value.contains_call()) {
+                    // Calls are evaluated at compile time, which requires the called functions
+                    self.deferred_global_lets.push(DeferredGlobalLet {
+                        module: self.context.current_module(),
+                        self_type: self.context.current_self_type(),
+                        value: value.as_ref().clone(),
+                        register: register.clone(),
+                        linkage,
+                    });
+                    Ok(Value::Void)
+                }
+                else if let Some(value) = value {
+                    self.generate_global_let_statement(value, register, linkage)
                 }
                 else {
                     // The work has already been done for us
                     Ok(Value::Void)
                 }
             }
-            GlobalNodeKind::Function { name, parameters, body, register, .. } => {
+            GlobalNodeKind::Function { body, register, .. } => {
                 let register = register.as_ref().expect("register should be valid after fill phase");
-                if let Some(body) = body {
-                    self.generate_function_definition(name, parameters, body, register)
+                if self.context.package().is_generated_early(register.identifier()) {
+                    // Already generated for an array length outside of any function body
+                    Ok(Value::Void)
+                }
+                else if body.is_some() {
+                    match self.try_generate_function_definition(node) {
+                        Err(error) if is_ungenerated_symbol_error(&error) => {
+                            self.deferred_functions.push(DeferredFunction {
+                                module: self.context.current_module(),
+                                self_type: self.context.current_self_type(),
+                                node: node.clone(),
+                            });
+                            Ok(Value::Void)
+                        }
+                        result => result,
+                    }
                 }
                 else {
                     // The work has already been done for us
@@ -88,6 +237,15 @@ impl<'ctx> Generator<'ctx> {
     }
 
     pub fn generate_local_node(&mut self, node: &LocalNode, local_context: &mut LocalContext, expected_type: Option<TypeHandle>) -> crate::Result<Value> {
+        // Instructions generated for this node are attributed to it in debug info
+        let parent_location = local_context.replace_location(Some(node.span()));
+        let result = self.generate_local_node_kind(node, local_context, expected_type);
+        local_context.replace_location(parent_location);
+
+        result
+    }
+
+    fn generate_local_node_kind(&mut self, node: &LocalNode, local_context: &mut LocalContext, expected_type: Option<TypeHandle>) -> crate::Result<Value> {
         if let Ok(constant) = self.generate_constant_node(node, Some(local_context), expected_type) {
             return Ok(Value::Constant(constant));
         }
@@ -210,7 +368,15 @@ impl<'ctx> Generator<'ctx> {
                         )));
                     }
                     else {
-                        let value = self.generate_local_node(value, local_context, Some(return_type))?;
+                        let value = self.generate_local_node(value, local_context, Some(return_type))
+                            .map_err(|mut error| {
+                                if let crate::ErrorKind::IncompatibleTypes { .. } = error.kind() {
+                                    if error.span() == Some(value.span()) {
+                                        error.add_label(local_context.return_type_span(), "expected due to this return type");
+                                    }
+                                }
+                                error
+                            })?;
                         return_value = self.coerce_to_rvalue(value, local_context)?;
                     }
                 }
@@ -254,8 +420,8 @@ impl<'ctx> Generator<'ctx> {
     }
 
     pub fn new_anonymous_constant(&mut self, pointer_type: TypeHandle) -> GlobalRegister {
-        let id = self.next_anonymous_constant_id;
-        self.next_anonymous_constant_id += 1;
+        let id = self.context.package().next_anonymous_constant_id();
+        self.context.package_mut().set_next_anonymous_constant_id(id + 1);
 
         let identifier = format!(".const.{}.{id}", self.context.package().info().name());
         GlobalRegister::new(identifier.as_bytes().into(), pointer_type)
@@ -629,6 +795,21 @@ impl<'ctx> Generator<'ctx> {
         })
     }
 
+    fn check_member_initializer_access(&self, struct_type: TypeHandle, member: &StructureMember, span: crate::Span) -> crate::Result<()> {
+        if self.context.is_member_accessible(struct_type, member) {
+            Ok(())
+        }
+        else {
+            Err(Box::new(crate::Error::new(
+                Some(span),
+                crate::ErrorKind::PrivateMember {
+                    member_name: member.name.to_string(),
+                    type_name: struct_type.path(self.context).to_string(),
+                },
+            )))
+        }
+    }
+
     fn generate_structure_literal(&mut self, span: crate::Span, type_name: &LocalNode, initializer_members: &[(Box<str>, LocalNode)], local_context: &mut LocalContext) -> crate::Result<Value> {
         let struct_type = self.context.interpret_node_as_type(type_name)?;
 
@@ -663,6 +844,7 @@ impl<'ctx> Generator<'ctx> {
             .map(|(index, member)| {
                 if let Some(initializer_index) = initializer_members.iter().position(|(name, _)| &member.name == name) {
                     let (_, member_value) = &initializer_members[initializer_index];
+                    self.check_member_initializer_access(struct_type, member, member_value.span())?;
                     let member_value = self.generate_local_node(member_value, local_context, Some(member.member_type))?;
 
                     initializer_members.swap_remove(initializer_index);
@@ -847,6 +1029,14 @@ impl<'ctx> Generator<'ctx> {
                 };
 
                 let value_type = self.context.interpret_type_node(type_node)?;
+                if !self.context.type_registry().is_layout_known(value_type) {
+                    return Err(Box::new(crate::Error::new(
+                        Some(type_node.span()),
+                        crate::ErrorKind::UnresolvedTypeLayout {
+                            type_name: value_type.path(self.context).to_string(),
+                        },
+                    )));
+                }
                 let Some(size) = self.context.type_size(value_type) else {
                     return Err(Box::new(crate::Error::new(
                         Some(type_node.span()),
@@ -865,6 +1055,14 @@ impl<'ctx> Generator<'ctx> {
                 };
 
                 let value_type = self.context.interpret_type_node(type_node)?;
+                if !self.context.type_registry().is_layout_known(value_type) {
+                    return Err(Box::new(crate::Error::new(
+                        Some(type_node.span()),
+                        crate::ErrorKind::UnresolvedTypeLayout {
+                            type_name: value_type.path(self.context).to_string(),
+                        },
+                    )));
+                }
                 let Some(alignment) = self.context.type_alignment(value_type) else {
                     return Err(Box::new(crate::Error::new(
                         Some(type_node.span()),
@@ -1455,6 +1653,61 @@ impl<'ctx> Generator<'ctx> {
         }
     }
 
+    /// Evaluate a call to a function at compile time by interpreting the generated code for it.
+    fn fold_call_operation(&mut self, node: &LocalNode, callee_node: &LocalNode, arguments: &[LocalNode], constant_id: &mut usize) -> crate::Result<(Constant, Vec<GlobalVariable>)> {
+        let (callee, mut intermediate_constants) = self.fold_as_constant(callee_node, constant_id, None, None)?;
+
+        let TypeRepr::Function { signature } = callee.get_type().repr(self.context).clone() else {
+            return Err(Box::new(crate::Error::new(
+                Some(callee_node.span()),
+                crate::ErrorKind::ExpectedFunction {
+                    type_name: callee.get_type().path(self.context).to_string(),
+                },
+            )));
+        };
+
+        let expected_count = signature.parameter_types().len();
+        let got_count = arguments.len();
+        if (!signature.is_variadic() && got_count > expected_count) || got_count < expected_count {
+            return Err(Box::new(crate::Error::new(
+                Some(callee_node.span()),
+                crate::ErrorKind::WrongFunctionArgumentCount {
+                    expected_count,
+                    got_count,
+                },
+            )));
+        }
+
+        let parameter_types = signature.parameter_types().iter()
+            .map(|&parameter_type| Some(parameter_type))
+            .chain(std::iter::repeat(None));
+        let argument_values: Vec<Constant> = std::iter::zip(arguments, parameter_types)
+            .map(|(argument, parameter_type)| {
+                let (argument, mut constants) = self.fold_as_constant(argument, constant_id, None, parameter_type)?;
+
+                intermediate_constants.append(&mut constants);
+                Ok(argument)
+            })
+            .collect::<crate::Result<_>>()?;
+
+        // The intermediate constants aren't part of the output yet, but the arguments may point to them
+        let mut intermediate_unit = CompilationUnit::new(self.context.package().output().main_path());
+        for intermediate_constant in &intermediate_constants {
+            intermediate_unit.add_global_variable(intermediate_constant.clone());
+        }
+        // The result may depend on the function bodies of dependencies, not just their interfaces
+        self.context.package_mut().record_constant_call();
+        // The current package goes last so that its functions are the ones with source locations
+        let units: Vec<&CompilationUnit> = self.context.finished_units().iter()
+            .chain([&intermediate_unit, self.context.package().output()])
+            .collect();
+
+        let value = crate::interp::evaluate_constant_call(self.context, &units, &callee, &argument_values)
+            .map_err(|kind| Box::new(crate::Error::new(Some(node.span()), kind)))?;
+
+        Ok((value, intermediate_constants))
+    }
+
     fn fold_subscript_operation(&mut self, lhs_node: &LocalNode, rhs_node: &LocalNode, constant_id: &mut usize, local_context: Option<&LocalContext>) -> crate::Result<(Constant, Vec<GlobalVariable>)> {
         let (lhs, mut intermediate_constants) = self.fold_as_constant(lhs_node, constant_id, local_context, None)?;
         let (rhs, mut constants) = self.fold_as_constant(rhs_node, constant_id, local_context, None)?;
@@ -1582,12 +1835,10 @@ impl<'ctx> Generator<'ctx> {
                         panic!("indirect value pointer is not a pointer type")
                     };
 
-                    let (member_index, member_type) = members
+                    let (member_index, member) = members
                         .iter()
                         .enumerate()
-                        .find_map(|(index, member)| {
-                            (member.name.as_ref() == member_name).then_some((index, member.member_type))
-                        })
+                        .find(|(_, member)| member.name.as_ref() == member_name)
                         .ok_or_else(|| Box::new(crate::Error::new(
                             Some(member_name_node.span()),
                             crate::ErrorKind::UndefinedMember {
@@ -1595,6 +1846,16 @@ impl<'ctx> Generator<'ctx> {
                                 type_name: lhs_type.path(self.context).to_string(),
                             },
                         )))?;
+                    if !self.context.is_member_accessible(lhs_type, member) {
+                        return Err(Box::new(crate::Error::new(
+                            Some(member_name_node.span()),
+                            crate::ErrorKind::PrivateMember {
+                                member_name: member_name.to_string(),
+                                type_name: lhs_type.path(self.context).to_string(),
+                            },
+                        )));
+                    }
+                    let member_type = member.member_type;
                     let member_pointer_type = self.context.get_pointer_type(member_type, semantics);
                     let member_pointer = local_context.new_anonymous_register(member_pointer_type);
 
@@ -1683,21 +1944,25 @@ impl<'ctx> Generator<'ctx> {
 
                 // Search in the type's implementation namespace for a matching method
                 let lhs_namespace = self.context.type_namespace(self_value.get_type());
-                if let Ok(value) = self.context.get_symbol_value(lhs_namespace, method_name, Some(&rhs.span())) {
+                match self.context.get_symbol_value(lhs_namespace, method_name, Some(&rhs.span())) {
                     // A method was found, so bind lhs as self and use it as the callee
-                    Value::BoundFunction {
+                    Ok(value) => Value::BoundFunction {
                         self_value: Box::new((lhs.span(), self_value)),
                         function_value: Box::new(value.clone()),
+                    },
+                    // The method exists, but can't be called from here
+                    Err(error) if matches!(error.kind(), crate::ErrorKind::PrivateSymbol { .. }) => {
+                        return Err(error);
+                    }
+                    Err(_) => {
+                        return Err(Box::new(crate::Error::new(
+                            Some(rhs.span()),
+                            crate::ErrorKind::NoSuchMethod {
+                                type_name: self_value.get_type().path(self.context).to_string(),
+                                method_name: method_name.to_string(),
+                            },
+                        )));
                     }
-                }
-                else {
-                    return Err(Box::new(crate::Error::new(
-                        Some(rhs.span()),
-                        crate::ErrorKind::NoSuchMethod {
-                            type_name: self_value.get_type().path(self.context).to_string(),
-                            method_name: method_name.to_string(),
-                        },
-                    )));
                 }
             }
             // Normal call operation
@@ -2008,7 +2273,16 @@ impl<'ctx> Generator<'ctx> {
 
         let value = match value {
             Some(node) => {
-                let value = self.generate_local_node(node, local_context, value_type)?;
+                let value = self.generate_local_node(node, local_context, value_type)
+                    .map_err(|mut error| {
+                        // Point out the annotation responsible for the expected type
+                        if let (Some(type_node), crate::ErrorKind::IncompatibleTypes { .. }) = (type_node, error.kind()) {
+                            if error.span() == Some(node.span()) {
+                                error.add_label(type_node.span(), "expected due to this type annotation");
+                            }
+                        }
+                        error
+                    })?;
                 Some(self.coerce_to_rvalue(value, local_context)?)
             }
             None => None,
@@ -2032,6 +2306,13 @@ impl<'ctx> Generator<'ctx> {
         let semantics = PointerSemantics::for_symbol(is_mutable);
         let pointer_type = self.context.get_pointer_type(value_type, semantics);
         let pointer = local_context.define_indirect_symbol(name.into(), pointer_type, value_type);
+        local_context.function_mut().add_debug_variable(DebugVariable {
+            name: name.into(),
+            pointer: pointer.clone(),
+            value_type,
+            span,
+            argument_number: None,
+        });
 
         local_context.add_instruction(Instruction::StackAllocate {
             result: pointer.clone(),
@@ -2046,7 +2327,7 @@ impl<'ctx> Generator<'ctx> {
         Ok(Value::Void)
     }
 
-    fn generate_global_let_statement(&mut self, value: &LocalNode, global_register: &GlobalRegister) -> crate::Result<Value> {
+    fn generate_global_let_statement(&mut self, value: &LocalNode, global_register: &GlobalRegister, linkage: Linkage) -> crate::Result<Value> {
         // The fill phase has done most of the work for us already
         let TypeRepr::Pointer { pointee_type, semantics } = *self.context.type_repr(global_register.get_type()) else {
             panic!("invalid global value register type");
@@ -2062,34 +2343,50 @@ impl<'ctx> Generator<'ctx> {
                 PointerSemantics::Mutable => GlobalVariableKind::Mutable,
             },
             value,
-        ));
+        ).with_linkage(linkage));
 
         Ok(Value::Void)
     }
 
-    fn generate_function_definition(&mut self, name: &str, parameters: &[FunctionParameterNode], body: &LocalNode, function_register: &GlobalRegister) -> crate::Result<Value> {
+    fn generate_function_definition(&mut self, node: &GlobalNode) -> crate::Result<Value> {
+        let GlobalNodeKind::Function { name, parameters, return_type, body: Some(body), inline_hint, register, .. } = node.kind() else {
+            panic!("node should be a function definition");
+        };
+        let span = node.span();
+        let function_register = register.as_ref().expect("register should be valid after fill phase");
+
         // The fill phase has done a lot of the initial work for us already
         let TypeRepr::Function { signature } = self.context.type_repr(function_register.get_type()) else {
             panic!("invalid global value register type");
         };
         let signature = signature.clone();
 
+        let function_path = self.context.current_namespace_info().path().child(name.as_ref());
         let mut local_context = LocalContext::new(
             FunctionDefinition::new(
                 function_register.clone(),
                 signature.return_type(),
                 signature.is_variadic(),
-            ),
-            self.context.current_namespace_info().path().child(name),
+            ).with_source_info(name.as_ref(), span).with_inline_hint(*inline_hint),
+            function_path,
+            return_type.span(),
         );
+        local_context.replace_location(Some(span));
 
-        for (parameter, &parameter_type) in std::iter::zip(parameters, signature.parameter_types()) {
+        for (index, (parameter, &parameter_type)) in std::iter::zip(parameters, signature.parameter_types()).enumerate() {
             let parameter_register = local_context.new_anonymous_register(parameter_type);
             local_context.function_mut().add_parameter_register(parameter_register.clone());
 
             let semantics = PointerSemantics::for_symbol(parameter.is_mutable);
             let pointer_type = self.context.get_pointer_type(parameter_type, semantics);
             let pointer = local_context.define_indirect_symbol(parameter.name.clone(), pointer_type, parameter_type);
+            local_context.function_mut().add_debug_variable(DebugVariable {
+                name: parameter.name.clone(),
+                pointer: pointer.clone(),
+                value_type: parameter_type,
+                span: parameter.span,
+                argument_number: Some(index + 1),
+            });
 
             local_context.add_instruction(Instruction::StackAllocate {
                 result: pointer.clone(),
@@ -2109,7 +2406,15 @@ impl<'ctx> Generator<'ctx> {
             });
         }
 
-        self.context.package_mut().output_mut().add_function_definition(local_context.finish());
+        let function_path = local_context.function_path().clone();
+        let mut function = local_context.finish();
+        // Transformations assume valid control flow, so the generated IR is checked before them too
+        crate::ir::verify_function(self.context, &function, &function_path)?;
+        crate::ir::promote_allocas(self.context, &mut function);
+        crate::ir::optimize_function(self.context, &mut function, self.context.optimization_level());
+        function.renumber_anonymous_registers();
+        crate::ir::verify_function(self.context, &function, &function_path)?;
+        self.context.package_mut().output_mut().add_function_definition(function);
 
         Ok(Value::Void)
     }
@@ -2148,9 +2453,9 @@ impl<'ctx> Generator<'ctx> {
     }
 
     pub fn generate_constant_node(&mut self, node: &LocalNode, local_context: Option<&LocalContext>, expected_type: Option<TypeHandle>) -> crate::Result<Constant> {
-        let mut constant_id = self.next_anonymous_constant_id;
+        let mut constant_id = self.context.package().next_anonymous_constant_id();
         let (constant, intermediate_constants) = self.fold_as_constant(node, &mut constant_id,  local_context, expected_type)?;
-        self.next_anonymous_constant_id = constant_id;
+        self.context.package_mut().set_next_anonymous_constant_id(constant_id);
 
         for intermediate_constant in intermediate_constants {
             self.context.package_mut().output_mut().add_global_variable(intermediate_constant);
@@ -2159,6 +2464,27 @@ impl<'ctx> Generator<'ctx> {
         Ok(constant)
     }
 
+    /// Evaluate the length of an array type given by the constant expression `node`. Nothing is
+    /// added to the output, since only the resulting length is needed.
+    pub fn evaluate_array_length(&mut self, node: &LocalNode) -> crate::Result<u64> {
+        let mut constant_id = self.context.package().next_anonymous_constant_id();
+        let (constant, _) = self.fold_as_constant(node, &mut constant_id, None, Some(TypeHandle::USIZE))
+            .map_err(|mut error| {
+                if let crate::ErrorKind::UnsupportedConstantExpression = error.kind() {
+                    *error.kind_mut() = crate::ErrorKind::NonConstantArrayLength;
+                }
+                error
+            })?;
+
+        match constant {
+            Constant::Integer(length) => Ok(length.raw() as u64),
+            _ => Err(Box::new(crate::Error::new(
+                Some(node.span()),
+                crate::ErrorKind::NonConstantArrayLength,
+            ))),
+        }
+    }
+
     pub fn fold_as_constant(&mut self, node: &LocalNode, constant_id: &mut usize, local_context: Option<&LocalContext>, expected_type: Option<TypeHandle>) -> crate::Result<(Constant, Vec<GlobalVariable>)> {
         let mut new_intermediate_constant = |constant: Constant, context: &mut GlobalContext| {
             let pointer = GlobalRegister::new(
@@ -2339,6 +2665,7 @@ impl<'ctx> Generator<'ctx> {
                     .map(|member| {
                         if let Some(initializer_index) = initializer_members.iter().position(|(name, _)| &member.name == name) {
                             let (_, member_value) = &initializer_members[initializer_index];
+                            self.check_member_initializer_access(struct_type, member, member_value.span())?;
                             let (member_value, mut constants) = self.fold_as_constant(member_value, constant_id, local_context, Some(member.member_type))?;
 
                             intermediate_constants.append(&mut constants);
@@ -2443,6 +2770,14 @@ impl<'ctx> Generator<'ctx> {
                     )));
                 }
             }
+            // Calls can only be evaluated outside of functions, since the value of a local
+            // expression must be computed when the function runs
+            LocalNodeKind::Call { callee, arguments } if local_context.is_none() => {
+                let (value, mut constants) = self.fold_call_operation(node, callee, arguments, constant_id)?;
+                intermediate_constants.append(&mut constants);
+
+                value
+            }
             LocalNodeKind::Grouping { content } => {
                 // Fine to bypass validation steps since this is literally just parentheses
                 return self.fold_as_constant(content, constant_id, local_context, expected_type);
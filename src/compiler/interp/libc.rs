@@ -0,0 +1,409 @@
+//! The subset of the C library available to interpreted programs.
+
+use crate::interp::{wrap_integer, Interpreter, RuntimeValue, Trap};
+use crate::interp::memory::Memory;
+
+/// A file opened for reading with `fopen`, which is read into memory all at once.
+pub(super) struct OpenFile {
+    contents: Vec<u8>,
+    position: usize,
+}
+
+/// Call the foreign function `name` with `arguments`, returning its result.
+pub(super) fn call(interpreter: &mut Interpreter, name: &[u8], arguments: &[RuntimeValue]) -> Result<RuntimeValue, Trap> {
+    let display_name = || String::from_utf8_lossy(name).into_owned();
+    let argument = |index: usize| {
+        arguments.get(index).ok_or_else(|| Trap::fault(format!("too few arguments to '{}'", display_name())))
+    };
+    let size = |index: usize| Ok::<_, Trap>(argument(index)?.as_integer()? as u64);
+    let pointer = |index: usize| argument(index)?.as_pointer();
+    let memory = &mut interpreter.memory;
+
+    match name {
+        b"printf" => {
+            let format = memory.read_c_string(pointer(0)?)?.to_vec();
+            let text = format_string(memory, interpreter.context.target().pointer_size(), &format, &arguments[1..])?;
+            interpreter.write_output(&text)?;
+            Ok(RuntimeValue::Integer(text.len() as i128))
+        }
+        b"puts" => {
+            let mut text = memory.read_c_string(pointer(0)?)?.to_vec();
+            text.push(b'\n');
+            interpreter.write_output(&text)?;
+            Ok(RuntimeValue::Integer(0))
+        }
+        b"putchar" => {
+            let character = argument(0)?.as_integer()? as u8;
+            interpreter.write_output(&[character])?;
+            Ok(RuntimeValue::Integer(character as i128))
+        }
+        b"malloc" => {
+            Ok(RuntimeValue::Pointer(memory.heap_allocate(size(0)?).unwrap_or(0)))
+        }
+        b"calloc" => {
+            // Heap memory is always zeroed when allocated
+            let address = size(0)?.checked_mul(size(1)?).and_then(|size| memory.heap_allocate(size));
+            Ok(RuntimeValue::Pointer(address.unwrap_or(0)))
+        }
+        b"realloc" => {
+            let old_address = pointer(0)?;
+            let new_size = size(1)?;
+            let old_size = match old_address {
+                0 => 0,
+                _ => memory.heap_allocation_size(old_address).ok_or_else(|| {
+                    Trap::fault(format!("{old_address:#x} is not the address of a live heap allocation"))
+                })?,
+            };
+            let Some(new_address) = memory.heap_allocate(new_size) else {
+                return Ok(RuntimeValue::Pointer(0));
+            };
+            if old_address != 0 {
+                memory.copy(new_address, old_address, old_size.min(new_size))?;
+                memory.heap_free(old_address)?;
+            }
+            Ok(RuntimeValue::Pointer(new_address))
+        }
+        b"free" => {
+            let address = pointer(0)?;
+            if address != 0 {
+                memory.heap_free(address)?;
+            }
+            Ok(RuntimeValue::Void)
+        }
+        b"memcpy" | b"memmove" => {
+            memory.copy(pointer(0)?, pointer(1)?, size(2)?)?;
+            Ok(RuntimeValue::Pointer(pointer(0)?))
+        }
+        b"memset" => {
+            memory.fill(pointer(0)?, argument(1)?.as_integer()? as u8, size(2)?)?;
+            Ok(RuntimeValue::Pointer(pointer(0)?))
+        }
+        b"strlen" => {
+            Ok(RuntimeValue::Integer(memory.read_c_string(pointer(0)?)?.len() as i128))
+        }
+        b"isdigit" => {
+            let is_digit = u8::try_from(argument(0)?.as_integer()?).is_ok_and(|byte| byte.is_ascii_digit());
+            Ok(RuntimeValue::Integer(is_digit as i128))
+        }
+        b"fopen" => {
+            let path = String::from_utf8_lossy(memory.read_c_string(pointer(0)?)?).into_owned();
+            let mode = memory.read_c_string(pointer(1)?)?;
+            if !mode.starts_with(b"r") || mode.contains(&b'+') {
+                return Err(Trap::fault("files can only be opened for reading"));
+            }
+            let Ok(contents) = std::fs::read(path) else {
+                return Ok(RuntimeValue::Pointer(0));
+            };
+            // The stream is identified by the address of an allocation made for it
+            let stream = interpreter.heap_allocate(1)?;
+            interpreter.open_files.insert(stream, OpenFile { contents, position: 0 });
+            Ok(RuntimeValue::Pointer(stream))
+        }
+        b"fgets" => {
+            let buffer = pointer(0)?;
+            let capacity = argument(1)?.as_integer()?;
+            let file = interpreter.open_files.get_mut(&pointer(2)?)
+                .ok_or_else(|| Trap::fault("'fgets' called with a stream which is not open"))?;
+            let remaining = &file.contents[file.position..];
+            if capacity < 1 || remaining.is_empty() {
+                return Ok(RuntimeValue::Pointer(0));
+            }
+            let limit = remaining.len().min(capacity as usize - 1);
+            let length = remaining[..limit].iter().position(|&byte| byte == b'\n').map_or(limit, |index| index + 1);
+            let mut line = remaining[..length].to_vec();
+            line.push(0);
+            file.position += length;
+            memory.write(buffer, &line)?;
+            Ok(RuntimeValue::Pointer(buffer))
+        }
+        b"fclose" => {
+            let stream = pointer(0)?;
+            interpreter.open_files.remove(&stream)
+                .ok_or_else(|| Trap::fault("'fclose' called with a stream which is not open"))?;
+            memory.heap_free(stream)?;
+            Ok(RuntimeValue::Integer(0))
+        }
+        b"exit" => {
+            Err(Trap::Exit(argument(0)?.as_integer()? as i32))
+        }
+        _ => {
+            // LLVM intrinsics for integer minimum and maximum, e.g. `llvm.umax.i64`; operands
+            // are already normalized for their signedness
+            let operation = name.strip_prefix(b"llvm.").and_then(|name| name.split(|&byte| byte == b'.').next());
+            match operation {
+                Some(b"smin" | b"umin") => Ok(RuntimeValue::Integer(argument(0)?.as_integer()?.min(argument(1)?.as_integer()?))),
+                Some(b"smax" | b"umax") => Ok(RuntimeValue::Integer(argument(0)?.as_integer()?.max(argument(1)?.as_integer()?))),
+                _ => Err(Trap::fault(format!("foreign function '{}' is not available in the interpreter", display_name()))),
+            }
+        }
+    }
+}
+
+/// A conversion specification in a `printf` format string, e.g. `%-08.3lx`.
+#[derive(Default)]
+struct Specification {
+    left_justify: bool,
+    force_sign: bool,
+    space_sign: bool,
+    alternate_form: bool,
+    zero_pad: bool,
+    width: usize,
+    precision: Option<usize>,
+    /// The size of the integer argument in bytes, as given by the length modifier.
+    integer_size: u64,
+}
+
+impl Specification {
+    /// Pad `prefix` (a sign or radix prefix) followed by `body` to the minimum width. Zeros are
+    /// inserted between the two when requested and `zero_pad_allowed`.
+    fn pad(&self, prefix: &[u8], body: &[u8], zero_pad_allowed: bool) -> Vec<u8> {
+        let padding = self.width.saturating_sub(prefix.len() + body.len());
+        let mut output = Vec::with_capacity(prefix.len() + body.len() + padding);
+        if self.left_justify {
+            output.extend_from_slice(prefix);
+            output.extend_from_slice(body);
+            output.resize(output.len() + padding, b' ');
+        }
+        else if self.zero_pad && zero_pad_allowed {
+            output.extend_from_slice(prefix);
+            output.resize(output.len() + padding, b'0');
+            output.extend_from_slice(body);
+        }
+        else {
+            output.resize(padding, b' ');
+            output.extend_from_slice(prefix);
+            output.extend_from_slice(body);
+        }
+        output
+    }
+
+    fn sign(&self, is_negative: bool) -> &'static [u8] {
+        if is_negative {
+            b"-"
+        }
+        else if self.force_sign {
+            b"+"
+        }
+        else if self.space_sign {
+            b" "
+        }
+        else {
+            b""
+        }
+    }
+
+    fn format_integer(&self, value: i128, conversion: u8) -> Vec<u8> {
+        let magnitude = value.unsigned_abs();
+        let mut digits = match conversion {
+            b'o' => format!("{magnitude:o}"),
+            b'x' => format!("{magnitude:x}"),
+            b'X' => format!("{magnitude:X}"),
+            _ => magnitude.to_string(),
+        }.into_bytes();
+        if self.precision == Some(0) && value == 0 {
+            digits.clear();
+        }
+        if let Some(precision) = self.precision {
+            if digits.len() < precision {
+                digits.splice(0..0, std::iter::repeat_n(b'0', precision - digits.len()));
+            }
+        }
+
+        let prefix: &[u8] = match conversion {
+            b'd' | b'i' => self.sign(value < 0),
+            b'o' if self.alternate_form && digits.first() != Some(&b'0') => b"0",
+            b'x' if self.alternate_form && value != 0 => b"0x",
+            b'X' if self.alternate_form && value != 0 => b"0X",
+            _ => b"",
+        };
+        self.pad(prefix, &digits, self.precision.is_none())
+    }
+
+    fn format_float(&self, value: f64, conversion: u8) -> Vec<u8> {
+        let uppercase = conversion.is_ascii_uppercase();
+        let sign = self.sign(value.is_sign_negative());
+        let magnitude = value.abs();
+        if !magnitude.is_finite() {
+            let body = match (magnitude.is_nan(), uppercase) {
+                (true, false) => "nan",
+                (true, true) => "NAN",
+                (false, false) => "inf",
+                (false, true) => "INF",
+            };
+            return self.pad(sign, body.as_bytes(), false);
+        }
+
+        let precision = self.precision.unwrap_or(6);
+        let mut body = match conversion.to_ascii_lowercase() {
+            b'e' => format_exponential(magnitude, precision, self.alternate_form),
+            b'g' => {
+                // Use the style which gives the shortest output for the exponent of the value
+                let significant_digits = precision.max(1);
+                let exponential = format_exponential(magnitude, significant_digits - 1, self.alternate_form);
+                let exponent: i32 = exponential[exponential.find('e').unwrap() + 1..].parse().unwrap();
+                let mut body = if exponent < -4 || exponent >= significant_digits as i32 {
+                    exponential
+                }
+                else {
+                    format_fixed(magnitude, (significant_digits as i32 - 1 - exponent) as usize, self.alternate_form)
+                };
+                if !self.alternate_form {
+                    let exponent_start = body.find('e').unwrap_or(body.len());
+                    let (mantissa, exponent) = body.split_at(exponent_start);
+                    if mantissa.contains('.') {
+                        body = format!("{}{exponent}", mantissa.trim_end_matches('0').trim_end_matches('.'));
+                    }
+                }
+                body
+            }
+            _ => format_fixed(magnitude, precision, self.alternate_form),
+        };
+        if uppercase {
+            body.make_ascii_uppercase();
+        }
+        self.pad(sign, body.as_bytes(), true)
+    }
+}
+
+/// Format `value` as `d.ddde±dd` with `precision` digits after the decimal point.
+fn format_exponential(value: f64, precision: usize, alternate_form: bool) -> String {
+    let formatted = format!("{value:.precision$e}");
+    let (mantissa, exponent) = formatted.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
+    let point = if alternate_form && precision == 0 { "." } else { "" };
+    let exponent_sign = if exponent < 0 { '-' } else { '+' };
+    format!("{mantissa}{point}e{exponent_sign}{:02}", exponent.unsigned_abs())
+}
+
+/// Format `value` as `ddd.ddd` with `precision` digits after the decimal point.
+fn format_fixed(value: f64, precision: usize, alternate_form: bool) -> String {
+    let point = if alternate_form && precision == 0 { "." } else { "" };
+    format!("{value:.precision$}{point}")
+}
+
+/// Format `arguments` according to the C format string `format`, using the same conventions as
+/// glibc for null strings and pointers.
+fn format_string(memory: &Memory, pointer_size: u64, format: &[u8], arguments: &[RuntimeValue]) -> Result<Vec<u8>, Trap> {
+    let mut arguments = arguments.iter();
+    let mut next_argument = || arguments.next().ok_or_else(|| Trap::fault("too few arguments for format string"));
+    let mut output = Vec::new();
+    let mut bytes = format.iter().copied().peekable();
+
+    while let Some(byte) = bytes.next() {
+        if byte != b'%' {
+            output.push(byte);
+            continue;
+        }
+
+        let mut specification = Specification::default();
+        while let Some(flag) = bytes.next_if(|byte| b"-+ #0".contains(byte)) {
+            match flag {
+                b'-' => specification.left_justify = true,
+                b'+' => specification.force_sign = true,
+                b' ' => specification.space_sign = true,
+                b'#' => specification.alternate_form = true,
+                _ => specification.zero_pad = true,
+            }
+        }
+
+        if bytes.next_if_eq(&b'*').is_some() {
+            let width = next_argument()?.as_integer()? as i32;
+            specification.left_justify |= width < 0;
+            specification.width = width.unsigned_abs() as usize;
+        }
+        else {
+            while let Some(digit) = bytes.next_if(u8::is_ascii_digit) {
+                specification.width = specification.width * 10 + (digit - b'0') as usize;
+            }
+        }
+
+        if bytes.next_if_eq(&b'.').is_some() {
+            if bytes.next_if_eq(&b'*').is_some() {
+                // A negative precision is taken as if it were omitted
+                let precision = next_argument()?.as_integer()? as i32;
+                specification.precision = usize::try_from(precision).ok();
+            }
+            else {
+                let mut precision = 0;
+                while let Some(digit) = bytes.next_if(u8::is_ascii_digit) {
+                    precision = precision * 10 + (digit - b'0') as usize;
+                }
+                specification.precision = Some(precision);
+            }
+        }
+
+        let mut length_modifier = Vec::new();
+        while let Some(modifier) = bytes.next_if(|byte| b"hljztL".contains(byte)) {
+            length_modifier.push(modifier);
+        }
+        // `long` has the same size as pointers on every supported target
+        specification.integer_size = match length_modifier.as_slice() {
+            b"hh" => 1,
+            b"h" => 2,
+            b"l" | b"z" | b"t" => pointer_size,
+            b"ll" | b"j" => 8,
+            _ => 4,
+        };
+
+        let Some(conversion) = bytes.next() else {
+            return Err(Trap::fault("incomplete conversion at end of format string"));
+        };
+        let formatted = match conversion {
+            b'%' => b"%".to_vec(),
+            b'd' | b'i' => {
+                let value = wrap_integer(next_argument()?.as_integer()?, specification.integer_size, true);
+                specification.format_integer(value, conversion)
+            }
+            b'u' | b'o' | b'x' | b'X' => {
+                let value = wrap_integer(next_argument()?.as_integer()?, specification.integer_size, false);
+                specification.format_integer(value, conversion)
+            }
+            b'c' => {
+                let character = next_argument()?.as_integer()? as u8;
+                specification.pad(b"", &[character], false)
+            }
+            b's' => {
+                let address = next_argument()?.as_pointer()?;
+                let string = match address {
+                    0 => b"(null)".to_vec(),
+                    _ => read_bounded_string(memory, address, specification.precision)?,
+                };
+                specification.pad(b"", &string, false)
+            }
+            b'p' => {
+                let address = next_argument()?.as_pointer()?;
+                if address == 0 {
+                    specification.pad(b"", b"(nil)", false)
+                }
+                else {
+                    specification.alternate_form = true;
+                    specification.format_integer(address as i128, b'x')
+                }
+            }
+            b'f' | b'F' | b'e' | b'E' | b'g' | b'G' => {
+                specification.format_float(next_argument()?.as_float()?, conversion)
+            }
+            _ => {
+                return Err(Trap::fault(format!("unsupported conversion '%{}' in format string", conversion as char)));
+            }
+        };
+        output.extend(formatted);
+    }
+
+    Ok(output)
+}
+
+/// Read the NUL-terminated string at `address`, stopping after `limit` bytes if given.
+fn read_bounded_string(memory: &Memory, address: u64, limit: Option<usize>) -> Result<Vec<u8>, Trap> {
+    let Some(limit) = limit else {
+        return Ok(memory.read_c_string(address)?.to_vec());
+    };
+    let mut string = Vec::new();
+    while string.len() < limit {
+        match memory.read(address + string.len() as u64, 1)?[0] {
+            0 => break,
+            byte => string.push(byte),
+        }
+    }
+    Ok(string)
+}
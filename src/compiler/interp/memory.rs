@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use crate::interp::{RuntimeValue, Trap};
+use crate::sema::{GlobalContext, TypeHandle, TypeRepr};
+use crate::target::Endianness;
+
+/// The most memory a program may use, including static data, the stack, and the heap.
+const MEMORY_LIMIT: u64 = 1 << 30;
+
+/// A flat, byte-addressed memory laid out according to the target.
+///
+/// Addresses below `mapped_start` are never readable or writable, which catches null pointers
+/// and leaves room for the fake addresses given to functions. Everything above is allocated
+/// upwards: static data first, then the stack, then the heap.
+pub struct Memory {
+    bytes: Vec<u8>,
+    mapped_start: u64,
+    limit: u64,
+    endianness: Endianness,
+    pointer_size: u64,
+    stack_start: u64,
+    stack_end: u64,
+    stack_pointer: u64,
+    /// Addresses below this may be read but not written.
+    read_only_end: u64,
+    /// The size of each live heap allocation, keyed by address.
+    heap_allocations: HashMap<u64, u64>,
+}
+
+impl Memory {
+    pub fn new(context: &GlobalContext, mapped_start: u64) -> Self {
+        let pointer_size = context.target().pointer_size();
+        let address_limit = 1_u64.checked_shl(pointer_size as u32 * 8).unwrap_or(u64::MAX);
+        Self {
+            bytes: vec![0; mapped_start as usize],
+            mapped_start,
+            limit: MEMORY_LIMIT.min(address_limit),
+            endianness: context.target().endianness(),
+            pointer_size,
+            stack_start: 0,
+            stack_end: 0,
+            stack_pointer: 0,
+            read_only_end: 0,
+            heap_allocations: HashMap::new(),
+        }
+    }
+
+    /// Reserve `size` zeroed bytes aligned to `alignment` at the end of memory, returning `None`
+    /// if the memory limit would be exceeded.
+    pub fn allocate(&mut self, size: u64, alignment: u64) -> Option<u64> {
+        let address = (self.bytes.len() as u64).checked_next_multiple_of(alignment.max(1))?;
+        let end = address.checked_add(size)?;
+        if end > self.limit {
+            return None;
+        }
+        self.bytes.resize(end as usize, 0);
+        Some(address)
+    }
+
+    /// Reserve `size` bytes for the stack. Must be called after all static data is allocated.
+    pub fn create_stack(&mut self, size: u64) -> Option<()> {
+        self.stack_start = self.allocate(size, 16)?;
+        self.stack_end = self.stack_start + size;
+        self.stack_pointer = self.stack_start;
+        Some(())
+    }
+
+    /// Make the static data allocated before the stack read-only.
+    pub fn protect_static_data(&mut self) {
+        self.read_only_end = self.stack_start;
+    }
+
+    pub fn stack_pointer(&self) -> u64 {
+        self.stack_pointer
+    }
+
+    /// Free everything allocated on the stack since the stack pointer was `stack_pointer`.
+    pub fn restore_stack_pointer(&mut self, stack_pointer: u64) {
+        self.stack_pointer = stack_pointer;
+    }
+
+    pub fn stack_allocate(&mut self, size: u64, alignment: u64) -> Result<u64, Trap> {
+        let address = self.stack_pointer.next_multiple_of(alignment.max(1));
+        if address + size > self.stack_end {
+            return Err(Trap::fault("stack overflow"));
+        }
+        self.stack_pointer = address + size;
+        self.bytes[address as usize..self.stack_pointer as usize].fill(0);
+        Ok(address)
+    }
+
+    /// Allocate `size` bytes on the heap, returning `None` if memory is exhausted.
+    pub fn heap_allocate(&mut self, size: u64) -> Option<u64> {
+        // Every allocation is distinct, even when empty, and aligned for any primitive type
+        let address = self.allocate(size.max(1), 16)?;
+        self.heap_allocations.insert(address, size);
+        Some(address)
+    }
+
+    /// The size of the live heap allocation starting at `address`, if there is one.
+    pub fn heap_allocation_size(&self, address: u64) -> Option<u64> {
+        self.heap_allocations.get(&address).copied()
+    }
+
+    pub fn heap_free(&mut self, address: u64) -> Result<(), Trap> {
+        match self.heap_allocations.remove(&address) {
+            Some(_) => Ok(()),
+            None => Err(Trap::fault(format!("{address:#x} is not the address of a live heap allocation"))),
+        }
+    }
+
+    fn check_range(&self, address: u64, size: u64) -> Result<std::ops::Range<usize>, Trap> {
+        if size == 0 {
+            return Ok(0..0);
+        }
+        let end = address.checked_add(size).filter(|&end| end <= self.bytes.len() as u64);
+        match end {
+            Some(end) if address >= self.mapped_start => Ok(address as usize..end as usize),
+            _ if address == 0 => Err(Trap::fault("null pointer dereference")),
+            _ => Err(Trap::fault(format!("invalid access of {size} bytes at address {address:#x}"))),
+        }
+    }
+
+    fn check_writable_range(&self, address: u64, size: u64) -> Result<std::ops::Range<usize>, Trap> {
+        let range = self.check_range(address, size)?;
+        if !range.is_empty() && (range.start as u64) < self.read_only_end {
+            return Err(Trap::fault(format!("write to read-only memory at address {address:#x}")));
+        }
+        Ok(range)
+    }
+
+    pub fn read(&self, address: u64, size: u64) -> Result<&[u8], Trap> {
+        let range = self.check_range(address, size)?;
+        Ok(&self.bytes[range])
+    }
+
+    pub fn write(&mut self, address: u64, bytes: &[u8]) -> Result<(), Trap> {
+        let range = self.check_writable_range(address, bytes.len() as u64)?;
+        self.bytes[range].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Copy `size` bytes from `source` to `destination`, which may overlap.
+    pub fn copy(&mut self, destination: u64, source: u64, size: u64) -> Result<(), Trap> {
+        let source_range = self.check_range(source, size)?;
+        let destination_range = self.check_writable_range(destination, size)?;
+        self.bytes.copy_within(source_range, destination_range.start);
+        Ok(())
+    }
+
+    pub fn fill(&mut self, address: u64, byte: u8, size: u64) -> Result<(), Trap> {
+        let range = self.check_writable_range(address, size)?;
+        self.bytes[range].fill(byte);
+        Ok(())
+    }
+
+    /// Read the NUL-terminated string starting at `address`, not including the terminator.
+    pub fn read_c_string(&self, address: u64) -> Result<&[u8], Trap> {
+        let start = self.check_range(address, 1)?.start;
+        match self.bytes[start..].iter().position(|&byte| byte == 0) {
+            Some(length) => Ok(&self.bytes[start..start + length]),
+            None => Err(Trap::fault(format!("unterminated string at address {address:#x}"))),
+        }
+    }
+
+    pub fn load(&self, context: &GlobalContext, address: u64, value_type: TypeHandle) -> Result<RuntimeValue, Trap> {
+        let size = context.type_size(value_type).unwrap_or(0);
+        let bytes = self.read(address, size)?;
+        Ok(self.decode(context, value_type, bytes))
+    }
+
+    pub fn store(&mut self, context: &GlobalContext, address: u64, value_type: TypeHandle, value: &RuntimeValue) -> Result<(), Trap> {
+        let size = context.type_size(value_type).unwrap_or(0);
+        let mut bytes = vec![0; size as usize];
+        self.encode(context, value_type, value, &mut bytes)?;
+        self.write(address, &bytes)
+    }
+
+    fn encode_integer(&self, raw: i128, bytes: &mut [u8]) {
+        let size = bytes.len();
+        let little_endian = raw.to_le_bytes();
+        bytes.copy_from_slice(&little_endian[..size]);
+        if self.endianness == Endianness::Big {
+            bytes.reverse();
+        }
+    }
+
+    fn decode_integer(&self, bytes: &[u8], signed: bool) -> i128 {
+        let mut little_endian = [0; 16];
+        little_endian[..bytes.len()].copy_from_slice(bytes);
+        if self.endianness == Endianness::Big {
+            little_endian[..bytes.len()].reverse();
+        }
+        let shift = 128 - 8 * bytes.len() as u32;
+        let raw = i128::from_le_bytes(little_endian) << shift;
+        if signed { raw >> shift } else { ((raw as u128) >> shift) as i128 }
+    }
+
+    /// Write the in-memory representation of `value` as type `value_type` into `bytes`, which is
+    /// exactly the size of the type.
+    pub fn encode(&self, context: &GlobalContext, value_type: TypeHandle, value: &RuntimeValue, bytes: &mut [u8]) -> Result<(), Trap> {
+        match (value_type.repr(context), value) {
+            (TypeRepr::Float32, &RuntimeValue::Float(value)) => {
+                self.encode_integer((value as f32).to_bits() as i128, bytes);
+            }
+            (TypeRepr::Float64, &RuntimeValue::Float(value)) => {
+                self.encode_integer(value.to_bits() as i128, bytes);
+            }
+            (TypeRepr::Array { item_type, .. }, RuntimeValue::Aggregate(items)) => {
+                let item_size = context.type_size(*item_type).unwrap_or(0) as usize;
+                for (index, item) in items.iter().enumerate() {
+                    self.encode(context, *item_type, item, &mut bytes[index * item_size..(index + 1) * item_size])?;
+                }
+            }
+            (TypeRepr::Tuple { .. } | TypeRepr::Structure { .. }, RuntimeValue::Aggregate(items)) => {
+                let offsets = context.type_registry().member_offsets(value_type).unwrap_or_default();
+                for ((item, offset), member_type) in items.iter().zip(offsets).zip(member_types(context, value_type)) {
+                    let start = offset as usize;
+                    let end = start + context.type_size(member_type).unwrap_or(0) as usize;
+                    self.encode(context, member_type, item, &mut bytes[start..end])?;
+                }
+            }
+            (_, RuntimeValue::Aggregate(..) | RuntimeValue::Float(..)) => {
+                return Err(Trap::fault(format!("cannot store value as type '{}'", value_type.path(context))));
+            }
+            (_, RuntimeValue::Void) => {}
+            (_, value) => {
+                self.encode_integer(value.as_integer()?, bytes);
+            }
+        }
+        Ok(())
+    }
+
+    /// Read a value of type `value_type` from its in-memory representation in `bytes`.
+    pub fn decode(&self, context: &GlobalContext, value_type: TypeHandle, bytes: &[u8]) -> RuntimeValue {
+        match *value_type.repr(context) {
+            TypeRepr::Boolean => RuntimeValue::Boolean(self.decode_integer(bytes, false) & 1 != 0),
+            TypeRepr::Integer { signed, .. } => RuntimeValue::Integer(self.decode_integer(bytes, signed)),
+            TypeRepr::Float32 => RuntimeValue::Float(f32::from_bits(self.decode_integer(bytes, false) as u32) as f64),
+            TypeRepr::Float64 => RuntimeValue::Float(f64::from_bits(self.decode_integer(bytes, false) as u64)),
+            TypeRepr::Pointer { .. } | TypeRepr::Function { .. } => {
+                RuntimeValue::Pointer(self.decode_integer(&bytes[..self.pointer_size as usize], false) as u64)
+            }
+            TypeRepr::Array { item_type, length: Some(length) } => {
+                let item_size = context.type_size(item_type).unwrap_or(0) as usize;
+                RuntimeValue::Aggregate((0..length as usize)
+                    .map(|index| self.decode(context, item_type, &bytes[index * item_size..(index + 1) * item_size]))
+                    .collect())
+            }
+            TypeRepr::Tuple { .. } | TypeRepr::Structure { .. } => {
+                let offsets = context.type_registry().member_offsets(value_type).unwrap_or_default();
+                RuntimeValue::Aggregate(offsets.into_iter().zip(member_types(context, value_type))
+                    .map(|(offset, member_type)| {
+                        let start = offset as usize;
+                        let end = start + context.type_size(member_type).unwrap_or(0) as usize;
+                        self.decode(context, member_type, &bytes[start..end])
+                    })
+                    .collect())
+            }
+            _ => RuntimeValue::Void,
+        }
+    }
+}
+
+/// The types of the members of a tuple or structure type, in order.
+pub fn member_types(context: &GlobalContext, aggregate_type: TypeHandle) -> Vec<TypeHandle> {
+    match aggregate_type.repr(context) {
+        TypeRepr::Tuple { item_types } => item_types.to_vec(),
+        TypeRepr::Structure { members, .. } => members.iter().map(|member| member.member_type).collect(),
+        _ => Vec::new(),
+    }
+}
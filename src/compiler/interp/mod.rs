@@ -0,0 +1,1072 @@
+//! An interpreter for Cupric IR, used to run programs without an external toolchain.
+//!
+//! Memory is a single byte array laid out according to the target, so pointers are plain
+//! addresses that can be freely converted to and from integers. Functions are given addresses in
+//! the unmapped region at the bottom of memory, which lets them be called through pointers while
+//! any attempt to read or write through such a pointer faults. Foreign functions are provided by
+//! a small built-in subset of the C library.
+//!
+//! The same interpreter evaluates calls in constant expressions at compile time, with restrictions
+//! that keep the result independent of the environment the compiler runs in.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::Write;
+use crate::ir::{CompilationUnit, FunctionDefinition};
+use crate::ir::instr::{Instruction, TerminatorInstruction};
+use crate::ir::value::{BlockLabel, Constant, FloatType, FloatValue, GlobalRegister, IntegerType, IntegerValue, LocalRegister, Value};
+use crate::sema::{display_symbol, ConversionOperation, GlobalContext, TypeHandle, TypeRepr};
+
+mod libc;
+mod memory;
+use memory::{member_types, Memory};
+
+/// The address of the first function. Address zero is reserved for null pointers.
+const FIRST_FUNCTION_ADDRESS: u64 = 16;
+/// The number of bytes available for stack allocations.
+const STACK_SIZE: u64 = 8 << 20;
+/// The deepest the call stack may get before the program is stopped for overflowing it.
+const MAX_CALL_DEPTH: usize = 1 << 16;
+/// The most callers listed in the notes of a runtime error.
+const MAX_BACKTRACE_NOTES: usize = 10;
+/// The most instructions a call in a constant expression may execute, which stops evaluation of
+/// functions that never return.
+const MAX_CONSTANT_EVALUATION_STEPS: u64 = 10_000_000;
+
+/// A value held in a register of the interpreted program.
+///
+/// Integers are kept in normalized form for their type, so signed values are sign-extended and
+/// unsigned values are zero-extended, which lets most arithmetic be done directly on `i128`.
+#[derive(Clone, PartialEq, Debug)]
+pub enum RuntimeValue {
+    Void,
+    Boolean(bool),
+    Integer(i128),
+    Float(f64),
+    Pointer(u64),
+    Aggregate(Vec<RuntimeValue>),
+}
+
+impl RuntimeValue {
+    fn as_integer(&self) -> Result<i128, Trap> {
+        match *self {
+            Self::Boolean(value) => Ok(value as i128),
+            Self::Integer(value) => Ok(value),
+            Self::Pointer(address) => Ok(address as i128),
+            _ => Err(Trap::fault(format!("expected an integer, got {self:?}"))),
+        }
+    }
+
+    fn as_pointer(&self) -> Result<u64, Trap> {
+        match *self {
+            Self::Integer(value) => Ok(value as u64),
+            Self::Pointer(address) => Ok(address),
+            _ => Err(Trap::fault(format!("expected a pointer, got {self:?}"))),
+        }
+    }
+
+    fn as_float(&self) -> Result<f64, Trap> {
+        match *self {
+            Self::Float(value) => Ok(value),
+            _ => Err(Trap::fault(format!("expected a floating-point number, got {self:?}"))),
+        }
+    }
+
+    fn as_boolean(&self) -> Result<bool, Trap> {
+        match *self {
+            Self::Boolean(value) => Ok(value),
+            _ => Err(Trap::fault(format!("expected a boolean, got {self:?}"))),
+        }
+    }
+}
+
+/// The reason execution of the program stopped early.
+enum Trap {
+    /// The program called `exit` with this status.
+    Exit(i32),
+    /// The program did something invalid, like dereferencing a null pointer.
+    Fault(String),
+    /// The program used a symbol with no definition, such as a global whose initializer is still
+    /// waiting to be evaluated at compile time. The demangled name of the symbol is given.
+    UndefinedSymbol {
+        name: String,
+        is_function: bool,
+    },
+    /// The program called a function declared by a package which was loaded from its interface
+    /// header, so the body of the function is unavailable.
+    InterfaceFunction {
+        name: String,
+        package_name: String,
+    },
+    /// The program used a value of a type whose layout isn't known yet, because it contains an
+    /// array whose length is still waiting to be evaluated at compile time.
+    UnresolvedTypeLayout {
+        type_name: String,
+    },
+}
+
+impl Trap {
+    fn fault(message: impl Into<String>) -> Self {
+        Self::Fault(message.into())
+    }
+}
+
+/// Fail if the layout of `value_type` isn't known yet, which can only happen while the array
+/// lengths of a package are being evaluated.
+fn check_layout(context: &GlobalContext, value_type: TypeHandle) -> Result<(), Trap> {
+    if context.type_registry().is_layout_known(value_type) {
+        Ok(())
+    }
+    else {
+        Err(Trap::UnresolvedTypeLayout {
+            type_name: value_type.path(context).to_string(),
+        })
+    }
+}
+
+/// Truncate `raw` to an integer of `size` bytes, then sign-extend or zero-extend it back.
+fn wrap_integer(raw: i128, size: u64, signed: bool) -> i128 {
+    let shift = 128_u32.saturating_sub(size as u32 * 8);
+    if shift == 0 {
+        raw
+    }
+    else if signed {
+        (raw << shift) >> shift
+    }
+    else {
+        (((raw as u128) << shift) >> shift) as i128
+    }
+}
+
+/// Run the program made up of `units`, which must include a definition of `main`, passing it
+/// `arguments` as `argv` if it accepts them. Anything the program prints is written to `output`.
+/// Returns the exit status of the program. Runtime errors only point to source locations for
+/// functions in the last unit, which should belong to the package whose source map is used to
+/// report errors.
+pub fn run_program(context: &GlobalContext, units: &[&CompilationUnit], arguments: &[String], output: &mut dyn Write) -> crate::Result<i32> {
+    let mut interpreter = Interpreter::new(context, units, output);
+
+    let main_function = interpreter.function_indices.get(b"main".as_slice()).copied()
+        .filter(|&index| matches!(interpreter.functions[index], Function::Defined { .. }))
+        .ok_or_else(|| Box::new(crate::Error::new(None, crate::ErrorKind::MissingMainFunction)))?;
+    let definition = interpreter.definition(main_function);
+    let arguments = match definition.parameter_registers().len() {
+        0 => None,
+        2 => Some(arguments),
+        _ => return Err(Box::new(crate::Error::new(
+            interpreter.function_span(main_function, definition.span()),
+            crate::ErrorKind::UnsupportedMainSignature,
+        ))),
+    };
+
+    let result = interpreter.initialize_globals()
+        .and_then(|()| interpreter.call_main(main_function, arguments));
+    let flush_result = interpreter.output.flush();
+    match result {
+        Ok(status) | Err(Trap::Exit(status)) => {
+            flush_result.map_err(|cause| interpreter.runtime_error(format!("failed to write output: {cause}")))?;
+            Ok(status)
+        }
+        Err(Trap::Fault(message)) => Err(interpreter.runtime_error(message)),
+        Err(Trap::UndefinedSymbol { name, .. }) => Err(interpreter.runtime_error(format!("undefined symbol '{name}'"))),
+        Err(Trap::InterfaceFunction { name, .. }) => Err(interpreter.runtime_error(format!("no definition of function '{name}'"))),
+        Err(Trap::UnresolvedTypeLayout { type_name }) => Err(interpreter.runtime_error(format!("layout of type '{type_name}' is unknown"))),
+    }
+}
+
+/// Evaluate a call to the function `callee` with `arguments` at compile time, where `units` must
+/// include the definition of the function and everything it uses.
+///
+/// Foreign functions other than LLVM intrinsics cannot be called, static data cannot be modified,
+/// and the result cannot contain pointers, since none of these would carry over to the compiled
+/// program. If evaluation fails, the kind of error to report is returned instead, which is
+/// [`UninitializedGlobal`](crate::ErrorKind::UninitializedGlobal) or
+/// [`UngeneratedFunction`](crate::ErrorKind::UngeneratedFunction) if a symbol missing from `units`
+/// was used, or [`InterfaceFunctionCall`](crate::ErrorKind::InterfaceFunctionCall) if a function
+/// of a package loaded from its interface header was called.
+pub fn evaluate_constant_call(context: &GlobalContext, units: &[&CompilationUnit], callee: &Constant, arguments: &[Constant]) -> Result<Constant, crate::ErrorKind> {
+    let failed = |message: String| crate::ErrorKind::ConstantEvaluationFailed { message };
+    let TypeRepr::Function { signature } = callee.get_type().repr(context) else {
+        return Err(failed("callee is not a function".to_owned()));
+    };
+    let mut output = std::io::sink();
+    let mut interpreter = Interpreter::new(context, units, &mut output);
+    interpreter.allow_foreign_calls = false;
+    interpreter.remaining_steps = Some(MAX_CONSTANT_EVALUATION_STEPS);
+
+    let result = interpreter.initialize_globals()
+        .and_then(|()| {
+            interpreter.memory.protect_static_data();
+            let function = interpreter.function_index(interpreter.evaluate_constant(callee)?.as_pointer()?)?;
+            // Variadic arguments have no parameter type to normalize them for
+            let arguments = arguments.iter().enumerate()
+                .map(|(index, argument)| {
+                    let value = interpreter.evaluate_constant(argument)?;
+                    match signature.parameter_types().get(index) {
+                        Some(&parameter_type) => interpreter.normalize(parameter_type, value),
+                        None => Ok(value),
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            match interpreter.functions[function] {
+                Function::Defined { .. } => {
+                    interpreter.push_frame(function, arguments, None)?;
+                    interpreter.execute()
+                }
+                Function::Foreign { name } => interpreter.call_foreign(name, &arguments),
+            }
+        });
+    match result {
+        Ok(value) => interpreter.materialize(signature.return_type(), value).map_err(failed),
+        Err(Trap::Exit(status)) => Err(failed(format!("program exited with status {status}"))),
+        Err(Trap::Fault(message)) => match interpreter.frames.last() {
+            Some(frame) => Err(failed(format!("{message} (in '{}')", interpreter.function_name(frame.function)))),
+            None => Err(failed(message)),
+        }
+        Err(Trap::UndefinedSymbol { name, is_function: true }) => Err(crate::ErrorKind::UngeneratedFunction { name }),
+        Err(Trap::UndefinedSymbol { name, is_function: false }) => Err(crate::ErrorKind::UninitializedGlobal { name }),
+        Err(Trap::InterfaceFunction { name, package_name }) => Err(crate::ErrorKind::InterfaceFunctionCall { name, package_name }),
+        Err(Trap::UnresolvedTypeLayout { type_name }) => Err(crate::ErrorKind::UnresolvedTypeLayout { type_name }),
+    }
+}
+
+enum Function<'a> {
+    Defined {
+        definition: &'a FunctionDefinition,
+        /// The index of each block in the function, keyed by label.
+        block_indices: HashMap<&'a [u8], usize>,
+        /// Whether source locations in the function belong to the source map used for errors.
+        has_source_map: bool,
+    },
+    Foreign {
+        name: &'a [u8],
+    },
+}
+
+struct Frame<'a> {
+    function: usize,
+    registers: HashMap<&'a [u8], RuntimeValue>,
+    block: usize,
+    next_instruction: usize,
+    /// The source location of the instruction currently being executed.
+    location: Option<crate::Span>,
+    /// The stack pointer to restore when the function returns.
+    stack_pointer: u64,
+    /// The register in the calling frame which receives the return value.
+    result: Option<&'a LocalRegister>,
+}
+
+struct Interpreter<'a> {
+    context: &'a GlobalContext,
+    units: &'a [&'a CompilationUnit],
+    functions: Vec<Function<'a>>,
+    function_indices: HashMap<&'a [u8], usize>,
+    global_addresses: HashMap<&'a [u8], u64>,
+    memory: Memory,
+    frames: Vec<Frame<'a>>,
+    open_files: HashMap<u64, libc::OpenFile>,
+    output: &'a mut dyn Write,
+    /// Whether foreign functions other than LLVM intrinsics may be called.
+    allow_foreign_calls: bool,
+    /// The number of instructions left to execute before giving up, if limited.
+    remaining_steps: Option<u64>,
+}
+
+impl<'a> Interpreter<'a> {
+    fn new(context: &'a GlobalContext, units: &'a [&'a CompilationUnit], output: &'a mut dyn Write) -> Self {
+        let mut functions = Vec::new();
+        let mut function_indices = HashMap::new();
+        for (unit_index, unit) in units.iter().enumerate() {
+            for definition in unit.function_definitions() {
+                let block_indices = definition.blocks().iter()
+                    .enumerate()
+                    .map(|(index, block)| (block.label().identifier(), index))
+                    .collect();
+                function_indices.insert(definition.register().identifier(), functions.len());
+                functions.push(Function::Defined {
+                    definition,
+                    block_indices,
+                    has_source_map: unit_index + 1 == units.len(),
+                });
+            }
+        }
+        for unit in units {
+            for function in unit.external_functions() {
+                let name = function.register().identifier();
+                function_indices.entry(name).or_insert_with(|| {
+                    functions.push(Function::Foreign { name });
+                    functions.len() - 1
+                });
+            }
+        }
+
+        let mapped_start = (FIRST_FUNCTION_ADDRESS + functions.len() as u64).next_multiple_of(4096);
+        Self {
+            context,
+            units,
+            functions,
+            function_indices,
+            global_addresses: HashMap::new(),
+            memory: Memory::new(context, mapped_start),
+            frames: Vec::new(),
+            open_files: HashMap::new(),
+            output,
+            allow_foreign_calls: true,
+            remaining_steps: None,
+        }
+    }
+
+    /// Allocate static memory for every global variable, then initialize the ones defined by the
+    /// program. Globals which are declared but never defined are left zeroed.
+    fn initialize_globals(&mut self) -> Result<(), Trap> {
+        let context = self.context;
+        let units = self.units;
+        let allocate = |memory: &mut Memory, value_type: TypeHandle| {
+            check_layout(context, value_type)?;
+            let size = context.type_size(value_type).unwrap_or(0);
+            let alignment = context.type_alignment(value_type).unwrap_or(1);
+            memory.allocate(size, alignment).ok_or_else(|| Trap::fault("out of memory for global variables"))
+        };
+        for unit in units {
+            for variable in unit.global_variables() {
+                let address = allocate(&mut self.memory, variable.value().get_type())?;
+                self.global_addresses.insert(variable.register().identifier(), address);
+            }
+        }
+        for unit in units {
+            for variable in unit.external_global_variables() {
+                let identifier = variable.register().identifier();
+                if !self.global_addresses.contains_key(identifier) && !self.function_indices.contains_key(identifier) {
+                    let address = allocate(&mut self.memory, variable.value_type())?;
+                    self.global_addresses.insert(identifier, address);
+                }
+            }
+        }
+        self.memory.create_stack(STACK_SIZE).ok_or_else(|| Trap::fault("out of memory for the stack"))?;
+
+        for unit in units {
+            for variable in unit.global_variables() {
+                let value = self.evaluate_constant(variable.value())?;
+                let address = self.global_addresses[variable.register().identifier()];
+                self.memory.store(context, address, variable.value().get_type(), &value)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn call_main(&mut self, main_function: usize, arguments: Option<&[String]>) -> Result<i32, Trap> {
+        let mut argument_values = Vec::new();
+        if let Some(arguments) = arguments {
+            // `argv` points to an array of strings terminated by a null pointer
+            let parameters = self.definition(main_function).parameter_registers();
+            let argv_type = parameters[1].get_type();
+            let &TypeRepr::Pointer { pointee_type, .. } = argv_type.repr(self.context) else {
+                return Err(Trap::fault("'argv' must be a pointer"));
+            };
+            let string_type = match *pointee_type.repr(self.context) {
+                TypeRepr::Array { item_type, length: None } => item_type,
+                _ => pointee_type,
+            };
+            let pointer_size = self.context.target().pointer_size();
+            let argv = self.heap_allocate((arguments.len() as u64 + 1) * pointer_size)?;
+            for (index, argument) in arguments.iter().enumerate() {
+                let mut bytes = argument.as_bytes().to_vec();
+                bytes.push(0);
+                let string = self.heap_allocate(bytes.len() as u64)?;
+                self.memory.write(string, &bytes)?;
+                self.memory.store(self.context, argv + index as u64 * pointer_size, string_type, &RuntimeValue::Pointer(string))?;
+            }
+            argument_values.push(self.normalize(parameters[0].get_type(), RuntimeValue::Integer(arguments.len() as i128))?);
+            argument_values.push(RuntimeValue::Pointer(argv));
+        }
+
+        self.push_frame(main_function, argument_values, None)?;
+        match self.execute()? {
+            RuntimeValue::Void => Ok(0),
+            value => Ok(value.as_integer()? as i32),
+        }
+    }
+
+    fn heap_allocate(&mut self, size: u64) -> Result<u64, Trap> {
+        self.memory.heap_allocate(size).ok_or_else(|| Trap::fault("out of memory"))
+    }
+
+    fn definition(&self, function: usize) -> &'a FunctionDefinition {
+        match self.functions[function] {
+            Function::Defined { definition, .. } => definition,
+            Function::Foreign { .. } => panic!("foreign function has no definition"),
+        }
+    }
+
+    fn function_name(&self, function: usize) -> String {
+        display_symbol(match self.functions[function] {
+            Function::Defined { definition, .. } => definition.register().identifier(),
+            Function::Foreign { name } => name,
+        })
+    }
+
+    /// Get `span` if it can be reported for a location in `function`.
+    fn function_span(&self, function: usize, span: Option<crate::Span>) -> Option<crate::Span> {
+        match self.functions[function] {
+            Function::Defined { has_source_map: true, .. } => span,
+            _ => None,
+        }
+    }
+
+    fn runtime_error(&self, message: String) -> Box<crate::Error> {
+        let mut frames = self.frames.iter().rev();
+        let (function_name, span) = match frames.next() {
+            Some(frame) => (self.function_name(frame.function), self.function_span(frame.function, frame.location)),
+            None => ("<startup>".to_owned(), None),
+        };
+        let mut error = crate::Error::new(span, crate::ErrorKind::RuntimeError {
+            function_name,
+            message,
+        });
+
+        // Runs of frames for the same function are collapsed, which keeps deep recursion readable
+        let mut callers: Vec<(usize, usize)> = Vec::new();
+        for frame in frames {
+            match callers.last_mut() {
+                Some((function, count)) if *function == frame.function => *count += 1,
+                _ => callers.push((frame.function, 1)),
+            }
+        }
+        for &(function, count) in callers.iter().take(MAX_BACKTRACE_NOTES) {
+            let repeated = if count > 1 { format!(" ({count} times)") } else { String::new() };
+            error.add_note(format!("called from '{}'{repeated}", self.function_name(function)));
+        }
+        if callers.len() > MAX_BACKTRACE_NOTES {
+            error.add_note(format!("... and {} more callers", callers.len() - MAX_BACKTRACE_NOTES));
+        }
+        Box::new(error)
+    }
+
+    fn write_output(&mut self, bytes: &[u8]) -> Result<(), Trap> {
+        self.output.write_all(bytes).map_err(|cause| Trap::fault(format!("failed to write output: {cause}")))
+    }
+
+    fn call_foreign(&mut self, name: &[u8], arguments: &[RuntimeValue]) -> Result<RuntimeValue, Trap> {
+        if !self.allow_foreign_calls && !name.starts_with(b"llvm.") {
+            if let Some(package_name) = self.context.interface_function_package(name) {
+                return Err(Trap::InterfaceFunction {
+                    name: display_symbol(name),
+                    package_name: package_name.to_owned(),
+                });
+            }
+            return Err(Trap::fault(format!("cannot call foreign function '{}' in a constant expression", display_symbol(name))));
+        }
+        libc::call(self, name, arguments)
+    }
+
+    fn push_frame(&mut self, function: usize, arguments: Vec<RuntimeValue>, result: Option<&'a LocalRegister>) -> Result<(), Trap> {
+        if self.frames.len() >= MAX_CALL_DEPTH {
+            return Err(Trap::fault("stack overflow"));
+        }
+        let definition = self.definition(function);
+        let registers = definition.parameter_registers().iter()
+            .map(LocalRegister::identifier)
+            .zip(arguments)
+            .collect();
+        self.frames.push(Frame {
+            function,
+            registers,
+            block: 0,
+            next_instruction: 0,
+            location: definition.span(),
+            stack_pointer: self.memory.stack_pointer(),
+            result,
+        });
+        Ok(())
+    }
+
+    /// Run until the outermost frame returns, giving its return value.
+    fn execute(&mut self) -> Result<RuntimeValue, Trap> {
+        loop {
+            if let Some(remaining_steps) = &mut self.remaining_steps {
+                *remaining_steps = remaining_steps.checked_sub(1)
+                    .ok_or_else(|| Trap::fault(format!("exceeded the limit of {MAX_CONSTANT_EVALUATION_STEPS} instructions")))?;
+            }
+
+            let frame = self.frames.last().expect("no frame to execute");
+            let block = &self.definition(frame.function).blocks()[frame.block];
+            let index = frame.next_instruction;
+            if let Some(instruction) = block.body().get(index) {
+                let frame = self.frames.last_mut().unwrap();
+                frame.next_instruction += 1;
+                frame.location = block.body_locations()[index];
+                self.execute_instruction(instruction)?;
+            }
+            else {
+                self.frames.last_mut().unwrap().location = block.terminator_location();
+                if let Some(value) = self.execute_terminator(block.terminator())? {
+                    return Ok(value);
+                }
+            }
+        }
+    }
+
+    /// Execute `terminator`, giving the return value if it returns from the outermost frame.
+    fn execute_terminator(&mut self, terminator: &'a TerminatorInstruction) -> Result<Option<RuntimeValue>, Trap> {
+        match terminator {
+            TerminatorInstruction::Return { value } => {
+                let value = self.evaluate(value)?;
+                let frame = self.frames.pop().unwrap();
+                self.memory.restore_stack_pointer(frame.stack_pointer);
+                match self.frames.last_mut() {
+                    Some(caller) => {
+                        if let Some(result) = frame.result {
+                            caller.registers.insert(result.identifier(), value);
+                        }
+                        Ok(None)
+                    }
+                    None => Ok(Some(value)),
+                }
+            }
+            TerminatorInstruction::Branch { to_label } => {
+                self.branch(to_label)?;
+                Ok(None)
+            }
+            TerminatorInstruction::ConditionalBranch { condition, consequent_label, alternative_label } => {
+                if self.evaluate(condition)?.as_boolean()? {
+                    self.branch(consequent_label)?;
+                }
+                else {
+                    self.branch(alternative_label)?;
+                }
+                Ok(None)
+            }
+            TerminatorInstruction::Unreachable => Err(Trap::fault("reached unreachable code")),
+        }
+    }
+
+    /// Move to the block labeled `label`, assigning the values of its phis for the block being
+    /// left.
+    fn branch(&mut self, label: &'a BlockLabel) -> Result<(), Trap> {
+        let frame = self.frames.last().unwrap();
+        let Function::Defined { definition, block_indices, .. } = &self.functions[frame.function] else {
+            unreachable!()
+        };
+        let definition: &'a FunctionDefinition = definition;
+        let target = *block_indices.get(label.identifier()).ok_or_else(|| {
+            Trap::fault(format!("branch to unknown block '{}'", String::from_utf8_lossy(label.identifier())))
+        })?;
+
+        let predecessor = definition.blocks()[frame.block].label();
+        let mut phi_values = Vec::new();
+        for phi in definition.blocks()[target].phis() {
+            let (value, _) = phi.inputs.iter()
+                .find(|(_, input_label)| input_label == predecessor)
+                .ok_or_else(|| Trap::fault(format!(
+                    "phi '%{}' has no input for the block branched from",
+                    String::from_utf8_lossy(phi.result.identifier()),
+                )))?;
+            phi_values.push((phi.result.identifier(), self.evaluate(value)?));
+        }
+
+        let frame = self.frames.last_mut().unwrap();
+        frame.registers.extend(phi_values);
+        frame.block = target;
+        frame.next_instruction = 0;
+        Ok(())
+    }
+
+    fn set_register(&mut self, register: &'a LocalRegister, value: RuntimeValue) -> Result<(), Trap> {
+        let value = self.normalize(register.get_type(), value)?;
+        self.frames.last_mut().unwrap().registers.insert(register.identifier(), value);
+        Ok(())
+    }
+
+    fn type_size(&self, value_type: TypeHandle) -> u64 {
+        self.context.type_size(value_type).unwrap_or(0)
+    }
+
+    /// Get the size and signedness of integers of type `value_type`, treating pointers as
+    /// unsigned integers.
+    fn integer_layout(&self, value_type: TypeHandle) -> (u64, bool) {
+        match *value_type.repr(self.context) {
+            TypeRepr::Integer { size, signed } => (size, signed),
+            TypeRepr::Boolean => (1, false),
+            _ => (self.context.target().pointer_size(), false),
+        }
+    }
+
+    /// Bring `value` into the normal form for a value of type `value_type`.
+    fn normalize(&self, value_type: TypeHandle, value: RuntimeValue) -> Result<RuntimeValue, Trap> {
+        Ok(match *value_type.repr(self.context) {
+            TypeRepr::Boolean => RuntimeValue::Boolean(value.as_integer()? & 1 != 0),
+            TypeRepr::Integer { size, signed } => RuntimeValue::Integer(wrap_integer(value.as_integer()?, size, signed)),
+            TypeRepr::Float32 => RuntimeValue::Float(value.as_float()? as f32 as f64),
+            TypeRepr::Float64 => RuntimeValue::Float(value.as_float()?),
+            TypeRepr::Pointer { .. } | TypeRepr::Function { .. } => {
+                let address = wrap_integer(value.as_integer()?, self.context.target().pointer_size(), false);
+                RuntimeValue::Pointer(address as u64)
+            }
+            _ => value,
+        })
+    }
+
+    fn zero_value(&self, value_type: TypeHandle) -> RuntimeValue {
+        match *value_type.repr(self.context) {
+            TypeRepr::Boolean => RuntimeValue::Boolean(false),
+            TypeRepr::Integer { .. } => RuntimeValue::Integer(0),
+            TypeRepr::Float32 | TypeRepr::Float64 => RuntimeValue::Float(0.0),
+            TypeRepr::Pointer { .. } | TypeRepr::Function { .. } => RuntimeValue::Pointer(0),
+            TypeRepr::Array { item_type, length: Some(length) } => {
+                RuntimeValue::Aggregate(vec![self.zero_value(item_type); length as usize])
+            }
+            TypeRepr::Tuple { .. } | TypeRepr::Structure { .. } => {
+                RuntimeValue::Aggregate(member_types(self.context, value_type).into_iter()
+                    .map(|member_type| self.zero_value(member_type))
+                    .collect())
+            }
+            _ => RuntimeValue::Void,
+        }
+    }
+
+    /// Get the index of the function at `address`.
+    fn function_index(&self, address: u64) -> Result<usize, Trap> {
+        address.checked_sub(FIRST_FUNCTION_ADDRESS)
+            .map(|index| index as usize)
+            .filter(|&index| index < self.functions.len())
+            .ok_or_else(|| Trap::fault(format!("call through invalid function pointer {address:#x}")))
+    }
+
+    fn symbol_address(&self, register: &GlobalRegister) -> Result<u64, Trap> {
+        let identifier = register.identifier();
+        if let Some(&index) = self.function_indices.get(identifier) {
+            Ok(FIRST_FUNCTION_ADDRESS + index as u64)
+        }
+        else if let Some(&address) = self.global_addresses.get(identifier) {
+            Ok(address)
+        }
+        else {
+            Err(Trap::UndefinedSymbol {
+                name: display_symbol(identifier),
+                is_function: matches!(register.get_type().repr(self.context), TypeRepr::Function { .. }),
+            })
+        }
+    }
+
+    fn evaluate(&self, value: &Value) -> Result<RuntimeValue, Trap> {
+        match value {
+            Value::Void => Ok(RuntimeValue::Void),
+            Value::Constant(constant) => self.evaluate_constant(constant),
+            Value::Register(register) => {
+                self.frames.last()
+                    .and_then(|frame| frame.registers.get(register.identifier()))
+                    .cloned()
+                    .ok_or_else(|| Trap::fault(format!(
+                        "use of undefined register '%{}'",
+                        String::from_utf8_lossy(register.identifier()),
+                    )))
+            }
+            Value::Indirect { pointer, pointee_type } => {
+                let address = self.evaluate(pointer)?.as_pointer()?;
+                self.memory.load(self.context, address, *pointee_type)
+            }
+            // The self value is already included in the arguments of the call
+            Value::BoundFunction { function_value, .. } => self.evaluate(function_value),
+            Value::Never | Value::Break | Value::Continue => Err(Trap::fault("cannot evaluate control flow value")),
+        }
+    }
+
+    fn evaluate_constant(&self, constant: &Constant) -> Result<RuntimeValue, Trap> {
+        match constant {
+            Constant::Undefined(value_type) | Constant::Poison(value_type) |
+            Constant::ZeroInitializer(value_type) | Constant::NullPointer(value_type) => {
+                Ok(self.zero_value(*value_type))
+            }
+            Constant::Boolean(value) => Ok(RuntimeValue::Boolean(*value)),
+            Constant::Integer(value) => Ok(RuntimeValue::Integer(value.raw())),
+            Constant::Float(value) => Ok(RuntimeValue::Float(value.raw())),
+            Constant::String { array_type, value } => {
+                let length = match *array_type.repr(self.context) {
+                    TypeRepr::Array { length: Some(length), .. } => length as usize,
+                    _ => value.len(),
+                };
+                Ok(RuntimeValue::Aggregate(value.bytes().iter()
+                    .map(|&byte| RuntimeValue::Integer(byte as i128))
+                    .chain(std::iter::repeat(RuntimeValue::Integer(0)))
+                    .take(length)
+                    .collect()))
+            }
+            Constant::Array { items, .. } | Constant::Tuple { items, .. } | Constant::Structure { members: items, .. } => {
+                Ok(RuntimeValue::Aggregate(items.iter()
+                    .map(|item| self.evaluate_constant(item))
+                    .collect::<Result<_, _>>()?))
+            }
+            Constant::Register(register) => Ok(RuntimeValue::Pointer(self.symbol_address(register)?)),
+            Constant::Indirect { pointee_type, pointer } => {
+                let address = self.evaluate_constant(pointer)?.as_pointer()?;
+                self.memory.load(self.context, address, *pointee_type)
+            }
+            Constant::Convert { operation, result_type, value } => {
+                let converted = self.evaluate_constant(value)?;
+                self.convert(*operation, converted, value.get_type(), *result_type)
+            }
+            Constant::GetElementPointer { aggregate_type, pointer, indices, .. } => {
+                let address = self.evaluate_constant(pointer)?.as_pointer()?;
+                let indices = indices.iter()
+                    .map(|index| self.evaluate_constant(index)?.as_integer())
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(RuntimeValue::Pointer(self.element_address(*aggregate_type, address, &indices)?))
+            }
+            Constant::Type(..) | Constant::Module(..) => Err(Trap::fault("cannot evaluate a type or module at runtime")),
+        }
+    }
+
+    /// Convert `value` of type `value_type` back into a constant, which fails for pointers other
+    /// than null since they cannot point into the compiled program.
+    fn materialize(&self, value_type: TypeHandle, value: RuntimeValue) -> Result<Constant, String> {
+        let value = self.normalize(value_type, value).map_err(|_| format!("invalid result of type '{}'", value_type.path(self.context)))?;
+        match (value_type.repr(self.context), value) {
+            (TypeRepr::Boolean, RuntimeValue::Boolean(value)) => Ok(Constant::Boolean(value)),
+            (TypeRepr::Integer { .. }, RuntimeValue::Integer(raw)) => {
+                let integer_type = IntegerType::from_handle(value_type).expect("integer type should be primitive");
+                Ok(Constant::Integer(IntegerValue::new(integer_type, raw)))
+            }
+            (TypeRepr::Float32 | TypeRepr::Float64, RuntimeValue::Float(raw)) => {
+                let float_type = FloatType::from_handle(value_type).expect("float type should be primitive");
+                Ok(Constant::Float(FloatValue::new(float_type, raw)))
+            }
+            (TypeRepr::Pointer { .. }, RuntimeValue::Pointer(0)) => Ok(Constant::NullPointer(value_type)),
+            (TypeRepr::Pointer { .. } | TypeRepr::Function { .. }, _) => {
+                Err(format!("result of type '{}' contains a pointer", value_type.path(self.context)))
+            }
+            (&TypeRepr::Array { item_type, .. }, RuntimeValue::Aggregate(items)) => Ok(Constant::Array {
+                array_type: value_type,
+                items: items.into_iter()
+                    .map(|item| self.materialize(item_type, item))
+                    .collect::<Result<_, _>>()?,
+            }),
+            (TypeRepr::Tuple { .. }, RuntimeValue::Aggregate(items)) => Ok(Constant::Tuple {
+                tuple_type: value_type,
+                items: self.materialize_members(value_type, items)?,
+            }),
+            (TypeRepr::Structure { .. }, RuntimeValue::Aggregate(members)) => Ok(Constant::Structure {
+                struct_type: value_type,
+                members: self.materialize_members(value_type, members)?,
+            }),
+            _ => Err(format!("cannot use a result of type '{}' as a constant", value_type.path(self.context))),
+        }
+    }
+
+    fn materialize_members(&self, aggregate_type: TypeHandle, members: Vec<RuntimeValue>) -> Result<Vec<Constant>, String> {
+        std::iter::zip(member_types(self.context, aggregate_type), members)
+            .map(|(member_type, member)| self.materialize(member_type, member))
+            .collect()
+    }
+
+    /// Compute the address of the element selected by `indices` from a pointer to `pointee_type`,
+    /// following the rules of `getelementptr` in LLVM.
+    fn element_address(&self, pointee_type: TypeHandle, address: u64, indices: &[i128]) -> Result<u64, Trap> {
+        // Unsized arrays are pointed to by pointers to their first item
+        let mut current_type = match *pointee_type.repr(self.context) {
+            TypeRepr::Array { item_type, length: None } => item_type,
+            _ => pointee_type,
+        };
+        let Some((&first_index, indices)) = indices.split_first() else {
+            return Ok(address);
+        };
+
+        let mut offset = first_index.wrapping_mul(self.type_size(current_type) as i128);
+        for &index in indices {
+            match *current_type.repr(self.context) {
+                TypeRepr::Array { item_type, .. } => {
+                    offset = offset.wrapping_add(index.wrapping_mul(self.type_size(item_type) as i128));
+                    current_type = item_type;
+                }
+                TypeRepr::Tuple { .. } | TypeRepr::Structure { .. } => {
+                    let offsets = self.context.type_registry().member_offsets(current_type).unwrap_or_default();
+                    let member_offset = usize::try_from(index).ok()
+                        .and_then(|index| offsets.get(index))
+                        .ok_or_else(|| Trap::fault(format!("member index {index} is out of range")))?;
+                    offset = offset.wrapping_add(*member_offset as i128);
+                    current_type = member_types(self.context, current_type)[index as usize];
+                }
+                _ => return Err(Trap::fault(format!("cannot index into type '{}'", current_type.path(self.context)))),
+            }
+        }
+
+        let address = wrap_integer((address as i128).wrapping_add(offset), self.context.target().pointer_size(), false);
+        Ok(address as u64)
+    }
+
+    fn convert(&self, operation: ConversionOperation, value: RuntimeValue, from_type: TypeHandle, to_type: TypeHandle) -> Result<RuntimeValue, Trap> {
+        let (from_size, _) = self.integer_layout(from_type);
+        let converted = match operation {
+            ConversionOperation::Truncate => value,
+            ConversionOperation::ZeroExtend => RuntimeValue::Integer(wrap_integer(value.as_integer()?, from_size, false)),
+            ConversionOperation::SignExtend => match value {
+                RuntimeValue::Boolean(value) => RuntimeValue::Integer(-(value as i128)),
+                value => RuntimeValue::Integer(wrap_integer(value.as_integer()?, from_size, true)),
+            },
+            ConversionOperation::FloatTruncate | ConversionOperation::FloatExtend => value,
+            ConversionOperation::FloatToSigned | ConversionOperation::FloatToUnsigned => {
+                let float = value.as_float()?.trunc();
+                let (to_size, to_signed) = self.integer_layout(to_type);
+                let raw = float as i128;
+                if !float.is_finite() || raw as f64 != float || wrap_integer(raw, to_size, to_signed) != raw {
+                    return Err(Trap::fault(format!("{float} is out of range for '{}'", to_type.path(self.context))));
+                }
+                RuntimeValue::Integer(raw)
+            }
+            ConversionOperation::UnsignedToFloat | ConversionOperation::SignedToFloat => {
+                let signed = operation == ConversionOperation::SignedToFloat;
+                let raw = wrap_integer(value.as_integer()?, from_size, signed);
+                match to_type.repr(self.context) {
+                    TypeRepr::Float32 => RuntimeValue::Float(raw as f32 as f64),
+                    _ => RuntimeValue::Float(raw as f64),
+                }
+            }
+            ConversionOperation::PointerToInteger => RuntimeValue::Integer(value.as_pointer()? as i128),
+            ConversionOperation::IntegerToPointer => RuntimeValue::Pointer(value.as_integer()? as u64),
+            ConversionOperation::BitwiseCast => {
+                let is_pointer = |value_type: TypeHandle| {
+                    matches!(value_type.repr(self.context), TypeRepr::Pointer { .. } | TypeRepr::Function { .. })
+                };
+                if is_pointer(from_type) && is_pointer(to_type) {
+                    value
+                }
+                else {
+                    let size = self.type_size(from_type);
+                    if size != self.type_size(to_type) {
+                        return Err(Trap::fault("bitwise cast between types of different sizes"));
+                    }
+                    let mut bytes = vec![0; size as usize];
+                    self.memory.encode(self.context, from_type, &value, &mut bytes)?;
+                    self.memory.decode(self.context, to_type, &bytes)
+                }
+            }
+        };
+        self.normalize(to_type, converted)
+    }
+
+    /// Evaluate both operands of a binary operation and apply `integer_operation` or
+    /// `float_operation` to them, depending on their kind.
+    fn binary_operation(
+        &self,
+        lhs: &Value,
+        rhs: &Value,
+        integer_operation: impl FnOnce(i128, i128) -> Result<i128, Trap>,
+        float_operation: impl FnOnce(f64, f64) -> f64,
+    ) -> Result<RuntimeValue, Trap> {
+        match (self.evaluate(lhs)?, self.evaluate(rhs)?) {
+            (RuntimeValue::Float(lhs), RuntimeValue::Float(rhs)) => Ok(RuntimeValue::Float(float_operation(lhs, rhs))),
+            (lhs, rhs) => Ok(RuntimeValue::Integer(integer_operation(lhs.as_integer()?, rhs.as_integer()?)?)),
+        }
+    }
+
+    /// Evaluate both operands of a bitwise operation and apply `operation` to them.
+    fn integer_operation(&self, lhs: &Value, rhs: &Value, operation: impl FnOnce(i128, i128) -> Result<i128, Trap>) -> Result<RuntimeValue, Trap> {
+        let lhs = self.evaluate(lhs)?.as_integer()?;
+        let rhs = self.evaluate(rhs)?.as_integer()?;
+        Ok(RuntimeValue::Integer(operation(lhs, rhs)?))
+    }
+
+    /// Divide or take the remainder of two integers of type `value_type` with `operation`,
+    /// faulting on division by zero and overflow.
+    fn integer_division(&self, value_type: TypeHandle, lhs: i128, rhs: i128, operation: fn(i128, i128) -> i128) -> Result<i128, Trap> {
+        if rhs == 0 {
+            return Err(Trap::fault("division by zero"));
+        }
+        let (size, signed) = self.integer_layout(value_type);
+        // Only the quotient can overflow, but the remainder is undefined in the same case
+        let quotient = lhs / rhs;
+        if wrap_integer(quotient, size, signed) != quotient {
+            return Err(Trap::fault(format!("overflow in division of {lhs} by {rhs}")));
+        }
+        Ok(operation(lhs, rhs))
+    }
+
+    /// Check that `amount` is a valid number of bits to shift an integer of type `value_type`.
+    fn shift_amount(&self, value_type: TypeHandle, amount: i128) -> Result<u32, Trap> {
+        let (size, _) = self.integer_layout(value_type);
+        if amount < 0 || amount >= (size * 8) as i128 {
+            return Err(Trap::fault(format!("shift amount {amount} is out of range for '{}'", value_type.path(self.context))));
+        }
+        Ok(amount as u32)
+    }
+
+    fn compare(&mut self, result: &'a LocalRegister, lhs: &Value, rhs: &Value, predicate: fn(Option<Ordering>) -> bool) -> Result<(), Trap> {
+        let ordering = match (self.evaluate(lhs)?, self.evaluate(rhs)?) {
+            (RuntimeValue::Float(lhs), RuntimeValue::Float(rhs)) => lhs.partial_cmp(&rhs),
+            (lhs, rhs) => Some(lhs.as_integer()?.cmp(&rhs.as_integer()?)),
+        };
+        self.set_register(result, RuntimeValue::Boolean(predicate(ordering)))
+    }
+
+    fn aggregate_indices(&self, indices: &[Value]) -> Result<Vec<usize>, Trap> {
+        indices.iter()
+            .map(|index| Ok(self.evaluate(index)?.as_integer()? as usize))
+            .collect()
+    }
+
+    fn execute_instruction(&mut self, instruction: &'a Instruction) -> Result<(), Trap> {
+        match instruction {
+            Instruction::Negate { result, operand } => {
+                let value = match self.evaluate(operand)? {
+                    RuntimeValue::Float(value) => RuntimeValue::Float(-value),
+                    value => RuntimeValue::Integer(value.as_integer()?.wrapping_neg()),
+                };
+                self.set_register(result, value)
+            }
+            Instruction::Not { result, operand } => {
+                let value = RuntimeValue::Integer(!self.evaluate(operand)?.as_integer()?);
+                self.set_register(result, value)
+            }
+            Instruction::Add { result, lhs, rhs } => {
+                let value = self.binary_operation(lhs, rhs, |lhs, rhs| Ok(lhs.wrapping_add(rhs)), |lhs, rhs| lhs + rhs)?;
+                self.set_register(result, value)
+            }
+            Instruction::Subtract { result, lhs, rhs } => {
+                let value = self.binary_operation(lhs, rhs, |lhs, rhs| Ok(lhs.wrapping_sub(rhs)), |lhs, rhs| lhs - rhs)?;
+                self.set_register(result, value)
+            }
+            Instruction::Multiply { result, lhs, rhs } => {
+                let value = self.binary_operation(lhs, rhs, |lhs, rhs| Ok(lhs.wrapping_mul(rhs)), |lhs, rhs| lhs * rhs)?;
+                self.set_register(result, value)
+            }
+            Instruction::Divide { result, lhs, rhs } => {
+                let value_type = result.get_type();
+                let value = self.binary_operation(
+                    lhs,
+                    rhs,
+                    |lhs, rhs| self.integer_division(value_type, lhs, rhs, |lhs, rhs| lhs / rhs),
+                    |lhs, rhs| lhs / rhs,
+                )?;
+                self.set_register(result, value)
+            }
+            Instruction::Remainder { result, lhs, rhs } => {
+                let value_type = result.get_type();
+                let value = self.binary_operation(
+                    lhs,
+                    rhs,
+                    |lhs, rhs| self.integer_division(value_type, lhs, rhs, |lhs, rhs| lhs % rhs),
+                    |lhs, rhs| lhs % rhs,
+                )?;
+                self.set_register(result, value)
+            }
+            Instruction::ShiftLeft { result, lhs, rhs } => {
+                let value_type = lhs.get_type();
+                let value = self.integer_operation(lhs, rhs, |lhs, rhs| Ok(lhs << self.shift_amount(value_type, rhs)?))?;
+                self.set_register(result, value)
+            }
+            Instruction::ShiftRight { result, lhs, rhs } => {
+                // Unsigned values are never negative, so this is a logical shift for them
+                let value_type = lhs.get_type();
+                let value = self.integer_operation(lhs, rhs, |lhs, rhs| Ok(lhs >> self.shift_amount(value_type, rhs)?))?;
+                self.set_register(result, value)
+            }
+            Instruction::And { result, lhs, rhs } => {
+                let value = self.integer_operation(lhs, rhs, |lhs, rhs| Ok(lhs & rhs))?;
+                self.set_register(result, value)
+            }
+            Instruction::Or { result, lhs, rhs } => {
+                let value = self.integer_operation(lhs, rhs, |lhs, rhs| Ok(lhs | rhs))?;
+                self.set_register(result, value)
+            }
+            Instruction::Xor { result, lhs, rhs } => {
+                let value = self.integer_operation(lhs, rhs, |lhs, rhs| Ok(lhs ^ rhs))?;
+                self.set_register(result, value)
+            }
+            Instruction::ExtractValue { result, aggregate, indices } => {
+                let mut value = self.evaluate(aggregate)?;
+                for index in self.aggregate_indices(indices)? {
+                    value = match value {
+                        RuntimeValue::Aggregate(mut items) if index < items.len() => items.swap_remove(index),
+                        _ => return Err(Trap::fault(format!("invalid aggregate index {index}"))),
+                    };
+                }
+                self.set_register(result, value)
+            }
+            Instruction::InsertValue { result, aggregate, value, indices } => {
+                let mut aggregate = self.evaluate(aggregate)?;
+                let mut target = &mut aggregate;
+                for index in self.aggregate_indices(indices)? {
+                    target = match target {
+                        RuntimeValue::Aggregate(items) if index < items.len() => &mut items[index],
+                        _ => return Err(Trap::fault(format!("invalid aggregate index {index}"))),
+                    };
+                }
+                *target = self.evaluate(value)?;
+                self.set_register(result, aggregate)
+            }
+            Instruction::StackAllocate { result } => {
+                let &TypeRepr::Pointer { pointee_type, .. } = result.get_type().repr(self.context) else {
+                    return Err(Trap::fault("stack allocation must produce a pointer"));
+                };
+                check_layout(self.context, pointee_type)?;
+                let size = self.type_size(pointee_type);
+                let alignment = self.context.type_alignment(pointee_type).unwrap_or(1);
+                let address = self.memory.stack_allocate(size, alignment)?;
+                self.set_register(result, RuntimeValue::Pointer(address))
+            }
+            Instruction::Load { result, pointer } => {
+                check_layout(self.context, result.get_type())?;
+                let address = self.evaluate(pointer)?.as_pointer()?;
+                let value = self.memory.load(self.context, address, result.get_type())?;
+                self.set_register(result, value)
+            }
+            Instruction::Store { value, pointer } => {
+                check_layout(self.context, value.get_type())?;
+                let address = self.evaluate(pointer)?.as_pointer()?;
+                let value_type = value.get_type();
+                let value = self.evaluate(value)?;
+                self.memory.store(self.context, address, value_type, &value)
+            }
+            Instruction::GetElementPointer { result, pointer, indices } => {
+                let &TypeRepr::Pointer { pointee_type, .. } = pointer.get_type().repr(self.context) else {
+                    return Err(Trap::fault("element pointer must be computed from a pointer"));
+                };
+                check_layout(self.context, pointee_type)?;
+                let address = self.evaluate(pointer)?.as_pointer()?;
+                let indices = indices.iter()
+                    .map(|index| self.evaluate(index)?.as_integer())
+                    .collect::<Result<Vec<_>, _>>()?;
+                let address = self.element_address(pointee_type, address, &indices)?;
+                self.set_register(result, RuntimeValue::Pointer(address))
+            }
+            Instruction::Convert { operation, result, value } => {
+                let converted = self.evaluate(value)?;
+                let converted = self.convert(*operation, converted, value.get_type(), result.get_type())?;
+                self.set_register(result, converted)
+            }
+            Instruction::CompareEqual { result, lhs, rhs } => {
+                self.compare(result, lhs, rhs, |ordering| ordering == Some(Ordering::Equal))
+            }
+            Instruction::CompareNotEqual { result, lhs, rhs } => {
+                self.compare(result, lhs, rhs, |ordering| ordering != Some(Ordering::Equal))
+            }
+            Instruction::CompareLessThan { result, lhs, rhs } => {
+                self.compare(result, lhs, rhs, |ordering| ordering == Some(Ordering::Less))
+            }
+            Instruction::CompareLessEqual { result, lhs, rhs } => {
+                self.compare(result, lhs, rhs, |ordering| matches!(ordering, Some(Ordering::Less | Ordering::Equal)))
+            }
+            Instruction::CompareGreaterThan { result, lhs, rhs } => {
+                self.compare(result, lhs, rhs, |ordering| ordering == Some(Ordering::Greater))
+            }
+            Instruction::CompareGreaterEqual { result, lhs, rhs } => {
+                self.compare(result, lhs, rhs, |ordering| matches!(ordering, Some(Ordering::Greater | Ordering::Equal)))
+            }
+            Instruction::Call { result, callee, arguments } => {
+                let function = self.function_index(self.evaluate(callee)?.as_pointer()?)?;
+                let arguments = arguments.iter()
+                    .map(|argument| self.evaluate(argument))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match self.functions[function] {
+                    Function::Defined { .. } => self.push_frame(function, arguments, result.as_ref()),
+                    Function::Foreign { name } => {
+                        let value = self.call_foreign(name, &arguments)?;
+                        match result {
+                            Some(result) => self.set_register(result, value),
+                            None => Ok(()),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use crate::ir::FunctionDefinition;
+use crate::ir::value::BlockLabel;
+
+/// The control flow graph of a function, with blocks identified by their index in
+/// [`FunctionDefinition::blocks`]. The first block is the entry block.
+pub struct ControlFlowGraph {
+    block_indices: HashMap<Box<[u8]>, usize>,
+    predecessors: Vec<Vec<usize>>,
+    successors: Vec<Vec<usize>>,
+    /// Blocks reachable from the entry block, in reverse postorder.
+    reverse_postorder: Vec<usize>,
+    /// The immediate dominator of each block, which is `None` for the entry block and for blocks
+    /// unreachable from it.
+    immediate_dominators: Vec<Option<usize>>,
+}
+
+impl ControlFlowGraph {
+    /// Build the control flow graph of `function`, failing with a description of the problem if
+    /// a label is defined twice or a branch targets an unknown label.
+    pub fn new(function: &FunctionDefinition) -> Result<Self, String> {
+        let blocks = function.blocks();
+        let mut block_indices = HashMap::new();
+        for (index, block) in blocks.iter().enumerate() {
+            if block_indices.insert(block.label().identifier().into(), index).is_some() {
+                return Err(format!("block label '{}' is used more than once", label_name(block.label())));
+            }
+        }
+
+        let mut predecessors = vec![Vec::new(); blocks.len()];
+        let mut successors = vec![Vec::new(); blocks.len()];
+        for (index, block) in blocks.iter().enumerate() {
+            for successor in block.terminator().successors() {
+                let &successor_index = block_indices.get(successor.identifier())
+                    .ok_or_else(|| format!("block '{}' branches to unknown block '{}'", label_name(block.label()), label_name(successor)))?;
+                if !successors[index].contains(&successor_index) {
+                    successors[index].push(successor_index);
+                    predecessors[successor_index].push(index);
+                }
+            }
+        }
+
+        let mut graph = Self {
+            block_indices,
+            predecessors,
+            successors,
+            reverse_postorder: Vec::new(),
+            immediate_dominators: vec![None; blocks.len()],
+        };
+        if !blocks.is_empty() {
+            graph.compute_reverse_postorder();
+            graph.compute_immediate_dominators();
+        }
+        Ok(graph)
+    }
+
+    pub fn block_count(&self) -> usize {
+        self.predecessors.len()
+    }
+
+    pub fn block_index(&self, label: &BlockLabel) -> Option<usize> {
+        self.block_indices.get(label.identifier()).copied()
+    }
+
+    pub fn predecessors(&self, block: usize) -> &[usize] {
+        &self.predecessors[block]
+    }
+
+    pub fn successors(&self, block: usize) -> &[usize] {
+        &self.successors[block]
+    }
+
+    /// Blocks reachable from the entry block, in reverse postorder, which visits every block
+    /// before any block it dominates.
+    pub fn reverse_postorder(&self) -> &[usize] {
+        &self.reverse_postorder
+    }
+
+    pub fn is_reachable(&self, block: usize) -> bool {
+        block == 0 || self.immediate_dominators[block].is_some()
+    }
+
+    pub fn immediate_dominator(&self, block: usize) -> Option<usize> {
+        self.immediate_dominators[block]
+    }
+
+    /// Whether every path from the entry block to `block` passes through `dominator`. Blocks
+    /// which are unreachable are considered to be dominated by every block.
+    pub fn dominates(&self, dominator: usize, block: usize) -> bool {
+        if !self.is_reachable(block) {
+            return true;
+        }
+        let mut current = block;
+        loop {
+            if current == dominator {
+                return true;
+            }
+            match self.immediate_dominators[current] {
+                Some(parent) => current = parent,
+                None => return false,
+            }
+        }
+    }
+
+    /// The children of each block in the dominator tree.
+    pub fn dominator_tree_children(&self) -> Vec<Vec<usize>> {
+        let mut children = vec![Vec::new(); self.block_count()];
+        for &block in &self.reverse_postorder {
+            if let Some(parent) = self.immediate_dominators[block] {
+                children[parent].push(block);
+            }
+        }
+        children
+    }
+
+    /// The dominance frontier of each reachable block: the blocks where its dominance ends,
+    /// which are where values defined along different paths meet.
+    pub fn dominance_frontiers(&self) -> Vec<Vec<usize>> {
+        let mut frontiers = vec![Vec::new(); self.block_count()];
+        for &block in &self.reverse_postorder {
+            let reachable_predecessors: Vec<usize> = self.predecessors[block].iter()
+                .copied()
+                .filter(|&predecessor| self.is_reachable(predecessor))
+                .collect();
+            if reachable_predecessors.len() < 2 {
+                continue;
+            }
+            for predecessor in reachable_predecessors {
+                let mut runner = predecessor;
+                while Some(runner) != self.immediate_dominators[block] {
+                    if !frontiers[runner].contains(&block) {
+                        frontiers[runner].push(block);
+                    }
+                    match self.immediate_dominators[runner] {
+                        Some(parent) => runner = parent,
+                        None => break,
+                    }
+                }
+            }
+        }
+        frontiers
+    }
+
+    fn compute_reverse_postorder(&mut self) {
+        let mut visited = vec![false; self.block_count()];
+        let mut postorder = Vec::with_capacity(self.block_count());
+        // Each stack entry is a block and the index of the next successor to visit
+        let mut stack = vec![(0, 0)];
+        visited[0] = true;
+        while let Some((block, next_successor)) = stack.last_mut() {
+            if let Some(&successor) = self.successors[*block].get(*next_successor) {
+                *next_successor += 1;
+                if !visited[successor] {
+                    visited[successor] = true;
+                    stack.push((successor, 0));
+                }
+            }
+            else {
+                postorder.push(*block);
+                stack.pop();
+            }
+        }
+        postorder.reverse();
+        self.reverse_postorder = postorder;
+    }
+
+    /// Find immediate dominators using the algorithm from "A Simple, Fast Dominance Algorithm"
+    /// by Cooper, Harvey, and Kennedy.
+    fn compute_immediate_dominators(&mut self) {
+        let mut order = vec![usize::MAX; self.block_count()];
+        for (position, &block) in self.reverse_postorder.iter().enumerate() {
+            order[block] = position;
+        }
+
+        // The entry block temporarily dominates itself so the intersection below terminates
+        let mut dominators: Vec<Option<usize>> = vec![None; self.block_count()];
+        dominators[0] = Some(0);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &block in self.reverse_postorder.iter().skip(1) {
+                let mut new_dominator = None;
+                for &predecessor in &self.predecessors[block] {
+                    if dominators[predecessor].is_none() {
+                        continue;
+                    }
+                    new_dominator = Some(match new_dominator {
+                        None => predecessor,
+                        Some(mut other) => {
+                            let mut finger = predecessor;
+                            while finger != other {
+                                while order[finger] > order[other] {
+                                    finger = dominators[finger].unwrap();
+                                }
+                                while order[other] > order[finger] {
+                                    other = dominators[other].unwrap();
+                                }
+                            }
+                            finger
+                        }
+                    });
+                }
+                if dominators[block] != new_dominator {
+                    dominators[block] = new_dominator;
+                    changed = true;
+                }
+            }
+        }
+        dominators[0] = None;
+        self.immediate_dominators = dominators;
+    }
+}
+
+pub fn label_name(label: &BlockLabel) -> String {
+    String::from_utf8_lossy(label.identifier()).into_owned()
+}
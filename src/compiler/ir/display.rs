@@ -0,0 +1,352 @@
+use crate::ir::*;
+use crate::ir::instr::*;
+use crate::ir::value::*;
+use crate::sema::GlobalContext;
+
+/// Writes an identifier as-is if it only contains plain characters, otherwise quoted with any
+/// unusual bytes written as `\XX` escapes.
+pub struct IRIdentifierDisplay<T: AsRef<[u8]>>(pub T);
+
+impl<T: AsRef<[u8]>> std::fmt::Display for IRIdentifierDisplay<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let identifier = self.0.as_ref();
+        let needs_quotes = identifier.is_empty() || identifier.iter().any(|&ch| {
+            !matches!(ch, b'0'..=b'9' | b'A'..=b'Z' | b'a'..=b'z' | b'_' | b'.' | b'$')
+        });
+
+        if needs_quotes {
+            IRStringDisplay(identifier).fmt(f)
+        }
+        else {
+            write!(f, "{}", String::from_utf8_lossy(identifier))
+        }
+    }
+}
+
+/// Writes a byte string in double quotes, escaping quotes, backslashes, and unprintable bytes.
+pub struct IRStringDisplay<T: AsRef<[u8]>>(pub T);
+
+impl<T: AsRef<[u8]>> std::fmt::Display for IRStringDisplay<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"")?;
+        for &ch in self.0.as_ref() {
+            if ch == b'"' || ch == b'\\' || !(ch == b' ' || ch.is_ascii_graphic()) {
+                write!(f, "\\{ch:02X}")?;
+            }
+            else {
+                write!(f, "{}", ch as char)?;
+            }
+        }
+        write!(f, "\"")
+    }
+}
+
+/// Textual form of the compiler's own IR, using Cupric type paths and the instruction names
+/// from [`Instruction`]. Intended for debugging the generator rather than being parsed back.
+pub trait IRDisplay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result;
+
+    fn ir<'a>(&'a self, context: &'a GlobalContext) -> IRDisplayWrapper<'a, Self>
+    where
+        Self: Sized,
+    {
+        IRDisplayWrapper {
+            inner: self,
+            context,
+        }
+    }
+}
+
+pub struct IRDisplayWrapper<'a, T: IRDisplay> {
+    pub inner: &'a T,
+    pub context: &'a GlobalContext,
+}
+
+impl<'a, T: IRDisplay> std::fmt::Display for IRDisplayWrapper<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f, self.context)
+    }
+}
+
+fn fmt_list<T: IRDisplay>(f: &mut std::fmt::Formatter<'_>, items: &[T], context: &GlobalContext) -> std::fmt::Result {
+    let mut items_iter = items.iter();
+    if let Some(item) = items_iter.next() {
+        write!(f, "{}", item.ir(context))?;
+        for item in items_iter {
+            write!(f, ", {}", item.ir(context))?;
+        }
+    }
+    Ok(())
+}
+
+impl IRDisplay for LocalRegister {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        write!(f, "%{}: {}", IRIdentifierDisplay(self.identifier()), self.get_type().path(context))
+    }
+}
+
+impl IRDisplay for BlockLabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        let _ = context;
+        write!(f, "{}", IRIdentifierDisplay(self.identifier()))
+    }
+}
+
+impl IRDisplay for Constant {
+    /// Writes the constant preceded by its type, e.g. `i32 5`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        write!(f, "{} ", self.get_type().path(context))?;
+        match self {
+            Self::Undefined(..) => write!(f, "undefined"),
+            Self::Poison(..) => write!(f, "poison"),
+            Self::ZeroInitializer(..) => write!(f, "zeroinitializer"),
+            Self::NullPointer(..) => write!(f, "null"),
+            Self::Boolean(value) => write!(f, "{value}"),
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{:?}", value.raw()),
+            Self::String { value, .. } => write!(f, "{}", IRStringDisplay(value.bytes())),
+            Self::Array { items, .. } => {
+                write!(f, "[")?;
+                fmt_list(f, items, context)?;
+                write!(f, "]")
+            }
+            Self::Tuple { items, .. } => {
+                write!(f, "(")?;
+                fmt_list(f, items, context)?;
+                write!(f, ")")
+            }
+            Self::Structure { members, .. } => {
+                write!(f, "{{ ")?;
+                fmt_list(f, members, context)?;
+                write!(f, " }}")
+            }
+            Self::Register(register) => write!(f, "@{}", IRIdentifierDisplay(register.identifier())),
+            Self::Indirect { pointer, .. } => write!(f, "*({})", pointer.ir(context)),
+            Self::Convert { operation, value, .. } => write!(f, "Convert {operation:?}({})", value.ir(context)),
+            Self::GetElementPointer { pointer, indices, .. } => {
+                write!(f, "GetElementPointer({}", pointer.ir(context))?;
+                for index in indices {
+                    write!(f, ", {}", index.ir(context))?;
+                }
+                write!(f, ")")
+            }
+            Self::Type(handle) => write!(f, "{}", handle.path(context)),
+            Self::Module(namespace) => write!(f, "{}", context.namespace_info(*namespace).path()),
+        }
+    }
+}
+
+impl IRDisplay for Value {
+    /// Writes the value preceded by its type, e.g. `i32 %x`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        match self {
+            Self::Never => write!(f, "never"),
+            Self::Break => write!(f, "break"),
+            Self::Continue => write!(f, "continue"),
+            Self::Void => write!(f, "void"),
+            Self::Constant(constant) => constant.fmt(f, context),
+            Self::Register(register) => {
+                write!(f, "{} %{}", register.get_type().path(context), IRIdentifierDisplay(register.identifier()))
+            }
+            Self::Indirect { pointer, pointee_type } => {
+                write!(f, "{} *({})", pointee_type.path(context), pointer.ir(context))
+            }
+            Self::BoundFunction { function_value, .. } => function_value.fmt(f, context),
+        }
+    }
+}
+
+impl IRDisplay for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        if let Some(result) = self.result() {
+            write!(f, "{} = ", result.ir(context))?;
+        }
+
+        match self {
+            Self::Negate { operand, .. } => write!(f, "Negate {}", operand.ir(context)),
+            Self::Add { lhs, rhs, .. } => write!(f, "Add {}, {}", lhs.ir(context), rhs.ir(context)),
+            Self::Subtract { lhs, rhs, .. } => write!(f, "Subtract {}, {}", lhs.ir(context), rhs.ir(context)),
+            Self::Multiply { lhs, rhs, .. } => write!(f, "Multiply {}, {}", lhs.ir(context), rhs.ir(context)),
+            Self::Divide { lhs, rhs, .. } => write!(f, "Divide {}, {}", lhs.ir(context), rhs.ir(context)),
+            Self::Remainder { lhs, rhs, .. } => write!(f, "Remainder {}, {}", lhs.ir(context), rhs.ir(context)),
+            Self::ShiftLeft { lhs, rhs, .. } => write!(f, "ShiftLeft {}, {}", lhs.ir(context), rhs.ir(context)),
+            Self::ShiftRight { lhs, rhs, .. } => write!(f, "ShiftRight {}, {}", lhs.ir(context), rhs.ir(context)),
+            Self::Not { operand, .. } => write!(f, "Not {}", operand.ir(context)),
+            Self::And { lhs, rhs, .. } => write!(f, "And {}, {}", lhs.ir(context), rhs.ir(context)),
+            Self::Or { lhs, rhs, .. } => write!(f, "Or {}, {}", lhs.ir(context), rhs.ir(context)),
+            Self::Xor { lhs, rhs, .. } => write!(f, "Xor {}, {}", lhs.ir(context), rhs.ir(context)),
+            Self::ExtractValue { aggregate, indices, .. } => {
+                write!(f, "ExtractValue {}", aggregate.ir(context))?;
+                for index in indices {
+                    write!(f, ", {}", index.ir(context))?;
+                }
+                Ok(())
+            }
+            Self::InsertValue { aggregate, value, indices, .. } => {
+                write!(f, "InsertValue {}, {}", aggregate.ir(context), value.ir(context))?;
+                for index in indices {
+                    write!(f, ", {}", index.ir(context))?;
+                }
+                Ok(())
+            }
+            Self::StackAllocate { .. } => write!(f, "StackAllocate"),
+            Self::Load { pointer, .. } => write!(f, "Load {}", pointer.ir(context)),
+            Self::Store { value, pointer } => write!(f, "Store {}, {}", value.ir(context), pointer.ir(context)),
+            Self::GetElementPointer { pointer, indices, .. } => {
+                write!(f, "GetElementPointer {}", pointer.ir(context))?;
+                for index in indices {
+                    write!(f, ", {}", index.ir(context))?;
+                }
+                Ok(())
+            }
+            Self::Convert { operation, value, .. } => write!(f, "Convert {operation:?} {}", value.ir(context)),
+            Self::CompareEqual { lhs, rhs, .. } => write!(f, "CompareEqual {}, {}", lhs.ir(context), rhs.ir(context)),
+            Self::CompareNotEqual { lhs, rhs, .. } => write!(f, "CompareNotEqual {}, {}", lhs.ir(context), rhs.ir(context)),
+            Self::CompareLessThan { lhs, rhs, .. } => write!(f, "CompareLessThan {}, {}", lhs.ir(context), rhs.ir(context)),
+            Self::CompareLessEqual { lhs, rhs, .. } => write!(f, "CompareLessEqual {}, {}", lhs.ir(context), rhs.ir(context)),
+            Self::CompareGreaterThan { lhs, rhs, .. } => write!(f, "CompareGreaterThan {}, {}", lhs.ir(context), rhs.ir(context)),
+            Self::CompareGreaterEqual { lhs, rhs, .. } => write!(f, "CompareGreaterEqual {}, {}", lhs.ir(context), rhs.ir(context)),
+            Self::Call { callee, arguments, .. } => {
+                write!(f, "Call {}(", callee.ir(context))?;
+                fmt_list(f, arguments, context)?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+impl IRDisplay for PhiInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        write!(f, "{} = Phi", self.result.ir(context))?;
+        let mut inputs_iter = self.inputs.iter();
+        if let Some((value, label)) = inputs_iter.next() {
+            write!(f, " [{} from {}]", value.ir(context), label.ir(context))?;
+            for (value, label) in inputs_iter {
+                write!(f, ", [{} from {}]", value.ir(context), label.ir(context))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl IRDisplay for TerminatorInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        match self {
+            Self::Return { value } => write!(f, "Return {}", value.ir(context)),
+            Self::Branch { to_label } => write!(f, "Branch {}", to_label.ir(context)),
+            Self::ConditionalBranch { condition, consequent_label, alternative_label } => {
+                write!(
+                    f,
+                    "ConditionalBranch {}, {}, {}",
+                    condition.ir(context),
+                    consequent_label.ir(context),
+                    alternative_label.ir(context),
+                )
+            }
+            Self::Unreachable => write!(f, "Unreachable"),
+        }
+    }
+}
+
+impl IRDisplay for BasicBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        writeln!(f, "{}:", self.label().ir(context))?;
+        for phi in self.phis() {
+            writeln!(f, "    {}", phi.ir(context))?;
+        }
+        for instruction in self.body() {
+            writeln!(f, "    {}", instruction.ir(context))?;
+        }
+        writeln!(f, "    {}", self.terminator().ir(context))
+    }
+}
+
+impl IRDisplay for GlobalVariableKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        let _ = context;
+        match self {
+            Self::Constant => write!(f, "constant"),
+            Self::AnonymousConstant => write!(f, "anonymous constant"),
+            Self::Mutable => write!(f, "mutable"),
+        }
+    }
+}
+
+impl IRDisplay for FunctionDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        match self.inline_hint() {
+            InlineHint::Default => {}
+            InlineHint::Always => write!(f, "inline ")?,
+            InlineHint::Never => write!(f, "noinline ")?,
+        }
+        write!(f, "function @{}(", IRIdentifierDisplay(self.register().identifier()))?;
+        fmt_list(f, self.parameter_registers(), context)?;
+        if self.is_variadic() {
+            if self.parameter_registers().is_empty() {
+                write!(f, "...")?;
+            }
+            else {
+                write!(f, ", ...")?;
+            }
+        }
+        writeln!(f, ") -> {} {{", self.return_type().path(context))?;
+        for block in self.blocks() {
+            block.fmt(f, context)?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+impl IRDisplay for CompilationUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        writeln!(f, "; Cupric IR generated from '{}'", self.main_path().display())?;
+
+        if !self.type_declarations().is_empty() {
+            writeln!(f)?;
+            for &type_handle in self.type_declarations() {
+                writeln!(f, "type {}", type_handle.path(context))?;
+            }
+        }
+
+        if !self.external_global_variables().is_empty() || !self.external_functions().is_empty() {
+            writeln!(f)?;
+            for variable in self.external_global_variables() {
+                writeln!(
+                    f,
+                    "external {} @{}: {}",
+                    variable.kind().ir(context),
+                    IRIdentifierDisplay(variable.register().identifier()),
+                    variable.value_type().path(context),
+                )?;
+            }
+            for function in self.external_functions() {
+                writeln!(
+                    f,
+                    "external function @{}: {}",
+                    IRIdentifierDisplay(function.register().identifier()),
+                    function.register().get_type().path(context),
+                )?;
+            }
+        }
+
+        if !self.global_variables().is_empty() {
+            writeln!(f)?;
+            for variable in self.global_variables() {
+                writeln!(
+                    f,
+                    "{} @{} = {}",
+                    variable.kind().ir(context),
+                    IRIdentifierDisplay(variable.register().identifier()),
+                    variable.value().ir(context),
+                )?;
+            }
+        }
+
+        for function in self.function_definitions() {
+            writeln!(f)?;
+            function.fmt(f, context)?;
+        }
+
+        Ok(())
+    }
+}
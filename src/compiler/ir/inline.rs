@@ -0,0 +1,355 @@
+use std::collections::{HashMap, HashSet};
+use crate::ir::{optimize_function, promote_allocas, replace_registers, verify_function, CompilationUnit, FunctionDefinition, InlineHint, OptimizationLevel};
+use crate::ir::instr::*;
+use crate::ir::value::*;
+use crate::sema::{display_symbol, GlobalContext};
+
+/// Replace calls to functions marked `inline` with the bodies of those functions, for every
+/// function defined in `unit`. Functions which changed have their stack allocations promoted again,
+/// are optimized at `level` and are verified again.
+///
+/// Only functions defined in `unit` can be inlined. Functions which are part of a cycle of calls
+/// between `inline` functions are left alone, since there would be no end to the expansion.
+pub fn inline_functions(context: &GlobalContext, unit: &mut CompilationUnit, level: OptimizationLevel) -> crate::Result<()> {
+    let function_indices: HashMap<Box<[u8]>, usize> = unit.function_definitions().iter()
+        .enumerate()
+        .map(|(index, function)| (function.register().identifier().into(), index))
+        .collect();
+    let mut candidates: HashSet<usize> = unit.function_definitions().iter()
+        .enumerate()
+        .filter(|(_, function)| can_inline(function))
+        .map(|(index, _)| index)
+        .collect();
+    if candidates.is_empty() {
+        return Ok(());
+    }
+
+    // Find which candidates each candidate calls, then drop the ones which can reach themselves
+    let callees: HashMap<usize, HashSet<usize>> = candidates.iter()
+        .map(|&index| {
+            let called = called_functions(&unit.function_definitions()[index])
+                .filter_map(|identifier| function_indices.get(identifier).copied())
+                .filter(|called| candidates.contains(called))
+                .collect();
+            (index, called)
+        })
+        .collect();
+    candidates.retain(|&index| !reaches(&callees, index, index));
+
+    // Candidates are expanded before anything that calls them, so each is only expanded once
+    let mut order = Vec::new();
+    let mut visited = HashSet::new();
+    let mut sorted_candidates: Vec<usize> = candidates.iter().copied().collect();
+    sorted_candidates.sort_unstable();
+    for &index in &sorted_candidates {
+        post_order(&callees, &candidates, index, &mut visited, &mut order);
+    }
+    order.extend((0..unit.function_definitions().len()).filter(|index| !candidates.contains(index)));
+
+    for index in order {
+        let function = &unit.function_definitions()[index];
+        let mut inliner = Inliner::new(function);
+        let mut blocks = function.blocks().to_vec();
+        let mut changed = false;
+
+        let mut block_index = 0;
+        while block_index < blocks.len() {
+            let call = blocks[block_index].body().iter()
+                .enumerate()
+                .find_map(|(instruction_index, instruction)| {
+                    let Instruction::Call { callee, .. } = instruction else {
+                        return None;
+                    };
+                    let callee_index = *function_indices.get(direct_callee(callee)?)?;
+                    candidates.contains(&callee_index).then_some((instruction_index, callee_index))
+                });
+            match call {
+                Some((instruction_index, callee_index)) => {
+                    let callee = &unit.function_definitions()[callee_index];
+                    let inserted_count = inliner.inline_call(&mut blocks, block_index, instruction_index, callee);
+                    // The continuation is the last block inserted, and may contain more calls
+                    block_index += inserted_count;
+                    changed = true;
+                }
+                None => block_index += 1,
+            }
+        }
+
+        if changed {
+            let function = &mut unit.function_definitions_mut()[index];
+            *function.blocks_mut() = blocks;
+            // Pointers passed into the inlined body no longer escape through the call
+            promote_allocas(context, function);
+            optimize_function(context, function, level);
+            function.renumber_anonymous_registers();
+            verify_function(context, function, &display_symbol(function.register().identifier()))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn can_inline(function: &FunctionDefinition) -> bool {
+    // The caller branches into the entry block, which must not need phis for that, and there must
+    // be somewhere for the caller to continue from
+    function.inline_hint() == InlineHint::Always
+        && !function.is_variadic()
+        && function.blocks().first().is_some_and(|entry| entry.phis().is_empty())
+        && function.blocks().iter().any(|block| matches!(block.terminator(), TerminatorInstruction::Return { .. }))
+}
+
+/// The identifier of the function `callee` refers to, if it is known at compile time.
+pub fn direct_callee(callee: &Value) -> Option<&[u8]> {
+    match callee {
+        Value::Constant(Constant::Register(register)) => Some(register.identifier()),
+        Value::BoundFunction { function_value, .. } => direct_callee(function_value),
+        _ => None,
+    }
+}
+
+fn called_functions(function: &FunctionDefinition) -> impl Iterator<Item = &[u8]> {
+    function.blocks().iter()
+        .flat_map(|block| block.body())
+        .filter_map(|instruction| match instruction {
+            Instruction::Call { callee, .. } => direct_callee(callee),
+            _ => None,
+        })
+}
+
+/// Whether `target` can be reached by following one or more calls from `start`.
+fn reaches(callees: &HashMap<usize, HashSet<usize>>, start: usize, target: usize) -> bool {
+    let mut visited = HashSet::new();
+    let mut stack: Vec<usize> = callees[&start].iter().copied().collect();
+    while let Some(index) = stack.pop() {
+        if index == target {
+            return true;
+        }
+        if visited.insert(index) {
+            stack.extend(callees[&index].iter().copied());
+        }
+    }
+    false
+}
+
+fn post_order(callees: &HashMap<usize, HashSet<usize>>, candidates: &HashSet<usize>, index: usize, visited: &mut HashSet<usize>, order: &mut Vec<usize>) {
+    if !visited.insert(index) {
+        return;
+    }
+    let mut called: Vec<usize> = callees[&index].iter()
+        .copied()
+        .filter(|called| candidates.contains(called))
+        .collect();
+    called.sort_unstable();
+    for called in called {
+        post_order(callees, candidates, called, visited, order);
+    }
+    order.push(index);
+}
+
+/// Hands out register and block names which are unused in the function being inlined into.
+struct Inliner {
+    next_register_id: usize,
+    next_block_id: usize,
+}
+
+impl Inliner {
+    fn new(function: &FunctionDefinition) -> Self {
+        let mut next_register_id = 0;
+        let mut consider = |register: &LocalRegister| {
+            if let Some(id) = std::str::from_utf8(register.identifier()).ok().and_then(|id| id.parse::<usize>().ok()) {
+                next_register_id = next_register_id.max(id + 1);
+            }
+        };
+        for register in function.parameter_registers() {
+            consider(register);
+        }
+        for block in function.blocks() {
+            for phi in block.phis() {
+                consider(&phi.result);
+            }
+            for instruction in block.body() {
+                if let Some(result) = instruction.result() {
+                    consider(result);
+                }
+            }
+        }
+
+        let next_block_id = function.blocks().iter()
+            .filter_map(|block| {
+                let id = block.label().identifier().strip_prefix(b".block.")?;
+                std::str::from_utf8(id).ok()?.parse::<usize>().ok()
+            })
+            .max()
+            .map_or(0, |id| id + 1);
+
+        Self {
+            next_register_id,
+            next_block_id,
+        }
+    }
+
+    fn new_register_identifier(&mut self) -> Box<[u8]> {
+        let id = self.next_register_id;
+        self.next_register_id += 1;
+        id.to_string().into_bytes().into()
+    }
+
+    fn new_block_label(&mut self) -> BlockLabel {
+        let id = self.next_block_id;
+        self.next_block_id += 1;
+        BlockLabel::new(format!(".block.{id}").as_bytes().into())
+    }
+
+    /// Replace the call at `instruction_index` in block `block_index` with a copy of the body of
+    /// `callee`. The block is split at the call, with the copied blocks inserted after it followed
+    /// by a block holding the rest of the original block. Returns the number of blocks inserted.
+    fn inline_call(&mut self, blocks: &mut Vec<BasicBlock>, block_index: usize, instruction_index: usize, callee: &FunctionDefinition) -> usize {
+        let mut instructions = blocks[block_index].take_instructions();
+        let rest = instructions.split_off(instruction_index + 1);
+        let (call, location) = instructions.pop().unwrap();
+        let Instruction::Call { result, arguments, .. } = call else {
+            panic!("instruction to inline is not a call");
+        };
+        for (instruction, location) in instructions {
+            blocks[block_index].add_instruction(instruction, location);
+        }
+
+        // Copy the callee, giving each of its registers and blocks a fresh name
+        let mut register_identifiers: HashMap<Box<[u8]>, Box<[u8]>> = HashMap::new();
+        let mut rename_definition = |register: &LocalRegister, inliner: &mut Self| {
+            register_identifiers.insert(register.identifier().into(), inliner.new_register_identifier());
+        };
+        for register in callee.parameter_registers() {
+            rename_definition(register, self);
+        }
+        for block in callee.blocks() {
+            for phi in block.phis() {
+                rename_definition(&phi.result, self);
+            }
+            for instruction in block.body() {
+                if let Some(result) = instruction.result() {
+                    rename_definition(result, self);
+                }
+            }
+        }
+        let block_labels: HashMap<&[u8], BlockLabel> = callee.blocks().iter()
+            .map(|block| (block.label().identifier(), self.new_block_label()))
+            .collect();
+        let continuation_label = self.new_block_label();
+
+        let mut rename = |register: &mut LocalRegister| {
+            if let Some(identifier) = register_identifiers.get(register.identifier()) {
+                register.set_identifier(identifier.clone());
+            }
+        };
+        let parameter_values: HashMap<Box<[u8]>, Value> = std::iter::zip(callee.parameter_registers(), arguments.into_vec())
+            .map(|(parameter, argument)| (register_identifiers[parameter.identifier()].clone(), argument))
+            .collect();
+        let substitute = |value: &mut Value, rename: &mut dyn FnMut(&mut LocalRegister)| {
+            value.for_each_register_mut(&mut |register| rename(register));
+            replace_registers(value, &parameter_values);
+        };
+
+        let mut stack_allocations = Vec::new();
+        let mut return_inputs = Vec::new();
+        let mut inlined_blocks = Vec::new();
+        for block in callee.blocks() {
+            let label = block_labels[block.label().identifier()].clone();
+            let mut inlined_block = BasicBlock::new(label.clone());
+            for phi in block.phis() {
+                let mut phi = phi.clone();
+                rename(&mut phi.result);
+                for (value, input_label) in phi.inputs.iter_mut() {
+                    substitute(value, &mut rename);
+                    *input_label = block_labels[input_label.identifier()].clone();
+                }
+                inlined_block.add_phi(phi);
+            }
+            for instruction in block.body() {
+                let mut instruction = instruction.clone();
+                if let Some(result) = instruction.result_mut() {
+                    rename(result);
+                }
+                for operand in instruction.operands_mut() {
+                    substitute(operand, &mut rename);
+                }
+                // Allocating inside the caller's loops would use more stack on every iteration
+                if let Instruction::StackAllocate { .. } = instruction {
+                    stack_allocations.push(instruction);
+                }
+                else {
+                    inlined_block.add_instruction(instruction, location);
+                }
+            }
+            let terminator = match block.terminator().clone() {
+                TerminatorInstruction::Return { mut value } => {
+                    substitute(&mut value, &mut rename);
+                    return_inputs.push((value, label));
+                    TerminatorInstruction::Branch {
+                        to_label: continuation_label.clone(),
+                    }
+                }
+                TerminatorInstruction::Branch { to_label } => TerminatorInstruction::Branch {
+                    to_label: block_labels[to_label.identifier()].clone(),
+                },
+                TerminatorInstruction::ConditionalBranch { mut condition, consequent_label, alternative_label } => {
+                    substitute(&mut condition, &mut rename);
+                    TerminatorInstruction::ConditionalBranch {
+                        condition,
+                        consequent_label: block_labels[consequent_label.identifier()].clone(),
+                        alternative_label: block_labels[alternative_label.identifier()].clone(),
+                    }
+                }
+                TerminatorInstruction::Unreachable => TerminatorInstruction::Unreachable,
+            };
+            inlined_block.set_terminator(terminator, location);
+            inlined_blocks.push(inlined_block);
+        }
+
+        // The rest of the original block continues after the call, receiving the return value
+        let mut continuation = BasicBlock::new(continuation_label.clone());
+        if let Some(result) = result {
+            continuation.add_phi(PhiInstruction {
+                result,
+                inputs: return_inputs.into_boxed_slice(),
+            });
+        }
+        for (instruction, location) in rest {
+            continuation.add_instruction(instruction, location);
+        }
+        let original_block = &mut blocks[block_index];
+        if original_block.is_terminated() {
+            let terminator_location = original_block.terminator_location();
+            let terminator = std::mem::replace(original_block.terminator_mut(), TerminatorInstruction::Unreachable);
+            continuation.set_terminator(terminator, terminator_location);
+        }
+        let entry_label = inlined_blocks[0].label().clone();
+        original_block.set_terminator(TerminatorInstruction::Branch { to_label: entry_label }, location);
+
+        // Successors of the original block are now reached from the continuation instead
+        let original_label = original_block.label().clone();
+        for block in blocks.iter_mut() {
+            for phi in block.phis_mut() {
+                for (_, input_label) in phi.inputs.iter_mut() {
+                    if *input_label == original_label {
+                        *input_label = continuation_label.clone();
+                    }
+                }
+            }
+        }
+
+        let entry_block = &mut blocks[0];
+        let entry_instructions = entry_block.take_instructions();
+        for instruction in stack_allocations {
+            entry_block.add_instruction(instruction, location);
+        }
+        for (instruction, location) in entry_instructions {
+            entry_block.add_instruction(instruction, location);
+        }
+
+        let inserted_count = inlined_blocks.len() + 1;
+        inlined_blocks.push(continuation);
+        blocks.splice(block_index + 1..block_index + 1, inlined_blocks);
+        inserted_count
+    }
+}
@@ -1,6 +1,7 @@
 use crate::ir::value::{BlockLabel, LocalRegister, Value};
 use crate::sema::ConversionOperation;
 
+#[derive(Clone)]
 pub enum Instruction {
     Negate {
         result: LocalRegister,
@@ -129,11 +130,158 @@ pub enum Instruction {
     },
 }
 
+impl Instruction {
+    /// The register this instruction assigns to, if any.
+    pub fn result(&self) -> Option<&LocalRegister> {
+        match self {
+            Self::Negate { result, .. } |
+            Self::Add { result, .. } |
+            Self::Subtract { result, .. } |
+            Self::Multiply { result, .. } |
+            Self::Divide { result, .. } |
+            Self::Remainder { result, .. } |
+            Self::ShiftLeft { result, .. } |
+            Self::ShiftRight { result, .. } |
+            Self::Not { result, .. } |
+            Self::And { result, .. } |
+            Self::Or { result, .. } |
+            Self::Xor { result, .. } |
+            Self::ExtractValue { result, .. } |
+            Self::InsertValue { result, .. } |
+            Self::StackAllocate { result } |
+            Self::Load { result, .. } |
+            Self::GetElementPointer { result, .. } |
+            Self::Convert { result, .. } |
+            Self::CompareEqual { result, .. } |
+            Self::CompareNotEqual { result, .. } |
+            Self::CompareLessThan { result, .. } |
+            Self::CompareLessEqual { result, .. } |
+            Self::CompareGreaterThan { result, .. } |
+            Self::CompareGreaterEqual { result, .. } => Some(result),
+            Self::Call { result, .. } => result.as_ref(),
+            Self::Store { .. } => None,
+        }
+    }
+
+    pub fn result_mut(&mut self) -> Option<&mut LocalRegister> {
+        match self {
+            Self::Negate { result, .. } |
+            Self::Add { result, .. } |
+            Self::Subtract { result, .. } |
+            Self::Multiply { result, .. } |
+            Self::Divide { result, .. } |
+            Self::Remainder { result, .. } |
+            Self::ShiftLeft { result, .. } |
+            Self::ShiftRight { result, .. } |
+            Self::Not { result, .. } |
+            Self::And { result, .. } |
+            Self::Or { result, .. } |
+            Self::Xor { result, .. } |
+            Self::ExtractValue { result, .. } |
+            Self::InsertValue { result, .. } |
+            Self::StackAllocate { result } |
+            Self::Load { result, .. } |
+            Self::GetElementPointer { result, .. } |
+            Self::Convert { result, .. } |
+            Self::CompareEqual { result, .. } |
+            Self::CompareNotEqual { result, .. } |
+            Self::CompareLessThan { result, .. } |
+            Self::CompareLessEqual { result, .. } |
+            Self::CompareGreaterThan { result, .. } |
+            Self::CompareGreaterEqual { result, .. } => Some(result),
+            Self::Call { result, .. } => result.as_mut(),
+            Self::Store { .. } => None,
+        }
+    }
+
+    /// The values this instruction uses, in the order they appear.
+    pub fn operands(&self) -> Vec<&Value> {
+        match self {
+            Self::Negate { operand, .. } |
+            Self::Not { operand, .. } => vec![operand],
+            Self::Add { lhs, rhs, .. } |
+            Self::Subtract { lhs, rhs, .. } |
+            Self::Multiply { lhs, rhs, .. } |
+            Self::Divide { lhs, rhs, .. } |
+            Self::Remainder { lhs, rhs, .. } |
+            Self::ShiftLeft { lhs, rhs, .. } |
+            Self::ShiftRight { lhs, rhs, .. } |
+            Self::And { lhs, rhs, .. } |
+            Self::Or { lhs, rhs, .. } |
+            Self::Xor { lhs, rhs, .. } |
+            Self::CompareEqual { lhs, rhs, .. } |
+            Self::CompareNotEqual { lhs, rhs, .. } |
+            Self::CompareLessThan { lhs, rhs, .. } |
+            Self::CompareLessEqual { lhs, rhs, .. } |
+            Self::CompareGreaterThan { lhs, rhs, .. } |
+            Self::CompareGreaterEqual { lhs, rhs, .. } => vec![lhs, rhs],
+            Self::ExtractValue { aggregate, indices, .. } => {
+                std::iter::once(aggregate).chain(indices.iter()).collect()
+            }
+            Self::InsertValue { aggregate, value, indices, .. } => {
+                [aggregate, value].into_iter().chain(indices.iter()).collect()
+            }
+            Self::StackAllocate { .. } => Vec::new(),
+            Self::Load { pointer, .. } => vec![pointer],
+            Self::Store { value, pointer } => vec![value, pointer],
+            Self::GetElementPointer { pointer, indices, .. } => {
+                std::iter::once(pointer).chain(indices.iter()).collect()
+            }
+            Self::Convert { value, .. } => vec![value],
+            Self::Call { callee, arguments, .. } => {
+                std::iter::once(callee).chain(arguments.iter()).collect()
+            }
+        }
+    }
+
+    /// Mutable references to the values this instruction uses, in the order they appear.
+    pub fn operands_mut(&mut self) -> Vec<&mut Value> {
+        match self {
+            Self::Negate { operand, .. } |
+            Self::Not { operand, .. } => vec![operand],
+            Self::Add { lhs, rhs, .. } |
+            Self::Subtract { lhs, rhs, .. } |
+            Self::Multiply { lhs, rhs, .. } |
+            Self::Divide { lhs, rhs, .. } |
+            Self::Remainder { lhs, rhs, .. } |
+            Self::ShiftLeft { lhs, rhs, .. } |
+            Self::ShiftRight { lhs, rhs, .. } |
+            Self::And { lhs, rhs, .. } |
+            Self::Or { lhs, rhs, .. } |
+            Self::Xor { lhs, rhs, .. } |
+            Self::CompareEqual { lhs, rhs, .. } |
+            Self::CompareNotEqual { lhs, rhs, .. } |
+            Self::CompareLessThan { lhs, rhs, .. } |
+            Self::CompareLessEqual { lhs, rhs, .. } |
+            Self::CompareGreaterThan { lhs, rhs, .. } |
+            Self::CompareGreaterEqual { lhs, rhs, .. } => vec![lhs, rhs],
+            Self::ExtractValue { aggregate, indices, .. } => {
+                std::iter::once(aggregate).chain(indices.iter_mut()).collect()
+            }
+            Self::InsertValue { aggregate, value, indices, .. } => {
+                [aggregate, value].into_iter().chain(indices.iter_mut()).collect()
+            }
+            Self::StackAllocate { .. } => Vec::new(),
+            Self::Load { pointer, .. } => vec![pointer],
+            Self::Store { value, pointer } => vec![value, pointer],
+            Self::GetElementPointer { pointer, indices, .. } => {
+                std::iter::once(pointer).chain(indices.iter_mut()).collect()
+            }
+            Self::Convert { value, .. } => vec![value],
+            Self::Call { callee, arguments, .. } => {
+                std::iter::once(callee).chain(arguments.iter_mut()).collect()
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct PhiInstruction {
     pub result: LocalRegister,
     pub inputs: Box<[(Value, BlockLabel)]>,
 }
 
+#[derive(Clone)]
 pub enum TerminatorInstruction {
     Return {
         value: Value,
@@ -149,11 +297,45 @@ pub enum TerminatorInstruction {
     Unreachable,
 }
 
+impl TerminatorInstruction {
+    /// The value this terminator uses, if any.
+    pub fn operand(&self) -> Option<&Value> {
+        match self {
+            Self::Return { value } => Some(value),
+            Self::ConditionalBranch { condition, .. } => Some(condition),
+            Self::Branch { .. } | Self::Unreachable => None,
+        }
+    }
+
+    pub fn operand_mut(&mut self) -> Option<&mut Value> {
+        match self {
+            Self::Return { value } => Some(value),
+            Self::ConditionalBranch { condition, .. } => Some(condition),
+            Self::Branch { .. } | Self::Unreachable => None,
+        }
+    }
+
+    /// The labels of the blocks this terminator may branch to.
+    pub fn successors(&self) -> Vec<&BlockLabel> {
+        match self {
+            Self::Branch { to_label } => vec![to_label],
+            Self::ConditionalBranch { consequent_label, alternative_label, .. } => vec![consequent_label, alternative_label],
+            Self::Return { .. } | Self::Unreachable => Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct BasicBlock {
     label: BlockLabel,
     phis: Vec<PhiInstruction>,
     body: Vec<Instruction>,
+    /// The source location of each instruction in `body`, used for debug info.
+    body_locations: Vec<Option<crate::Span>>,
     terminator: TerminatorInstruction,
+    terminator_location: Option<crate::Span>,
+    /// Whether a terminator was ever set, as opposed to the default of `Unreachable`.
+    is_terminated: bool,
 }
 
 impl BasicBlock {
@@ -162,7 +344,10 @@ impl BasicBlock {
             label,
             phis: Vec::new(),
             body: Vec::new(),
+            body_locations: Vec::new(),
             terminator: TerminatorInstruction::Unreachable,
+            terminator_location: None,
+            is_terminated: false,
         }
     }
 
@@ -174,6 +359,10 @@ impl BasicBlock {
         &self.phis
     }
 
+    pub fn phis_mut(&mut self) -> &mut Vec<PhiInstruction> {
+        &mut self.phis
+    }
+
     pub fn add_phi(&mut self, phi: PhiInstruction) {
         self.phis.push(phi);
     }
@@ -182,15 +371,43 @@ impl BasicBlock {
         &self.body
     }
 
-    pub fn add_instruction(&mut self, instruction: Instruction) {
+    /// The source location of each instruction in the body, in the same order as [`Self::body`].
+    pub fn body_locations(&self) -> &[Option<crate::Span>] {
+        &self.body_locations
+    }
+
+    pub fn add_instruction(&mut self, instruction: Instruction, location: Option<crate::Span>) {
         self.body.push(instruction);
+        self.body_locations.push(location);
+    }
+
+    /// Remove every instruction from the body along with its location, so a pass can add back
+    /// the ones it keeps with [`Self::add_instruction`].
+    pub fn take_instructions(&mut self) -> Vec<(Instruction, Option<crate::Span>)> {
+        let body = std::mem::take(&mut self.body);
+        let body_locations = std::mem::take(&mut self.body_locations);
+        body.into_iter().zip(body_locations).collect()
     }
 
     pub fn terminator(&self) -> &TerminatorInstruction {
         &self.terminator
     }
 
-    pub fn set_terminator(&mut self, terminator: TerminatorInstruction) {
+    pub fn terminator_mut(&mut self) -> &mut TerminatorInstruction {
+        &mut self.terminator
+    }
+
+    pub fn terminator_location(&self) -> Option<crate::Span> {
+        self.terminator_location
+    }
+
+    pub fn is_terminated(&self) -> bool {
+        self.is_terminated
+    }
+
+    pub fn set_terminator(&mut self, terminator: TerminatorInstruction, location: Option<crate::Span>) {
         self.terminator = terminator;
+        self.terminator_location = location;
+        self.is_terminated = true;
     }
 }
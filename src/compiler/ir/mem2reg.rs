@@ -0,0 +1,332 @@
+use std::collections::{HashMap, HashSet};
+use crate::ir::{replace_registers, ControlFlowGraph, FunctionDefinition};
+use crate::ir::instr::*;
+use crate::ir::value::*;
+use crate::sema::{GlobalContext, TypeHandle, TypeRepr};
+
+/// Promote stack allocations to SSA registers where possible, replacing each `Load` with the
+/// value last stored and inserting phis where different stored values meet.
+///
+/// An allocation can be promoted if its address is only ever used directly by `Load` and `Store`
+/// instructions of the allocated type. When debug info is enabled, allocations holding named
+/// variables are left alone so debuggers can still find them.
+///
+/// Functions with invalid control flow are left alone for [`verify_function`](crate::ir::verify_function)
+/// to report.
+pub fn promote_allocas(context: &GlobalContext, function: &mut FunctionDefinition) {
+    let Ok(graph) = ControlFlowGraph::new(function) else {
+        return;
+    };
+    let allocas = find_promotable_allocas(context, function);
+    if allocas.is_empty() {
+        return;
+    }
+
+    let mut promoter = Promoter {
+        allocas,
+        phi_allocas: HashMap::new(),
+        current_values: Vec::new(),
+        replacements: HashMap::new(),
+    };
+    promoter.insert_phis(function, &graph);
+    promoter.rename(function, &graph);
+    function.replace_register_uses(&promoter.replacements);
+}
+
+/// An allocation being promoted.
+struct Alloca {
+    identifier: Box<[u8]>,
+    allocated_type: TypeHandle,
+}
+
+/// Find the allocations which can be promoted, along with the type each holds.
+fn find_promotable_allocas(context: &GlobalContext, function: &FunctionDefinition) -> Vec<Alloca> {
+    let mut candidates: HashMap<&[u8], TypeHandle> = HashMap::new();
+    for block in function.blocks() {
+        for instruction in block.body() {
+            if let Instruction::StackAllocate { result } = instruction {
+                if let &TypeRepr::Pointer { pointee_type, .. } = result.get_type().repr(context) {
+                    candidates.insert(result.identifier(), pointee_type);
+                }
+            }
+        }
+    }
+    if context.debug_info() {
+        for variable in function.debug_variables() {
+            candidates.remove(variable.pointer.identifier());
+        }
+    }
+
+    // Rule out any allocation whose address escapes or which is accessed as a different type
+    let mut rejected = HashSet::new();
+    for block in function.blocks() {
+        for phi in block.phis() {
+            for (value, _) in phi.inputs.iter() {
+                value.for_each_register(&mut |register| {
+                    rejected.insert(register.identifier());
+                });
+            }
+        }
+        for instruction in block.body() {
+            let (accessed_pointer, accessed_type) = match instruction {
+                Instruction::Load { result, pointer } => (pointer, result.get_type()),
+                Instruction::Store { value, pointer } => {
+                    value.for_each_register(&mut |register| {
+                        rejected.insert(register.identifier());
+                    });
+                    (pointer, value.get_type())
+                }
+                _ => {
+                    for operand in instruction.operands() {
+                        operand.for_each_register(&mut |register| {
+                            rejected.insert(register.identifier());
+                        });
+                    }
+                    continue;
+                }
+            };
+            match accessed_pointer {
+                Value::Register(register) => {
+                    let is_same_type = candidates.get(register.identifier())
+                        .is_none_or(|&allocated_type| is_same_type(context, accessed_type, allocated_type));
+                    if !is_same_type {
+                        rejected.insert(register.identifier());
+                    }
+                }
+                _ => {
+                    accessed_pointer.for_each_register(&mut |register| {
+                        rejected.insert(register.identifier());
+                    });
+                }
+            }
+        }
+        if let Some(value) = block.terminator().operand() {
+            value.for_each_register(&mut |register| {
+                rejected.insert(register.identifier());
+            });
+        }
+    }
+
+    // Keep the order allocations appear in so the output is deterministic
+    let mut allocas = Vec::new();
+    for block in function.blocks() {
+        for instruction in block.body() {
+            if let Instruction::StackAllocate { result } = instruction {
+                let identifier = result.identifier();
+                if let Some(&allocated_type) = candidates.get(identifier).filter(|_| !rejected.contains(identifier)) {
+                    allocas.push(Alloca {
+                        identifier: identifier.into(),
+                        allocated_type,
+                    });
+                }
+            }
+        }
+    }
+    allocas
+}
+
+/// Whether values of `got_type` can stand in for `expected_type` without any conversion.
+/// Pointers differing only in what they point to are interchangeable.
+fn is_same_type(context: &GlobalContext, got_type: TypeHandle, expected_type: TypeHandle) -> bool {
+    got_type == expected_type || matches!(
+        (got_type.repr(context), expected_type.repr(context)),
+        (TypeRepr::Pointer { .. }, TypeRepr::Pointer { .. })
+    )
+}
+
+struct Promoter {
+    allocas: Vec<Alloca>,
+    /// For each block, the phis inserted at its start and the index of the allocation each
+    /// stands for.
+    phi_allocas: HashMap<usize, Vec<(usize, LocalRegister)>>,
+    /// The value each allocation holds at the current point of renaming.
+    current_values: Vec<Value>,
+    /// Values to use in place of the results of removed `Load` instructions.
+    replacements: HashMap<Box<[u8]>, Value>,
+}
+
+impl Promoter {
+    fn alloca_index(&self, pointer: &Value) -> Option<usize> {
+        let Value::Register(register) = pointer else {
+            return None;
+        };
+        self.allocas.iter().position(|alloca| alloca.identifier.as_ref() == register.identifier())
+    }
+
+    /// Place a phi for each allocation at the start of every block in the iterated dominance
+    /// frontier of the blocks storing to it.
+    fn insert_phis(&mut self, function: &mut FunctionDefinition, graph: &ControlFlowGraph) {
+        let frontiers = graph.dominance_frontiers();
+        let mut used_identifiers: HashSet<Box<[u8]>> = HashSet::new();
+        for register in function.parameter_registers() {
+            used_identifiers.insert(register.identifier().into());
+        }
+        for block in function.blocks() {
+            for phi in block.phis() {
+                used_identifiers.insert(phi.result.identifier().into());
+            }
+            for instruction in block.body() {
+                if let Some(result) = instruction.result() {
+                    used_identifiers.insert(result.identifier().into());
+                }
+            }
+        }
+
+        let mut next_anonymous_id = used_identifiers.iter()
+            .filter_map(|identifier| std::str::from_utf8(identifier).ok()?.parse::<usize>().ok())
+            .max()
+            .unwrap_or(0);
+
+        for (alloca_index, alloca) in self.allocas.iter().enumerate() {
+            let mut defining_blocks: Vec<usize> = function.blocks().iter().enumerate()
+                .filter(|(_, block)| block.body().iter().any(|instruction| match instruction {
+                    Instruction::StackAllocate { result } => result.identifier() == alloca.identifier.as_ref(),
+                    Instruction::Store { pointer: Value::Register(register), .. } => register.identifier() == alloca.identifier.as_ref(),
+                    _ => false
+                }))
+                .map(|(index, _)| index)
+                .collect();
+
+            let mut has_phi = vec![false; graph.block_count()];
+            while let Some(block) = defining_blocks.pop() {
+                if !graph.is_reachable(block) {
+                    continue;
+                }
+                for &frontier_block in &frontiers[block] {
+                    if has_phi[frontier_block] {
+                        continue;
+                    }
+                    has_phi[frontier_block] = true;
+
+                    // Numbered names are reserved for anonymous registers, so those get a new number
+                    let identifier: Box<[u8]> = if alloca.identifier.iter().all(u8::is_ascii_digit) {
+                        next_anonymous_id += 1;
+                        next_anonymous_id.to_string().into_bytes().into()
+                    }
+                    else {
+                        let mut counter = 0;
+                        loop {
+                            let identifier = format!("{}.{counter}", String::from_utf8_lossy(&alloca.identifier)).into_bytes().into_boxed_slice();
+                            if !used_identifiers.contains(&identifier) {
+                                break identifier;
+                            }
+                            counter += 1;
+                        }
+                    };
+                    used_identifiers.insert(identifier.clone());
+                    let result = LocalRegister::new(identifier, alloca.allocated_type);
+                    self.phi_allocas.entry(frontier_block).or_default().push((alloca_index, result));
+
+                    // The phi itself is a new definition which may need to be merged further on
+                    defining_blocks.push(frontier_block);
+                }
+            }
+        }
+    }
+
+    /// Walk the dominator tree, removing accesses to promoted allocations and recording the
+    /// value each `Load` sees, then fill in the inputs of the inserted phis.
+    fn rename(&mut self, function: &mut FunctionDefinition, graph: &ControlFlowGraph) {
+        let children = graph.dominator_tree_children();
+        let mut phi_inputs: HashMap<usize, Vec<Vec<(Value, BlockLabel)>>> = self.phi_allocas.iter()
+            .map(|(&block, phis)| (block, vec![Vec::new(); phis.len()]))
+            .collect();
+
+        // Unreachable blocks are outside the dominator tree, so each is cleaned up on its own
+        let roots = std::iter::once(0).chain((1..graph.block_count()).filter(|&block| !graph.is_reachable(block)));
+        for root in roots.collect::<Vec<_>>() {
+            self.current_values = self.allocas.iter()
+                .map(|alloca| Value::Constant(Constant::Undefined(alloca.allocated_type)))
+                .collect();
+            // Each stack entry is a block to visit, along with the values to restore after
+            // its subtree has been visited
+            let mut stack = vec![(root, None)];
+            while let Some((block_index, saved_values)) = stack.pop() {
+                if let Some(saved_values) = saved_values {
+                    self.current_values = saved_values;
+                    continue;
+                }
+                let values_before = self.current_values.clone();
+                self.rename_block(function, graph, block_index, &mut phi_inputs);
+                stack.push((block_index, Some(values_before)));
+                for &child in children[block_index].iter().rev() {
+                    stack.push((child, None));
+                }
+            }
+        }
+
+        for (block_index, phis) in std::mem::take(&mut self.phi_allocas) {
+            let inputs = phi_inputs.remove(&block_index).unwrap();
+            let block = &mut function.blocks_mut()[block_index];
+            let existing_phis = std::mem::take(block.phis_mut());
+            for ((_, result), inputs) in phis.into_iter().zip(inputs) {
+                block.add_phi(PhiInstruction {
+                    result,
+                    inputs: inputs.into_boxed_slice(),
+                });
+            }
+            block.phis_mut().extend(existing_phis);
+        }
+    }
+
+    fn rename_block(
+        &mut self,
+        function: &mut FunctionDefinition,
+        graph: &ControlFlowGraph,
+        block_index: usize,
+        phi_inputs: &mut HashMap<usize, Vec<Vec<(Value, BlockLabel)>>>,
+    ) {
+        if let Some(phis) = self.phi_allocas.get(&block_index) {
+            for (alloca_index, result) in phis {
+                self.current_values[*alloca_index] = Value::Register(result.clone());
+            }
+        }
+
+        let block = &mut function.blocks_mut()[block_index];
+        for (instruction, location) in block.take_instructions() {
+            match &instruction {
+                Instruction::StackAllocate { result } => {
+                    if let Some(alloca_index) = self.alloca_index(&Value::Register(result.clone())) {
+                        self.current_values[alloca_index] = Value::Constant(Constant::Undefined(self.allocas[alloca_index].allocated_type));
+                        continue;
+                    }
+                }
+                Instruction::Load { result, pointer } => {
+                    if let Some(alloca_index) = self.alloca_index(pointer) {
+                        let mut value = self.current_values[alloca_index].clone();
+                        value.set_type(result.get_type());
+                        self.replacements.insert(result.identifier().into(), value);
+                        continue;
+                    }
+                }
+                Instruction::Store { value, pointer } => {
+                    if let Some(alloca_index) = self.alloca_index(pointer) {
+                        let mut value = self.replaced(value);
+                        value.set_type(self.allocas[alloca_index].allocated_type);
+                        self.current_values[alloca_index] = value;
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+            block.add_instruction(instruction, location);
+        }
+
+        let label = block.label().clone();
+        for &successor in graph.successors(block_index) {
+            if let Some(phis) = self.phi_allocas.get(&successor) {
+                let inputs = phi_inputs.get_mut(&successor).unwrap();
+                for ((alloca_index, _), inputs) in phis.iter().zip(inputs) {
+                    inputs.push((self.current_values[*alloca_index].clone(), label.clone()));
+                }
+            }
+        }
+    }
+
+    /// Get `value` with any register replaced by the value it stands for.
+    fn replaced(&self, value: &Value) -> Value {
+        let mut value = value.clone();
+        replace_registers(&mut value, &self.replacements);
+        value
+    }
+}
@@ -1,10 +1,114 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap};
 use std::path::Path;
-use crate::ir::value::{Constant, LocalRegister, GlobalRegister};
+use crate::ir::value::{Constant, LocalRegister, GlobalRegister, Value};
 use crate::sema::TypeHandle;
 
 pub mod value;
 pub mod instr;
+mod display;
+pub use display::*;
+pub mod cfg;
+pub use cfg::ControlFlowGraph;
+mod verify;
+pub use verify::*;
+mod mem2reg;
+pub use mem2reg::*;
+mod optimize;
+pub use optimize::*;
+mod inline;
+pub use inline::*;
+mod unwind;
+pub use unwind::*;
+
+/// Whether calls to a function should be replaced with the body of the function.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum InlineHint {
+    /// No preference was given.
+    #[default]
+    Default,
+    /// Marked `inline`, so calls are replaced wherever possible.
+    Always,
+    /// Marked `noinline`, so calls are never replaced.
+    Never,
+}
+
+/// The calling convention of a function, which can only be chosen for `foreign` functions.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum CallingConvention {
+    /// The platform's C calling convention.
+    #[default]
+    C,
+    Fast,
+    Cold,
+    StdCall,
+    FastCall,
+    VectorCall,
+    Win64,
+    SysV64,
+}
+
+impl CallingConvention {
+    pub const NAMES: &'static [(&'static str, CallingConvention)] = &[
+        ("C", Self::C),
+        ("fast", Self::Fast),
+        ("cold", Self::Cold),
+        ("stdcall", Self::StdCall),
+        ("fastcall", Self::FastCall),
+        ("vectorcall", Self::VectorCall),
+        ("win64", Self::Win64),
+        ("sysv64", Self::SysV64),
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::NAMES.iter().find_map(|&(known_name, convention)| (known_name == name).then_some(convention))
+    }
+
+    pub fn name(self) -> &'static str {
+        Self::NAMES.iter().find_map(|&(name, convention)| (convention == self).then_some(name)).unwrap()
+    }
+}
+
+/// Whether a function can be referred to from outside the package defining it.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Linkage {
+    #[default]
+    External,
+    /// Only visible to the package defining the function.
+    Internal,
+}
+
+/// Promises made about a pointer parameter by annotating it.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct ParameterAttributes {
+    /// `noalias`: no other pointer accessible to the function refers to the same memory.
+    pub is_noalias: bool,
+    /// `nonnull`: the pointer is never null.
+    pub is_nonnull: bool,
+    /// `readonly`: the function never writes through the pointer.
+    pub is_readonly: bool,
+}
+
+impl ParameterAttributes {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Properties of a function which are not part of its type, inferred in the fill phase and used
+/// by backends to describe the function more precisely.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct FunctionAttributes {
+    pub linkage: Linkage,
+    pub calling_convention: CallingConvention,
+    /// The function returns `never`, so calls to it never return.
+    pub is_noreturn: bool,
+    /// No exception can unwind out of the function. Cupric has no way to unwind the stack, so this
+    /// is inferred for functions which only call functions that can't unwind, and is otherwise
+    /// only true for declarations marked `nounwind`.
+    pub is_nounwind: bool,
+    /// Attributes of each named parameter, in order.
+    pub parameter_attributes: Box<[ParameterAttributes]>,
+}
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum GlobalVariableKind {
@@ -57,9 +161,11 @@ impl ExternalFunction {
     }
 }
 
+#[derive(Clone, Debug)]
 pub struct GlobalVariable {
     register: GlobalRegister,
     kind: GlobalVariableKind,
+    linkage: Linkage,
     value: Constant,
 }
 
@@ -68,10 +174,16 @@ impl GlobalVariable {
         Self {
             register,
             kind,
+            linkage: Linkage::External,
             value,
         }
     }
 
+    pub fn with_linkage(mut self, linkage: Linkage) -> Self {
+        self.linkage = linkage;
+        self
+    }
+
     pub fn register(&self) -> &GlobalRegister {
         &self.register
     }
@@ -80,17 +192,37 @@ impl GlobalVariable {
         self.kind
     }
 
+    pub fn linkage(&self) -> Linkage {
+        self.linkage
+    }
+
     pub fn value(&self) -> &Constant {
         &self.value
     }
 }
 
+/// A named local variable (including parameters) described in debug info.
+pub struct DebugVariable {
+    pub name: Box<str>,
+    /// The stack allocation holding the variable's value.
+    pub pointer: LocalRegister,
+    pub value_type: TypeHandle,
+    pub span: crate::Span,
+    /// The 1-based position of the variable in the parameter list, if it is a parameter.
+    pub argument_number: Option<usize>,
+}
+
 pub struct FunctionDefinition {
     register: GlobalRegister,
     return_type: TypeHandle,
     parameter_registers: Vec<LocalRegister>,
     is_variadic: bool,
     blocks: Vec<instr::BasicBlock>,
+    /// The name of the function as written in source.
+    source_name: Option<Box<str>>,
+    span: Option<crate::Span>,
+    debug_variables: Vec<DebugVariable>,
+    inline_hint: InlineHint,
 }
 
 impl FunctionDefinition {
@@ -105,13 +237,32 @@ impl FunctionDefinition {
             parameter_registers: Vec::new(),
             is_variadic,
             blocks: Vec::new(),
+            source_name: None,
+            span: None,
+            debug_variables: Vec::new(),
+            inline_hint: InlineHint::Default,
         }
     }
 
+    pub fn with_source_info(mut self, source_name: impl Into<Box<str>>, span: crate::Span) -> Self {
+        self.source_name = Some(source_name.into());
+        self.span = Some(span);
+        self
+    }
+
+    pub fn with_inline_hint(mut self, inline_hint: InlineHint) -> Self {
+        self.inline_hint = inline_hint;
+        self
+    }
+
     pub fn register(&self) -> &GlobalRegister {
         &self.register
     }
 
+    pub fn inline_hint(&self) -> InlineHint {
+        self.inline_hint
+    }
+
     pub fn return_type(&self) -> TypeHandle {
         self.return_type
     }
@@ -132,14 +283,146 @@ impl FunctionDefinition {
         &self.blocks
     }
 
+    pub fn blocks_mut(&mut self) -> &mut Vec<instr::BasicBlock> {
+        &mut self.blocks
+    }
+
+    /// The total number of instructions in this function, including phi and terminator
+    /// instructions.
+    pub fn instruction_count(&self) -> usize {
+        self.blocks.iter()
+            .map(|block| block.phis().len() + block.body().len() + 1)
+            .sum()
+    }
+
     pub fn add_block(&mut self, block: instr::BasicBlock) {
         self.blocks.push(block);
     }
+
+    /// Number anonymous registers consecutively in order of definition, as LLVM requires. Passes
+    /// which remove instructions leave gaps in the numbering, so this must be run after them.
+    pub fn renumber_anonymous_registers(&mut self) {
+        let mut new_identifiers: HashMap<Box<[u8]>, Box<[u8]>> = HashMap::new();
+        let mut assign = |register: &LocalRegister| {
+            if register.is_anonymous() {
+                let identifier = new_identifiers.len().to_string().into_bytes().into_boxed_slice();
+                new_identifiers.insert(register.identifier().into(), identifier);
+            }
+        };
+        for register in &self.parameter_registers {
+            assign(register);
+        }
+        for block in &self.blocks {
+            for phi in block.phis() {
+                assign(&phi.result);
+            }
+            for instruction in block.body() {
+                if let Some(result) = instruction.result() {
+                    assign(result);
+                }
+            }
+        }
+
+        let mut rename = |register: &mut LocalRegister| {
+            if let Some(identifier) = new_identifiers.get(register.identifier()) {
+                register.set_identifier(identifier.clone());
+            }
+        };
+        for register in &mut self.parameter_registers {
+            rename(register);
+        }
+        for block in &mut self.blocks {
+            for phi in block.phis_mut() {
+                rename(&mut phi.result);
+                for (value, _) in phi.inputs.iter_mut() {
+                    value.for_each_register_mut(&mut rename);
+                }
+            }
+            for (mut instruction, location) in block.take_instructions() {
+                if let Some(result) = instruction.result_mut() {
+                    rename(result);
+                }
+                for operand in instruction.operands_mut() {
+                    operand.for_each_register_mut(&mut rename);
+                }
+                block.add_instruction(instruction, location);
+            }
+            if let Some(operand) = block.terminator_mut().operand_mut() {
+                operand.for_each_register_mut(&mut rename);
+            }
+        }
+        for variable in &mut self.debug_variables {
+            rename(&mut variable.pointer);
+        }
+    }
+
+    /// Replace every use of a register with the value it stands for in `replacements`, which is
+    /// keyed by register identifier.
+    pub fn replace_register_uses(&mut self, replacements: &HashMap<Box<[u8]>, Value>) {
+        for block in &mut self.blocks {
+            for phi in block.phis_mut() {
+                for (value, _) in phi.inputs.iter_mut() {
+                    replace_registers(value, replacements);
+                }
+            }
+            for (mut instruction, location) in block.take_instructions() {
+                for operand in instruction.operands_mut() {
+                    replace_registers(operand, replacements);
+                }
+                block.add_instruction(instruction, location);
+            }
+            if let Some(operand) = block.terminator_mut().operand_mut() {
+                replace_registers(operand, replacements);
+            }
+        }
+    }
+
+    pub fn source_name(&self) -> Option<&str> {
+        self.source_name.as_deref()
+    }
+
+    pub fn span(&self) -> Option<crate::Span> {
+        self.span
+    }
+
+    pub fn debug_variables(&self) -> &[DebugVariable] {
+        &self.debug_variables
+    }
+
+    pub fn debug_variables_mut(&mut self) -> &mut Vec<DebugVariable> {
+        &mut self.debug_variables
+    }
+
+    pub fn add_debug_variable(&mut self, variable: DebugVariable) {
+        self.debug_variables.push(variable);
+    }
+}
+
+/// Replace any register in `value` with the value it stands for in `replacements`, which is keyed
+/// by register identifier. Replacement values may themselves contain replaced registers.
+pub fn replace_registers(value: &mut Value, replacements: &HashMap<Box<[u8]>, Value>) {
+    match value {
+        Value::Register(register) => {
+            if let Some(replacement) = replacements.get(register.identifier()) {
+                let register_type = register.get_type();
+                *value = replacement.clone();
+                value.set_type(register_type);
+                replace_registers(value, replacements);
+            }
+        }
+        Value::Indirect { pointer, .. } => replace_registers(pointer, replacements),
+        Value::BoundFunction { self_value, function_value } => {
+            replace_registers(&mut self_value.1, replacements);
+            replace_registers(function_value, replacements);
+        }
+        Value::Never | Value::Break | Value::Continue | Value::Void | Value::Constant(..) => {}
+    }
 }
 
 pub struct CompilationUnit {
     main_path: Box<Path>,
-    type_declarations: HashSet<TypeHandle>,
+    /// Ordered by type handle, which corresponds to the order in which types were defined.
+    type_declarations: BTreeSet<TypeHandle>,
     external_global_variables: Vec<ExternalGlobalVariable>,
     external_functions: Vec<ExternalFunction>,
     global_variables: Vec<GlobalVariable>,
@@ -150,7 +433,7 @@ impl CompilationUnit {
     pub fn new(main_path: impl Into<Box<Path>>) -> Self {
         Self {
             main_path: main_path.into(),
-            type_declarations: HashSet::new(),
+            type_declarations: BTreeSet::new(),
             external_global_variables: Vec::new(),
             external_functions: Vec::new(),
             global_variables: Vec::new(),
@@ -162,7 +445,7 @@ impl CompilationUnit {
         &self.main_path
     }
 
-    pub fn type_declarations(&self) -> &HashSet<TypeHandle> {
+    pub fn type_declarations(&self) -> &BTreeSet<TypeHandle> {
         &self.type_declarations
     }
 
@@ -194,10 +477,19 @@ impl CompilationUnit {
         self.global_variables.push(global_variable);
     }
 
+    /// Remove every global variable after the first `count`.
+    pub fn truncate_global_variables(&mut self, count: usize) {
+        self.global_variables.truncate(count);
+    }
+
     pub fn function_definitions(&self) -> &[FunctionDefinition] {
         &self.function_definitions
     }
 
+    pub fn function_definitions_mut(&mut self) -> &mut [FunctionDefinition] {
+        &mut self.function_definitions
+    }
+
     pub fn add_function_definition(&mut self, function: FunctionDefinition) {
         self.function_definitions.push(function);
     }
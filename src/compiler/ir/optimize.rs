@@ -0,0 +1,397 @@
+use std::collections::{HashMap, HashSet};
+use clap::ValueEnum;
+use crate::ir::{replace_registers, ControlFlowGraph, FunctionDefinition};
+use crate::ir::instr::*;
+use crate::ir::value::*;
+use crate::sema::{ConversionOperation, GlobalContext, TypeHandle};
+
+/// How much work the compiler does to simplify the IR it generates.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Default, ValueEnum)]
+pub enum OptimizationLevel {
+    /// Only promote local variables to registers.
+    #[default]
+    #[value(name = "0")]
+    None,
+    /// Also fold constants and remove unreachable blocks and unused instructions.
+    #[value(name = "1")]
+    Basic,
+}
+
+/// Run the passes selected by `level` over `function` until none of them changes anything.
+pub fn optimize_function(context: &GlobalContext, function: &mut FunctionDefinition, level: OptimizationLevel) {
+    if level == OptimizationLevel::None {
+        return;
+    }
+
+    loop {
+        let mut changed = fold_constants(context, function);
+        changed |= remove_unreachable_blocks(function);
+        changed |= remove_unused_instructions(function);
+        if !changed {
+            break;
+        }
+    }
+}
+
+/// Replace instructions and phis whose results are known at compile time with constants, and
+/// conditional branches on constants with unconditional branches.
+fn fold_constants(context: &GlobalContext, function: &mut FunctionDefinition) -> bool {
+    let mut replacements: HashMap<Box<[u8]>, Value> = HashMap::new();
+    let mut changed = false;
+
+    for block_index in 0..function.blocks().len() {
+        let block = &mut function.blocks_mut()[block_index];
+        let mut kept_phis = Vec::new();
+        for mut phi in std::mem::take(block.phis_mut()) {
+            for (value, _) in phi.inputs.iter_mut() {
+                replace_registers(value, &replacements);
+            }
+            if let Some(value) = single_phi_input(&phi) {
+                replacements.insert(phi.result.identifier().into(), value);
+            }
+            else {
+                kept_phis.push(phi);
+            }
+        }
+        *block.phis_mut() = kept_phis;
+
+        for (mut instruction, location) in block.take_instructions() {
+            for operand in instruction.operands_mut() {
+                replace_registers(operand, &replacements);
+            }
+            if let Some(constant) = fold_instruction(context, &instruction) {
+                let result = instruction.result().expect("folded instruction should have a result");
+                replacements.insert(result.identifier().into(), Value::Constant(constant));
+                continue;
+            }
+            block.add_instruction(instruction, location);
+        }
+
+        if let Some(operand) = block.terminator_mut().operand_mut() {
+            replace_registers(operand, &replacements);
+        }
+        let TerminatorInstruction::ConditionalBranch {
+            condition: Value::Constant(Constant::Boolean(condition)),
+            consequent_label,
+            alternative_label,
+        } = block.terminator() else {
+            continue;
+        };
+        let (taken_label, skipped_label) = if *condition {
+            (consequent_label.clone(), alternative_label.clone())
+        }
+        else {
+            (alternative_label.clone(), consequent_label.clone())
+        };
+        let label = block.label().clone();
+        let location = block.terminator_location();
+        block.set_terminator(TerminatorInstruction::Branch { to_label: taken_label.clone() }, location);
+        changed = true;
+
+        // The skipped block's phis no longer receive a value from this block
+        if skipped_label != taken_label {
+            if let Some(skipped_block) = function.blocks_mut().iter_mut().find(|block| block.label() == &skipped_label) {
+                for phi in skipped_block.phis_mut() {
+                    phi.inputs = phi.inputs.iter()
+                        .filter(|(_, from_label)| from_label != &label)
+                        .cloned()
+                        .collect();
+                }
+            }
+        }
+    }
+
+    if !replacements.is_empty() {
+        function.replace_register_uses(&replacements);
+        changed = true;
+    }
+    changed
+}
+
+/// The value of a phi whose inputs are all the same, ignoring inputs from the phi itself. An
+/// input which reaches the phi along every path must dominate it, so it can be used directly.
+fn single_phi_input(phi: &PhiInstruction) -> Option<Value> {
+    let mut inputs = phi.inputs.iter()
+        .map(|(value, _)| value)
+        .filter(|value| !matches!(value, Value::Register(register) if register.identifier() == phi.result.identifier()));
+    let first = inputs.next()?;
+    if !matches!(first, Value::Constant(..) | Value::Register(..)) || !inputs.all(|value| value == first) {
+        return None;
+    }
+    let mut value = first.clone();
+    value.set_type(phi.result.get_type());
+    Some(value)
+}
+
+/// Compute the result of `instruction` if all of its operands are constants.
+fn fold_instruction(context: &GlobalContext, instruction: &Instruction) -> Option<Constant> {
+    let integer = |raw: i128, result: &LocalRegister| {
+        IntegerValue::from_unknown_type(raw, result.get_type(), context.target()).map(Constant::Integer)
+    };
+    // Only fold results which fit in the result type without wrapping
+    let exact_integer = |raw: i128, result: &LocalRegister| {
+        integer(raw, result).filter(|folded| matches!(folded, Constant::Integer(value) if value.raw() == raw))
+    };
+    let float = |raw: f64, result: &LocalRegister| {
+        FloatValue::from_unknown_type(raw, result.get_type(), context.target()).map(Constant::Float)
+    };
+
+    match instruction {
+        Instruction::Negate { result, operand } => match operand.as_constant()? {
+            Constant::Integer(value) => integer(value.raw().wrapping_neg(), result),
+            Constant::Float(value) => float(-value.raw(), result),
+            _ => None
+        },
+        Instruction::Not { result, operand } => match operand.as_constant()? {
+            Constant::Boolean(value) => Some(Constant::Boolean(!value)),
+            Constant::Integer(value) => integer(!value.raw(), result),
+            _ => None
+        },
+        Instruction::Add { result, lhs, rhs } => match constant_operands(lhs, rhs)? {
+            (Constant::Integer(lhs), Constant::Integer(rhs)) => integer(lhs.raw().wrapping_add(rhs.raw()), result),
+            (Constant::Float(lhs), Constant::Float(rhs)) => float(lhs.raw() + rhs.raw(), result),
+            _ => None
+        },
+        Instruction::Subtract { result, lhs, rhs } => match constant_operands(lhs, rhs)? {
+            (Constant::Integer(lhs), Constant::Integer(rhs)) => integer(lhs.raw().wrapping_sub(rhs.raw()), result),
+            (Constant::Float(lhs), Constant::Float(rhs)) => float(lhs.raw() - rhs.raw(), result),
+            _ => None
+        },
+        Instruction::Multiply { result, lhs, rhs } => match constant_operands(lhs, rhs)? {
+            (Constant::Integer(lhs), Constant::Integer(rhs)) => integer(lhs.raw().wrapping_mul(rhs.raw()), result),
+            (Constant::Float(lhs), Constant::Float(rhs)) => float(lhs.raw() * rhs.raw(), result),
+            _ => None
+        },
+        Instruction::Divide { result, lhs, rhs } => match constant_operands(lhs, rhs)? {
+            // Division by zero and signed overflow are undefined, so leave them for runtime
+            (Constant::Integer(lhs), Constant::Integer(rhs)) => exact_integer(lhs.raw().checked_div(rhs.raw())?, result),
+            (Constant::Float(lhs), Constant::Float(rhs)) => float(lhs.raw() / rhs.raw(), result),
+            _ => None
+        },
+        Instruction::Remainder { result, lhs, rhs } => match constant_operands(lhs, rhs)? {
+            (Constant::Integer(lhs), Constant::Integer(rhs)) => {
+                exact_integer(lhs.raw().checked_div(rhs.raw())?, result)?;
+                integer(lhs.raw() % rhs.raw(), result)
+            }
+            (Constant::Float(lhs), Constant::Float(rhs)) => float(lhs.raw() % rhs.raw(), result),
+            _ => None
+        },
+        Instruction::ShiftLeft { result, lhs, rhs } => match constant_operands(lhs, rhs)? {
+            (Constant::Integer(lhs), Constant::Integer(rhs)) => {
+                let shift = shift_amount(context, lhs, rhs)?;
+                integer(lhs.raw().wrapping_shl(shift), result)
+            }
+            _ => None
+        },
+        Instruction::ShiftRight { result, lhs, rhs } => match constant_operands(lhs, rhs)? {
+            // Signed values are stored sign-extended and unsigned values are never negative, so
+            // this is an arithmetic or logical shift as appropriate
+            (Constant::Integer(lhs), Constant::Integer(rhs)) => {
+                let shift = shift_amount(context, lhs, rhs)?;
+                integer(lhs.raw() >> shift, result)
+            }
+            _ => None
+        },
+        Instruction::And { result, lhs, rhs } => match constant_operands(lhs, rhs)? {
+            (Constant::Boolean(lhs), Constant::Boolean(rhs)) => Some(Constant::Boolean(lhs & rhs)),
+            (Constant::Integer(lhs), Constant::Integer(rhs)) => integer(lhs.raw() & rhs.raw(), result),
+            _ => None
+        },
+        Instruction::Or { result, lhs, rhs } => match constant_operands(lhs, rhs)? {
+            (Constant::Boolean(lhs), Constant::Boolean(rhs)) => Some(Constant::Boolean(lhs | rhs)),
+            (Constant::Integer(lhs), Constant::Integer(rhs)) => integer(lhs.raw() | rhs.raw(), result),
+            _ => None
+        },
+        Instruction::Xor { result, lhs, rhs } => match constant_operands(lhs, rhs)? {
+            (Constant::Boolean(lhs), Constant::Boolean(rhs)) => Some(Constant::Boolean(lhs ^ rhs)),
+            (Constant::Integer(lhs), Constant::Integer(rhs)) => integer(lhs.raw() ^ rhs.raw(), result),
+            _ => None
+        },
+        Instruction::CompareEqual { lhs, rhs, .. } => match constant_operands(lhs, rhs)? {
+            (Constant::Boolean(lhs), Constant::Boolean(rhs)) => Some(Constant::Boolean(lhs == rhs)),
+            (Constant::Integer(lhs), Constant::Integer(rhs)) => Some(Constant::Boolean(lhs.raw() == rhs.raw())),
+            (Constant::Float(lhs), Constant::Float(rhs)) => Some(Constant::Boolean(lhs.raw() == rhs.raw())),
+            _ => None
+        },
+        Instruction::CompareNotEqual { lhs, rhs, .. } => match constant_operands(lhs, rhs)? {
+            (Constant::Boolean(lhs), Constant::Boolean(rhs)) => Some(Constant::Boolean(lhs != rhs)),
+            (Constant::Integer(lhs), Constant::Integer(rhs)) => Some(Constant::Boolean(lhs.raw() != rhs.raw())),
+            (Constant::Float(lhs), Constant::Float(rhs)) => Some(Constant::Boolean(lhs.raw() != rhs.raw())),
+            _ => None
+        },
+        Instruction::CompareLessThan { lhs, rhs, .. } => fold_ordering(lhs, rhs, |lhs, rhs| lhs < rhs, |lhs, rhs| lhs < rhs),
+        Instruction::CompareLessEqual { lhs, rhs, .. } => fold_ordering(lhs, rhs, |lhs, rhs| lhs <= rhs, |lhs, rhs| lhs <= rhs),
+        Instruction::CompareGreaterThan { lhs, rhs, .. } => fold_ordering(lhs, rhs, |lhs, rhs| lhs > rhs, |lhs, rhs| lhs > rhs),
+        Instruction::CompareGreaterEqual { lhs, rhs, .. } => fold_ordering(lhs, rhs, |lhs, rhs| lhs >= rhs, |lhs, rhs| lhs >= rhs),
+        Instruction::Convert { operation, result, value } => fold_conversion(context, *operation, result, value.as_constant()?),
+        _ => None
+    }
+}
+
+fn constant_operands<'a>(lhs: &'a Value, rhs: &'a Value) -> Option<(&'a Constant, &'a Constant)> {
+    Some((lhs.as_constant()?, rhs.as_constant()?))
+}
+
+/// The shift amount given by `rhs`, if it is less than the width of `lhs`. Larger shifts produce
+/// poison, so they are left for runtime.
+fn shift_amount(context: &GlobalContext, lhs: &IntegerValue, rhs: &IntegerValue) -> Option<u32> {
+    let bit_width = lhs.integer_type().size(context.target()) * 8;
+    u32::try_from(rhs.raw()).ok().filter(|&shift| u64::from(shift) < bit_width)
+}
+
+/// Fold an ordered comparison. Integer values are stored with the correct sign for their type, so
+/// they can be compared directly regardless of signedness.
+fn fold_ordering(
+    lhs: &Value,
+    rhs: &Value,
+    compare_integers: impl Fn(i128, i128) -> bool,
+    compare_floats: impl Fn(f64, f64) -> bool,
+) -> Option<Constant> {
+    match constant_operands(lhs, rhs)? {
+        (Constant::Integer(lhs), Constant::Integer(rhs)) => Some(Constant::Boolean(compare_integers(lhs.raw(), rhs.raw()))),
+        (Constant::Float(lhs), Constant::Float(rhs)) => Some(Constant::Boolean(compare_floats(lhs.raw(), rhs.raw()))),
+        _ => None
+    }
+}
+
+fn fold_conversion(context: &GlobalContext, operation: ConversionOperation, result: &LocalRegister, value: &Constant) -> Option<Constant> {
+    let target = context.target();
+    let result_type = result.get_type();
+    match (operation, value) {
+        (ConversionOperation::Truncate, Constant::Integer(value)) => {
+            IntegerValue::from_unknown_type(value.raw(), result_type, target).map(Constant::Integer)
+        }
+        (ConversionOperation::ZeroExtend | ConversionOperation::UnsignedToFloat, Constant::Integer(value)) => {
+            let bit_width = value.integer_type().size(target) * 8;
+            let unsigned = value.raw() & ((1 << bit_width) - 1);
+            fold_extension(target, result_type, unsigned)
+        }
+        (ConversionOperation::SignExtend | ConversionOperation::SignedToFloat, Constant::Integer(value)) => {
+            let unused_bits = 128 - value.integer_type().size(target) as u32 * 8;
+            let signed = (value.raw() << unused_bits) >> unused_bits;
+            fold_extension(target, result_type, signed)
+        }
+        (ConversionOperation::FloatTruncate | ConversionOperation::FloatExtend, Constant::Float(value)) => {
+            FloatValue::from_unknown_type(value.raw(), result_type, target).map(Constant::Float)
+        }
+        (ConversionOperation::FloatToSigned | ConversionOperation::FloatToUnsigned, Constant::Float(value)) => {
+            // Values which don't fit in the result type produce poison, so leave them for runtime
+            let truncated = value.raw().trunc();
+            if !truncated.is_finite() || truncated.abs() >= 2f64.powi(127) {
+                return None;
+            }
+            let raw = truncated as i128;
+            let folded = IntegerValue::from_unknown_type(raw, result_type, target)?;
+            (folded.raw() == raw).then_some(Constant::Integer(folded))
+        }
+        _ => None
+    }
+}
+
+/// Build an integer or float constant of `result_type` from an integer which has already been
+/// extended according to the signedness of the conversion.
+fn fold_extension(target: &crate::target::TargetInfo, result_type: TypeHandle, raw: i128) -> Option<Constant> {
+    if let Some(value) = IntegerValue::from_unknown_type(raw, result_type, target) {
+        return Some(Constant::Integer(value));
+    }
+    let float_type = FloatType::from_handle(result_type)?;
+    // Convert directly to the result precision to avoid rounding twice
+    let raw = match float_type.size(target) {
+        4 => raw as f32 as f64,
+        _ => raw as f64,
+    };
+    FloatValue::from_unknown_type(raw, result_type, target).map(Constant::Float)
+}
+
+/// Remove blocks which can't be reached from the entry block, along with the phi inputs and debug
+/// variables which refer to them.
+fn remove_unreachable_blocks(function: &mut FunctionDefinition) -> bool {
+    let Ok(graph) = ControlFlowGraph::new(function) else {
+        return false;
+    };
+    let reachable: Vec<bool> = (0..graph.block_count()).map(|block| graph.is_reachable(block)).collect();
+    if reachable.iter().all(|&is_reachable| is_reachable) {
+        return false;
+    }
+
+    let mut removed_labels = Vec::new();
+    let mut removed_registers = HashSet::new();
+    let mut block_index = 0;
+    function.blocks_mut().retain(|block| {
+        let is_reachable = reachable[block_index];
+        block_index += 1;
+        if !is_reachable {
+            removed_labels.push(block.label().clone());
+            for instruction in block.body() {
+                if let Some(result) = instruction.result() {
+                    removed_registers.insert(Box::<[u8]>::from(result.identifier()));
+                }
+            }
+        }
+        is_reachable
+    });
+
+    for block in function.blocks_mut() {
+        for phi in block.phis_mut() {
+            if phi.inputs.iter().any(|(_, label)| removed_labels.contains(label)) {
+                phi.inputs = phi.inputs.iter()
+                    .filter(|(_, label)| !removed_labels.contains(label))
+                    .cloned()
+                    .collect();
+            }
+        }
+    }
+    function.debug_variables_mut().retain(|variable| !removed_registers.contains(variable.pointer.identifier()));
+    true
+}
+
+/// Remove phis and instructions without side effects whose results are never used.
+fn remove_unused_instructions(function: &mut FunctionDefinition) -> bool {
+    let mut changed = false;
+    loop {
+        let mut used: HashSet<Box<[u8]>> = HashSet::new();
+        let mut mark_used = |register: &LocalRegister| {
+            used.insert(register.identifier().into());
+        };
+        for block in function.blocks() {
+            for phi in block.phis() {
+                for (value, _) in phi.inputs.iter() {
+                    value.for_each_register(&mut mark_used);
+                }
+            }
+            for instruction in block.body() {
+                for operand in instruction.operands() {
+                    operand.for_each_register(&mut mark_used);
+                }
+            }
+            if let Some(operand) = block.terminator().operand() {
+                operand.for_each_register(&mut mark_used);
+            }
+        }
+        for variable in function.debug_variables() {
+            mark_used(&variable.pointer);
+        }
+
+        let mut removed_any = false;
+        for block in function.blocks_mut() {
+            let phi_count = block.phis().len();
+            block.phis_mut().retain(|phi| used.contains(phi.result.identifier()));
+            removed_any |= block.phis().len() != phi_count;
+
+            for (instruction, location) in block.take_instructions() {
+                let has_side_effects = matches!(instruction, Instruction::Store { .. } | Instruction::Call { .. });
+                let is_unused = instruction.result().is_some_and(|result| !used.contains(result.identifier()));
+                if is_unused && !has_side_effects {
+                    removed_any = true;
+                    continue;
+                }
+                block.add_instruction(instruction, location);
+            }
+        }
+
+        if !removed_any {
+            return changed;
+        }
+        changed = true;
+    }
+}
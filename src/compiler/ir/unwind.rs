@@ -0,0 +1,58 @@
+use std::collections::HashSet;
+use crate::ir::{direct_callee, CompilationUnit, FunctionDefinition};
+use crate::ir::instr::*;
+use crate::sema::GlobalContext;
+
+/// Find the functions defined in `unit` which can't unwind, since every function they call is
+/// known not to unwind. Functions called through a pointer could be anything, so they are assumed
+/// to unwind. Functions already marked `nounwind` are trusted and left out of the result.
+pub fn find_nounwind_functions(context: &GlobalContext, unit: &CompilationUnit) -> Vec<Box<[u8]>> {
+    let is_marked_nounwind = |identifier: &[u8]| {
+        context.function_attributes(identifier).is_some_and(|attributes| attributes.is_nounwind)
+    };
+    let candidates: Vec<&FunctionDefinition> = unit.function_definitions().iter()
+        .filter(|function| !is_marked_nounwind(function.register().identifier()))
+        .collect();
+    let candidate_identifiers: HashSet<&[u8]> = candidates.iter()
+        .map(|function| function.register().identifier())
+        .collect();
+
+    // Start by assuming every candidate can't unwind, so functions calling themselves still can't,
+    // then rule out candidates until nothing changes
+    let mut may_unwind: HashSet<&[u8]> = HashSet::new();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for function in &candidates {
+            let identifier = function.register().identifier();
+            if may_unwind.contains(identifier) {
+                continue;
+            }
+            let calls_unwinding_function = calls(function).any(|callee| match callee {
+                Some(callee) if candidate_identifiers.contains(callee) => may_unwind.contains(callee),
+                Some(callee) => !is_marked_nounwind(callee),
+                None => true,
+            });
+            if calls_unwinding_function {
+                may_unwind.insert(identifier);
+                changed = true;
+            }
+        }
+    }
+
+    candidate_identifiers.into_iter()
+        .filter(|identifier| !may_unwind.contains(identifier))
+        .map(Box::from)
+        .collect()
+}
+
+/// The identifier of the function each call in `function` is made to, or `None` for calls through
+/// a pointer.
+fn calls(function: &FunctionDefinition) -> impl Iterator<Item = Option<&[u8]>> {
+    function.blocks().iter()
+        .flat_map(|block| block.body())
+        .filter_map(|instruction| match instruction {
+            Instruction::Call { callee, .. } => Some(direct_callee(callee)),
+            _ => None,
+        })
+}
@@ -286,6 +286,15 @@ impl LocalRegister {
         &self.identifier
     }
 
+    pub fn set_identifier(&mut self, identifier: Box<[u8]>) {
+        self.identifier = identifier;
+    }
+
+    /// Whether this register is numbered rather than named, like LLVM's unnamed values.
+    pub fn is_anonymous(&self) -> bool {
+        !self.identifier.is_empty() && self.identifier.iter().all(u8::is_ascii_digit)
+    }
+
     pub fn get_type(&self) -> TypeHandle {
         self.value_type
     }
@@ -589,6 +598,31 @@ impl Value {
         }
     }
 
+    /// Call `f` on every local register mentioned anywhere in this value.
+    pub fn for_each_register<'a>(&'a self, f: &mut impl FnMut(&'a LocalRegister)) {
+        match self {
+            Self::Register(register) => f(register),
+            Self::Indirect { pointer, .. } => pointer.for_each_register(f),
+            Self::BoundFunction { self_value, function_value } => {
+                self_value.1.for_each_register(f);
+                function_value.for_each_register(f);
+            }
+            Self::Never | Self::Break | Self::Continue | Self::Void | Self::Constant(..) => {}
+        }
+    }
+
+    pub fn for_each_register_mut(&mut self, f: &mut impl FnMut(&mut LocalRegister)) {
+        match self {
+            Self::Register(register) => f(register),
+            Self::Indirect { pointer, .. } => pointer.for_each_register_mut(f),
+            Self::BoundFunction { self_value, function_value } => {
+                self_value.1.for_each_register_mut(f);
+                function_value.for_each_register_mut(f);
+            }
+            Self::Never | Self::Break | Self::Continue | Self::Void | Self::Constant(..) => {}
+        }
+    }
+
     pub fn bound_self_value(&self) -> Option<&Value> {
         match self {
             Self::BoundFunction { self_value, .. } => Some(&self_value.1),
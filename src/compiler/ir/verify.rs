@@ -0,0 +1,444 @@
+use std::collections::HashMap;
+use crate::ir::{ControlFlowGraph, FunctionDefinition};
+use crate::ir::cfg::label_name;
+use crate::ir::instr::*;
+use crate::ir::value::*;
+use crate::sema::{ConversionOperation, GlobalContext, TypeHandle, TypeRepr};
+
+/// Check that `function` is well-formed before it is handed to a backend: every block ends in
+/// a terminator, phi inputs match the predecessors of their block, operand types are what each
+/// instruction expects, and every register is defined exactly once before any of its uses.
+///
+/// Any problem found is a bug in the compiler rather than the source code, so it is reported
+/// as an [`InvalidIR`](crate::ErrorKind::InvalidIR) error naming `function_path`.
+pub fn verify_function(context: &GlobalContext, function: &FunctionDefinition, function_path: &impl std::fmt::Display) -> crate::Result<()> {
+    Verifier::new(context, function).and_then(Verifier::verify).map_err(|(message, location)| Box::new(crate::Error::new(
+        location.or(function.span()),
+        crate::ErrorKind::InvalidIR {
+            function_path: function_path.to_string(),
+            message,
+        },
+    ).with_note("this is a bug in the compiler, not in the code being compiled")))
+}
+
+/// A description of a problem, along with the source location of the offending instruction if
+/// it is known.
+type VerifyError = (String, Option<crate::Span>);
+
+/// Where a register is defined: the index of its block, and its position within that block
+/// (`None` for phis and parameters, which are defined at the start of the block).
+type Definition = (usize, Option<usize>);
+
+struct Verifier<'a> {
+    context: &'a GlobalContext,
+    function: &'a FunctionDefinition,
+    graph: ControlFlowGraph,
+    definitions: HashMap<&'a [u8], Definition>,
+}
+
+impl<'a> Verifier<'a> {
+    fn new(context: &'a GlobalContext, function: &'a FunctionDefinition) -> Result<Self, VerifyError> {
+        Ok(Self {
+            context,
+            function,
+            graph: ControlFlowGraph::new(function).map_err(|message| (message, None))?,
+            definitions: HashMap::new(),
+        })
+    }
+
+    fn verify(mut self) -> Result<(), VerifyError> {
+        let blocks = self.function.blocks();
+        if blocks.is_empty() {
+            return Err(("function has no blocks".to_owned(), None));
+        }
+
+        for block in blocks {
+            if !block.is_terminated() {
+                return Err((format!("block '{}' has no terminator", label_name(block.label())), None));
+            }
+            if block.body().len() != block.body_locations().len() {
+                return Err((format!("block '{}' has a location count which does not match its instruction count", label_name(block.label())), None));
+            }
+        }
+        if !self.graph.predecessors(0).is_empty() {
+            return Err((format!("entry block '{}' has predecessors", label_name(blocks[0].label())), None));
+        }
+
+        // Record every definition before checking uses, since a phi may use a register defined
+        // later in the block order
+        for register in self.function.parameter_registers() {
+            self.define(register, (0, None)).map_err(|message| (message, None))?;
+        }
+        for (block_index, block) in blocks.iter().enumerate() {
+            for phi in block.phis() {
+                self.define(&phi.result, (block_index, None)).map_err(|message| (message, None))?;
+            }
+            for (position, instruction) in block.body().iter().enumerate() {
+                if let Some(result) = instruction.result() {
+                    self.define(result, (block_index, Some(position)))
+                        .map_err(|message| (message, block.body_locations()[position]))?;
+                }
+            }
+        }
+
+        for (block_index, block) in blocks.iter().enumerate() {
+            let block_name = label_name(block.label());
+            self.verify_phis(block_index, block)
+                .map_err(|message| (format!("in block '{block_name}': {message}"), None))?;
+            for (position, instruction) in block.body().iter().enumerate() {
+                self.verify_instruction(block_index, position, instruction)
+                    .map_err(|message| (
+                        format!("in block '{block_name}', instruction {position}: {message}"),
+                        block.body_locations()[position],
+                    ))?;
+            }
+            self.verify_terminator(block_index, block)
+                .map_err(|message| (
+                    format!("in block '{block_name}', terminator: {message}"),
+                    block.terminator_location(),
+                ))?;
+        }
+
+        Ok(())
+    }
+
+    fn define(&mut self, register: &'a LocalRegister, definition: Definition) -> Result<(), String> {
+        if self.definitions.insert(register.identifier(), definition).is_some() {
+            Err(format!("register {} is defined more than once", register_name(register)))
+        }
+        else {
+            Ok(())
+        }
+    }
+
+    /// Check that `value` is usable at `position` in block `block_index`, where `None` means
+    /// the end of the block.
+    fn verify_use(&self, value: &Value, block_index: usize, position: Option<usize>) -> Result<(), String> {
+        match value {
+            Value::Register(register) => {
+                let &(definition_block, definition_position) = self.definitions.get(register.identifier())
+                    .ok_or_else(|| format!("register {} is never defined", register_name(register)))?;
+                let is_defined_before = if definition_block == block_index {
+                    match (definition_position, position) {
+                        (None, _) => true,
+                        (Some(_), None) => true,
+                        (Some(definition_position), Some(position)) => definition_position < position,
+                    }
+                }
+                else {
+                    self.graph.dominates(definition_block, block_index)
+                };
+                if !is_defined_before {
+                    return Err(format!("register {} is used before it is defined", register_name(register)));
+                }
+                let definition_type = self.definition_type(register.identifier())
+                    .expect("definition was recorded without its register");
+                if !self.is_same_representation(register.get_type(), definition_type) {
+                    return Err(format!(
+                        "register {} is used with type '{}' but defined with type '{}'",
+                        register_name(register),
+                        self.type_name(register.get_type()),
+                        self.type_name(definition_type),
+                    ));
+                }
+                Ok(())
+            }
+            Value::Constant(constant) => verify_constant(constant),
+            Value::Void => Ok(()),
+            Value::BoundFunction { function_value, .. } => self.verify_use(function_value, block_index, position),
+            Value::Never | Value::Break | Value::Continue => Err("operand is a never value".to_owned()),
+            Value::Indirect { .. } => Err("operand is an indirect value which was never loaded".to_owned()),
+        }
+    }
+
+    fn definition_type(&self, identifier: &[u8]) -> Option<TypeHandle> {
+        let &(block_index, position) = self.definitions.get(identifier)?;
+        let block = &self.function.blocks()[block_index];
+        let register = match position {
+            Some(position) => block.body()[position].result(),
+            None => block.phis().iter().map(|phi| &phi.result)
+                .chain(if block_index == 0 { self.function.parameter_registers() } else { &[] })
+                .find(|register| register.identifier() == identifier),
+        };
+        register.map(LocalRegister::get_type)
+    }
+
+    fn verify_phis(&self, block_index: usize, block: &BasicBlock) -> Result<(), String> {
+        for phi in block.phis() {
+            let mut input_blocks = Vec::with_capacity(phi.inputs.len());
+            for (value, label) in phi.inputs.iter() {
+                let input_block = self.graph.block_index(label)
+                    .ok_or_else(|| format!("phi {} has an input from unknown block '{}'", register_name(&phi.result), label_name(label)))?;
+                if !self.graph.predecessors(block_index).contains(&input_block) {
+                    return Err(format!("phi {} has an input from '{}', which is not a predecessor", register_name(&phi.result), label_name(label)));
+                }
+                if input_blocks.contains(&input_block) {
+                    return Err(format!("phi {} has more than one input from '{}'", register_name(&phi.result), label_name(label)));
+                }
+                input_blocks.push(input_block);
+                self.verify_use(value, input_block, None)?;
+                self.expect_type(value.get_type(), phi.result.get_type(), "phi input")?;
+            }
+            if let Some(&missing_block) = self.graph.predecessors(block_index).iter().find(|predecessor| !input_blocks.contains(predecessor)) {
+                return Err(format!(
+                    "phi {} has no input from predecessor '{}'",
+                    register_name(&phi.result),
+                    label_name(self.function.blocks()[missing_block].label()),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn verify_instruction(&self, block_index: usize, position: usize, instruction: &Instruction) -> Result<(), String> {
+        for operand in instruction.operands() {
+            self.verify_use(operand, block_index, Some(position))?;
+        }
+
+        match instruction {
+            Instruction::Negate { result, operand } => {
+                self.expect_type(operand.get_type(), result.get_type(), "operand")?;
+                self.expect_kind(operand.get_type(), "an integer or float", is_integer(self.context, operand.get_type()) || is_float(self.context, operand.get_type()))
+            }
+            Instruction::Add { result, lhs, rhs } |
+            Instruction::Subtract { result, lhs, rhs } |
+            Instruction::Multiply { result, lhs, rhs } |
+            Instruction::Divide { result, lhs, rhs } |
+            Instruction::Remainder { result, lhs, rhs } => {
+                self.expect_type(lhs.get_type(), result.get_type(), "left operand")?;
+                self.expect_type(rhs.get_type(), result.get_type(), "right operand")?;
+                self.expect_kind(lhs.get_type(), "an integer or float", is_integer(self.context, lhs.get_type()) || is_float(self.context, lhs.get_type()))
+            }
+            Instruction::ShiftLeft { result, lhs, rhs } |
+            Instruction::ShiftRight { result, lhs, rhs } => {
+                self.expect_type(lhs.get_type(), result.get_type(), "left operand")?;
+                self.expect_type(rhs.get_type(), result.get_type(), "right operand")?;
+                self.expect_kind(lhs.get_type(), "an integer", is_integer(self.context, lhs.get_type()))
+            }
+            Instruction::Not { result, operand } => {
+                self.expect_type(operand.get_type(), result.get_type(), "operand")?;
+                self.expect_kind(operand.get_type(), "an integer or boolean", is_integer(self.context, operand.get_type()) || operand.get_type() == TypeHandle::BOOL)
+            }
+            Instruction::And { result, lhs, rhs } |
+            Instruction::Or { result, lhs, rhs } |
+            Instruction::Xor { result, lhs, rhs } => {
+                self.expect_type(lhs.get_type(), result.get_type(), "left operand")?;
+                self.expect_type(rhs.get_type(), result.get_type(), "right operand")?;
+                self.expect_kind(lhs.get_type(), "an integer or boolean", is_integer(self.context, lhs.get_type()) || lhs.get_type() == TypeHandle::BOOL)
+            }
+            Instruction::ExtractValue { result, aggregate, indices } => {
+                let member_type = self.member_type(aggregate.get_type(), indices)?;
+                self.expect_type(result.get_type(), member_type, "result")
+            }
+            Instruction::InsertValue { result, aggregate, value, indices } => {
+                self.expect_type(aggregate.get_type(), result.get_type(), "aggregate")?;
+                let member_type = self.member_type(aggregate.get_type(), indices)?;
+                self.expect_type(value.get_type(), member_type, "inserted value")
+            }
+            Instruction::StackAllocate { result } => {
+                self.pointee_type(result.get_type()).map(|_| ())
+            }
+            Instruction::Load { result, pointer } => {
+                let pointee_type = self.pointee_type(pointer.get_type())?;
+                self.expect_type(result.get_type(), pointee_type, "result")
+            }
+            Instruction::Store { value, pointer } => {
+                let pointee_type = self.pointee_type(pointer.get_type())?;
+                self.expect_type(value.get_type(), pointee_type, "stored value")
+            }
+            Instruction::GetElementPointer { result, pointer, indices } => {
+                self.pointee_type(pointer.get_type())?;
+                self.pointee_type(result.get_type())?;
+                for index in indices.iter() {
+                    self.expect_kind(index.get_type(), "an integer", is_integer(self.context, index.get_type()))?;
+                }
+                Ok(())
+            }
+            Instruction::Convert { operation, result, value } => {
+                self.verify_conversion(*operation, value.get_type(), result.get_type())
+            }
+            Instruction::CompareEqual { result, lhs, rhs } |
+            Instruction::CompareNotEqual { result, lhs, rhs } => {
+                self.expect_type(result.get_type(), TypeHandle::BOOL, "result")?;
+                self.expect_type(rhs.get_type(), lhs.get_type(), "right operand")?;
+                let operand_type = lhs.get_type();
+                self.expect_kind(operand_type, "a scalar", is_integer(self.context, operand_type) || is_float(self.context, operand_type) || is_pointer(self.context, operand_type) || operand_type == TypeHandle::BOOL)
+            }
+            Instruction::CompareLessThan { result, lhs, rhs } |
+            Instruction::CompareLessEqual { result, lhs, rhs } |
+            Instruction::CompareGreaterThan { result, lhs, rhs } |
+            Instruction::CompareGreaterEqual { result, lhs, rhs } => {
+                self.expect_type(result.get_type(), TypeHandle::BOOL, "result")?;
+                self.expect_type(rhs.get_type(), lhs.get_type(), "right operand")?;
+                let operand_type = lhs.get_type();
+                self.expect_kind(operand_type, "an integer, float, or pointer", is_integer(self.context, operand_type) || is_float(self.context, operand_type) || is_pointer(self.context, operand_type))
+            }
+            Instruction::Call { result, callee, arguments } => {
+                let TypeRepr::Function { signature } = callee.get_type().repr(self.context) else {
+                    return Err(format!("callee has non-function type '{}'", self.type_name(callee.get_type())));
+                };
+                let parameter_types = signature.parameter_types();
+                if arguments.len() < parameter_types.len() || (arguments.len() > parameter_types.len() && !signature.is_variadic()) {
+                    return Err(format!("call has {} arguments, but the callee expects {}", arguments.len(), parameter_types.len()));
+                }
+                for (argument, &parameter_type) in std::iter::zip(arguments.iter(), parameter_types) {
+                    self.expect_type(argument.get_type(), parameter_type, "argument")?;
+                }
+                match result {
+                    Some(result) => self.expect_type(result.get_type(), signature.return_type(), "result"),
+                    None => Ok(())
+                }
+            }
+        }
+    }
+
+    fn verify_conversion(&self, operation: ConversionOperation, from_type: TypeHandle, to_type: TypeHandle) -> Result<(), String> {
+        let context = self.context;
+        let is_integer_like = |handle| is_integer(context, handle) || handle == TypeHandle::BOOL;
+        let is_valid = match operation {
+            ConversionOperation::Truncate | ConversionOperation::ZeroExtend | ConversionOperation::SignExtend => {
+                is_integer_like(from_type) && is_integer_like(to_type)
+            }
+            ConversionOperation::FloatTruncate | ConversionOperation::FloatExtend => {
+                is_float(context, from_type) && is_float(context, to_type)
+            }
+            ConversionOperation::FloatToUnsigned | ConversionOperation::FloatToSigned => {
+                is_float(context, from_type) && is_integer(context, to_type)
+            }
+            ConversionOperation::UnsignedToFloat | ConversionOperation::SignedToFloat => {
+                is_integer_like(from_type) && is_float(context, to_type)
+            }
+            ConversionOperation::PointerToInteger => {
+                is_pointer(context, from_type) && is_integer(context, to_type)
+            }
+            ConversionOperation::IntegerToPointer => {
+                is_integer(context, from_type) && is_pointer(context, to_type)
+            }
+            ConversionOperation::BitwiseCast => true,
+        };
+        if is_valid {
+            Ok(())
+        }
+        else {
+            Err(format!(
+                "cannot convert from '{}' to '{}' with {operation:?}",
+                self.type_name(from_type),
+                self.type_name(to_type),
+            ))
+        }
+    }
+
+    fn verify_terminator(&self, block_index: usize, block: &BasicBlock) -> Result<(), String> {
+        match block.terminator() {
+            TerminatorInstruction::Return { value } => {
+                self.verify_use(value, block_index, None)?;
+                self.expect_type(value.get_type(), self.function.return_type(), "return value")
+            }
+            TerminatorInstruction::ConditionalBranch { condition, .. } => {
+                self.verify_use(condition, block_index, None)?;
+                self.expect_type(condition.get_type(), TypeHandle::BOOL, "condition")
+            }
+            TerminatorInstruction::Branch { .. } | TerminatorInstruction::Unreachable => Ok(())
+        }
+    }
+
+    /// Find the type reached by indexing into `aggregate_type` with `indices`, as done by
+    /// `ExtractValue` and `InsertValue`.
+    fn member_type(&self, aggregate_type: TypeHandle, indices: &[Value]) -> Result<TypeHandle, String> {
+        let mut current_type = aggregate_type;
+        for index in indices {
+            let Some(Constant::Integer(index)) = index.as_constant() else {
+                return Err("aggregate index must be a constant integer".to_owned());
+            };
+            let index = index.raw();
+            let member_type = match current_type.repr(self.context) {
+                TypeRepr::Array { item_type, .. } => Some(*item_type),
+                TypeRepr::Tuple { item_types } => usize::try_from(index).ok().and_then(|index| item_types.get(index).copied()),
+                TypeRepr::Structure { members, .. } => usize::try_from(index).ok().and_then(|index| members.get(index)).map(|member| member.member_type),
+                _ => return Err(format!("cannot index into non-aggregate type '{}'", self.type_name(current_type)))
+            };
+            current_type = member_type.ok_or_else(|| format!("index {index} is out of range for type '{}'", self.type_name(current_type)))?;
+        }
+        Ok(current_type)
+    }
+
+    fn pointee_type(&self, pointer_type: TypeHandle) -> Result<TypeHandle, String> {
+        match *pointer_type.repr(self.context) {
+            TypeRepr::Pointer { pointee_type, .. } => Ok(pointee_type),
+            _ => Err(format!("expected a pointer, got type '{}'", self.type_name(pointer_type)))
+        }
+    }
+
+    /// Whether values of `got_type` can be used where `expected_type` is needed. Pointers are
+    /// interchangeable regardless of what they point to, matching LLVM's opaque pointers.
+    fn is_compatible(&self, got_type: TypeHandle, expected_type: TypeHandle) -> bool {
+        got_type == expected_type || (is_pointer(self.context, got_type) && is_pointer(self.context, expected_type))
+    }
+
+    /// Whether a value of `got_type` can stand in for `expected_type` without converting it, as
+    /// the generator does for conversions which need no operation (such as `i64` to `usize`).
+    fn is_same_representation(&self, got_type: TypeHandle, expected_type: TypeHandle) -> bool {
+        self.is_compatible(got_type, expected_type)
+            || self.context.try_explicit_conversion(got_type, expected_type, false)
+                .is_some_and(|conversion| conversion.operation_needed.is_none())
+    }
+
+    fn expect_type(&self, got_type: TypeHandle, expected_type: TypeHandle, what: &str) -> Result<(), String> {
+        if self.is_compatible(got_type, expected_type) {
+            Ok(())
+        }
+        else {
+            Err(format!(
+                "{what} has type '{}', expected '{}'",
+                self.type_name(got_type),
+                self.type_name(expected_type),
+            ))
+        }
+    }
+
+    fn expect_kind(&self, got_type: TypeHandle, expected: &str, is_valid: bool) -> Result<(), String> {
+        if is_valid {
+            Ok(())
+        }
+        else {
+            Err(format!("expected {expected}, got type '{}'", self.type_name(got_type)))
+        }
+    }
+
+    fn type_name(&self, handle: TypeHandle) -> String {
+        handle.path(self.context).to_string()
+    }
+}
+
+/// Check that `constant` and everything it contains can exist at runtime.
+fn verify_constant(constant: &Constant) -> Result<(), String> {
+    match constant {
+        Constant::Array { items, .. } | Constant::Tuple { items, .. } | Constant::Structure { members: items, .. } => {
+            items.iter().try_for_each(verify_constant)
+        }
+        Constant::Convert { value, .. } => verify_constant(value),
+        Constant::GetElementPointer { pointer, indices, .. } => {
+            verify_constant(pointer)?;
+            indices.iter().try_for_each(verify_constant)
+        }
+        Constant::Indirect { .. } => Err("operand is an indirect constant which was never loaded".to_owned()),
+        Constant::Type(..) | Constant::Module(..) => Err("operand is a type or module rather than a value".to_owned()),
+        _ => Ok(())
+    }
+}
+
+fn is_integer(context: &GlobalContext, handle: TypeHandle) -> bool {
+    matches!(handle.repr(context), TypeRepr::Integer { .. })
+}
+
+fn is_float(context: &GlobalContext, handle: TypeHandle) -> bool {
+    matches!(handle.repr(context), TypeRepr::Float32 | TypeRepr::Float64)
+}
+
+fn is_pointer(context: &GlobalContext, handle: TypeHandle) -> bool {
+    matches!(handle.repr(context), TypeRepr::Pointer { .. } | TypeRepr::Function { .. })
+}
+
+fn register_name(register: &LocalRegister) -> String {
+    format!("%{}", String::from_utf8_lossy(register.identifier()))
+}
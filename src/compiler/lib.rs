@@ -2,11 +2,14 @@ pub mod ast;
 pub mod cli;
 pub mod error;
 pub mod gen;
+pub mod interp;
 pub mod ir;
 pub mod package;
 pub mod sema;
 pub mod target;
 pub mod token;
+mod c;
 mod llvm;
+mod wasm;
 
 pub use error::*;
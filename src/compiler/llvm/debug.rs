@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+use super::*;
+use crate::ir::{CompilationUnit, DebugVariable, FunctionDefinition};
+use crate::sema::{StructureMember, TypeHandle};
+
+/// Collects the DWARF metadata nodes referenced while printing a compilation unit. Node IDs are
+/// assigned in the order nodes are first requested, so output is deterministic.
+pub struct DebugInfoBuilder<'a> {
+    context: &'a GlobalContext,
+    nodes: Vec<String>,
+    compile_unit_id: usize,
+    module_flag_ids: [usize; 2],
+    file_ids: HashMap<&'a Path, usize>,
+    type_ids: HashMap<TypeHandle, Option<usize>>,
+    location_ids: HashMap<(usize, usize, usize), usize>,
+}
+
+impl<'a> DebugInfoBuilder<'a> {
+    pub fn new(context: &'a GlobalContext, unit: &'a CompilationUnit) -> Self {
+        let mut builder = Self {
+            context,
+            nodes: Vec::new(),
+            compile_unit_id: 0,
+            module_flag_ids: [0; 2],
+            file_ids: HashMap::new(),
+            type_ids: HashMap::new(),
+            location_ids: HashMap::new(),
+        };
+
+        builder.compile_unit_id = builder.reserve_node();
+        let file_id = builder.file_id(unit.main_path());
+        builder.nodes[builder.compile_unit_id] = format!(
+            "distinct !DICompileUnit(language: DW_LANG_C99, file: !{file_id}, producer: {}, isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug)",
+            QuotedStringDisplay(concat!("cupric ", env!("CARGO_PKG_VERSION"))),
+        );
+        builder.module_flag_ids = [
+            builder.add_node("!{i32 7, !\"Dwarf Version\", i32 4}".to_owned()),
+            builder.add_node("!{i32 2, !\"Debug Info Version\", i32 3}".to_owned()),
+        ];
+
+        builder
+    }
+
+    fn reserve_node(&mut self) -> usize {
+        self.add_node(String::new())
+    }
+
+    fn add_node(&mut self, node: String) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    fn file_id(&mut self, path: &'a Path) -> usize {
+        if let Some(&id) = self.file_ids.get(path) {
+            return id;
+        }
+
+        let filename = path.file_name().map_or(path.as_os_str(), |name| name);
+        let directory = path.parent().map_or(Path::new("").as_os_str(), Path::as_os_str);
+        let id = self.add_node(format!(
+            "!DIFile(filename: {}, directory: {})",
+            QuotedStringDisplay(filename.as_encoded_bytes()),
+            QuotedStringDisplay(directory.as_encoded_bytes()),
+        ));
+        self.file_ids.insert(path, id);
+        id
+    }
+
+    /// Get the file ID, line, and column at the start of `span`.
+    fn span_position(&mut self, span: crate::Span) -> (usize, usize, usize) {
+        let source_file = self.context.package().source_map().file(span.source_id);
+        let (line, column) = source_file.line_column(span.start_index);
+        (self.file_id(source_file.path()), line, column)
+    }
+
+    /// Get the `DISubprogram` node for `function`, or `None` if the function has no source
+    /// information attached.
+    pub fn subprogram(&mut self, function: &FunctionDefinition) -> Option<usize> {
+        let span = function.span()?;
+        let (file_id, line, _) = self.span_position(span);
+
+        let type_id = self.subroutine_type(function.register().get_type());
+
+        Some(self.add_node(format!(
+            "distinct !DISubprogram(name: {}, linkageName: {}, scope: !{file_id}, file: !{file_id}, line: {line}, type: !{type_id}, scopeLine: {line}, spFlags: DISPFlagDefinition, unit: !{})",
+            QuotedStringDisplay(function.source_name().unwrap_or_default()),
+            QuotedStringDisplay(function.register().identifier()),
+            self.compile_unit_id,
+        )))
+    }
+
+    /// Get the `DILocation` node for the start of `span` within subprogram `scope_id`.
+    pub fn location(&mut self, span: crate::Span, scope_id: usize) -> usize {
+        let (_, line, column) = self.span_position(span);
+        if let Some(&id) = self.location_ids.get(&(line, column, scope_id)) {
+            return id;
+        }
+
+        let id = self.add_node(format!("!DILocation(line: {line}, column: {column}, scope: !{scope_id})"));
+        self.location_ids.insert((line, column, scope_id), id);
+        id
+    }
+
+    /// Get the `DILocalVariable` node describing `variable` within subprogram `scope_id`.
+    pub fn local_variable(&mut self, variable: &DebugVariable, scope_id: usize) -> usize {
+        let (file_id, line, _) = self.span_position(variable.span);
+        let type_ref = self.type_ref(variable.value_type);
+        let argument = match variable.argument_number {
+            Some(number) => format!("arg: {number}, "),
+            None => String::new(),
+        };
+
+        self.add_node(format!(
+            "!DILocalVariable(name: {}, {argument}scope: !{scope_id}, file: !{file_id}, line: {line}, type: {type_ref})",
+            QuotedStringDisplay(variable.name.as_bytes()),
+        ))
+    }
+
+    /// Get a reference to the debug type node for `handle`, which is `null` for types without
+    /// values such as `void`.
+    pub fn type_ref(&mut self, handle: TypeHandle) -> String {
+        match self.type_id(handle) {
+            Some(id) => format!("!{id}"),
+            None => "null".to_owned(),
+        }
+    }
+
+    fn type_id(&mut self, handle: TypeHandle) -> Option<usize> {
+        if let Some(&id) = self.type_ids.get(&handle) {
+            return id;
+        }
+
+        let context = self.context;
+        let bit_size = |handle: TypeHandle| context.type_registry().type_size(handle).unwrap_or(0) * 8;
+
+        let node = match handle.repr(context) {
+            TypeRepr::Unresolved | TypeRepr::Meta | TypeRepr::Never | TypeRepr::Void => {
+                None
+            }
+            TypeRepr::Boolean => {
+                Some("!DIBasicType(name: \"bool\", size: 8, encoding: DW_ATE_boolean)".to_owned())
+            }
+            &TypeRepr::Integer { size, signed } => {
+                let (prefix, encoding) = match signed {
+                    true => ('i', "DW_ATE_signed"),
+                    false => ('u', "DW_ATE_unsigned"),
+                };
+                Some(format!("!DIBasicType(name: \"{prefix}{}\", size: {}, encoding: {encoding})", size * 8, size * 8))
+            }
+            TypeRepr::PointerSizedInteger { .. } => {
+                panic!("unresolved pointer sized integer")
+            }
+            TypeRepr::Float32 => {
+                Some("!DIBasicType(name: \"f32\", size: 32, encoding: DW_ATE_float)".to_owned())
+            }
+            TypeRepr::Float64 => {
+                Some("!DIBasicType(name: \"f64\", size: 64, encoding: DW_ATE_float)".to_owned())
+            }
+            &TypeRepr::Pointer { pointee_type, .. } => {
+                // Reserve the ID first in case the pointee type refers back to this type
+                let id = self.reserve_type_id(handle);
+                let base_type = self.type_ref(pointee_type);
+                self.nodes[id] = format!(
+                    "!DIDerivedType(tag: DW_TAG_pointer_type, baseType: {base_type}, size: {})",
+                    context.target().pointer_size() * 8,
+                );
+                return Some(id);
+            }
+            &TypeRepr::Array { item_type, length } => match length {
+                Some(length) => {
+                    let base_type = self.type_ref(item_type);
+                    Some(format!(
+                        "!DICompositeType(tag: DW_TAG_array_type, baseType: {base_type}, size: {}, elements: !{{!DISubrange(count: {length})}})",
+                        bit_size(handle),
+                    ))
+                }
+                None => {
+                    // Unsized arrays are represented by their item type, as in LLVM IR
+                    let id = self.type_id(item_type);
+                    self.type_ids.insert(handle, id);
+                    return id;
+                }
+            }
+            TypeRepr::Tuple { item_types } => {
+                let members: Vec<StructureMember> = item_types.iter()
+                    .enumerate()
+                    .map(|(index, &member_type)| StructureMember {
+                        name: index.to_string().into(),
+                        is_public: true,
+                        member_type,
+                    })
+                    .collect();
+                return Some(self.structure_type(handle, &members));
+            }
+            TypeRepr::Structure { members, .. } => {
+                return Some(self.structure_type(handle, members));
+            }
+            TypeRepr::OpaqueStructure { .. } => {
+                Some(format!(
+                    "!DICompositeType(tag: DW_TAG_structure_type, name: {}, flags: DIFlagFwdDecl)",
+                    QuotedStringDisplay(type_name(context, handle)),
+                ))
+            }
+            TypeRepr::Function { .. } => {
+                // Function values are function pointers
+                let id = self.reserve_type_id(handle);
+                let subroutine_id = self.subroutine_type(handle);
+                self.nodes[id] = format!(
+                    "!DIDerivedType(tag: DW_TAG_pointer_type, baseType: !{subroutine_id}, size: {})",
+                    context.target().pointer_size() * 8,
+                );
+                return Some(id);
+            }
+        };
+
+        let id = node.map(|node| self.add_node(node));
+        self.type_ids.insert(handle, id);
+        id
+    }
+
+    fn reserve_type_id(&mut self, handle: TypeHandle) -> usize {
+        let id = self.reserve_node();
+        self.type_ids.insert(handle, Some(id));
+        id
+    }
+
+    fn subroutine_type(&mut self, function_type: TypeHandle) -> usize {
+        let TypeRepr::Function { signature } = function_type.repr(self.context) else {
+            panic!("'{}' is not a function type", function_type.path(self.context));
+        };
+        let mut type_list = self.type_ref(signature.return_type());
+        for &parameter_type in signature.parameter_types() {
+            write!(type_list, ", {}", self.type_ref(parameter_type)).unwrap();
+        }
+        self.add_node(format!("!DISubroutineType(types: !{{{type_list}}})"))
+    }
+
+    fn structure_type(&mut self, handle: TypeHandle, members: &[StructureMember]) -> usize {
+        let context = self.context;
+        // Reserve the ID first so members can refer back to this type through pointers
+        let id = self.reserve_type_id(handle);
+
+        let mut element_list = String::new();
+        let offsets = context.type_registry().member_offsets(handle).unwrap_or_default();
+        for (member, &offset) in members.iter().zip(&offsets) {
+            let size = context.type_registry().type_size(member.member_type).unwrap_or(0);
+
+            let base_type = self.type_ref(member.member_type);
+            let member_id = self.add_node(format!(
+                "!DIDerivedType(tag: DW_TAG_member, name: {}, scope: !{id}, baseType: {base_type}, size: {}, offset: {})",
+                QuotedStringDisplay(member.name.as_bytes()),
+                size * 8,
+                offset * 8,
+            ));
+            if !element_list.is_empty() {
+                element_list.push_str(", ");
+            }
+            write!(element_list, "!{member_id}").unwrap();
+        }
+
+        self.nodes[id] = format!(
+            "distinct !DICompositeType(tag: DW_TAG_structure_type, name: {}, size: {}, elements: !{{{element_list}}})",
+            QuotedStringDisplay(type_name(context, handle)),
+            context.type_registry().type_size(handle).unwrap_or(0) * 8,
+        );
+        id
+    }
+
+    pub fn fmt_nodes(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "!llvm.dbg.cu = !{{!{}}}", self.compile_unit_id)?;
+        writeln!(f, "!llvm.module.flags = !{{!{}, !{}}}", self.module_flag_ids[0], self.module_flag_ids[1])?;
+        writeln!(f)?;
+        for (id, node) in self.nodes.iter().enumerate() {
+            writeln!(f, "!{id} = {node}")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn type_name(context: &GlobalContext, handle: TypeHandle) -> String {
+    let path = handle.path(context).to_string();
+    match path.strip_prefix("::") {
+        Some(name) => name.to_owned(),
+        None => path,
+    }
+}
+
+/// Print the `llvm.dbg.declare` call associating `variable` with its stack allocation.
+pub fn fmt_debug_declare(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    variable: &DebugVariable,
+    variable_id: usize,
+    location_id: usize,
+) -> std::fmt::Result {
+    write!(
+        f,
+        "call void @llvm.dbg.declare(metadata {} {}, metadata !{variable_id}, metadata !DIExpression()), !dbg !{location_id}",
+        variable.pointer.get_type().llvm(context),
+        variable.pointer.llvm(context),
+    )
+}
@@ -578,7 +578,15 @@ pub fn fmt_call(
     if let Some(result) = result {
         write!(f, "{} = ", result.llvm(context))?;
     }
-    write!(f, "call {}(", signature.return_type().llvm(context))?;
+    // The calling convention of the call must match that of the function being called
+    let calling_convention = crate::ir::direct_callee(callee)
+        .and_then(|identifier| context.function_attributes(identifier))
+        .map(|attributes| attributes.calling_convention)
+        .filter(|&calling_convention| calling_convention != crate::ir::CallingConvention::C);
+    match calling_convention {
+        Some(calling_convention) => write!(f, "call {} {}(", calling_convention.llvm(context), signature.return_type().llvm(context))?,
+        None => write!(f, "call {}(", signature.return_type().llvm(context))?,
+    }
 
     let mut parameters_iter = signature.parameter_types().iter();
     if let Some(&parameter_type) = parameters_iter.next() {
@@ -3,6 +3,7 @@ use crate::sema::{GlobalContext, TypeRepr};
 mod instr;
 mod value;
 mod types;
+mod debug;
 
 pub struct QuotedStringDisplay<T: AsRef<[u8]>>(pub T);
 
@@ -97,24 +98,92 @@ impl LLVMDisplay for crate::ir::ExternalGlobalVariable {
     }
 }
 
+impl LLVMDisplay for crate::ir::CallingConvention {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        let _ = context;
+        match self {
+            Self::C => write!(f, "ccc"),
+            Self::Fast => write!(f, "fastcc"),
+            Self::Cold => write!(f, "coldcc"),
+            Self::StdCall => write!(f, "x86_stdcallcc"),
+            Self::FastCall => write!(f, "x86_fastcallcc"),
+            Self::VectorCall => write!(f, "x86_vectorcallcc"),
+            Self::Win64 => write!(f, "win64cc"),
+            Self::SysV64 => write!(f, "x86_64_sysvcc"),
+        }
+    }
+}
+
+impl LLVMDisplay for crate::ir::ParameterAttributes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        let _ = context;
+        if self.is_noalias {
+            write!(f, " noalias")?;
+        }
+        if self.is_nonnull {
+            write!(f, " nonnull")?;
+        }
+        if self.is_readonly {
+            write!(f, " readonly")?;
+        }
+        Ok(())
+    }
+}
+
+/// Write the linkage and calling convention of a function, each followed by a space if present.
+fn fmt_function_prefix(f: &mut std::fmt::Formatter<'_>, context: &GlobalContext, attributes: &crate::ir::FunctionAttributes) -> std::fmt::Result {
+    if attributes.linkage == crate::ir::Linkage::Internal {
+        write!(f, "internal ")?;
+    }
+    if attributes.calling_convention != crate::ir::CallingConvention::C {
+        write!(f, "{} ", attributes.calling_convention.llvm(context))?;
+    }
+    Ok(())
+}
+
+fn fmt_parameter_attributes(f: &mut std::fmt::Formatter<'_>, context: &GlobalContext, attributes: &crate::ir::FunctionAttributes, index: usize) -> std::fmt::Result {
+    match attributes.parameter_attributes.get(index) {
+        Some(parameter_attributes) => LLVMDisplay::fmt(parameter_attributes, f, context),
+        None => Ok(()),
+    }
+}
+
+/// Write the attributes following the parameter list of a function, each preceded by a space.
+fn fmt_function_suffix(f: &mut std::fmt::Formatter<'_>, attributes: &crate::ir::FunctionAttributes) -> std::fmt::Result {
+    if attributes.is_noreturn {
+        write!(f, " noreturn")?;
+    }
+    if attributes.is_nounwind {
+        write!(f, " nounwind")?;
+    }
+    Ok(())
+}
+
 impl LLVMDisplay for crate::ir::ExternalFunction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
         let TypeRepr::Function { signature } = self.register().get_type().repr(context) else {
             panic!("'{}' is not a function type", self.register().get_type().path(context));
         };
 
+        let default_attributes = crate::ir::FunctionAttributes::default();
+        let attributes = context.function_attributes(self.register().identifier()).unwrap_or(&default_attributes);
+
+        write!(f, "declare ")?;
+        fmt_function_prefix(f, context, attributes)?;
         write!(
             f,
-            "declare {} {}(",
+            "{} {}(",
             signature.return_type().llvm(context),
             self.register().llvm(context),
         )?;
 
-        let mut parameters_iter = signature.parameter_types().iter().copied();
-        if let Some(parameter_type) = parameters_iter.next() {
+        let mut parameters_iter = signature.parameter_types().iter().copied().enumerate();
+        if let Some((index, parameter_type)) = parameters_iter.next() {
             write!(f, "{}", parameter_type.llvm(context))?;
-            for parameter_type in parameters_iter {
+            fmt_parameter_attributes(f, context, attributes, index)?;
+            for (index, parameter_type) in parameters_iter {
                 write!(f, ", {}", parameter_type.llvm(context))?;
+                fmt_parameter_attributes(f, context, attributes, index)?;
             }
             if signature.is_variadic() {
                 write!(f, ", ...")?;
@@ -124,16 +193,20 @@ impl LLVMDisplay for crate::ir::ExternalFunction {
             write!(f, "...")?;
         }
 
-        write!(f, ")")
+        write!(f, ")")?;
+        fmt_function_suffix(f, attributes)
     }
 }
 
 impl LLVMDisplay for crate::ir::GlobalVariable {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        write!(f, "{} = ", self.register().llvm(context))?;
+        if self.linkage() == crate::ir::Linkage::Internal {
+            write!(f, "internal ")?;
+        }
         write!(
             f,
-            "{} = {} {} {}",
-            self.register().llvm(context),
+            "{} {} {}",
             self.kind().llvm(context),
             self.value().get_type().llvm(context),
             self.value().llvm(context),
@@ -143,39 +216,115 @@ impl LLVMDisplay for crate::ir::GlobalVariable {
 
 impl LLVMDisplay for crate::ir::FunctionDefinition {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
-        let TypeRepr::Function { signature } = self.register().get_type().repr(context) else {
-            panic!("'{}' is not a function type", self.register().get_type().path(context));
-        };
+        fmt_function_definition(f, context, self, None)
+    }
+}
 
-        write!(
-            f,
-            "define {} {}(",
-            signature.return_type().llvm(context),
-            self.register().llvm(context),
-        )?;
+fn fmt_function_definition(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    function: &crate::ir::FunctionDefinition,
+    mut debug_info: Option<&mut debug::DebugInfoBuilder>,
+) -> std::fmt::Result {
+    let TypeRepr::Function { signature } = function.register().get_type().repr(context) else {
+        panic!("'{}' is not a function type", function.register().get_type().path(context));
+    };
 
-        let mut parameters_iter = self.parameter_registers().iter();
-        if let Some(parameter) = parameters_iter.next() {
-            write!(f, "{} {}", parameter.get_type().llvm(context), parameter.llvm(context))?;
-            for parameter in parameters_iter {
-                write!(f, ", {} {}", parameter.get_type().llvm(context), parameter.llvm(context))?;
-            }
-            if signature.is_variadic() {
-                write!(f, ", ...")?;
-            }
+    let default_attributes = crate::ir::FunctionAttributes::default();
+    let attributes = context.function_attributes(function.register().identifier()).unwrap_or(&default_attributes);
+
+    write!(f, "define ")?;
+    fmt_function_prefix(f, context, attributes)?;
+    write!(
+        f,
+        "{} {}(",
+        signature.return_type().llvm(context),
+        function.register().llvm(context),
+    )?;
+
+    let mut parameters_iter = function.parameter_registers().iter().enumerate();
+    if let Some((index, parameter)) = parameters_iter.next() {
+        write!(f, "{}", parameter.get_type().llvm(context))?;
+        fmt_parameter_attributes(f, context, attributes, index)?;
+        write!(f, " {}", parameter.llvm(context))?;
+        for (index, parameter) in parameters_iter {
+            write!(f, ", {}", parameter.get_type().llvm(context))?;
+            fmt_parameter_attributes(f, context, attributes, index)?;
+            write!(f, " {}", parameter.llvm(context))?;
         }
-        else if signature.is_variadic() {
-            write!(f, "...")?;
+        if signature.is_variadic() {
+            write!(f, ", ...")?;
         }
+    }
+    else if signature.is_variadic() {
+        write!(f, "...")?;
+    }
 
-        writeln!(f, ") {{")?;
+    write!(f, ")")?;
+    fmt_function_suffix(f, attributes)?;
 
-        for block in self.blocks() {
-            block.fmt(f, context)?;
+    match function.inline_hint() {
+        crate::ir::InlineHint::Default => {}
+        crate::ir::InlineHint::Always => write!(f, " alwaysinline")?,
+        crate::ir::InlineHint::Never => write!(f, " noinline")?,
+    }
+
+    // Debug info is only emitted for functions with a known source location
+    let subprogram = debug_info.as_deref_mut().and_then(|debug_info| {
+        let scope_id = debug_info.subprogram(function)?;
+        Some((scope_id, function.span()?))
+    });
+    let mut debug_info = debug_info.filter(|_| subprogram.is_some());
+    if let Some((scope_id, _)) = subprogram {
+        write!(f, " !dbg !{scope_id}")?;
+    }
+
+    writeln!(f, " {{")?;
+
+    for block in function.blocks() {
+        match (debug_info.as_deref_mut(), subprogram) {
+            (Some(debug_info), Some((scope_id, function_span))) => {
+                fmt_debug_basic_block(f, context, block, function, debug_info, scope_id, function_span)?;
+            }
+            _ => {
+                block.fmt(f, context)?;
+            }
         }
+    }
+
+    write!(f, "}}")
+}
 
-        write!(f, "}}")
+fn fmt_debug_basic_block(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &GlobalContext,
+    block: &crate::ir::instr::BasicBlock,
+    function: &crate::ir::FunctionDefinition,
+    debug_info: &mut debug::DebugInfoBuilder,
+    scope_id: usize,
+    function_span: crate::Span,
+) -> std::fmt::Result {
+    writeln!(f, "{}:", IdentifierDisplay(block.label().identifier()))?;
+    for phi in block.phis() {
+        writeln!(f, "\t{}", phi.llvm(context))?;
     }
+    for (instruction, location) in std::iter::zip(block.body(), block.body_locations()) {
+        let location_id = debug_info.location(location.unwrap_or(function_span), scope_id);
+        writeln!(f, "\t{}, !dbg !{location_id}", instruction.llvm(context))?;
+
+        // Declare variables immediately after their stack allocation
+        if let crate::ir::instr::Instruction::StackAllocate { result } = instruction {
+            for variable in function.debug_variables().iter().filter(|variable| &variable.pointer == result) {
+                let variable_id = debug_info.local_variable(variable, scope_id);
+                let location_id = debug_info.location(variable.span, scope_id);
+                write!(f, "\t")?;
+                debug::fmt_debug_declare(f, context, variable, variable_id, location_id)?;
+                writeln!(f)?;
+            }
+        }
+    }
+    let location_id = debug_info.location(block.terminator_location().unwrap_or(function_span), scope_id);
+    writeln!(f, "\t{}, !dbg !{location_id}", block.terminator().llvm(context))
 }
 
 impl LLVMDisplay for crate::ir::instr::BasicBlock {
@@ -195,6 +344,12 @@ impl LLVMDisplay for crate::ir::CompilationUnit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
         let source_filename = self.main_path().as_os_str().as_encoded_bytes();
         writeln!(f, "source_filename = {}", QuotedStringDisplay(&source_filename))?;
+        if let Some(data_layout) = context.target().data_layout() {
+            writeln!(f, "target datalayout = {}", QuotedStringDisplay(data_layout))?;
+        }
+        if let Some(triple) = context.target().triple() {
+            writeln!(f, "target triple = {}", QuotedStringDisplay(triple))?;
+        }
         writeln!(f)?;
 
         for &declared_type in self.type_declarations() {
@@ -214,11 +369,23 @@ impl LLVMDisplay for crate::ir::CompilationUnit {
             writeln!(f, "{}", variable.llvm(context))?;
             writeln!(f)?;
         }
+        if !context.debug_info() {
+            for function in self.function_definitions() {
+                writeln!(f, "{}", function.llvm(context))?;
+                writeln!(f)?;
+            }
+
+            return Ok(());
+        }
+
+        let mut debug_info = debug::DebugInfoBuilder::new(context, self);
         for function in self.function_definitions() {
-            writeln!(f, "{}", function.llvm(context))?;
+            fmt_function_definition(f, context, function, Some(&mut debug_info))?;
+            writeln!(f)?;
             writeln!(f)?;
         }
-
-        Ok(())
+        writeln!(f, "declare void @llvm.dbg.declare(metadata, metadata, metadata)")?;
+        writeln!(f)?;
+        debug_info.fmt_nodes(f)
     }
 }
@@ -1,5 +1,6 @@
 use super::*;
 use crate::sema::{StructureMember, TypeHandle};
+use crate::target::LLVMPointerStyle;
 
 impl LLVMDisplay for TypeHandle {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
@@ -28,6 +29,10 @@ impl LLVMDisplay for TypeHandle {
             TypeRepr::Float64 => {
                 write!(f, "double")
             }
+            TypeRepr::Pointer { .. } | TypeRepr::Function { .. } if context.target().llvm_pointer_style() == LLVMPointerStyle::Opaque => {
+                // Function values are represented as function pointers, so both print as `ptr`
+                write!(f, "ptr")
+            }
             TypeRepr::Pointer { pointee_type, .. } => match *pointee_type {
                 TypeHandle::NEVER | TypeHandle::VOID => {
                     write!(f, "{{}}*")
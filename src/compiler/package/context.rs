@@ -1,5 +1,5 @@
 use super::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use crate::ir::CompilationUnit;
 use crate::sema::AbsolutePath;
 
@@ -8,10 +8,18 @@ pub struct PackageContext {
     output: CompilationUnit,
     current_module: NamespaceHandle,
     current_self_type: Option<TypeHandle>,
-    source_paths: Vec<PathBuf>,
+    source_map: SourceMap,
     parse_queue: VecDeque<SimplePath>,
     fill_phase_complete: bool,
     known_external_paths: HashSet<AbsolutePath>,
+    interface_path: Option<PathBuf>,
+    next_anonymous_constant_id: usize,
+    /// Array lengths given by constant expressions other than integer literals, keyed by the span
+    /// of the expression.
+    array_lengths: HashMap<crate::Span, u64>,
+    has_constant_calls: bool,
+    /// Functions which were generated during the fill phase, by symbol identifier.
+    early_functions: HashSet<Box<[u8]>>,
 }
 
 impl PackageContext {
@@ -23,10 +31,15 @@ impl PackageContext {
             output,
             current_module: package_root_module,
             current_self_type: None,
-            source_paths: Vec::new(),
+            source_map: SourceMap::new(),
             parse_queue: VecDeque::from([main_module_path]),
             fill_phase_complete: false,
             known_external_paths: HashSet::new(),
+            interface_path: None,
+            next_anonymous_constant_id: 0,
+            array_lengths: HashMap::new(),
+            has_constant_calls: false,
+            early_functions: HashSet::new(),
         }
     }
 
@@ -42,8 +55,14 @@ impl PackageContext {
         &mut self.output
     }
 
-    pub fn source_paths(&self) -> &[PathBuf] {
-        &self.source_paths
+    /// Move the generated output out of the package, leaving an empty compilation unit behind.
+    pub fn take_output(&mut self) -> CompilationUnit {
+        let empty_output = CompilationUnit::new(self.output.main_path());
+        std::mem::replace(&mut self.output, empty_output)
+    }
+
+    pub fn source_map(&self) -> &SourceMap {
+        &self.source_map
     }
 
     /// The namespace of the module currently being analyzed.
@@ -91,22 +110,37 @@ impl PackageContext {
         self.parse_queue.push_back(module_path);
     }
 
-    pub fn register_source_path(&mut self, path: PathBuf) -> Option<usize> {
-        if self.source_paths.contains(&path) {
-            None
+    /// Read the source file at `path` into the source map and return its source ID, or `None` if
+    /// the file has already been read.
+    pub fn register_source_path(&mut self, path: PathBuf) -> crate::Result<Option<usize>> {
+        if self.source_map.find_path(&path).is_some() {
+            Ok(None)
         }
         else {
-            let source_id = self.source_paths.len();
-            self.source_paths.push(path);
-            Some(source_id)
+            self.source_map.add_file(path).map(Some)
         }
     }
 
+    /// The directory of interface headers the package is being loaded from, or `None` if it is
+    /// being compiled from source.
+    pub fn interface_path(&self) -> Option<&Path> {
+        self.interface_path.as_deref()
+    }
+
+    /// Load the package from the interface headers in `interface_path` instead of its sources.
+    /// Must be called before any modules are parsed.
+    pub fn load_from_interface(&mut self, interface_path: PathBuf) {
+        self.interface_path = Some(interface_path);
+    }
+
     pub fn get_file_path_for_module(&self, module_path: &SimplePath) -> PathBuf {
         let parent_module_path = module_path.parent()
             .expect("module path should have at least 1 segment");
 
-        if parent_module_path.is_empty() {
+        if let Some(interface_path) = &self.interface_path {
+            interface_path.join(get_interface_file_path(module_path))
+        }
+        else if parent_module_path.is_empty() {
             self.info.main_path().to_path_buf()
         }
         else {
@@ -126,4 +160,42 @@ impl PackageContext {
     pub fn register_external_path(&mut self, path: AbsolutePath) -> bool {
         self.known_external_paths.insert(path)
     }
+
+    /// The ID to give the next anonymous constant in the output, which is unique within the package.
+    pub fn next_anonymous_constant_id(&self) -> usize {
+        self.next_anonymous_constant_id
+    }
+
+    pub fn set_next_anonymous_constant_id(&mut self, id: usize) {
+        self.next_anonymous_constant_id = id;
+    }
+
+    /// The evaluated length of the array type whose length expression is at `span`, if it has
+    /// been evaluated.
+    pub fn array_length(&self, span: crate::Span) -> Option<u64> {
+        self.array_lengths.get(&span).copied()
+    }
+
+    pub fn set_array_length(&mut self, span: crate::Span, length: u64) {
+        self.array_lengths.insert(span, length);
+    }
+
+    /// Whether any function call was evaluated at compile time while generating the package.
+    pub fn has_constant_calls(&self) -> bool {
+        self.has_constant_calls
+    }
+
+    pub fn record_constant_call(&mut self) {
+        self.has_constant_calls = true;
+    }
+
+    /// Whether the function with symbol `identifier` was generated during the fill phase, since
+    /// an array length outside of any function body called it.
+    pub fn is_generated_early(&self, identifier: &[u8]) -> bool {
+        self.early_functions.contains(identifier)
+    }
+
+    pub fn record_early_function(&mut self, identifier: &[u8]) {
+        self.early_functions.insert(identifier.into());
+    }
 }
@@ -0,0 +1,274 @@
+//! Interface headers, which declare everything defined by a package without any function bodies
+//! or initial values. Dependencies whose headers are up to date are loaded from them instead of
+//! being compiled from source again.
+//!
+//! Each module has its own header, named `module.cuprh` and placed in a directory path matching
+//! the module path within the package. Headers are written in the same syntax as source files,
+//! except that functions and variables are declared without being defined, every module is
+//! declared with `module name;`, and types are always written as absolute paths.
+
+use super::*;
+use std::fmt::Write;
+use crate::ast::{GlobalNode, GlobalNodeKind};
+use crate::ast::parse::ParsedModule;
+use crate::ir::{CallingConvention, InlineHint};
+use crate::sema::{GlobalContext, TypeRepr, Visibility};
+
+/// The name of the interface header of each module.
+pub const INTERFACE_FILE_NAME: &str = "module.cuprh";
+/// The file extension of interface headers.
+pub const INTERFACE_EXTENSION: &str = "cuprh";
+
+/// Get the path of the interface header for the module at `module_path`, relative to the
+/// directory containing all headers of its package.
+pub fn get_interface_file_path(module_path: &SimplePath) -> PathBuf {
+    module_path.segments()[1..] // Skip package name
+        .iter()
+        .map(AsRef::as_ref)
+        .chain([INTERFACE_FILE_NAME])
+        .collect()
+}
+
+/// Generate the interface header of each module in the package currently being compiled, which
+/// must have completed the fill phase. Returns the text of each header along with its path
+/// relative to the headers directory.
+pub fn generate_interfaces(context: &mut GlobalContext, modules: &[ParsedModule]) -> crate::Result<Vec<(PathBuf, String)>> {
+    let mut generator = InterfaceGenerator {
+        context,
+        interfaces: Vec::new(),
+        declared_modules: Vec::new(),
+    };
+    for parsed_module in modules {
+        generator.add_module(parsed_module.namespace(), parsed_module.statements())?;
+    }
+
+    Ok(generator.interfaces
+        .into_iter()
+        .map(|(namespace, text)| {
+            let module_path = generator.context.namespace_info(namespace).path().simple();
+            (get_interface_file_path(module_path), text)
+        })
+        .collect())
+}
+
+/// Write the interface headers of the package currently being compiled into `headers_path`.
+/// Headers which are unchanged are left alone so their modification times stay the same, and
+/// headers of modules which no longer exist are removed.
+pub fn write_interfaces(context: &mut GlobalContext, modules: &[ParsedModule], headers_path: &Path) -> crate::Result<()> {
+    let write_error = |path: &Path, cause: std::io::Error| Box::new(crate::Error::new(
+        None,
+        crate::ErrorKind::OutputFileWrite {
+            filename: path.display().to_string(),
+            cause,
+        },
+    ));
+
+    let mut written_paths = Vec::new();
+    for (relative_path, text) in generate_interfaces(context, modules)? {
+        let path = headers_path.join(relative_path);
+        if std::fs::read_to_string(&path).ok().as_deref() != Some(text.as_str()) {
+            path.parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|_| std::fs::write(&path, text))
+                .map_err(|cause| write_error(&path, cause))?;
+        }
+        written_paths.push(path);
+    }
+
+    let mut existing_paths = Vec::new();
+    collect_files(headers_path, INTERFACE_EXTENSION, &mut existing_paths);
+    for stale_path in existing_paths.iter().filter(|&path| !written_paths.contains(path)) {
+        std::fs::remove_file(stale_path).map_err(|cause| write_error(stale_path, cause))?;
+    }
+
+    Ok(())
+}
+
+struct InterfaceGenerator<'a> {
+    context: &'a mut GlobalContext,
+    /// The text of the header for each module, in the order the modules were encountered.
+    interfaces: Vec<(NamespaceHandle, String)>,
+    /// Modules which have been declared by their parent module's header.
+    declared_modules: Vec<NamespaceHandle>,
+}
+
+impl InterfaceGenerator<'_> {
+    fn interface_text(&mut self, namespace: NamespaceHandle) -> &mut String {
+        let index = match self.interfaces.iter().position(|&(module, _)| module == namespace) {
+            Some(index) => index,
+            None => {
+                self.interfaces.push((namespace, String::new()));
+                self.interfaces.len() - 1
+            }
+        };
+        &mut self.interfaces[index].1
+    }
+
+    fn add_module(&mut self, namespace: NamespaceHandle, statements: &[GlobalNode]) -> crate::Result<()> {
+        // Empty modules still need a header for the declaration in their parent to refer to
+        self.interface_text(namespace);
+
+        let parent_module = self.context.replace_current_module(namespace);
+        let result = statements.iter().try_for_each(|statement| {
+            let text = match statement.kind() {
+                GlobalNodeKind::Module { name, statements, namespace: child_namespace } => {
+                    self.add_module(*child_namespace, statements)?;
+                    self.module_declaration(namespace, name)
+                }
+                GlobalNodeKind::ModuleFile { name } => {
+                    self.module_declaration(namespace, name)
+                }
+                _ => self.declaration(statement, "")?,
+            };
+            self.interface_text(namespace).push_str(&text);
+            Ok(())
+        });
+        self.context.replace_current_module(parent_module);
+
+        result
+    }
+
+    /// Declare the module `name` within `parent`, unless it was already declared. A module can be
+    /// extended by several `module` statements, so its visibility comes from its symbol instead.
+    fn module_declaration(&mut self, parent: NamespaceHandle, name: &str) -> String {
+        let symbol = self.context.namespace_info(parent).find(name)
+            .expect("module should have been defined while parsing");
+        let namespace = symbol.as_module()
+            .expect("module symbol should refer to a module");
+        if self.declared_modules.contains(&namespace) {
+            return String::new();
+        }
+        self.declared_modules.push(namespace);
+
+        format!("{}module {name};\n", visibility_prefix(symbol.visibility() == Visibility::Public))
+    }
+
+    fn declaration(&mut self, node: &GlobalNode, indent: &str) -> crate::Result<String> {
+        let mut text = String::from(indent);
+        text.push_str(visibility_prefix(node.is_public()));
+
+        match node.kind() {
+            GlobalNodeKind::Let { name, symbol_name, is_mutable, register, .. } => {
+                let register = register.as_ref().expect("register should be valid after fill phase");
+                let &TypeRepr::Pointer { pointee_type, .. } = self.context.type_repr(register.get_type()) else {
+                    panic!("global variable register should be a pointer");
+                };
+
+                push_foreign_modifier(&mut text, name, symbol_name.as_deref());
+                let mutability = if *is_mutable { "mut " } else { "" };
+                writeln!(text, "let {mutability}{name}: {};", self.context.type_path(pointee_type)).unwrap();
+            }
+            GlobalNodeKind::Function { name, symbol_name, parameters, is_variadic, inline_hint, calling_convention, register, .. } => {
+                let register = register.as_ref().expect("register should be valid after fill phase");
+                let TypeRepr::Function { signature } = self.context.type_repr(register.get_type()) else {
+                    panic!("function register should have a function type");
+                };
+
+                push_foreign_modifier(&mut text, name, symbol_name.as_deref());
+                if *calling_convention != CallingConvention::C {
+                    write!(text, "callconv(\"{}\") ", calling_convention.name()).unwrap();
+                }
+                // Whether the function can unwind was inferred from its body, which the header lacks
+                let is_nounwind = self.context.function_attributes(register.identifier())
+                    .is_some_and(|attributes| attributes.is_nounwind);
+                if is_nounwind {
+                    text.push_str("nounwind ");
+                }
+                match inline_hint {
+                    InlineHint::Default => {}
+                    InlineHint::Always => text.push_str("inline "),
+                    InlineHint::Never => text.push_str("noinline "),
+                }
+
+                let mut parameter_texts: Vec<String> = std::iter::zip(parameters.iter(), signature.parameter_types())
+                    .map(|(parameter, &parameter_type)| {
+                        let mut parameter_text = String::new();
+                        if parameter.attributes.is_noalias {
+                            parameter_text.push_str("noalias ");
+                        }
+                        if parameter.attributes.is_nonnull {
+                            parameter_text.push_str("nonnull ");
+                        }
+                        if parameter.attributes.is_readonly {
+                            parameter_text.push_str("readonly ");
+                        }
+                        write!(parameter_text, "{}: {}", parameter.name, self.context.type_path(parameter_type)).unwrap();
+                        parameter_text
+                    })
+                    .collect();
+                if *is_variadic {
+                    parameter_texts.push("..".to_owned());
+                }
+
+                writeln!(
+                    text,
+                    "function {name}({}) -> {};",
+                    parameter_texts.join(", "),
+                    self.context.type_path(signature.return_type()),
+                ).unwrap();
+            }
+            GlobalNodeKind::Structure { name, self_type, .. } => {
+                if let TypeRepr::Structure { members, .. } = self.context.type_repr(*self_type) {
+                    if members.is_empty() {
+                        writeln!(text, "struct {name} {{}}").unwrap();
+                        return Ok(text);
+                    }
+                    writeln!(text, "struct {name} {{").unwrap();
+                    for member in members {
+                        writeln!(
+                            text,
+                            "{indent}    {}{}: {},",
+                            visibility_prefix(member.is_public),
+                            member.name,
+                            self.context.type_path(member.member_type),
+                        ).unwrap();
+                    }
+                    writeln!(text, "{indent}}}").unwrap();
+                }
+                else {
+                    writeln!(text, "struct {name} {{ .. }}").unwrap();
+                }
+            }
+            GlobalNodeKind::Implement { self_type, statements } => {
+                let self_type = self.context.interpret_type_node(self_type)?;
+                writeln!(text, "implement {} {{", self.context.type_path(self_type)).unwrap();
+                for statement in statements {
+                    text.push_str(&self.declaration(statement, &format!("{indent}    "))?);
+                }
+                writeln!(text, "{indent}}}").unwrap();
+            }
+            GlobalNodeKind::Import { segments, alias } => {
+                let path = self.context.get_absolute_path(node.span(), segments)?;
+                let alias = alias.as_deref()
+                    .or_else(|| path.tail_name())
+                    .expect("import should have a name after parsing");
+                writeln!(text, "import {path} as {alias};").unwrap();
+            }
+            GlobalNodeKind::GlobImport { segments } => {
+                let path = self.context.get_absolute_path(node.span(), segments)?;
+                writeln!(text, "import {path}::*;").unwrap();
+            }
+            GlobalNodeKind::Module { .. } | GlobalNodeKind::ModuleFile { .. } => {
+                panic!("modules should be declared separately");
+            }
+        }
+
+        Ok(text)
+    }
+}
+
+fn visibility_prefix(is_public: bool) -> &'static str {
+    if is_public { "pub " } else { "" }
+}
+
+fn push_foreign_modifier(text: &mut String, name: &str, symbol_name: Option<&[u8]>) {
+    match symbol_name {
+        Some(symbol_name) if symbol_name == name.as_bytes() => {
+            text.push_str("foreign ");
+        }
+        Some(symbol_name) => {
+            write!(text, "foreign(\"{}\") ", String::from_utf8_lossy(symbol_name)).unwrap();
+        }
+        None => {}
+    }
+}
@@ -0,0 +1,168 @@
+//! Build manifests, which record hashes of everything that went into compiling a package. When a
+//! package is compiled again with a manifest matching the one written by its last successful
+//! build, it is loaded from its interface headers and its existing output is reused.
+
+use super::*;
+use std::collections::BTreeMap;
+
+/// The file extension of build manifests.
+pub const MANIFEST_EXTENSION: &str = "manifest";
+
+/// Hash `bytes` with 64-bit FNV-1a, which is stable across compiler builds unlike the hashers
+/// provided by the standard library.
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01B3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| (hash ^ byte as u64).wrapping_mul(PRIME))
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BuildManifest {
+    compiler_version: String,
+    options: String,
+    /// Hashes of the source files and `package.toml`, keyed by their path relative to the package.
+    inputs: BTreeMap<String, String>,
+    /// Hashes of the interface headers of every package depended on, directly or not.
+    interfaces: BTreeMap<String, String>,
+    /// Hashes of the output of every package depended on, directly or not. Only kept for packages
+    /// which evaluated calls at compile time, since those can use values computed by the function
+    /// bodies of their dependencies.
+    outputs: BTreeMap<String, String>,
+    /// Whether the package evaluated calls at compile time, which means the function bodies of
+    /// its dependencies are needed whenever it is compiled again.
+    has_constant_calls: bool,
+}
+
+impl BuildManifest {
+    /// Create a manifest for building `info` into output files with `output_extension` with the
+    /// options described by `options`, using the current contents of its inputs. Files which
+    /// can't be read are left out, which makes the manifest differ from that of any successful
+    /// build.
+    pub fn new(info: &PackageInfo, dependencies: &[Rc<PackageInfo>], out_dir: Option<&Path>, output_extension: &str, options: String) -> Self {
+        let mut input_paths = info.get_source_paths();
+        input_paths.push(info.path().join("package.toml"));
+        let inputs = input_paths.iter()
+            .filter_map(|path| {
+                let bytes = std::fs::read(path).ok()?;
+                let name = path.strip_prefix(info.path()).unwrap_or(path);
+                Some((name.display().to_string().replace('\\', "/"), format!("{:016x}", hash_bytes(&bytes))))
+            })
+            .collect();
+
+        let interfaces = dependencies.iter()
+            .map(|dependency| {
+                // Each header's path is hashed along with its contents, so moving one is noticed
+                let headers_path = dependency.get_headers_path(out_dir);
+                let mut header_paths = Vec::new();
+                collect_files(&headers_path, INTERFACE_EXTENSION, &mut header_paths);
+                header_paths.sort();
+
+                let mut bytes = Vec::new();
+                for header_path in &header_paths {
+                    let relative_path = header_path.strip_prefix(&headers_path).unwrap_or(header_path);
+                    bytes.extend(relative_path.display().to_string().replace('\\', "/").bytes());
+                    bytes.push(0);
+                    bytes.extend(std::fs::read(header_path).unwrap_or_default());
+                    bytes.push(0);
+                }
+                (dependency.name().to_owned(), format!("{:016x}", hash_bytes(&bytes)))
+            })
+            .collect();
+
+        let outputs = dependencies.iter()
+            .filter_map(|dependency| {
+                let bytes = std::fs::read(dependency.get_output_path_in(out_dir, output_extension)).ok()?;
+                Some((dependency.name().to_owned(), format!("{:016x}", hash_bytes(&bytes))))
+            })
+            .collect();
+
+        Self {
+            // The build id changes with the compiler sources, unlike the version number
+            compiler_version: format!("{}+{}", env!("CARGO_PKG_VERSION"), env!("CUPRIC_BUILD_ID")),
+            options,
+            inputs,
+            interfaces,
+            outputs,
+            has_constant_calls: false,
+        }
+    }
+
+    /// Record whether the package evaluated calls at compile time. The hashes of dependency
+    /// outputs are dropped if it didn't, since its output then only depends on the interfaces of
+    /// its dependencies.
+    pub fn with_constant_calls(mut self, has_constant_calls: bool) -> Self {
+        if !has_constant_calls {
+            self.outputs.clear();
+        }
+        self.has_constant_calls = has_constant_calls;
+        self
+    }
+
+    pub fn has_constant_calls(&self) -> bool {
+        self.has_constant_calls
+    }
+
+    /// Whether a build recorded by this manifest is still current, where `manifest` is the
+    /// manifest for building the package now. Dependency outputs are only compared if this
+    /// manifest recorded them.
+    pub fn is_current_for(&self, manifest: &Self) -> bool {
+        self.compiler_version == manifest.compiler_version
+            && self.options == manifest.options
+            && self.inputs == manifest.inputs
+            && self.interfaces == manifest.interfaces
+            && (!self.has_constant_calls || self.outputs == manifest.outputs)
+    }
+
+    /// Read the manifest at `path`, returning `None` if it is missing or malformed.
+    pub fn read(path: &Path) -> Option<Self> {
+        let table = std::fs::read_to_string(path).ok()?.parse::<toml::Table>().ok()?;
+        let hash_table = |key: &str| -> Option<BTreeMap<String, String>> {
+            table.get(key)?
+                .as_table()?
+                .iter()
+                .map(|(name, hash)| Some((name.clone(), hash.as_str()?.to_owned())))
+                .collect()
+        };
+
+        Some(Self {
+            compiler_version: table.get("compiler_version")?.as_str()?.to_owned(),
+            options: table.get("options")?.as_str()?.to_owned(),
+            inputs: hash_table("inputs")?,
+            interfaces: hash_table("interfaces")?,
+            outputs: match table.get("outputs") {
+                Some(_) => hash_table("outputs")?,
+                None => BTreeMap::new(),
+            },
+            has_constant_calls: table.get("has_constant_calls")?.as_bool()?,
+        })
+    }
+
+    pub fn write(&self, path: &Path) -> crate::Result<()> {
+        let hash_table = |hashes: &BTreeMap<String, String>| -> toml::Value {
+            toml::Value::Table(hashes.iter()
+                .map(|(name, hash)| (name.clone(), toml::Value::String(hash.clone())))
+                .collect())
+        };
+        let mut table = toml::Table::new();
+        table.insert("compiler_version".to_owned(), toml::Value::String(self.compiler_version.clone()));
+        table.insert("options".to_owned(), toml::Value::String(self.options.clone()));
+        table.insert("inputs".to_owned(), hash_table(&self.inputs));
+        table.insert("interfaces".to_owned(), hash_table(&self.interfaces));
+        if !self.outputs.is_empty() {
+            table.insert("outputs".to_owned(), hash_table(&self.outputs));
+        }
+        table.insert("has_constant_calls".to_owned(), toml::Value::Boolean(self.has_constant_calls));
+
+        path.parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(path, table.to_string()))
+            .map_err(|cause| Box::new(crate::Error::new(
+                None,
+                crate::ErrorKind::OutputFileWrite {
+                    filename: path.display().to_string(),
+                    cause,
+                },
+            )))
+    }
+}
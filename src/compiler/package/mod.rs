@@ -6,6 +6,12 @@ use crate::sema::{NamespaceHandle, SimplePath, TypeHandle};
 
 mod context;
 pub use context::*;
+mod interface;
+pub use interface::*;
+mod manifest;
+pub use manifest::*;
+mod source;
+pub use source::*;
 
 fn package_file_error(cause: impl ToString) -> Box<crate::Error> {
     Box::new(crate::Error::new(
@@ -16,6 +22,23 @@ fn package_file_error(cause: impl ToString) -> Box<crate::Error> {
     ))
 }
 
+/// Add the path of every file with the extension `extension` within `dir` and its subdirectories
+/// to `paths`. Anything which cannot be read is skipped.
+fn collect_files(dir: &Path, extension: &str, paths: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, extension, paths);
+        }
+        else if path.extension().is_some_and(|path_extension| path_extension == extension) {
+            paths.push(path);
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct PackageDependency {
     name: Box<str>,
@@ -122,6 +145,42 @@ impl PackageInfo {
         self.path.join(format!("out{}{}.ll", std::path::MAIN_SEPARATOR, self.name))
     }
 
+    /// Get the path of the output file with extension `extension`, which is placed in `out_dir`
+    /// if given or in the `out` directory of the package otherwise.
+    pub fn get_output_path_in(&self, out_dir: Option<&Path>, extension: &str) -> PathBuf {
+        match out_dir {
+            Some(out_dir) => out_dir.join(format!("{}.{extension}", self.name)),
+            None => self.get_output_path().with_extension(extension),
+        }
+    }
+
+    /// Get the directory the interface headers of the package are written to.
+    pub fn get_headers_path(&self, out_dir: Option<&Path>) -> PathBuf {
+        match out_dir {
+            Some(out_dir) => out_dir.join("headers").join(&*self.name),
+            None => self.path.join("out").join("headers"),
+        }
+    }
+
+    /// Get the path of the build manifest written after the package is successfully compiled.
+    pub fn get_manifest_path(&self, out_dir: Option<&Path>) -> PathBuf {
+        self.get_output_path_in(out_dir, MANIFEST_EXTENSION)
+    }
+
+    /// Get the path of the main source file and of every source file in the `modules` directory
+    /// beside it, in sorted order. Not all of them are necessarily part of the package.
+    pub fn get_source_paths(&self) -> Vec<PathBuf> {
+        let mut module_paths = Vec::new();
+        if let Some(main_dir) = self.main_path.parent() {
+            collect_files(&main_dir.join("modules"), "cupr", &mut module_paths);
+        }
+        module_paths.sort();
+
+        std::iter::once(self.main_path.to_path_buf())
+            .chain(module_paths)
+            .collect()
+    }
+
     fn parse_package_toml(parent_dir: impl AsRef<Path>) -> crate::Result<Self> {
         let parent_dir = parent_dir.as_ref();
         if !parent_dir.is_dir() {
@@ -255,6 +314,38 @@ impl PackageManager {
         self.package_registry.get(name).cloned()
     }
 
+    /// Get every package `info` depends on, directly or through other dependencies, sorted by name.
+    pub fn get_all_dependencies(&self, info: &PackageInfo) -> Vec<Rc<PackageInfo>> {
+        let mut dependencies: Vec<Rc<PackageInfo>> = Vec::new();
+        let mut frontier: Vec<&PackageDependency> = info.dependencies().iter().collect();
+        while let Some(dependency) = frontier.pop() {
+            if dependencies.iter().any(|existing| existing.name() == dependency.name()) {
+                continue;
+            }
+            let dependency_info = &self.package_registry[dependency.name()];
+            frontier.extend(dependency_info.dependencies());
+            dependencies.push(dependency_info.clone());
+        }
+        dependencies.sort_by(|a, b| a.name().cmp(b.name()));
+        dependencies
+    }
+
+    /// Whether the existing output of `info` at `output_path` and its interface headers can be
+    /// reused, which requires them to exist and `manifest` to match the one written by its last
+    /// successful build. Headers are only rewritten when their contents change, so
+    /// changes to a dependency which don't affect its interface don't cause dependent packages to
+    /// be rebuilt.
+    pub fn is_build_current(&self, info: &PackageInfo, out_dir: Option<&Path>, output_path: &Path, manifest: &BuildManifest) -> bool {
+        output_path.is_file()
+            && info.get_headers_path(out_dir).join(INTERFACE_FILE_NAME).is_file()
+            && BuildManifest::read(&info.get_manifest_path(out_dir)).is_some_and(|previous| previous.is_current_for(manifest))
+    }
+
+    /// Get the packages which are still waiting to be compiled, in the order they will be compiled.
+    pub fn get_remaining_to_compile(&self) -> impl Iterator<Item = &Rc<PackageInfo>> {
+        self.compile_queue.iter().map(|name| &self.package_registry[name])
+    }
+
     pub fn get_next_to_compile(&mut self) -> Option<Rc<PackageInfo>> {
         self.compile_queue.pop_front()
             .map(|name| self.package_registry[&name].clone())
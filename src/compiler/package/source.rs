@@ -0,0 +1,151 @@
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// The text of a single source file along with the byte index at which each line begins.
+#[derive(Clone, Debug)]
+pub struct SourceFile {
+    path: PathBuf,
+    text: Rc<str>,
+    line_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    pub fn new(path: PathBuf, text: Rc<str>) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        Self {
+            path,
+            text,
+            line_starts,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn text(&self) -> &Rc<str> {
+        &self.text
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Get the 0-based index of the line containing byte index `index`.
+    pub fn line_index(&self, index: usize) -> usize {
+        self.line_starts.partition_point(|&line_start| line_start <= index) - 1
+    }
+
+    /// Get the text of the 0-based line `line_index`, excluding the line terminator.
+    pub fn line_text(&self, line_index: usize) -> &str {
+        let start_index = self.line_starts[line_index];
+        let end_index = self.line_starts.get(line_index + 1).copied().unwrap_or(self.text.len());
+        self.text[start_index..end_index].trim_end_matches(['\n', '\r'])
+    }
+
+    /// Get the 1-based line and column numbers for byte index `index`. Columns are counted in
+    /// characters rather than bytes.
+    pub fn line_column(&self, index: usize) -> (usize, usize) {
+        let line_index = self.line_index(index);
+        let line_start = self.line_starts[line_index];
+        let index = index.min(self.text.len());
+        let column_index = self.text[line_start..index].chars().count();
+        (line_index + 1, column_index + 1)
+    }
+
+    /// Render the lines covered by `span`, each followed by a line of markers underlining the
+    /// spanned text. Returns the line and column numbers of the start of the span as well.
+    pub fn context_to_string(&self, span: crate::Span) -> (usize, usize, String) {
+        let (line_number, column_number) = self.line_column(span.start_index);
+        let end_index = span.start_index + span.length;
+        let last_line_index = if span.length == 0 {
+            line_number - 1
+        }
+        else {
+            self.line_index(end_index - 1)
+        };
+        let mut context = String::new();
+
+        for line_index in line_number - 1 ..= last_line_index {
+            let line_start = self.line_starts[line_index];
+            let line_text = self.line_text(line_index);
+            let line_end = line_start + line_text.len();
+
+            // Write the line from the source file
+            context.push('\t');
+            context.push_str(line_text.trim_end());
+            context.push('\n');
+
+            // Write the span markers on the line below
+            let marker_start = span.start_index.clamp(line_start, line_end);
+            let marker_end = end_index.clamp(line_start, line_end);
+            context.push('\t');
+            context.extend(std::iter::repeat_n(' ', self.text[line_start..marker_start].chars().count()));
+            if span.length == 0 {
+                context.push('^');
+            }
+            else {
+                context.extend(std::iter::repeat_n('~', self.text[marker_start..marker_end].chars().count()));
+            }
+            context.push('\n');
+        }
+
+        (line_number, column_number, context)
+    }
+}
+
+/// The collection of source files read during compilation of a package. Source IDs in spans
+/// refer to indices into this collection.
+#[derive(Clone, Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    pub fn file(&self, source_id: usize) -> &SourceFile {
+        &self.files[source_id]
+    }
+
+    pub fn find_path(&self, path: &Path) -> Option<usize> {
+        self.files.iter().position(|file| file.path() == path)
+    }
+
+    /// Add a source file whose text is already in memory, returning its source ID.
+    pub fn add_source(&mut self, path: PathBuf, text: impl Into<Rc<str>>) -> usize {
+        let source_id = self.files.len();
+        self.files.push(SourceFile::new(path, text.into()));
+        source_id
+    }
+
+    /// Read the file at `path` from disk and add it, returning its source ID.
+    pub fn add_file(&mut self, path: PathBuf) -> crate::Result<usize> {
+        match std::fs::read_to_string(&path) {
+            Ok(text) => Ok(self.add_source(path, text)),
+            Err(cause) => {
+                // The file is still registered (with no text) so the error can name the path
+                let source_id = self.add_source(path, "");
+                let span = Some(crate::Span {
+                    source_id,
+                    start_index: 0,
+                    length: 0,
+                });
+                let kind = match cause.kind() {
+                    std::io::ErrorKind::InvalidData => crate::ErrorKind::SourceFileRead { cause },
+                    _ => crate::ErrorKind::SourceFileOpen { cause },
+                };
+                Err(Box::new(crate::Error::new(span, kind)))
+            }
+        }
+    }
+}
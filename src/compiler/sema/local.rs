@@ -40,7 +40,10 @@ impl BreakScope {
 pub struct LocalContext {
     function: FunctionDefinition,
     function_path: AbsolutePath,
+    /// The span of the return type annotation, or where it would be if it was left out.
+    return_type_span: crate::Span,
     current_block: BasicBlock,
+    current_location: Option<crate::Span>,
     break_scope_stack: Vec<BreakScope>,
     continue_scope_stack: Vec<BlockLabel>,
     symbol_versions: HashMap<Box<str>, usize>,
@@ -50,11 +53,13 @@ pub struct LocalContext {
 }
 
 impl LocalContext {
-    pub fn new(function: FunctionDefinition, function_path: AbsolutePath) -> Self {
+    pub fn new(function: FunctionDefinition, function_path: AbsolutePath, return_type_span: crate::Span) -> Self {
         Self {
             function,
             function_path,
+            return_type_span,
             current_block: BasicBlock::new(BlockLabel::new(b".block.0".as_slice().into())),
+            current_location: None,
             break_scope_stack: Vec::new(),
             continue_scope_stack: Vec::new(),
             symbol_versions: HashMap::new(),
@@ -80,6 +85,10 @@ impl LocalContext {
         self.function.return_type()
     }
 
+    pub fn return_type_span(&self) -> crate::Span {
+        self.return_type_span
+    }
+
     pub fn current_block(&self) -> &BasicBlock {
         &self.current_block
     }
@@ -92,12 +101,22 @@ impl LocalContext {
         self.current_block.add_phi(phi);
     }
 
+    pub fn current_location(&self) -> Option<crate::Span> {
+        self.current_location
+    }
+
+    /// Set the source location attached to subsequently added instructions, returning the
+    /// previous location so it can be restored.
+    pub fn replace_location(&mut self, location: Option<crate::Span>) -> Option<crate::Span> {
+        std::mem::replace(&mut self.current_location, location)
+    }
+
     pub fn add_instruction(&mut self, instruction: Instruction) {
-        self.current_block.add_instruction(instruction);
+        self.current_block.add_instruction(instruction, self.current_location);
     }
 
     pub fn set_terminator(&mut self, terminator: TerminatorInstruction) {
-        self.current_block.set_terminator(terminator);
+        self.current_block.set_terminator(terminator, self.current_location);
     }
 
     pub fn start_new_block(&mut self, label: BlockLabel) -> &BlockLabel {
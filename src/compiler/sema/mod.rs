@@ -1,9 +1,10 @@
 use crate::ast::{GlobalNode, GlobalNodeKind, LocalNode, LocalNodeKind, PathSegment, TypeNode, TypeNodeKind};
 use crate::ast::parse::ParsedModule;
 use crate::ir::value::{Constant, GlobalRegister, IntegerValue, LocalRegister, Value};
-use crate::package::{PackageContext, PackageManager};
+use crate::package::{PackageContext, PackageKind, PackageManager};
 use crate::target::TargetInfo;
 use crate::token::Literal;
+use std::collections::HashMap;
 use std::path::Path;
 
 mod local;
@@ -14,14 +15,36 @@ pub use symbol::*;
 
 mod types;
 pub use types::*;
-use crate::ir::{ExternalFunction, ExternalGlobalVariable, GlobalVariableKind};
+use crate::ir::{CompilationUnit, ExternalFunction, ExternalGlobalVariable, FunctionAttributes, GlobalVariableKind, Linkage, OptimizationLevel};
+
+/// Whether filling a global statement failed because an array length in its types couldn't be
+/// evaluated yet, which may succeed once other statements have been filled or the functions it
+/// calls have been generated.
+fn is_postponable_error(error: &crate::Error) -> bool {
+    matches!(
+        error.kind(),
+        crate::ErrorKind::UndefinedSymbol { .. }
+            | crate::ErrorKind::UninitializedGlobal { .. }
+            | crate::ErrorKind::UngeneratedFunction { .. }
+            | crate::ErrorKind::UnresolvedTypeLayout { .. },
+    )
+}
 
 pub struct GlobalContext {
     target: TargetInfo,
+    debug_info: bool,
+    optimization_level: OptimizationLevel,
     package_manager: PackageManager,
     namespace_registry: NamespaceRegistry,
     type_registry: TypeRegistry,
     package: PackageContext,
+    /// The output of every package which has already been compiled, in order of compilation.
+    finished_units: Vec<CompilationUnit>,
+    /// The attributes of every function defined or declared so far, by symbol identifier.
+    function_attributes: HashMap<Box<[u8]>, FunctionAttributes>,
+    /// The name of the package declaring each function loaded from an interface header, by symbol
+    /// identifier. These functions have no bodies to evaluate at compile time.
+    interface_functions: HashMap<Box<[u8]>, Box<str>>,
 }
 
 impl GlobalContext {
@@ -41,10 +64,15 @@ impl GlobalContext {
 
         Ok(Self {
             target,
+            debug_info: false,
+            optimization_level: OptimizationLevel::default(),
             package_manager,
             namespace_registry,
             type_registry,
             package,
+            finished_units: Vec::new(),
+            function_attributes: HashMap::new(),
+            interface_functions: HashMap::new(),
         })
     }
 
@@ -52,6 +80,24 @@ impl GlobalContext {
         &self.target
     }
 
+    /// Whether debug info metadata should be included in the output.
+    pub fn debug_info(&self) -> bool {
+        self.debug_info
+    }
+
+    pub fn set_debug_info(&mut self, debug_info: bool) {
+        self.debug_info = debug_info;
+    }
+
+    /// Which optimization passes are run on each generated function.
+    pub fn optimization_level(&self) -> OptimizationLevel {
+        self.optimization_level
+    }
+
+    pub fn set_optimization_level(&mut self, optimization_level: OptimizationLevel) {
+        self.optimization_level = optimization_level;
+    }
+
     pub fn package_manager(&self) -> &PackageManager {
         &self.package_manager
     }
@@ -89,6 +135,7 @@ impl GlobalContext {
         self.type_registry.finish_package();
 
         if let Some(package_info) = self.package_manager.get_next_to_compile() {
+            self.finished_units.push(self.package.take_output());
             self.package = self.namespace_registry.create_package_context(package_info);
             true
         }
@@ -97,17 +144,39 @@ impl GlobalContext {
         }
     }
 
+    /// The output of the packages compiled before the current one, which it may depend on.
+    pub fn finished_units(&self) -> &[CompilationUnit] {
+        &self.finished_units
+    }
+
+    pub fn function_attributes(&self, identifier: &[u8]) -> Option<&FunctionAttributes> {
+        self.function_attributes.get(identifier)
+    }
+
+    /// Mark the function `identifier` as unable to unwind once its body has been generated.
+    pub fn mark_nounwind(&mut self, identifier: &[u8]) {
+        if let Some(attributes) = self.function_attributes.get_mut(identifier) {
+            attributes.is_nounwind = true;
+        }
+    }
+
+    /// The name of the package which declared the function `identifier` in its interface header,
+    /// or `None` if the function wasn't loaded from one.
+    pub fn interface_function_package(&self, identifier: &[u8]) -> Option<&str> {
+        self.interface_functions.get(identifier).map(AsRef::as_ref)
+    }
+
     pub fn prepare_next_source(&mut self) -> crate::Result<Option<(usize, NamespaceHandle)>> {
         loop {
             let Some(module_path) = self.package.get_next_module_to_parse() else {
                 break Ok(None);
             };
             let file_path = self.package.get_file_path_for_module(&module_path);
-            if let Some(source_id) = self.package.register_source_path(file_path) {
+            if let Some(source_id) = self.package.register_source_path(file_path)? {
                 let namespace = module_path.segments().iter().try_fold(
                     NamespaceHandle::GLOBAL_ROOT,
                     |parent_namespace, segment| {
-                        self.get_or_create_module(parent_namespace, segment)
+                        self.get_or_create_module(parent_namespace, segment, false)
                     },
                 )?;
                 break Ok(Some((source_id, namespace)));
@@ -177,24 +246,69 @@ impl GlobalContext {
         self.namespace_info_mut(self.current_namespace())
     }
 
-    pub fn get_or_create_module(&mut self, parent_module: NamespaceHandle, name: &str) -> crate::Result<NamespaceHandle> {
+    pub fn get_or_create_module(&mut self, parent_module: NamespaceHandle, name: &str, is_public: bool) -> crate::Result<NamespaceHandle> {
         let parent_module_info = self.namespace_info(parent_module);
 
         if let Some(namespace) = parent_module_info.find(name).and_then(Symbol::as_module) {
-            // Extend the existing module
+            // Extend the existing module, which becomes public if any of its declarations are
+            if is_public {
+                if let Some(symbol) = self.namespace_info_mut(parent_module).find_mut(name) {
+                    symbol.set_visibility(Visibility::Public);
+                }
+            }
             Ok(namespace)
         }
         else {
             // Create a new module
             let module_path = parent_module_info.path().child(name);
             let namespace = self.namespace_registry.create_namespace(module_path);
+            let visibility = if is_public { Visibility::Public } else { Visibility::Private(parent_module) };
 
-            self.namespace_info_mut(parent_module).define(name, Symbol::new(SymbolKind::Module(namespace)))?;
+            self.namespace_info_mut(parent_module).define(
+                name,
+                Symbol::new(SymbolKind::Module(namespace)).with_visibility(visibility),
+            )?;
 
             Ok(namespace)
         }
     }
 
+    /// The visibility of a symbol declared in the current module, given whether it was marked `pub`.
+    pub fn declared_visibility(&self, is_public: bool) -> Visibility {
+        if is_public {
+            Visibility::Public
+        }
+        else {
+            Visibility::Private(self.current_module())
+        }
+    }
+
+    /// Whether a symbol with the given visibility can be accessed from the current module.
+    /// Interface headers refer to everything by its full path, which was already checked when
+    /// the package was compiled from source, so nothing is off limits while loading them.
+    pub fn is_accessible(&self, visibility: Visibility) -> bool {
+        if self.package.interface_path().is_some() {
+            return true;
+        }
+        match visibility {
+            Visibility::Public => true,
+            Visibility::Private(module) => self.is_within_module(self.namespace_info(module).path()),
+        }
+    }
+
+    /// Whether a member of the given structure type can be accessed from the current module.
+    /// Private members are accessible from the module that defines the structure type.
+    pub fn is_member_accessible(&self, struct_type: TypeHandle, member: &StructureMember) -> bool {
+        member.is_public || self.type_path(struct_type).parent().is_some_and(|module_path| {
+            self.is_within_module(&module_path)
+        })
+    }
+
+    fn is_within_module(&self, module_path: &AbsolutePath) -> bool {
+        self.current_module_info().path().simple().segments()
+            .starts_with(module_path.simple().segments())
+    }
+
     pub fn replace_current_module(&mut self, module: NamespaceHandle) -> NamespaceHandle {
         self.package.replace_current_module(module)
     }
@@ -271,7 +385,7 @@ impl GlobalContext {
                     item_type: self.type_path_for_type_node(item_type)?,
                     length: match length {
                         Some(node) => {
-                            // Must be an integer literal (for now)
+                            // Other constant expressions must have been evaluated beforehand
                             if let &LocalNodeKind::Literal(Literal::Integer(raw, _)) = node.kind() {
                                 // Must be an acceptable usize value
                                 let Some(value) = IntegerValue::from_unknown_type(raw, TypeHandle::USIZE, self.target()) else {
@@ -285,6 +399,9 @@ impl GlobalContext {
                                 };
                                 Some(value.raw() as u64)
                             }
+                            else if let Some(length) = self.package.array_length(node.span()) {
+                                Some(length)
+                            }
                             else {
                                 return Err(Box::new(crate::Error::new(
                                     Some(node.span()),
@@ -349,16 +466,65 @@ impl GlobalContext {
     }
 
     pub fn interpret_type_node(&mut self, type_node: &TypeNode) -> crate::Result<TypeHandle> {
+        self.evaluate_array_lengths(type_node)?;
         let type_path = self.type_path_for_type_node(type_node)?;
         self.get_path_type(&type_path, Some(&type_node.span()))
     }
 
     pub fn interpret_node_as_type(&mut self, node: &LocalNode) -> crate::Result<TypeHandle> {
+        match node.kind() {
+            LocalNodeKind::Type(type_node) => self.evaluate_array_lengths(type_node)?,
+            LocalNodeKind::Path { segments, .. } => self.evaluate_segment_array_lengths(segments)?,
+            _ => {}
+        }
         let type_path = self.type_path_for_node(node)?;
         self.get_path_type(&type_path, Some(&node.span()))
     }
 
-    pub fn outline_structure_type(&mut self, name: Box<str>) -> crate::Result<TypeHandle> {
+    /// Evaluate the length of every array type within `type_node` which is given by a constant
+    /// expression other than an integer literal, so [`Self::type_path_for_type_node`] can find it.
+    /// Lengths calling functions of the package being compiled can only be evaluated once those
+    /// functions have been generated, which [`Self::fill_package`] takes care of.
+    fn evaluate_array_lengths(&mut self, type_node: &TypeNode) -> crate::Result<()> {
+        match type_node.kind() {
+            TypeNodeKind::Path { segments } => {
+                self.evaluate_segment_array_lengths(segments)
+            }
+            TypeNodeKind::Pointer { pointee_type, .. } => {
+                self.evaluate_array_lengths(pointee_type)
+            }
+            TypeNodeKind::Array { item_type, length } => {
+                self.evaluate_array_lengths(item_type)?;
+                if let Some(node) = length {
+                    let is_literal = matches!(node.kind(), LocalNodeKind::Literal(Literal::Integer(..)));
+                    if !is_literal && self.package.array_length(node.span()).is_none() {
+                        let length = crate::gen::Generator::new(self).evaluate_array_length(node)?;
+                        self.package.set_array_length(node.span(), length);
+                    }
+                }
+                Ok(())
+            }
+            TypeNodeKind::Tuple { item_types } => {
+                item_types.iter().try_for_each(|item_type| self.evaluate_array_lengths(item_type))
+            }
+            TypeNodeKind::Function { parameter_types, return_type, .. } => {
+                parameter_types.iter().try_for_each(|parameter_type| self.evaluate_array_lengths(parameter_type))?;
+                self.evaluate_array_lengths(return_type)
+            }
+            TypeNodeKind::Grouping { content } => {
+                self.evaluate_array_lengths(content)
+            }
+        }
+    }
+
+    fn evaluate_segment_array_lengths(&mut self, segments: &[PathSegment]) -> crate::Result<()> {
+        segments.iter().try_for_each(|segment| match segment {
+            PathSegment::Type(type_node) => self.evaluate_array_lengths(type_node),
+            _ => Ok(()),
+        })
+    }
+
+    pub fn outline_structure_type(&mut self, name: Box<str>, span: crate::Span, is_public: bool) -> crate::Result<TypeHandle> {
         let path = self.current_module_info().path().child(name.clone());
         let handle = self.type_registry.create_type(
             path.clone(),
@@ -367,8 +533,12 @@ impl GlobalContext {
             &self.target,
             self.package.fill_phase_complete(),
         );
+        self.type_registry.set_type_span(handle, span);
 
-        self.current_module_info_mut().define(&name, Symbol::new(SymbolKind::Type(handle)))?;
+        let symbol = Symbol::new(SymbolKind::Type(handle))
+            .with_span(span)
+            .with_visibility(self.declared_visibility(is_public));
+        self.current_module_info_mut().define(&name, symbol)?;
 
         Ok(handle)
     }
@@ -376,12 +546,25 @@ impl GlobalContext {
     pub fn get_symbol_value(&mut self, namespace: NamespaceHandle, name: &str, span: Option<&crate::Span>) -> crate::Result<Value> {
         // FIXME: does not detect recursive import
         if let Some(symbol) = self.namespace_info(namespace).find(name) {
+            if !self.is_accessible(symbol.visibility()) {
+                return Err(Box::new(crate::Error::new(
+                    span.copied(),
+                    crate::ErrorKind::PrivateSymbol {
+                        name: self.namespace_info(namespace).path().child(name).to_string(),
+                        module: self.current_module_info().path().to_string(),
+                    },
+                )));
+            }
             let is_external = symbol.is_external();
 
             let value = match symbol.kind() {
                 SymbolKind::Alias(target_path) => {
+                    // The import is resolved from the module it was declared in, which is what
+                    // allows `pub import` to re-export symbols that are otherwise inaccessible
                     let target_path = target_path.clone();
-                    self.get_path_value(&target_path, span)?
+                    self.with_current_module(namespace, |context| {
+                        context.get_path_value(&target_path, span)
+                    })?
                 }
                 SymbolKind::Module(namespace) => {
                     Value::Constant(Constant::Module(*namespace))
@@ -407,23 +590,31 @@ impl GlobalContext {
             Ok(value)
         }
         else {
-            let glob_imports = self.namespace_info(namespace)
+            let glob_imports: Vec<AbsolutePath> = self.namespace_info(namespace)
                 .glob_imports()
-                .to_vec();
+                .iter()
+                .filter(|&&(_, visibility)| self.is_accessible(visibility))
+                .map(|(glob_import_path, _)| glob_import_path.clone())
+                .collect();
 
             // TODO: wtf, kinda
-            let search_results: Vec<(AbsolutePath, Value)> = glob_imports
-                .into_iter()
-                .map(|glob_import_path| glob_import_path.into_child(name))
-                .chain((namespace != NamespaceHandle::GLOBAL_ROOT).then(|| {
-                    AbsolutePath::at_root().into_child(name)
-                }))
-                .filter_map(|test_path| {
-                    self.get_path_value(&test_path, span)
-                        .ok()
-                        .map(|value| (test_path, value))
-                })
-                .collect();
+            let mut search_results: Vec<(AbsolutePath, Value)> = self.with_current_module(namespace, |context| {
+                glob_imports
+                    .into_iter()
+                    .map(|glob_import_path| glob_import_path.into_child(name))
+                    .filter_map(|test_path| {
+                        context.get_path_value(&test_path, span)
+                            .ok()
+                            .map(|value| (test_path, value))
+                    })
+                    .collect()
+            });
+            if namespace != NamespaceHandle::GLOBAL_ROOT {
+                let test_path = AbsolutePath::at_root().into_child(name);
+                if let Ok(value) = self.get_path_value(&test_path, span) {
+                    search_results.push((test_path, value));
+                }
+            }
 
             if search_results.is_empty() {
                 Err(Box::new(crate::Error::new(
@@ -453,6 +644,15 @@ impl GlobalContext {
         }
     }
 
+    /// Run `f` as if `module` were the current module, such as when resolving an import from the
+    /// perspective of the module which declared it.
+    fn with_current_module<T>(&mut self, module: NamespaceHandle, f: impl FnOnce(&mut Self) -> T) -> T {
+        let previous_module = self.replace_current_module(module);
+        let result = f(self);
+        self.replace_current_module(previous_module);
+        result
+    }
+
     pub fn get_path_value(&mut self, path: &AbsolutePath, span: Option<&crate::Span>) -> crate::Result<Value> {
         path.simple().segments().iter().try_fold(
             match path.base_type() {
@@ -580,92 +780,240 @@ impl GlobalContext {
         self.type_registry.try_explicit_conversion(from_type, to_type, from_mutable)
     }
 
-    pub fn process_package<'a, I>(&mut self, modules: I) -> crate::Result<()>
-    where
-        I: IntoIterator<Item = &'a mut ParsedModule>,
-    {
+    pub fn process_package(&mut self, modules: &mut [ParsedModule]) -> crate::Result<()> {
+        self.fill_package(modules)?;
+        self.complete_fill_phase()
+    }
+
+    /// Perform the fill pass on each module. Type properties are only calculated if array lengths
+    /// outside of function bodies call functions of the package, in which case the functions are
+    /// generated before the statements using those array types are filled again, until every
+    /// statement has been filled.
+    pub fn fill_package(&mut self, modules: &mut [ParsedModule]) -> crate::Result<()> {
+        let mut postponed = self.fill_modules(modules)?;
+        let mut postponed_count = usize::MAX;
+        while !postponed.is_empty() {
+            // Functions can only be generated once the layout of the types they use is known
+            self.complete_fill_phase()?;
+            let generated_any = self.generate_postponed_functions(modules, &postponed)?;
+            if !generated_any && postponed.len() >= postponed_count {
+                let error = postponed.swap_remove(0);
+                return Err(Box::new(match error.kind() {
+                    crate::ErrorKind::UndefinedSymbol { .. } => error,
+                    _ => error.with_note("constant expressions cannot depend on each other in a cycle"),
+                }));
+            }
+
+            postponed_count = postponed.len();
+            postponed = self.fill_modules(modules)?;
+        }
+
+        Ok(())
+    }
+
+    /// Fill every statement of `modules` which hasn't been filled yet, returning the errors of
+    /// those which had to be postponed.
+    fn fill_modules(&mut self, modules: &mut [ParsedModule]) -> crate::Result<Vec<crate::Error>> {
+        let mut postponed = Vec::new();
         for parsed_module in modules {
             let previous_module = self.replace_current_module(parsed_module.namespace());
 
-            self.process_global_statements(parsed_module.statements_mut())?;
+            let result = self.process_global_statements(parsed_module.statements_mut(), &mut postponed);
 
             self.replace_current_module(previous_module);
+            result?;
         }
 
-        self.complete_fill_phase()
+        Ok(postponed)
     }
 
-    pub fn process_global_statements<'a, I>(&mut self, global_statements: I) -> crate::Result<()>
+    /// Generate the functions of the package which the `postponed` statements failed to call,
+    /// along with any functions those need to be generated first. Returns whether any function
+    /// was generated.
+    fn generate_postponed_functions(&mut self, modules: &[ParsedModule], postponed: &[crate::Error]) -> crate::Result<bool> {
+        let mut pending_names: Vec<String> = postponed.iter()
+            .filter_map(|error| match error.kind() {
+                crate::ErrorKind::UngeneratedFunction { name } => Some(name.clone()),
+                _ => None,
+            })
+            .collect();
+        let mut attempted_names = Vec::new();
+        let mut generated_any = false;
+        while let Some(name) = pending_names.pop() {
+            if attempted_names.contains(&name) {
+                continue;
+            }
+            let Some((module, self_type, node)) = self.find_function_definition(modules, &name) else {
+                continue;
+            };
+            attempted_names.push(name);
+
+            match crate::gen::Generator::new(self).generate_function_early(module, self_type, node) {
+                Ok(()) => generated_any = true,
+                Err(error) if is_postponable_error(&error) => {
+                    // The function may be waiting on another one to evaluate an array length
+                    if let crate::ErrorKind::UngeneratedFunction { name } = error.kind() {
+                        pending_names.push(name.clone());
+                    }
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(generated_any)
+    }
+
+    /// Find the definition of the filled function whose demangled symbol is `name`, along with the
+    /// module and `Self` type it is defined with.
+    fn find_function_definition<'a>(&mut self, modules: &'a [ParsedModule], name: &str) -> Option<(NamespaceHandle, Option<TypeHandle>, &'a GlobalNode)> {
+        fn find_in<'a>(context: &mut GlobalContext, module: NamespaceHandle, self_type: Option<TypeHandle>, statements: &'a [GlobalNode], name: &str) -> Option<(NamespaceHandle, Option<TypeHandle>, &'a GlobalNode)> {
+            statements.iter().find_map(|statement| match statement.kind() {
+                GlobalNodeKind::Function { body: Some(_), register: Some(register), .. } => {
+                    (display_symbol(register.identifier()) == name).then_some((module, self_type, statement))
+                }
+                GlobalNodeKind::Implement { self_type: type_node, statements } => {
+                    let parent_module = context.replace_current_module(module);
+                    let self_type = context.interpret_type_node(type_node).ok();
+                    context.replace_current_module(parent_module);
+                    find_in(context, module, Some(self_type?), statements, name)
+                }
+                GlobalNodeKind::Module { statements, namespace, .. } => {
+                    find_in(context, *namespace, self_type, statements, name)
+                }
+                _ => None,
+            })
+        }
+
+        modules.iter().find_map(|parsed_module| {
+            find_in(self, parsed_module.namespace(), None, parsed_module.statements(), name)
+        })
+    }
+
+    /// Fill each of `global_statements`, adding the errors of those which must be postponed to
+    /// `postponed`.
+    pub fn process_global_statements<'a, I>(&mut self, global_statements: I, postponed: &mut Vec<crate::Error>) -> crate::Result<()>
     where
         I: IntoIterator<Item = &'a mut GlobalNode>,
     {
         for global_statement in global_statements {
-            self.process_global_statement(global_statement)?;
+            self.process_global_statement(global_statement, postponed)?;
         }
 
         Ok(())
     }
 
-    pub fn process_global_statement(&mut self, node: &mut GlobalNode) -> crate::Result<()> {
+    /// Fill `node` unless it has been filled already. If an array length in its types can't be
+    /// evaluated yet, the error is added to `postponed` so it can be filled again later.
+    pub fn process_global_statement(&mut self, node: &mut GlobalNode, postponed: &mut Vec<crate::Error>) -> crate::Result<()> {
+        match self.fill_global_statement(node, postponed) {
+            Err(error) if is_postponable_error(&error) => {
+                postponed.push(*error);
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    fn fill_global_statement(&mut self, node: &mut GlobalNode, postponed: &mut Vec<crate::Error>) -> crate::Result<()> {
+        let span = node.span();
+        let is_public = node.is_public();
         match node.kind_mut() {
+            GlobalNodeKind::Let { register: Some(_), .. } | GlobalNodeKind::Function { register: Some(_), .. } => {
+                // Already filled in an earlier pass
+            }
             GlobalNodeKind::Let { name, symbol_name, value_type, is_mutable, value, register } => {
                 let value_type = self.interpret_type_node(value_type)?;
 
-                let identifier = self.get_global_identifier(name, symbol_name.as_ref());
+                let identifier = self.get_global_identifier(name, symbol_name.as_deref());
                 let pointer_type = self.get_pointer_type(value_type, PointerSemantics::for_symbol(*is_mutable));
                 let global_register = GlobalRegister::new(identifier, pointer_type);
 
                 let mut symbol = Symbol::new(SymbolKind::Value(Value::Constant(Constant::Indirect {
                     pointee_type: value_type,
                     pointer: Box::new(Constant::Register(global_register.clone())),
-                })));
+                })))
+                    .with_span(span)
+                    .with_visibility(self.declared_visibility(is_public));
                 symbol.set_external(value.is_none());
                 self.current_namespace_info_mut().define(name, symbol)?;
 
                 *register = Some(global_register);
             }
-            GlobalNodeKind::Function { name, symbol_name, parameters, is_variadic, return_type, body, register } => {
-                let parameter_types = parameters
+            GlobalNodeKind::Function { name, symbol_name, parameters, is_variadic, return_type, body, calling_convention, is_nounwind, register, .. } => {
+                let parameter_types: Box<[TypeHandle]> = parameters
                     .iter()
                     .map(|parameter| {
                         self.interpret_type_node(&parameter.type_node)
                     })
                     .collect::<crate::Result<_>>()?;
+                for (parameter, &parameter_type) in std::iter::zip(parameters.iter(), &parameter_types) {
+                    let is_pointer = matches!(parameter_type.repr(self), TypeRepr::Pointer { .. });
+                    if !parameter.attributes.is_empty() && !is_pointer {
+                        return Err(Box::new(crate::Error::new(
+                            Some(parameter.span),
+                            crate::ErrorKind::ExpectedPointer {
+                                type_name: parameter_type.path(self).to_string(),
+                            },
+                        )));
+                    }
+                }
                 let return_type = self.interpret_type_node(return_type)?;
                 let signature = FunctionSignature::new(return_type, parameter_types, *is_variadic);
                 let function_type = self.get_function_type(&signature);
 
-                let identifier = self.get_global_identifier(name, symbol_name.as_ref());
+                let identifier = self.get_global_identifier(name, symbol_name.as_deref());
                 let global_register = GlobalRegister::new(identifier, function_type);
 
-                let mut symbol = Symbol::new(SymbolKind::Value(Value::from(global_register.clone())));
+                self.function_attributes.insert(global_register.identifier().into(), FunctionAttributes {
+                    linkage: self.global_linkage(symbol_name.is_some(), is_public),
+                    calling_convention: *calling_convention,
+                    is_noreturn: return_type == TypeHandle::NEVER,
+                    // Functions with a body are inferred not to unwind once they have been generated,
+                    // and LLVM intrinsics never unwind
+                    is_nounwind: *is_nounwind || symbol_name.as_deref().is_some_and(|symbol_name| symbol_name.starts_with(b"llvm.")),
+                    parameter_attributes: parameters.iter().map(|parameter| parameter.attributes).collect(),
+                });
+                if self.package.interface_path().is_some() {
+                    self.interface_functions.insert(global_register.identifier().into(), self.package.info().name().into());
+                }
+
+                let mut symbol = Symbol::new(SymbolKind::Value(Value::from(global_register.clone())))
+                    .with_span(span)
+                    .with_visibility(self.declared_visibility(is_public));
                 symbol.set_external(body.is_none());
                 self.current_namespace_info_mut().define(name, symbol)?;
 
                 *register = Some(global_register);
             }
+            GlobalNodeKind::Structure { self_type, .. } if !matches!(self_type.repr(self), TypeRepr::Unresolved) => {
+                // Already filled in an earlier pass
+            }
             GlobalNodeKind::Structure { name, members, self_type } => {
                 if let Some(members) = members {
+                    let member_spans = members.iter().map(|member| member.span).collect();
+                    self.type_registry.set_member_spans(*self_type, member_spans);
                     self.set_self_type(*self_type);
 
                     let members = crate::Result::from_iter(members
                         .iter()
                         .map(|member| Ok(StructureMember {
                             name: member.name.clone(),
+                            is_public: member.is_public,
                             member_type: self.interpret_type_node(&member.type_node)?,
-                        })))?;
+                        })));
+
+                    self.unset_self_type();
+
                     self.type_registry.update_type_repr(
                         *self_type,
                         TypeRepr::Structure {
                             name: name.clone(),
-                            members,
+                            members: members?,
                             is_external: false,
                         },
                         &self.target,
                         self.package.fill_phase_complete(),
-                    );
-
-                    self.unset_self_type();
+                    )?;
                 } else {
                     self.type_registry.update_type_repr(
                         *self_type,
@@ -675,27 +1023,25 @@ impl GlobalContext {
                         },
                         &self.target,
                         self.package.fill_phase_complete(),
-                    );
+                    )?;
                 }
             }
             GlobalNodeKind::Implement { self_type, statements, .. } => {
                 let self_type = self.interpret_type_node(self_type)?;
                 self.set_self_type(self_type);
 
-                for statement in statements {
-                    self.process_global_statement(statement)?;
-                }
+                let result = self.process_global_statements(statements, postponed);
 
                 self.unset_self_type();
+                result?;
             }
             GlobalNodeKind::Module { statements, namespace, .. } => {
                 let parent_module = self.replace_current_module(*namespace);
 
-                for statement in statements {
-                    self.process_global_statement(statement)?;
-                }
+                let result = self.process_global_statements(statements, postponed);
 
                 self.replace_current_module(parent_module);
+                result?;
             }
             _ => {}
         }
@@ -703,18 +1049,27 @@ impl GlobalContext {
         Ok(())
     }
 
-    fn get_global_identifier(&self, name: &str, symbol_name: Option<&Box<[u8]>>) -> Box<[u8]> {
+    /// The linkage of a global value given whether it is foreign and whether it was marked `pub`.
+    /// Nothing can depend on an executable, so only its foreign symbols are visible to the linker.
+    pub fn global_linkage(&self, is_foreign: bool, is_public: bool) -> Linkage {
+        if is_foreign || (is_public && self.package.info().kind() == PackageKind::Library) {
+            Linkage::External
+        }
+        else {
+            Linkage::Internal
+        }
+    }
+
+    fn get_global_identifier(&self, name: &str, symbol_name: Option<&[u8]>) -> Box<[u8]> {
         match symbol_name {
             Some(symbol_name) => {
-                symbol_name.clone()
+                symbol_name.into()
             }
             None => {
-                self.current_namespace_info()
-                    .path()
-                    .child(name)
-                    .to_string()
-                    .as_bytes()
-                    .into()
+                let path = self.current_namespace_info().path().child(name);
+                mangle_path(self.package.info().name(), &path)
+                    .into_bytes()
+                    .into_boxed_slice()
             }
         }
     }
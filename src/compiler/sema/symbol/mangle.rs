@@ -0,0 +1,307 @@
+//! Symbol name mangling for non-`foreign` globals.
+//!
+//! A mangled symbol is `_C` followed by a path:
+//!
+//! ```text
+//! path       := "N" segment* "E"
+//! segment    := identifier generics?      (a module, type or item name)
+//!             | "X" type                  (the target of an `implement` block)
+//! identifier := <decimal byte length> <name>
+//! generics   := "I" type* "E"             (reserved for generic arguments)
+//! type       := <primitive code> | "u" identifier
+//!             | "P" type | "M" type       (`*T` and `*mut T`)
+//!             | "A" <decimal length>? "_" type
+//!             | "T" type* "E"
+//!             | "F" type type* "V"? "E"   (return type, then parameter types)
+//!             | path
+//! ```
+//!
+//! The first segment of a path is always the name of a package. For example,
+//! `::std::string::String::push` is mangled as `_CN3std6string6String4pushE`, and `to_string` in
+//! `implement i64` within package `test_1` is mangled as `_CN6test_1Xx9to_stringE`.
+
+use super::*;
+use std::fmt::Write;
+
+const MANGLED_PREFIX: &str = "_C";
+
+const PRIMITIVE_CODES: &[(&str, u8)] = &[
+    ("never", b'z'),
+    ("void", b'v'),
+    ("bool", b'b'),
+    ("i8", b'a'),
+    ("u8", b'h'),
+    ("i16", b's'),
+    ("u16", b't'),
+    ("i32", b'i'),
+    ("u32", b'j'),
+    ("i64", b'x'),
+    ("u64", b'y'),
+    ("isize", b'l'),
+    ("usize", b'm'),
+    ("f32", b'f'),
+    ("f64", b'd'),
+];
+
+/// Get the mangled symbol name for the global at `path`, which is defined in the package named
+/// `package_name`.
+pub fn mangle_path(package_name: &str, path: &AbsolutePath) -> String {
+    let mut mangled = String::from(MANGLED_PREFIX);
+    push_path(&mut mangled, Some(package_name), path);
+    mangled
+}
+
+fn push_identifier(mangled: &mut String, name: &str) {
+    write!(mangled, "{}{name}", name.len()).unwrap();
+}
+
+fn push_path(mangled: &mut String, package_name: Option<&str>, path: &AbsolutePath) {
+    mangled.push('N');
+    if let Some(base_type) = path.base_type() {
+        // Paths starting from a base type don't name a package, so it is added explicitly
+        if let Some(package_name) = package_name {
+            push_identifier(mangled, package_name);
+        }
+        mangled.push('X');
+        push_base_type(mangled, base_type);
+    }
+    for segment in path.simple().segments() {
+        push_identifier(mangled, segment);
+    }
+    mangled.push('E');
+}
+
+fn push_type(mangled: &mut String, path: &AbsolutePath) {
+    match path.base_type() {
+        Some(base_type) if path.simple().is_empty() => push_base_type(mangled, base_type),
+        _ => push_path(mangled, None, path),
+    }
+}
+
+fn push_base_type(mangled: &mut String, base_type: &PathBaseType) {
+    match base_type {
+        PathBaseType::Primitive(primitive) => {
+            match PRIMITIVE_CODES.iter().find(|&&(name, _)| name == primitive.name) {
+                Some(&(_, code)) => mangled.push(code as char),
+                None => {
+                    mangled.push('u');
+                    push_identifier(mangled, primitive.name);
+                }
+            }
+        }
+        PathBaseType::Pointer { pointee_type, semantics } => {
+            mangled.push(match semantics {
+                PointerSemantics::Immutable | PointerSemantics::ImmutableSymbol => 'P',
+                PointerSemantics::Mutable => 'M',
+            });
+            push_type(mangled, pointee_type);
+        }
+        PathBaseType::Array { item_type, length } => {
+            mangled.push('A');
+            if let Some(length) = length {
+                write!(mangled, "{length}").unwrap();
+            }
+            mangled.push('_');
+            push_type(mangled, item_type);
+        }
+        PathBaseType::Tuple { item_types } => {
+            mangled.push('T');
+            for item_type in item_types {
+                push_type(mangled, item_type);
+            }
+            mangled.push('E');
+        }
+        PathBaseType::Function { parameter_types, is_variadic, return_type } => {
+            mangled.push('F');
+            push_type(mangled, return_type);
+            for parameter_type in parameter_types {
+                push_type(mangled, parameter_type);
+            }
+            if *is_variadic {
+                mangled.push('V');
+            }
+            mangled.push('E');
+        }
+    }
+}
+
+/// Get the readable form of a mangled symbol name, such as `std::string::String::push`, or `None`
+/// if `symbol` is not a valid mangled name.
+pub fn demangle(symbol: &str) -> Option<String> {
+    let mut demangler = Demangler::new(symbol.strip_prefix(MANGLED_PREFIX)?);
+    let demangled = demangler.path()?;
+    demangler.is_finished().then_some(demangled)
+}
+
+/// Get the name of the symbol `identifier` as it would be written in source code.
+pub fn display_symbol(identifier: &[u8]) -> String {
+    let name = String::from_utf8_lossy(identifier);
+    demangle(&name).unwrap_or_else(|| name.into_owned())
+}
+
+/// Replace every mangled symbol name found in `text` with its readable form.
+pub fn demangle_text(text: &str) -> String {
+    let mut demangled_text = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(index) = rest.find(MANGLED_PREFIX) {
+        let is_word_start = !rest[..index].ends_with(|ch: char| ch == '_' || ch.is_ascii_alphanumeric());
+        let mut demangler = Demangler::new(&rest[index + MANGLED_PREFIX.len() ..]);
+
+        match demangler.path().filter(|_| is_word_start) {
+            Some(demangled) => {
+                demangled_text.push_str(&rest[..index]);
+                demangled_text.push_str(&demangled);
+                rest = demangler.rest();
+            }
+            None => {
+                demangled_text.push_str(&rest[..index + MANGLED_PREFIX.len()]);
+                rest = &rest[index + MANGLED_PREFIX.len() ..];
+            }
+        }
+    }
+    demangled_text.push_str(rest);
+
+    demangled_text
+}
+
+struct Demangler<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Demangler<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            position: 0,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.position == self.input.len()
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position ..]
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.position += 1;
+        Some(byte)
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        (self.next()? == byte).then_some(())
+    }
+
+    fn number(&mut self) -> Option<usize> {
+        let digit_count = self.rest().bytes().take_while(u8::is_ascii_digit).count();
+        let number = self.rest()[..digit_count].parse().ok()?;
+        self.position += digit_count;
+        Some(number)
+    }
+
+    fn identifier(&mut self) -> Option<&'a str> {
+        let length = self.number()?;
+        let identifier = self.rest().get(..length)?;
+        self.position += length;
+        Some(identifier)
+    }
+
+    fn path(&mut self) -> Option<String> {
+        self.expect(b'N')?;
+
+        let mut segments = Vec::new();
+        loop {
+            match self.peek()? {
+                b'E' => {
+                    self.position += 1;
+                    break;
+                }
+                b'X' => {
+                    self.position += 1;
+                    segments.push(format!("<{}>", self.type_name()?));
+                }
+                _ => {
+                    let mut segment = self.identifier()?.to_owned();
+                    if let Some(b'I') = self.peek() {
+                        self.position += 1;
+                        segment.push('<');
+                        segment.push_str(&self.type_list()?.join(", "));
+                        segment.push('>');
+                    }
+                    segments.push(segment);
+                }
+            }
+        }
+
+        Some(segments.join("::"))
+    }
+
+    /// Parse types until the closing `E`.
+    fn type_list(&mut self) -> Option<Vec<String>> {
+        let mut type_names = Vec::new();
+        while self.peek()? != b'E' {
+            type_names.push(self.type_name()?);
+        }
+        self.position += 1;
+        Some(type_names)
+    }
+
+    fn type_name(&mut self) -> Option<String> {
+        match self.peek()? {
+            b'N' => self.path(),
+            code => {
+                self.position += 1;
+                match code {
+                    b'u' => self.identifier().map(str::to_owned),
+                    b'P' => Some(format!("*{}", self.type_name()?)),
+                    b'M' => Some(format!("*mut {}", self.type_name()?)),
+                    b'A' => {
+                        let length = match self.peek()? {
+                            b'_' => None,
+                            _ => Some(self.number()?),
+                        };
+                        self.expect(b'_')?;
+                        let item_type = self.type_name()?;
+                        match length {
+                            Some(length) => Some(format!("[{item_type}; {length}]")),
+                            None => Some(format!("[{item_type}]")),
+                        }
+                    }
+                    b'T' => match self.type_list()?.as_slice() {
+                        [item_type] => Some(format!("({item_type},)")),
+                        item_types => Some(format!("({})", item_types.join(", "))),
+                    },
+                    b'F' => {
+                        let return_type = self.type_name()?;
+                        let mut parameter_types = Vec::new();
+                        loop {
+                            match self.peek()? {
+                                b'E' => break,
+                                b'V' => {
+                                    self.position += 1;
+                                    parameter_types.push("..".to_owned());
+                                    break;
+                                }
+                                _ => parameter_types.push(self.type_name()?),
+                            }
+                        }
+                        self.expect(b'E')?;
+                        Some(format!("function({}) -> {return_type}", parameter_types.join(", ")))
+                    }
+                    code => PRIMITIVE_CODES
+                        .iter()
+                        .find(|&&(_, primitive_code)| primitive_code == code)
+                        .map(|&(name, _)| name.to_owned()),
+                }
+            }
+        }
+    }
+}
@@ -2,6 +2,9 @@ use super::*;
 use std::collections::HashMap;
 use std::num::NonZeroUsize;
 
+mod mangle;
+pub use mangle::*;
+
 mod registry;
 pub use registry::*;
 
@@ -247,7 +250,7 @@ impl NamespaceHandle {
 pub struct NamespaceInfo {
     path: AbsolutePath,
     symbols: HashMap<Box<str>, Symbol>,
-    glob_imports: Vec<AbsolutePath>,
+    glob_imports: Vec<(AbsolutePath, Visibility)>,
 }
 
 impl NamespaceInfo {
@@ -263,10 +266,14 @@ impl NamespaceInfo {
         &self.path
     }
 
-    pub fn glob_imports(&self) -> &[AbsolutePath] {
+    pub fn glob_imports(&self) -> &[(AbsolutePath, Visibility)] {
         &self.glob_imports
     }
 
+    pub fn symbol_count(&self) -> usize {
+        self.symbols.len()
+    }
+
     pub fn find(&self, name: &str) -> Option<&Symbol> {
         self.symbols.get(name)
     }
@@ -276,20 +283,30 @@ impl NamespaceInfo {
     }
 
     pub fn define(&mut self, name: &str, symbol: Symbol) -> crate::Result<()> {
-        match self.symbols.insert(name.into(), symbol) {
-            Some(..) => Err(Box::new(crate::Error::new(
-                None, // TODO
+        if let Some(existing_symbol) = self.symbols.get(name) {
+            let mut error = crate::Error::new(
+                symbol.span(),
                 crate::ErrorKind::GlobalSymbolConflict {
                     namespace: self.path().to_string(),
                     name: name.to_owned(),
                 },
-            ))),
-            None => Ok(()),
+            );
+            // Spans of symbols from dependencies refer to the source maps of those packages
+            if existing_symbol.is_external() {
+                error.add_note(format!("'{name}' was previously defined by a dependency"));
+            }
+            else if let Some(existing_span) = existing_symbol.span() {
+                error.add_label(existing_span, format!("'{name}' previously defined here"));
+            }
+            return Err(Box::new(error));
         }
+
+        self.symbols.insert(name.into(), symbol);
+        Ok(())
     }
 
-    pub fn add_glob_import(&mut self, path: impl Into<AbsolutePath>) {
-        self.glob_imports.push(path.into());
+    pub fn add_glob_import(&mut self, path: impl Into<AbsolutePath>, visibility: Visibility) {
+        self.glob_imports.push((path.into(), visibility));
     }
 }
 
@@ -309,20 +326,42 @@ pub enum SymbolKind {
     Value(Value),
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Visibility {
+    /// The symbol can be accessed from any module, including modules of other packages.
+    Public,
+    /// The symbol can only be accessed from the given module and the modules nested within it.
+    Private(NamespaceHandle),
+}
+
 #[derive(Clone, Debug)]
 pub struct Symbol {
     kind: SymbolKind,
+    visibility: Visibility,
     is_external: bool,
+    span: Option<crate::Span>,
 }
 
 impl Symbol {
     pub fn new(kind: SymbolKind) -> Self {
         Self {
             kind,
+            visibility: Visibility::Public,
             is_external: false,
+            span: None,
         }
     }
 
+    pub fn with_span(mut self, span: crate::Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    pub fn with_visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
     pub fn kind(&self) -> &SymbolKind {
         &self.kind
     }
@@ -331,6 +370,14 @@ impl Symbol {
         &mut self.kind
     }
 
+    pub fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+
+    pub fn set_visibility(&mut self, visibility: Visibility) {
+        self.visibility = visibility;
+    }
+
     pub fn is_external(&self) -> bool {
         self.is_external
     }
@@ -339,6 +386,11 @@ impl Symbol {
         self.is_external = is_external;
     }
 
+    /// The location of the definition of this symbol, if it originated from source code.
+    pub fn span(&self) -> Option<crate::Span> {
+        self.span
+    }
+
     pub fn as_alias(&self) -> Option<&AbsolutePath> {
         match self.kind {
             SymbolKind::Alias(ref path) => Some(path),
@@ -22,6 +22,11 @@ impl NamespaceRegistry {
         &mut self.namespace_table[handle.registry_index()]
     }
 
+    /// The total number of symbols defined across all namespaces.
+    pub fn symbol_count(&self) -> usize {
+        self.namespace_table.iter().map(NamespaceInfo::symbol_count).sum()
+    }
+
     pub fn create_namespace(&mut self, path: AbsolutePath) -> NamespaceHandle {
         let handle = NamespaceHandle::new(self.namespace_table.len());
 
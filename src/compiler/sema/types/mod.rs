@@ -41,6 +41,8 @@ impl PointerSemantics {
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct StructureMember {
     pub name: Box<str>,
+    /// Whether the member was marked `pub`, making it accessible outside of the defining module.
+    pub is_public: bool,
     pub member_type: TypeHandle,
 }
 
@@ -204,7 +206,7 @@ impl std::fmt::Display for PrimitiveType {
 }
 
 #[repr(transparent)]
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct TypeHandle(NonZeroUsize);
 
 impl TypeHandle {
@@ -7,6 +7,10 @@ struct TypeEntry {
     namespace: NamespaceHandle,
     alignment: Option<Option<u64>>,
     size: Option<Option<u64>>,
+    /// Where the type was defined, if it was defined in source code.
+    span: Option<crate::Span>,
+    /// Where each member of a structure type was defined, in order, for reporting errors.
+    member_spans: Box<[crate::Span]>,
 }
 
 pub struct TypeRegistry {
@@ -19,6 +23,12 @@ pub struct TypeRegistry {
     function_types: HashMap<FunctionSignature, TypeHandle>,
 }
 
+impl Default for TypeRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TypeRegistry {
     pub fn new() -> Self {
         Self {
@@ -69,6 +79,10 @@ impl TypeRegistry {
         &mut self.type_table[handle.registry_index()]
     }
 
+    pub fn type_count(&self) -> usize {
+        self.type_table.len()
+    }
+
     pub fn type_path(&self, handle: TypeHandle) -> &AbsolutePath {
         &self.type_entry(handle).path
     }
@@ -91,31 +105,75 @@ impl TypeRegistry {
             .expect("type size cannot be known before fill phase is completed")
     }
 
+    /// Whether the size and alignment of a type have been calculated, which isn't the case for
+    /// types containing a structure whose members are still being resolved.
+    pub fn is_layout_known(&self, handle: TypeHandle) -> bool {
+        let entry = self.type_entry(handle);
+        entry.alignment.is_some() && entry.size.is_some()
+    }
+
+    /// Get the offset in bytes of each member of a tuple or structure type, following the same
+    /// layout rules as `type_size`.
+    pub fn member_offsets(&self, handle: TypeHandle) -> Option<Vec<u64>> {
+        let member_types: Vec<TypeHandle> = match self.type_repr(handle) {
+            TypeRepr::Tuple { item_types } => item_types.to_vec(),
+            TypeRepr::Structure { members, .. } => members.iter().map(|member| member.member_type).collect(),
+            _ => return None,
+        };
+
+        let mut offsets = Vec::with_capacity(member_types.len());
+        let mut current_offset: u64 = 0;
+        for member_type in member_types {
+            let alignment = self.type_alignment(member_type).unwrap_or(1).max(1);
+            current_offset = current_offset.next_multiple_of(alignment);
+            offsets.push(current_offset);
+            current_offset += self.type_size(member_type)?;
+        }
+
+        Some(offsets)
+    }
+
     pub fn create_type(&mut self, path: AbsolutePath, repr: TypeRepr, namespace: NamespaceHandle, target: &TargetInfo, fill_phase_complete: bool) -> TypeHandle {
         let handle = TypeHandle::new(self.type_table.len());
 
-        let alignment = fill_phase_complete.then(|| self.calculate_alignment(&repr, target));
-        let size = fill_phase_complete.then(|| self.calculate_size(&repr, target));
-
         self.type_table.push(TypeEntry {
             path,
             repr,
             namespace,
-            alignment,
-            size,
+            alignment: None,
+            size: None,
+            span: None,
+            member_spans: Box::new([]),
         });
+        if fill_phase_complete {
+            // Nothing can contain a type which didn't exist yet, so there is no cycle to find
+            self.calculate_properties_for_type(handle, target, true, true, &mut Vec::new())
+                .expect("new type should not contain itself");
+        }
 
         handle
     }
 
-    pub fn update_type_repr(&mut self, handle: TypeHandle, repr: TypeRepr, target: &TargetInfo, fill_phase_complete: bool) {
-        let alignment = fill_phase_complete.then(|| self.calculate_alignment(&repr, target));
-        let size = fill_phase_complete.then(|| self.calculate_size(&repr, target));
+    /// Record where the type `handle` was defined.
+    pub fn set_type_span(&mut self, handle: TypeHandle, span: crate::Span) {
+        self.type_entry_mut(handle).span = Some(span);
+    }
+
+    /// Record where each member of the structure type `handle` was defined.
+    pub fn set_member_spans(&mut self, handle: TypeHandle, member_spans: Box<[crate::Span]>) {
+        self.type_entry_mut(handle).member_spans = member_spans;
+    }
 
+    pub fn update_type_repr(&mut self, handle: TypeHandle, repr: TypeRepr, target: &TargetInfo, fill_phase_complete: bool) -> crate::Result<()> {
         let entry = self.type_entry_mut(handle);
         entry.repr = repr;
-        entry.alignment = alignment;
-        entry.size = size;
+        entry.alignment = None;
+        entry.size = None;
+        if fill_phase_complete {
+            self.calculate_properties_for_type(handle, target, true, true, &mut Vec::new())?;
+        }
+
+        Ok(())
     }
 
     pub fn path_base_type(&self, base_type: &PathBaseType) -> Option<TypeHandle> {
@@ -264,6 +322,9 @@ impl TypeRegistry {
         Conversion::try_explicit(self, from_type, to_type, from_mutable)
     }
 
+    /// Calculate the size and alignment of every type which doesn't have them yet. Types containing
+    /// a structure whose members are still unresolved are skipped, so this can be called again
+    /// once they have been resolved.
     pub fn calculate_type_properties(&mut self, target: &TargetInfo) -> crate::Result<()> {
         let mut dependency_stack = Vec::new();
         for registry_index in 0 .. self.type_table.len() {
@@ -282,6 +343,17 @@ impl TypeRegistry {
         Ok(())
     }
 
+    /// The span of the first member of the structure type `handle` which has type `member_type`.
+    fn member_span(&self, handle: TypeHandle, member_type: TypeHandle) -> Option<crate::Span> {
+        let TypeRepr::Structure { members, .. } = self.type_repr(handle) else {
+            return None;
+        };
+        let index = members.iter().position(|member| member.member_type == member_type)?;
+        self.type_entry(handle).member_spans.get(index).copied()
+    }
+
+    /// Calculate the requested properties of `handle` and the types it contains, returning whether
+    /// they are known, which they aren't if an unresolved type is contained.
     fn calculate_properties_for_type(
         &mut self,
         handle: TypeHandle,
@@ -289,38 +361,48 @@ impl TypeRegistry {
         get_alignment: bool,
         get_size: bool,
         dependency_stack: &mut Vec<TypeHandle>,
-    ) -> crate::Result<()> {
+    ) -> crate::Result<bool> {
         let entry = self.type_entry(handle);
         let get_alignment = get_alignment && entry.alignment.is_none();
         let get_size = get_size && entry.size.is_none();
 
         if !get_alignment && !get_size {
-            return Ok(());
+            return Ok(true);
         }
 
-        if dependency_stack.contains(&handle) {
-            return Err(Box::new(crate::Error::new(
-                None,
+        if let Some(cycle_start) = dependency_stack.iter().position(|&dependency| dependency == handle) {
+            let mut error = crate::Error::new(
+                entry.span,
                 crate::ErrorKind::RecursiveTypeDefinition {
                     type_name: self.type_path(handle).to_string(),
                 },
-            )));
+            );
+            // Point out the chain of members which leads back to this type
+            let cycle = &dependency_stack[cycle_start..];
+            for (index, &outer_type) in cycle.iter().enumerate() {
+                let inner_type = cycle.get(index + 1).copied().unwrap_or(handle);
+                let message = format!(
+                    "'{}' contains a value of type '{}'",
+                    self.type_path(outer_type),
+                    self.type_path(inner_type),
+                );
+                match self.member_span(outer_type, inner_type) {
+                    Some(member_span) => error.add_label(member_span, message),
+                    None => error.add_note(message),
+                }
+            }
+            return Err(Box::new(error));
         }
         dependency_stack.push(handle);
 
         let repr = self.type_repr(handle).clone();
-        match repr {
-            TypeRepr::Unresolved => {
-                panic!("{handle:?} is still unresolved at the end of the fill phase");
-            }
-            TypeRepr::Pointer { pointee_type, .. } => {
-                self.calculate_properties_for_type(
-                    pointee_type,
-                    target,
-                    false,
-                    false,
-                    dependency_stack,
-                )?;
+        let is_known = match repr {
+            // Structures are unresolved while their members use array lengths which can't be
+            // evaluated yet
+            TypeRepr::Unresolved => false,
+            TypeRepr::Pointer { .. } => {
+                // The pointee type doesn't affect the layout of the pointer
+                true
             }
             TypeRepr::Array { item_type, length } => {
                 self.calculate_properties_for_type(
@@ -329,11 +411,12 @@ impl TypeRegistry {
                     get_alignment,
                     get_size && length.is_some(),
                     dependency_stack,
-                )?;
+                )?
             }
             TypeRepr::Tuple { ref item_types } => {
+                let mut is_known = true;
                 for &item_type in item_types {
-                    self.calculate_properties_for_type(
+                    is_known &= self.calculate_properties_for_type(
                         item_type,
                         target,
                         get_alignment || get_size,
@@ -341,10 +424,12 @@ impl TypeRegistry {
                         dependency_stack,
                     )?;
                 }
+                is_known
             }
             TypeRepr::Structure { ref members, .. } => {
+                let mut is_known = true;
                 for member in members {
-                    self.calculate_properties_for_type(
+                    is_known &= self.calculate_properties_for_type(
                         member.member_type,
                         target,
                         get_alignment || get_size,
@@ -352,11 +437,15 @@ impl TypeRegistry {
                         dependency_stack,
                     )?;
                 }
+                is_known
             }
-            _ => {}
-        }
+            _ => true,
+        };
 
         dependency_stack.pop();
+        if !is_known {
+            return Ok(false);
+        }
 
         if get_alignment {
             let alignment = self.calculate_alignment(&repr, target);
@@ -367,7 +456,7 @@ impl TypeRegistry {
             self.type_entry_mut(handle).size = Some(size);
         }
 
-        Ok(())
+        Ok(true)
     }
 
     fn calculate_alignment(&self, repr: &TypeRepr, target: &TargetInfo) -> Option<u64> {
@@ -377,12 +466,12 @@ impl TypeRegistry {
             TypeRepr::Never => None,
             TypeRepr::Void => None,
             TypeRepr::Boolean => Some(1),
-            TypeRepr::Integer { size, .. } => Some(size),
+            TypeRepr::Integer { size, .. } => Some(target.integer_alignment(size)),
             TypeRepr::PointerSizedInteger { .. } => panic!("unresolved pointer sized integer"),
-            TypeRepr::Float32 => Some(4),
-            TypeRepr::Float64 => Some(8),
-            TypeRepr::Pointer { .. } => Some(target.pointer_size()),
-            TypeRepr::Function { .. } => Some(target.pointer_size()),
+            TypeRepr::Float32 => Some(target.float32_alignment()),
+            TypeRepr::Float64 => Some(target.float64_alignment()),
+            TypeRepr::Pointer { .. } => Some(target.pointer_alignment()),
+            TypeRepr::Function { .. } => Some(target.pointer_alignment()),
             TypeRepr::Array { item_type, .. } => self.type_alignment(item_type),
             TypeRepr::Tuple { ref item_types } => item_types
                 .iter()
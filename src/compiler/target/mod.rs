@@ -0,0 +1,196 @@
+use clap::ValueEnum;
+
+/// How pointer types are written in LLVM IR output.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, ValueEnum)]
+pub enum LLVMPointerStyle {
+    /// All pointers are written as `ptr`, as required by LLVM 15 and later.
+    #[default]
+    Opaque,
+    /// Pointers are written with their pointee type (e.g. `i32*`), as required by LLVM 14 and
+    /// earlier.
+    Typed,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+#[derive(Clone, Debug)]
+pub struct TargetInfo {
+    /// The LLVM target triple, if compiling for a known target.
+    triple: Option<&'static str>,
+    /// The LLVM data layout string, if compiling for a known target.
+    data_layout: Option<&'static str>,
+    endianness: Endianness,
+    /// The size of a pointer in bytes.
+    pointer_size: u64,
+    /// The ABI alignment of a pointer in bytes.
+    pointer_alignment: u64,
+    /// Pairs of (integer size, ABI alignment) in bytes. Integer sizes not listed here are aligned
+    /// to their own size.
+    integer_alignments: &'static [(u64, u64)],
+    float32_alignment: u64,
+    float64_alignment: u64,
+    /// The style of pointer types to use in LLVM IR output.
+    llvm_pointer_style: LLVMPointerStyle,
+}
+
+/// Descriptions of all targets which can be selected with `--target`.
+const KNOWN_TARGETS: &[TargetInfo] = &[
+    TargetInfo {
+        triple: Some("x86_64-unknown-linux-gnu"),
+        data_layout: Some("e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-i128:128-f80:128-n8:16:32:64-S128"),
+        endianness: Endianness::Little,
+        pointer_size: 8,
+        pointer_alignment: 8,
+        integer_alignments: &[(16, 16)],
+        float32_alignment: 4,
+        float64_alignment: 8,
+        llvm_pointer_style: LLVMPointerStyle::Opaque,
+    },
+    TargetInfo {
+        triple: Some("i686-unknown-linux-gnu"),
+        data_layout: Some("e-m:e-p:32:32-p270:32:32-p271:32:32-p272:64:64-i128:128-f64:32:64-f80:32-n8:16:32-S128"),
+        endianness: Endianness::Little,
+        pointer_size: 4,
+        pointer_alignment: 4,
+        integer_alignments: &[(8, 4), (16, 16)],
+        float32_alignment: 4,
+        float64_alignment: 4,
+        llvm_pointer_style: LLVMPointerStyle::Opaque,
+    },
+    TargetInfo {
+        triple: Some("aarch64-unknown-linux-gnu"),
+        data_layout: Some("e-m:e-i8:8:32-i16:16:32-i64:64-i128:128-n32:64-S128"),
+        endianness: Endianness::Little,
+        pointer_size: 8,
+        pointer_alignment: 8,
+        integer_alignments: &[(16, 16)],
+        float32_alignment: 4,
+        float64_alignment: 8,
+        llvm_pointer_style: LLVMPointerStyle::Opaque,
+    },
+    TargetInfo {
+        triple: Some("riscv64-unknown-linux-gnu"),
+        data_layout: Some("e-m:e-p:64:64-i64:64-i128:128-n32:64-S128"),
+        endianness: Endianness::Little,
+        pointer_size: 8,
+        pointer_alignment: 8,
+        integer_alignments: &[(16, 16)],
+        float32_alignment: 4,
+        float64_alignment: 8,
+        llvm_pointer_style: LLVMPointerStyle::Opaque,
+    },
+    TargetInfo {
+        triple: Some("wasm32-unknown-unknown"),
+        data_layout: Some("e-m:e-p:32:32-p10:8:8-p20:8:8-i64:64-n32:64-S128-ni:1:10:20"),
+        endianness: Endianness::Little,
+        pointer_size: 4,
+        pointer_alignment: 4,
+        integer_alignments: &[(16, 16)],
+        float32_alignment: 4,
+        float64_alignment: 8,
+        llvm_pointer_style: LLVMPointerStyle::Opaque,
+    },
+];
+
+impl TargetInfo {
+    /// Create a description of an unspecified target with the given pointer size. No target
+    /// triple or data layout will be emitted, and types are aligned to their own size.
+    pub fn new(pointer_size: u64) -> Self {
+        Self {
+            triple: None,
+            data_layout: None,
+            endianness: Endianness::Little,
+            pointer_size,
+            pointer_alignment: pointer_size,
+            integer_alignments: &[],
+            float32_alignment: 4,
+            float64_alignment: 8,
+            llvm_pointer_style: LLVMPointerStyle::default(),
+        }
+    }
+
+    /// Look up the description of a known target by its triple.
+    pub fn from_triple(triple: &str) -> Option<Self> {
+        KNOWN_TARGETS.iter()
+            .find(|target| target.triple == Some(triple))
+            .cloned()
+    }
+
+    /// Get the description of the target the compiler itself is running on, falling back to an
+    /// unspecified target if the host is not a known target.
+    pub fn host() -> Self {
+        let host_triple = match std::env::consts::ARCH {
+            "x86_64" => "x86_64-unknown-linux-gnu",
+            "x86" => "i686-unknown-linux-gnu",
+            "aarch64" => "aarch64-unknown-linux-gnu",
+            "riscv64" => "riscv64-unknown-linux-gnu",
+            _ => "",
+        };
+
+        if std::env::consts::OS == "linux" {
+            if let Some(target) = Self::from_triple(host_triple) {
+                return target;
+            }
+        }
+
+        Self::new(size_of::<&()>() as u64)
+    }
+
+    pub fn known_triples() -> impl Iterator<Item = &'static str> {
+        KNOWN_TARGETS.iter().filter_map(|target| target.triple)
+    }
+
+    pub fn with_llvm_pointer_style(mut self, llvm_pointer_style: LLVMPointerStyle) -> Self {
+        self.llvm_pointer_style = llvm_pointer_style;
+        self
+    }
+
+    pub fn triple(&self) -> Option<&'static str> {
+        self.triple
+    }
+
+    /// Whether this target is 32-bit WebAssembly, which is compiled with the WebAssembly backend
+    /// by default.
+    pub fn is_wasm32(&self) -> bool {
+        self.triple.is_some_and(|triple| triple.starts_with("wasm32-"))
+    }
+
+    pub fn data_layout(&self) -> Option<&'static str> {
+        self.data_layout
+    }
+
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    pub fn pointer_size(&self) -> u64 {
+        self.pointer_size
+    }
+
+    pub fn pointer_alignment(&self) -> u64 {
+        self.pointer_alignment
+    }
+
+    /// Get the ABI alignment of an integer type with the given size in bytes.
+    pub fn integer_alignment(&self, size: u64) -> u64 {
+        self.integer_alignments.iter()
+            .find(|&&(integer_size, _)| integer_size == size)
+            .map_or(size, |&(_, alignment)| alignment)
+    }
+
+    pub fn float32_alignment(&self) -> u64 {
+        self.float32_alignment
+    }
+
+    pub fn float64_alignment(&self) -> u64 {
+        self.float64_alignment
+    }
+
+    pub fn llvm_pointer_style(&self) -> LLVMPointerStyle {
+        self.llvm_pointer_style
+    }
+}
@@ -101,10 +101,18 @@ pub enum Token {
     Break,
     Continue,
     Return,
+    Pub,
     Let,
     Const,
     Mut,
     Function,
+    Inline,
+    NoInline,
+    NoUnwind,
+    NoAlias,
+    NonNull,
+    ReadOnly,
+    CallConv,
     Struct,
     Implement,
     Module,
@@ -177,6 +185,7 @@ impl std::fmt::Display for Token {
             Self::Break => write!(f, "break"),
             Self::Continue => write!(f, "continue"),
             Self::Return => write!(f, "return"),
+            Self::Pub => write!(f, "pub"),
             Self::Let => write!(f, "let"),
             Self::Const => write!(f, "const"),
             Self::Mut => write!(f, "mut"),
@@ -186,6 +195,13 @@ impl std::fmt::Display for Token {
             Self::Module => write!(f, "module"),
             Self::Import => write!(f, "import"),
             Self::Foreign => write!(f, "foreign"),
+            Self::Inline => write!(f, "inline"),
+            Self::NoInline => write!(f, "noinline"),
+            Self::NoUnwind => write!(f, "nounwind"),
+            Self::NoAlias => write!(f, "noalias"),
+            Self::NonNull => write!(f, "nonnull"),
+            Self::ReadOnly => write!(f, "readonly"),
+            Self::CallConv => write!(f, "callconv"),
             Self::Super => write!(f, "super"),
             Self::SelfType => write!(f, "Self"),
             Self::Literal(literal) => write!(f, "{literal}"),
@@ -256,10 +272,18 @@ pub const KEYWORD_TOKENS: &[(&str, Token)] = &[
     ("break", Token::Break),
     ("continue", Token::Continue),
     ("return", Token::Return),
+    ("pub", Token::Pub),
     ("let", Token::Let),
     ("const", Token::Const),
     ("mut", Token::Mut),
     ("function", Token::Function),
+    ("inline", Token::Inline),
+    ("noinline", Token::NoInline),
+    ("nounwind", Token::NoUnwind),
+    ("noalias", Token::NoAlias),
+    ("nonnull", Token::NonNull),
+    ("readonly", Token::ReadOnly),
+    ("callconv", Token::CallConv),
     ("struct", Token::Struct),
     ("implement", Token::Implement),
     ("module", Token::Module),
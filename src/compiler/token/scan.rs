@@ -1,44 +1,39 @@
 use super::*;
 
-use std::io::{BufRead, BufReader};
-use std::fs::File;
-use std::path::Path;
-use utf8_chars::BufReadCharsExt;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
+/// Produces tokens from the text of a source file. Spans created by the scanner are measured in
+/// bytes from the start of the source text.
 #[derive(Debug)]
-pub struct Scanner<T: BufRead> {
+pub struct Scanner {
     source_id: usize,
     next_index: usize,
+    read_index: usize,
     line: usize,
-    source: T,
+    source: Rc<str>,
     put_backs: Vec<char>,
+    token_count: usize,
+    is_timed: bool,
+    scan_time: Duration,
 }
 
-impl Scanner<BufReader<File>> {
-    pub fn from_path(source_id: usize, path: impl AsRef<Path>) -> crate::Result<Self> {
-        File::open(path)
-            .map(|file| Self::new(source_id, BufReader::new(file)))
-            .map_err(|cause| Box::new(crate::Error::new(
-                Some(crate::Span {
-                    source_id,
-                    start_index: 0,
-                    length: 0,
-                }),
-                crate::ErrorKind::SourceFileOpen {
-                    cause,
-                },
-            )))
+impl Scanner {
+    pub fn from_source_map(source_map: &crate::package::SourceMap, source_id: usize) -> Self {
+        Self::new(source_id, source_map.file(source_id).text().clone())
     }
-}
 
-impl<T: BufRead> Scanner<T> {
-    pub fn new(source_id: usize, source: T) -> Self {
+    pub fn new(source_id: usize, source: Rc<str>) -> Self {
         Self {
             source_id,
             next_index: 0,
+            read_index: 0,
             line: 1,
             source,
             put_backs: Vec::new(),
+            token_count: 0,
+            is_timed: false,
+            scan_time: Duration::ZERO,
         }
     }
 
@@ -54,6 +49,22 @@ impl<T: BufRead> Scanner<T> {
         self.next_index
     }
 
+    /// The number of tokens produced by this scanner so far.
+    pub fn token_count(&self) -> usize {
+        self.token_count
+    }
+
+    /// Measure the time spent producing each token, which costs a clock read per token.
+    pub fn set_timed(&mut self, is_timed: bool) {
+        self.is_timed = is_timed;
+    }
+
+    /// The total time spent inside [`Self::next_token`] so far, or zero if the scanner isn't
+    /// [timed](Self::set_timed).
+    pub fn scan_time(&self) -> Duration {
+        self.scan_time
+    }
+
     pub fn create_span(&self, start_index: usize, end_index: usize) -> crate::Span {
         crate::Span {
             source_id: self.source_id,
@@ -63,6 +74,18 @@ impl<T: BufRead> Scanner<T> {
     }
 
     pub fn next_token(&mut self) -> crate::Result<Option<(crate::Span, Token)>> {
+        let start_time = self.is_timed.then(Instant::now);
+        let result = self.scan_next_token();
+        if let Some(start_time) = start_time {
+            self.scan_time += start_time.elapsed();
+        }
+        if let Ok(Some(..)) = result {
+            self.token_count += 1;
+        }
+        result
+    }
+
+    fn scan_next_token(&mut self) -> crate::Result<Option<(crate::Span, Token)>> {
         if let Some(ch) = self.next_non_space_char()? {
             if ch.is_ascii_digit() {
                 self.put_back(ch);
@@ -77,11 +100,11 @@ impl<T: BufRead> Scanner<T> {
                     match self.next_char()? {
                         Some('/') => {
                             self.skip_line_comment()?;
-                            return self.next_token();
+                            return self.scan_next_token();
                         }
                         Some('*') => {
                             self.skip_block_comment()?;
-                            return self.next_token();
+                            return self.scan_next_token();
                         }
                         Some(next_ch) => {
                             self.put_back(next_ch);
@@ -106,38 +129,25 @@ impl<T: BufRead> Scanner<T> {
 
     fn next_char(&mut self) -> crate::Result<Option<char>> {
         if let Some(ch) = self.put_backs.pop() {
-            self.next_index += 1;
+            self.next_index += ch.len_utf8();
             Ok(Some(ch))
         }
-        else {
-            let read = self.source.read_char()
-                .map_err(|cause| Box::new(crate::Error::new(
-                    Some(crate::Span {
-                        source_id: self.source_id,
-                        start_index: self.next_index,
-                        length: 0,
-                    }),
-                    crate::ErrorKind::SourceFileRead {
-                        cause,
-                    },
-                )))?;
-
-            if let Some(ch) = read {
-                if ch == '\n' {
-                    self.line += 1;
-                }
-                self.next_index += 1;
-                Ok(Some(ch))
-            }
-            else {
-                Ok(None)
+        else if let Some(ch) = self.source[self.read_index..].chars().next() {
+            if ch == '\n' {
+                self.line += 1;
             }
+            self.read_index += ch.len_utf8();
+            self.next_index += ch.len_utf8();
+            Ok(Some(ch))
+        }
+        else {
+            Ok(None)
         }
     }
 
     fn put_back(&mut self, ch: char) {
         self.put_backs.push(ch);
-        self.next_index -= 1;
+        self.next_index -= ch.len_utf8();
     }
 
     fn next_non_space_char(&mut self) -> crate::Result<Option<char>> {
@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+use super::*;
+use crate::ir::value::{Constant, FloatType, GlobalRegister};
+use crate::sema::ConversionOperation;
+
+/// The alignment of the static data as a power of two, which is enough for any type.
+const DATA_ALIGNMENT_LOG2: u32 = 4;
+
+/// Where an address points, relative to the bases this module was instantiated with.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Address {
+    /// An offset into this module's static data.
+    Memory(u64),
+    /// An index into this module's entries in the function table.
+    Table(usize),
+    /// The address held by an imported global.
+    Imported(Box<[u8]>),
+}
+
+/// A location in static data which must be filled with an address once the module is
+/// instantiated, since addresses are not known until then.
+struct Relocation {
+    offset: u64,
+    size: u64,
+    address: Address,
+    addend: i64,
+}
+
+/// The static data of a module, made up of global variables followed by any aggregate
+/// constants used by functions.
+pub struct StaticData {
+    bytes: Vec<u8>,
+    relocations: Vec<Relocation>,
+    global_offsets: HashMap<Box<[u8]>, u64>,
+    constants: Vec<(Constant, u64)>,
+    /// Functions which are referred to by value, in the order of their table entries.
+    table: Vec<Box<[u8]>>,
+}
+
+impl StaticData {
+    pub fn new(context: &GlobalContext, unit: &CompilationUnit) -> Self {
+        let mut static_data = Self {
+            bytes: Vec::new(),
+            relocations: Vec::new(),
+            global_offsets: HashMap::new(),
+            constants: Vec::new(),
+            table: Vec::new(),
+        };
+
+        // Lay out every variable before writing any, since they may refer to each other
+        let mut offsets = Vec::new();
+        for variable in unit.global_variables() {
+            let offset = static_data.allocate(context, variable.value().get_type());
+            static_data.global_offsets.insert(variable.register().identifier().into(), offset);
+            offsets.push(offset);
+        }
+        for (variable, offset) in unit.global_variables().iter().zip(offsets) {
+            static_data.write_constant(context, offset, variable.value());
+        }
+
+        static_data
+    }
+
+    fn allocate(&mut self, context: &GlobalContext, value_type: TypeHandle) -> u64 {
+        let offset = (self.bytes.len() as u64).next_multiple_of(type_alignment(context, value_type));
+        self.bytes.resize((offset + type_size(context, value_type)) as usize, 0);
+        offset
+    }
+
+    pub fn table_index(&mut self, identifier: &[u8]) -> usize {
+        match self.table.iter().position(|entry| entry.as_ref() == identifier) {
+            Some(index) => index,
+            None => {
+                self.table.push(identifier.into());
+                self.table.len() - 1
+            }
+        }
+    }
+
+    pub fn global_address(&mut self, context: &GlobalContext, register: &GlobalRegister) -> Address {
+        if let TypeRepr::Function { .. } = register.get_type().repr(context) {
+            Address::Table(self.table_index(register.identifier()))
+        }
+        else if let Some(&offset) = self.global_offsets.get(register.identifier()) {
+            Address::Memory(offset)
+        }
+        else {
+            Address::Imported(register.identifier().into())
+        }
+    }
+
+    /// Get the offset in static data of a copy of `constant`, adding one if needed.
+    pub fn constant_offset(&mut self, context: &GlobalContext, constant: &Constant) -> u64 {
+        if let Some(&(_, offset)) = self.constants.iter().find(|(existing, _)| existing == constant) {
+            return offset;
+        }
+
+        let offset = self.allocate(context, constant.get_type());
+        self.write_constant(context, offset, constant);
+        self.constants.push((constant.clone(), offset));
+        offset
+    }
+
+    /// Evaluate a constant pointer as an address plus a byte offset, if it is not null.
+    fn constant_address(&mut self, context: &GlobalContext, constant: &Constant) -> Option<(Address, i64)> {
+        match constant {
+            Constant::Register(register) => {
+                Some((self.global_address(context, register), 0))
+            }
+            Constant::Convert {
+                operation: ConversionOperation::BitwiseCast | ConversionOperation::PointerToInteger | ConversionOperation::IntegerToPointer,
+                value,
+                ..
+            } => {
+                self.constant_address(context, value)
+            }
+            Constant::GetElementPointer { aggregate_type, pointer, indices, .. } => {
+                let (address, addend) = self.constant_address(context, pointer)?;
+                let mut element_offset = 0;
+                let mut current_type = match *aggregate_type.repr(context) {
+                    TypeRepr::Array { item_type, length: None } => item_type,
+                    _ => *aggregate_type,
+                };
+                for (position, index) in indices.iter().enumerate() {
+                    let Constant::Integer(index) = index else {
+                        panic!("constant element index must be an integer");
+                    };
+                    let index = index.raw() as i64;
+                    if position == 0 {
+                        element_offset += index * type_size(context, current_type) as i64;
+                        continue;
+                    }
+                    match *current_type.repr(context) {
+                        TypeRepr::Array { item_type, .. } => {
+                            element_offset += index * type_size(context, item_type) as i64;
+                            current_type = item_type;
+                        }
+                        TypeRepr::Tuple { ref item_types } => {
+                            element_offset += context.type_registry().member_offsets(current_type).unwrap()[index as usize] as i64;
+                            current_type = item_types[index as usize];
+                        }
+                        TypeRepr::Structure { ref members, .. } => {
+                            element_offset += context.type_registry().member_offsets(current_type).unwrap()[index as usize] as i64;
+                            current_type = members[index as usize].member_type;
+                        }
+                        _ => panic!("cannot index into type '{}'", current_type.path(context))
+                    }
+                }
+                Some((address, addend + element_offset))
+            }
+            _ => None
+        }
+    }
+
+    fn write_bytes(&mut self, offset: u64, bytes: &[u8]) {
+        let offset = offset as usize;
+        self.bytes[offset .. offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    fn write_constant(&mut self, context: &GlobalContext, offset: u64, constant: &Constant) {
+        match constant {
+            Constant::Undefined(..) | Constant::Poison(..) | Constant::ZeroInitializer(..) | Constant::NullPointer(..) => {
+                // Memory is already zeroed
+            }
+            &Constant::Boolean(value) => {
+                self.write_bytes(offset, &[value as u8]);
+            }
+            Constant::Integer(value) => {
+                let size = value.integer_type().size(context.target()) as usize;
+                self.write_bytes(offset, &value.raw().to_le_bytes()[..size]);
+            }
+            Constant::Float(value) => match value.float_type() {
+                FloatType::F32 => self.write_bytes(offset, &(value.raw() as f32).to_le_bytes()),
+                FloatType::F64 => self.write_bytes(offset, &value.raw().to_le_bytes()),
+            }
+            Constant::String { array_type, value } => {
+                let length = (type_size(context, *array_type) as usize).min(value.len());
+                self.write_bytes(offset, &value.bytes()[..length]);
+            }
+            Constant::Array { array_type, items } => {
+                let &TypeRepr::Array { item_type, .. } = array_type.repr(context) else {
+                    panic!("array constant with non-array type");
+                };
+                let item_size = type_size(context, item_type);
+                for (index, item) in items.iter().enumerate() {
+                    self.write_constant(context, offset + index as u64 * item_size, item);
+                }
+            }
+            Constant::Tuple { tuple_type: aggregate_type, items: members } | Constant::Structure { struct_type: aggregate_type, members } => {
+                let member_offsets = context.type_registry().member_offsets(*aggregate_type).expect("invalid aggregate type");
+                for (member, member_offset) in members.iter().zip(member_offsets) {
+                    self.write_constant(context, offset + member_offset, member);
+                }
+            }
+            Constant::Register(..) | Constant::Convert { .. } | Constant::GetElementPointer { .. } => {
+                if let Some((address, addend)) = self.constant_address(context, constant) {
+                    self.relocations.push(Relocation {
+                        offset,
+                        size: type_size(context, constant.get_type()),
+                        address,
+                        addend,
+                    });
+                }
+                else if let Constant::Convert { value, result_type, .. } = constant {
+                    // Conversions between integers and pointers to or from null keep the raw value
+                    if let Constant::Integer(value) = value.as_ref() {
+                        let size = type_size(context, *result_type) as usize;
+                        self.write_bytes(offset, &value.raw().to_le_bytes()[..size]);
+                    }
+                }
+            }
+            Constant::Indirect { .. } | Constant::Type(..) | Constant::Module(..) => {
+                panic!("invalid constant in static data");
+            }
+        }
+    }
+
+    /// Write the `dylink.0` custom section giving the amount of memory and table entries to reserve
+    /// for this module, so a loader can place it before instantiating it. Alignments are given as
+    /// powers of two.
+    pub fn fmt_memory_info(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let data_size = (self.bytes.len() as u64).next_multiple_of(1 << DATA_ALIGNMENT_LOG2);
+        writeln!(
+            f,
+            "  (@dylink.0 (mem-info (memory {data_size} {DATA_ALIGNMENT_LOG2}) (table {} 0)))",
+            self.table.len(),
+        )
+    }
+
+    /// Write the data segment, table entries, and the start function which fills in addresses.
+    pub fn fmt_declarations(&self, f: &mut std::fmt::Formatter<'_>, unit: &CompilationUnit) -> std::fmt::Result {
+        // Variables are exported as globals holding their address
+        for variable in unit.global_variables() {
+            if variable.kind() != crate::ir::GlobalVariableKind::AnonymousConstant {
+                let identifier = variable.register().identifier();
+                writeln!(
+                    f,
+                    "  (global ${} (export {}) (mut i32) (i32.const 0))",
+                    IdentifierDisplay(identifier),
+                    StringDisplay(identifier),
+                )?;
+            }
+        }
+
+        if !self.bytes.is_empty() {
+            writeln!(f, "  (data (global.get $__memory_base) {})", StringDisplay(&self.bytes))?;
+        }
+        if !self.table.is_empty() {
+            write!(f, "  (elem (global.get $__table_base) func")?;
+            for identifier in &self.table {
+                write!(f, " ${}", IdentifierDisplay(identifier))?;
+            }
+            writeln!(f, ")")?;
+        }
+
+        writeln!(f, "  (func $%init")?;
+        for relocation in &self.relocations {
+            writeln!(f, "    global.get $__memory_base")?;
+            if relocation.offset != 0 {
+                writeln!(f, "    i32.const {}", relocation.offset)?;
+                writeln!(f, "    i32.add")?;
+            }
+            fmt_address(f, &relocation.address, relocation.addend)?;
+            match relocation.size {
+                8 => {
+                    writeln!(f, "    i64.extend_i32_u")?;
+                    writeln!(f, "    i64.store")?;
+                }
+                _ => writeln!(f, "    i32.store")?,
+            }
+        }
+        for variable in unit.global_variables() {
+            if variable.kind() != crate::ir::GlobalVariableKind::AnonymousConstant {
+                let identifier = variable.register().identifier();
+                fmt_address(f, &Address::Memory(self.global_offsets[identifier]), 0)?;
+                writeln!(f, "    global.set ${}", IdentifierDisplay(identifier))?;
+            }
+        }
+        writeln!(f, "  )")?;
+        writeln!(f, "  (start $%init)")
+    }
+}
+
+/// Write instructions which push `address` plus `addend` onto the stack.
+pub fn fmt_address(out: &mut impl Write, address: &Address, addend: i64) -> std::fmt::Result {
+    match address {
+        Address::Memory(offset) => {
+            writeln!(out, "    global.get $__memory_base")?;
+            let offset = *offset as i64 + addend;
+            if offset != 0 {
+                writeln!(out, "    i32.const {}", offset as i32)?;
+                writeln!(out, "    i32.add")?;
+            }
+            return Ok(());
+        }
+        Address::Table(index) => {
+            writeln!(out, "    global.get $__table_base")?;
+            if *index != 0 {
+                writeln!(out, "    i32.const {index}")?;
+                writeln!(out, "    i32.add")?;
+            }
+        }
+        Address::Imported(identifier) => {
+            writeln!(out, "    global.get ${}", IdentifierDisplay(identifier))?;
+        }
+    }
+
+    if addend != 0 {
+        writeln!(out, "    i32.const {}", addend as i32)?;
+        writeln!(out, "    i32.add")?;
+    }
+    Ok(())
+}
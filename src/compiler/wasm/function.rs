@@ -0,0 +1,970 @@
+use std::collections::HashMap;
+use super::*;
+use super::data::{Address, StaticData};
+use crate::ir::FunctionDefinition;
+use crate::ir::instr::{BasicBlock, Instruction, TerminatorInstruction};
+use crate::ir::value::{BlockLabel, Constant, FloatType, LocalRegister, Value};
+use crate::sema::ConversionOperation;
+
+/// Write one instruction per line in a function body.
+macro_rules! emit {
+    ($writer:expr, $($arg:tt)*) => {
+        writeln!($writer.out, "    {}", format_args!($($arg)*))
+    };
+}
+
+/// Storage reserved in the shadow stack frame of a function, as offsets from the frame pointer.
+struct FrameLayout {
+    size: u64,
+    /// Storage for stack allocations and for aggregate registers, by register identifier.
+    slots: HashMap<Box<[u8]>, u64>,
+    /// Storage holding the inputs of aggregate phi instructions while jumping between blocks.
+    phi_input_slots: HashMap<Box<[u8]>, u64>,
+    /// Start of the buffer used to pass variadic arguments.
+    varargs_offset: u64,
+}
+
+impl FrameLayout {
+    fn new(context: &GlobalContext, function: &FunctionDefinition) -> Self {
+        let mut layout = Self {
+            size: 0,
+            slots: HashMap::new(),
+            phi_input_slots: HashMap::new(),
+            varargs_offset: 0,
+        };
+        let mut varargs_size = 0;
+
+        for block in function.blocks() {
+            for phi in block.phis() {
+                if is_aggregate(context, phi.result.get_type()) {
+                    let offset = layout.allocate(context, phi.result.get_type());
+                    layout.slots.insert(phi.result.identifier().into(), offset);
+                    let offset = layout.allocate(context, phi.result.get_type());
+                    layout.phi_input_slots.insert(phi.result.identifier().into(), offset);
+                }
+            }
+            for instruction in block.body() {
+                match instruction {
+                    Instruction::StackAllocate { result } => {
+                        let &TypeRepr::Pointer { pointee_type, .. } = result.get_type().repr(context) else {
+                            panic!("stack allocation result is not a pointer");
+                        };
+                        let offset = layout.allocate(context, pointee_type);
+                        layout.slots.insert(result.identifier().into(), offset);
+                    }
+                    Instruction::Load { result, .. } | Instruction::ExtractValue { result, .. } | Instruction::InsertValue { result, .. } | Instruction::Call { result: Some(result), .. }
+                        if is_aggregate(context, result.get_type()) =>
+                    {
+                        let offset = layout.allocate(context, result.get_type());
+                        layout.slots.insert(result.identifier().into(), offset);
+                    }
+                    _ => {}
+                }
+                if let Instruction::Call { callee, arguments, .. } = instruction {
+                    varargs_size = varargs_size.max(varargs_layout(context, callee, arguments).1);
+                }
+            }
+        }
+
+        layout.varargs_offset = layout.size.next_multiple_of(8);
+        // The stack pointer is kept aligned to 16 bytes
+        layout.size = (layout.varargs_offset + varargs_size).next_multiple_of(16);
+        layout
+    }
+
+    fn allocate(&mut self, context: &GlobalContext, value_type: TypeHandle) -> u64 {
+        let offset = self.size.next_multiple_of(type_alignment(context, value_type));
+        self.size = offset + type_size(context, value_type);
+        offset
+    }
+}
+
+/// Get the offset of each variadic argument of a call within the buffer used to pass them, along
+/// with the size of the buffer.
+fn varargs_layout(context: &GlobalContext, callee: &Value, arguments: &[Value]) -> (Vec<u64>, u64) {
+    let TypeRepr::Function { signature } = callee.get_type().repr(context) else {
+        panic!("callee is not a function");
+    };
+    if !signature.is_variadic() {
+        return (Vec::new(), 0);
+    }
+
+    let mut offsets = Vec::new();
+    let mut size: u64 = 0;
+    for argument in &arguments[signature.parameter_types().len()..] {
+        let offset = size.next_multiple_of(type_alignment(context, argument.get_type()));
+        offsets.push(offset);
+        size = offset + type_size(context, argument.get_type());
+    }
+    (offsets, size)
+}
+
+/// Get the integer size and signedness used to keep narrow integers in canonical form, where
+/// the upper bits of a WebAssembly `i32` are a sign or zero extension of the value.
+fn narrow_integer(context: &GlobalContext, handle: TypeHandle) -> Option<(u64, bool)> {
+    match *handle.repr(context) {
+        TypeRepr::Integer { size: size @ (1 | 2), signed } => Some((size, signed)),
+        _ => None,
+    }
+}
+
+fn is_signed_integer(context: &GlobalContext, handle: TypeHandle) -> bool {
+    matches!(handle.repr(context), TypeRepr::Integer { signed: true, .. })
+}
+
+fn is_float(context: &GlobalContext, handle: TypeHandle) -> bool {
+    matches!(handle.repr(context), TypeRepr::Float32 | TypeRepr::Float64)
+}
+
+fn load_instruction(context: &GlobalContext, handle: TypeHandle) -> &'static str {
+    match *handle.repr(context) {
+        TypeRepr::Boolean => "i32.load8_u",
+        TypeRepr::Integer { size: 1, signed: true } => "i32.load8_s",
+        TypeRepr::Integer { size: 1, signed: false } => "i32.load8_u",
+        TypeRepr::Integer { size: 2, signed: true } => "i32.load16_s",
+        TypeRepr::Integer { size: 2, signed: false } => "i32.load16_u",
+        TypeRepr::Integer { size: 8, .. } => "i64.load",
+        TypeRepr::Float32 => "f32.load",
+        TypeRepr::Float64 => "f64.load",
+        _ => "i32.load",
+    }
+}
+
+fn store_instruction(context: &GlobalContext, handle: TypeHandle) -> &'static str {
+    match *handle.repr(context) {
+        TypeRepr::Boolean | TypeRepr::Integer { size: 1, .. } => "i32.store8",
+        TypeRepr::Integer { size: 2, .. } => "i32.store16",
+        TypeRepr::Integer { size: 8, .. } => "i64.store",
+        TypeRepr::Float32 => "f32.store",
+        TypeRepr::Float64 => "f64.store",
+        _ => "i32.store",
+    }
+}
+
+/// Writes a floating-point constant in WebAssembly text format.
+struct FloatDisplay(f64, FloatType);
+
+impl std::fmt::Display for FloatDisplay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = self.0;
+        if value.is_nan() {
+            write!(f, "nan")
+        }
+        else if value.is_infinite() {
+            write!(f, "{}inf", if value < 0.0 { "-" } else { "" })
+        }
+        else {
+            match self.1 {
+                FloatType::F32 => write!(f, "{:?}", value as f32),
+                FloatType::F64 => write!(f, "{value:?}"),
+            }
+        }
+    }
+}
+
+struct FunctionWriter<'a> {
+    out: &'a mut String,
+    context: &'a GlobalContext,
+    static_data: &'a mut StaticData,
+    function: &'a FunctionDefinition,
+    frame: FrameLayout,
+}
+
+pub fn fmt_function_definition(
+    out: &mut String,
+    context: &GlobalContext,
+    static_data: &mut StaticData,
+    function: &FunctionDefinition,
+) -> std::fmt::Result {
+    let mut writer = FunctionWriter {
+        out,
+        context,
+        static_data,
+        function,
+        frame: FrameLayout::new(context, function),
+    };
+    writer.fmt_definition()
+}
+
+impl FunctionWriter<'_> {
+    fn fmt_definition(&mut self) -> std::fmt::Result {
+        let context = self.context;
+        let function = self.function;
+        let signature = function_signature(context, function.register());
+        let identifier = function.register().identifier();
+
+        write!(self.out, "  (func ${} (export {})", IdentifierDisplay(identifier), StringDisplay(identifier))?;
+        if is_aggregate(context, signature.return_type()) {
+            write!(self.out, " (param $%sret i32)")?;
+        }
+        for parameter in function.parameter_registers() {
+            if let Some(parameter_type) = value_type(context, parameter.get_type()) {
+                write!(self.out, " (param $r.{} {parameter_type})", IdentifierDisplay(parameter.identifier()))?;
+            }
+        }
+        if signature.is_variadic() {
+            write!(self.out, " (param $%varargs i32)")?;
+        }
+        if let Some(return_type) = value_type(context, signature.return_type()).filter(|_| !is_aggregate(context, signature.return_type())) {
+            write!(self.out, " (result {return_type})")?;
+        }
+        writeln!(self.out)?;
+
+        // Every register is a local, since blocks can be jumped between freely
+        emit!(self, "(local $%frame i32)")?;
+        emit!(self, "(local $%block i32)")?;
+        for block in function.blocks() {
+            for phi in block.phis() {
+                if let Some(phi_type) = value_type(context, phi.result.get_type()) {
+                    emit!(self, "(local $r.{} {phi_type})", IdentifierDisplay(phi.result.identifier()))?;
+                    if !is_aggregate(context, phi.result.get_type()) {
+                        emit!(self, "(local $p.{} {phi_type})", IdentifierDisplay(phi.result.identifier()))?;
+                    }
+                }
+            }
+            for instruction in block.body() {
+                if let Some(result) = instruction.result() {
+                    if let Some(result_type) = value_type(context, result.get_type()) {
+                        emit!(self, "(local $r.{} {result_type})", IdentifierDisplay(result.identifier()))?;
+                    }
+                }
+            }
+        }
+
+        // Prologue: reserve the stack frame and point aggregate registers at their storage
+        if self.frame.size > 0 {
+            emit!(self, "global.get $__stack_pointer")?;
+            emit!(self, "i32.const {}", self.frame.size)?;
+            emit!(self, "i32.sub")?;
+            emit!(self, "local.tee $%frame")?;
+            emit!(self, "global.set $__stack_pointer")?;
+        }
+        for block in function.blocks() {
+            let phi_results = block.phis().iter().map(|phi| &phi.result);
+            let instruction_results = block.body().iter()
+                .filter(|instruction| !matches!(instruction, Instruction::StackAllocate { .. }))
+                .filter_map(Instruction::result);
+            for result in phi_results.chain(instruction_results) {
+                if let Some(&offset) = self.frame.slots.get(result.identifier()) {
+                    self.push_frame_address(offset)?;
+                    emit!(self, "local.set $r.{}", IdentifierDisplay(result.identifier()))?;
+                }
+            }
+        }
+
+        let blocks = function.blocks();
+        let needs_dispatch = blocks.len() > 1 || blocks.iter().any(|block| {
+            matches!(block.terminator(), TerminatorInstruction::Branch { .. } | TerminatorInstruction::ConditionalBranch { .. })
+        });
+
+        if needs_dispatch {
+            // Blocks are selected by index in a loop, which allows arbitrary control flow
+            emit!(self, "loop $%dispatch")?;
+            for block in blocks.iter().rev() {
+                emit!(self, "block $b.{}", IdentifierDisplay(block.label().identifier()))?;
+            }
+            emit!(self, "local.get $%block")?;
+            write!(self.out, "    br_table")?;
+            for block in blocks {
+                write!(self.out, " $b.{}", IdentifierDisplay(block.label().identifier()))?;
+            }
+            writeln!(self.out)?;
+            for block in blocks {
+                emit!(self, "end")?;
+                self.fmt_block(block)?;
+            }
+            emit!(self, "end")?;
+        }
+        else {
+            for block in blocks {
+                self.fmt_block(block)?;
+            }
+        }
+        emit!(self, "unreachable")?;
+
+        writeln!(self.out, "  )")
+    }
+
+    fn fmt_block(&mut self, block: &BasicBlock) -> std::fmt::Result {
+        let context = self.context;
+        emit!(self, ";; {}", IdentifierDisplay(block.label().identifier()))?;
+
+        for phi in block.phis() {
+            let result = IdentifierDisplay(phi.result.identifier());
+            if is_aggregate(context, phi.result.get_type()) {
+                emit!(self, "local.get $r.{result}")?;
+                self.push_frame_address(self.frame.phi_input_slots[phi.result.identifier()])?;
+                emit!(self, "i32.const {}", type_size(context, phi.result.get_type()))?;
+                emit!(self, "memory.copy")?;
+            }
+            else if value_type(context, phi.result.get_type()).is_some() {
+                emit!(self, "local.get $p.{result}")?;
+                emit!(self, "local.set $r.{result}")?;
+            }
+        }
+
+        for instruction in block.body() {
+            self.fmt_instruction(instruction)?;
+        }
+
+        match block.terminator() {
+            TerminatorInstruction::Return { value } => {
+                self.fmt_return(value)
+            }
+            TerminatorInstruction::Branch { to_label } => {
+                self.fmt_jump(block.label(), to_label)
+            }
+            TerminatorInstruction::ConditionalBranch { condition, consequent_label, alternative_label } => {
+                self.push_value(condition)?;
+                emit!(self, "if")?;
+                self.fmt_jump(block.label(), consequent_label)?;
+                emit!(self, "else")?;
+                self.fmt_jump(block.label(), alternative_label)?;
+                emit!(self, "end")
+            }
+            TerminatorInstruction::Unreachable => {
+                emit!(self, "unreachable")
+            }
+        }
+    }
+
+    fn fmt_return(&mut self, value: &Value) -> std::fmt::Result {
+        let context = self.context;
+        let value_type = value.get_type();
+        if is_aggregate(context, value_type) {
+            emit!(self, "local.get $%sret")?;
+            self.push_value(value)?;
+            emit!(self, "i32.const {}", type_size(context, value_type))?;
+            emit!(self, "memory.copy")?;
+        }
+        else if super::value_type(context, value_type).is_some() {
+            self.push_value(value)?;
+        }
+
+        if self.frame.size > 0 {
+            emit!(self, "local.get $%frame")?;
+            emit!(self, "i32.const {}", self.frame.size)?;
+            emit!(self, "i32.add")?;
+            emit!(self, "global.set $__stack_pointer")?;
+        }
+        emit!(self, "return")
+    }
+
+    /// Jump from block `from_label` to block `to_label`, first passing the inputs for any phi
+    /// instructions in the destination block.
+    fn fmt_jump(&mut self, from_label: &BlockLabel, to_label: &BlockLabel) -> std::fmt::Result {
+        let context = self.context;
+        let blocks = self.function.blocks();
+        let to_index = blocks.iter()
+            .position(|block| block.label() == to_label)
+            .expect("branch to nonexistent block");
+
+        for phi in blocks[to_index].phis() {
+            let (value, _) = phi.inputs.iter()
+                .find(|(_, label)| label == from_label)
+                .expect("phi has no input for predecessor block");
+            if matches!(value, Value::Void | Value::Never | Value::Break | Value::Continue) {
+                continue;
+            }
+
+            if is_aggregate(context, phi.result.get_type()) {
+                self.push_frame_address(self.frame.phi_input_slots[phi.result.identifier()])?;
+                self.push_value(value)?;
+                emit!(self, "i32.const {}", type_size(context, phi.result.get_type()))?;
+                emit!(self, "memory.copy")?;
+            }
+            else {
+                self.push_value(value)?;
+                emit!(self, "local.set $p.{}", IdentifierDisplay(phi.result.identifier()))?;
+            }
+        }
+
+        emit!(self, "i32.const {to_index}")?;
+        emit!(self, "local.set $%block")?;
+        emit!(self, "br $%dispatch")
+    }
+
+    fn push_frame_address(&mut self, offset: u64) -> std::fmt::Result {
+        emit!(self, "local.get $%frame")?;
+        if offset != 0 {
+            emit!(self, "i32.const {offset}")?;
+            emit!(self, "i32.add")?;
+        }
+        Ok(())
+    }
+
+    fn set_result(&mut self, result: &LocalRegister) -> std::fmt::Result {
+        emit!(self, "local.set $r.{}", IdentifierDisplay(result.identifier()))
+    }
+
+    fn push_value(&mut self, value: &Value) -> std::fmt::Result {
+        match value {
+            Value::Constant(constant) => {
+                self.push_constant(constant)
+            }
+            Value::Register(register) => {
+                emit!(self, "local.get $r.{}", IdentifierDisplay(register.identifier()))
+            }
+            Value::BoundFunction { function_value, .. } => {
+                self.push_value(function_value)
+            }
+            Value::Never | Value::Break | Value::Continue | Value::Void => {
+                Ok(())
+            }
+            Value::Indirect { .. } => {
+                panic!("indirect value in function body")
+            }
+        }
+    }
+
+    fn push_constant(&mut self, constant: &Constant) -> std::fmt::Result {
+        let context = self.context;
+        match constant {
+            Constant::Undefined(constant_type) | Constant::Poison(constant_type) | Constant::ZeroInitializer(constant_type) | Constant::NullPointer(constant_type) => {
+                if is_aggregate(context, *constant_type) {
+                    let offset = self.static_data.constant_offset(context, constant);
+                    data::fmt_address(self.out, &Address::Memory(offset), 0)
+                }
+                else if let Some(value_type) = value_type(context, *constant_type) {
+                    emit!(self, "{value_type}.const 0")
+                }
+                else {
+                    Ok(())
+                }
+            }
+            &Constant::Boolean(value) => {
+                emit!(self, "i32.const {}", value as i32)
+            }
+            Constant::Integer(value) => {
+                let value_type = super::value_type(context, value.integer_type().as_handle()).unwrap();
+                emit!(self, "{value_type}.const {}", value.raw())
+            }
+            Constant::Float(value) => {
+                let value_type = super::value_type(context, value.float_type().as_handle()).unwrap();
+                emit!(self, "{value_type}.const {}", FloatDisplay(value.raw(), value.float_type()))
+            }
+            Constant::String { .. } | Constant::Array { .. } | Constant::Tuple { .. } | Constant::Structure { .. } => {
+                let offset = self.static_data.constant_offset(context, constant);
+                data::fmt_address(self.out, &Address::Memory(offset), 0)
+            }
+            Constant::Register(register) => {
+                let address = self.static_data.global_address(context, register);
+                data::fmt_address(self.out, &address, 0)
+            }
+            Constant::Convert { operation, result_type, value } => {
+                self.push_constant(value)?;
+                self.fmt_conversion(*operation, value.get_type(), *result_type)
+            }
+            Constant::GetElementPointer { aggregate_type, pointer, indices, .. } => {
+                let indices: Vec<Value> = indices.iter().cloned().map(Value::Constant).collect();
+                self.push_constant(pointer)?;
+                self.fmt_element_offsets(*aggregate_type, &indices)
+            }
+            Constant::Indirect { .. } | Constant::Type(..) | Constant::Module(..) => {
+                panic!("invalid constant in function body")
+            }
+        }
+    }
+
+    /// Bring the integer on top of the stack into canonical form for `value_type`.
+    fn fmt_normalize(&mut self, value_type: TypeHandle) -> std::fmt::Result {
+        match narrow_integer(self.context, value_type) {
+            Some((size, true)) => emit!(self, "i32.extend{}_s", size * 8),
+            Some((size, false)) => {
+                emit!(self, "i32.const {}", (1u32 << (size * 8)) - 1)?;
+                emit!(self, "i32.and")
+            }
+            None => Ok(())
+        }
+    }
+
+    /// Reinterpret the narrow integer on top of the stack as signed or unsigned, regardless of
+    /// the signedness of `value_type`.
+    fn fmt_extend_narrow(&mut self, value_type: TypeHandle, signed: bool) -> std::fmt::Result {
+        match narrow_integer(self.context, value_type) {
+            Some((size, _)) if signed => emit!(self, "i32.extend{}_s", size * 8),
+            Some((size, _)) => {
+                emit!(self, "i32.const {}", (1u32 << (size * 8)) - 1)?;
+                emit!(self, "i32.and")
+            }
+            None => Ok(())
+        }
+    }
+
+    fn fmt_conversion(&mut self, operation: ConversionOperation, from_type: TypeHandle, to_type: TypeHandle) -> std::fmt::Result {
+        let context = self.context;
+        let from = value_type(context, from_type).unwrap_or("i32");
+        let to = value_type(context, to_type).unwrap_or("i32");
+
+        match operation {
+            ConversionOperation::Truncate => {
+                if from == "i64" && to == "i32" {
+                    emit!(self, "i32.wrap_i64")?;
+                }
+                self.fmt_normalize(to_type)
+            }
+            ConversionOperation::ZeroExtend | ConversionOperation::SignExtend => {
+                let signed = operation == ConversionOperation::SignExtend;
+                if from_type == TypeHandle::BOOL && signed {
+                    // Sign extending a single bit turns true into all ones
+                    emit!(self, "i32.const -1")?;
+                    emit!(self, "i32.mul")?;
+                }
+                self.fmt_extend_narrow(from_type, signed)?;
+                if from == "i32" && to == "i64" {
+                    emit!(self, "i64.extend_i32_{}", if signed { "s" } else { "u" })?;
+                }
+                self.fmt_normalize(to_type)
+            }
+            ConversionOperation::FloatTruncate => {
+                emit!(self, "f32.demote_f64")
+            }
+            ConversionOperation::FloatExtend => {
+                emit!(self, "f64.promote_f32")
+            }
+            ConversionOperation::FloatToUnsigned | ConversionOperation::FloatToSigned => {
+                let sign = if operation == ConversionOperation::FloatToSigned { "s" } else { "u" };
+                emit!(self, "{to}.trunc_sat_{from}_{sign}")?;
+                self.fmt_normalize(to_type)
+            }
+            ConversionOperation::UnsignedToFloat | ConversionOperation::SignedToFloat => {
+                let signed = operation == ConversionOperation::SignedToFloat;
+                self.fmt_extend_narrow(from_type, signed)?;
+                emit!(self, "{to}.convert_{from}_{}", if signed { "s" } else { "u" })
+            }
+            ConversionOperation::PointerToInteger => {
+                if to == "i64" {
+                    emit!(self, "i64.extend_i32_u")?;
+                }
+                self.fmt_normalize(to_type)
+            }
+            ConversionOperation::IntegerToPointer => {
+                if from == "i64" {
+                    emit!(self, "i32.wrap_i64")
+                }
+                else {
+                    self.fmt_extend_narrow(from_type, false)
+                }
+            }
+            ConversionOperation::BitwiseCast => {
+                match (from, to) {
+                    ("i32", "f32") | ("i64", "f64") => emit!(self, "{to}.reinterpret_{from}"),
+                    ("f32", "i32") | ("f64", "i64") => {
+                        emit!(self, "{to}.reinterpret_{from}")?;
+                        self.fmt_normalize(to_type)
+                    }
+                    _ => self.fmt_normalize(to_type)
+                }
+            }
+        }
+    }
+
+    /// Add the byte offset selected by `indices` to the pointer on top of the stack, following
+    /// the rules of `getelementptr` in LLVM.
+    fn fmt_element_offsets(&mut self, pointee_type: TypeHandle, indices: &[Value]) -> std::fmt::Result {
+        let context = self.context;
+        // Unsized arrays are pointed to by pointers to their first item
+        let mut current_type = match *pointee_type.repr(context) {
+            TypeRepr::Array { item_type, length: None } => item_type,
+            _ => pointee_type,
+        };
+        let [first_index, indices @ ..] = indices else {
+            panic!("element pointer requires at least one index");
+        };
+
+        self.fmt_scaled_index(first_index, type_size(context, current_type))?;
+        for index in indices {
+            match *current_type.repr(context) {
+                TypeRepr::Array { item_type, .. } => {
+                    self.fmt_scaled_index(index, type_size(context, item_type))?;
+                    current_type = item_type;
+                }
+                TypeRepr::Tuple { .. } | TypeRepr::Structure { .. } => {
+                    let (offset, member_type) = member_offset(context, current_type, constant_index(index));
+                    if offset != 0 {
+                        emit!(self, "i32.const {offset}")?;
+                        emit!(self, "i32.add")?;
+                    }
+                    current_type = member_type;
+                }
+                _ => panic!("cannot index into type '{}'", current_type.path(context))
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fmt_scaled_index(&mut self, index: &Value, stride: u64) -> std::fmt::Result {
+        if let Value::Constant(Constant::Integer(index)) = index {
+            let offset = (index.raw() as i64).wrapping_mul(stride as i64) as i32;
+            if offset != 0 {
+                emit!(self, "i32.const {offset}")?;
+                emit!(self, "i32.add")?;
+            }
+            return Ok(());
+        }
+
+        self.push_value(index)?;
+        if value_type(self.context, index.get_type()) == Some("i64") {
+            emit!(self, "i32.wrap_i64")?;
+        }
+        if stride != 1 {
+            emit!(self, "i32.const {stride}")?;
+            emit!(self, "i32.mul")?;
+        }
+        emit!(self, "i32.add")
+    }
+
+    fn fmt_instruction(&mut self, instruction: &Instruction) -> std::fmt::Result {
+        let context = self.context;
+        match instruction {
+            Instruction::Negate { result, operand } => {
+                let operand_type = value_type(context, operand.get_type()).unwrap();
+                if is_float(context, operand.get_type()) {
+                    self.push_value(operand)?;
+                    emit!(self, "{operand_type}.neg")?;
+                }
+                else {
+                    emit!(self, "{operand_type}.const 0")?;
+                    self.push_value(operand)?;
+                    emit!(self, "{operand_type}.sub")?;
+                    self.fmt_normalize(result.get_type())?;
+                }
+                self.set_result(result)
+            }
+            Instruction::Add { result, lhs, rhs } => {
+                self.fmt_binary_operation(result, lhs, rhs, "add", "add")
+            }
+            Instruction::Subtract { result, lhs, rhs } => {
+                self.fmt_binary_operation(result, lhs, rhs, "sub", "sub")
+            }
+            Instruction::Multiply { result, lhs, rhs } => {
+                self.fmt_binary_operation(result, lhs, rhs, "mul", "mul")
+            }
+            Instruction::Divide { result, lhs, rhs } => {
+                let operation = if is_signed_integer(context, lhs.get_type()) { "div_s" } else { "div_u" };
+                self.fmt_binary_operation(result, lhs, rhs, operation, "div")
+            }
+            Instruction::Remainder { result, lhs, rhs } => {
+                if is_float(context, lhs.get_type()) {
+                    // WebAssembly has no floating-point remainder, so compute lhs - trunc(lhs / rhs) * rhs
+                    let float_type = value_type(context, lhs.get_type()).unwrap();
+                    self.push_value(lhs)?;
+                    self.push_value(lhs)?;
+                    self.push_value(rhs)?;
+                    emit!(self, "{float_type}.div")?;
+                    emit!(self, "{float_type}.trunc")?;
+                    self.push_value(rhs)?;
+                    emit!(self, "{float_type}.mul")?;
+                    emit!(self, "{float_type}.sub")?;
+                    self.set_result(result)
+                }
+                else {
+                    let operation = if is_signed_integer(context, lhs.get_type()) { "rem_s" } else { "rem_u" };
+                    self.fmt_binary_operation(result, lhs, rhs, operation, "")
+                }
+            }
+            Instruction::ShiftLeft { result, lhs, rhs } => {
+                self.fmt_binary_operation(result, lhs, rhs, "shl", "")
+            }
+            Instruction::ShiftRight { result, lhs, rhs } => {
+                let operation = if is_signed_integer(context, lhs.get_type()) { "shr_s" } else { "shr_u" };
+                self.fmt_binary_operation(result, lhs, rhs, operation, "")
+            }
+            Instruction::Not { result, operand } => {
+                self.push_value(operand)?;
+                if operand.get_type() == TypeHandle::BOOL {
+                    emit!(self, "i32.eqz")?;
+                }
+                else {
+                    let operand_type = value_type(context, operand.get_type()).unwrap();
+                    emit!(self, "{operand_type}.const -1")?;
+                    emit!(self, "{operand_type}.xor")?;
+                    self.fmt_normalize(result.get_type())?;
+                }
+                self.set_result(result)
+            }
+            Instruction::And { result, lhs, rhs } => {
+                self.fmt_binary_operation(result, lhs, rhs, "and", "")
+            }
+            Instruction::Or { result, lhs, rhs } => {
+                self.fmt_binary_operation(result, lhs, rhs, "or", "")
+            }
+            Instruction::Xor { result, lhs, rhs } => {
+                self.fmt_binary_operation(result, lhs, rhs, "xor", "")
+            }
+            Instruction::ExtractValue { result, aggregate, indices } => {
+                let (offset, member_type) = aggregate_path(context, aggregate.get_type(), indices);
+                if is_aggregate(context, member_type) {
+                    self.push_value(&Value::Register(result.clone()))?;
+                    self.push_value(aggregate)?;
+                    emit!(self, "i32.const {offset}")?;
+                    emit!(self, "i32.add")?;
+                    emit!(self, "i32.const {}", type_size(context, member_type))?;
+                    emit!(self, "memory.copy")
+                }
+                else if value_type(context, member_type).is_some() {
+                    self.push_value(aggregate)?;
+                    emit!(self, "{} offset={offset}", load_instruction(context, member_type))?;
+                    self.set_result(result)
+                }
+                else {
+                    Ok(())
+                }
+            }
+            Instruction::InsertValue { result, aggregate, value, indices } => {
+                let result_value = Value::Register(result.clone());
+                self.push_value(&result_value)?;
+                self.push_value(aggregate)?;
+                emit!(self, "i32.const {}", type_size(context, aggregate.get_type()))?;
+                emit!(self, "memory.copy")?;
+
+                let (offset, member_type) = aggregate_path(context, aggregate.get_type(), indices);
+                self.fmt_store(&result_value, offset, value, member_type)
+            }
+            Instruction::StackAllocate { result } => {
+                self.push_frame_address(self.frame.slots[result.identifier()])?;
+                self.set_result(result)
+            }
+            Instruction::Load { result, pointer } => {
+                if is_aggregate(context, result.get_type()) {
+                    self.push_value(&Value::Register(result.clone()))?;
+                    self.push_value(pointer)?;
+                    emit!(self, "i32.const {}", type_size(context, result.get_type()))?;
+                    emit!(self, "memory.copy")
+                }
+                else if value_type(context, result.get_type()).is_some() {
+                    self.push_value(pointer)?;
+                    emit!(self, "{}", load_instruction(context, result.get_type()))?;
+                    self.set_result(result)
+                }
+                else {
+                    Ok(())
+                }
+            }
+            Instruction::Store { value, pointer } => {
+                self.fmt_store(pointer, 0, value, value.get_type())
+            }
+            Instruction::GetElementPointer { result, pointer, indices } => {
+                let &TypeRepr::Pointer { pointee_type, .. } = pointer.get_type().repr(context) else {
+                    panic!("element pointer operand is not a pointer");
+                };
+                self.push_value(pointer)?;
+                self.fmt_element_offsets(pointee_type, indices)?;
+                self.set_result(result)
+            }
+            Instruction::Convert { operation, result, value } => {
+                self.push_value(value)?;
+                self.fmt_conversion(*operation, value.get_type(), result.get_type())?;
+                self.set_result(result)
+            }
+            Instruction::CompareEqual { result, lhs, rhs } => {
+                self.fmt_comparison(result, lhs, rhs, "eq", "eq")
+            }
+            Instruction::CompareNotEqual { result, lhs, rhs } => {
+                self.fmt_comparison(result, lhs, rhs, "ne", "ne")
+            }
+            Instruction::CompareLessThan { result, lhs, rhs } => {
+                self.fmt_comparison(result, lhs, rhs, "lt", "lt")
+            }
+            Instruction::CompareLessEqual { result, lhs, rhs } => {
+                self.fmt_comparison(result, lhs, rhs, "le", "le")
+            }
+            Instruction::CompareGreaterThan { result, lhs, rhs } => {
+                self.fmt_comparison(result, lhs, rhs, "gt", "gt")
+            }
+            Instruction::CompareGreaterEqual { result, lhs, rhs } => {
+                self.fmt_comparison(result, lhs, rhs, "ge", "ge")
+            }
+            Instruction::Call { result, callee, arguments } => {
+                self.fmt_call(result.as_ref(), callee, arguments)
+            }
+        }
+    }
+
+    fn fmt_binary_operation(
+        &mut self,
+        result: &LocalRegister,
+        lhs: &Value,
+        rhs: &Value,
+        integer_operation: &str,
+        float_operation: &str,
+    ) -> std::fmt::Result {
+        let context = self.context;
+        let operand_type = value_type(context, lhs.get_type()).unwrap();
+        self.push_value(lhs)?;
+        self.push_value(rhs)?;
+        // Shift amounts may be a different width from the value being shifted
+        match (operand_type, value_type(context, rhs.get_type())) {
+            ("i64", Some("i32")) => emit!(self, "i64.extend_i32_u")?,
+            ("i32", Some("i64")) => emit!(self, "i32.wrap_i64")?,
+            _ => {}
+        }
+
+        if is_float(context, lhs.get_type()) {
+            emit!(self, "{operand_type}.{float_operation}")?;
+        }
+        else {
+            emit!(self, "{operand_type}.{integer_operation}")?;
+            self.fmt_normalize(result.get_type())?;
+        }
+        self.set_result(result)
+    }
+
+    fn fmt_comparison(
+        &mut self,
+        result: &LocalRegister,
+        lhs: &Value,
+        rhs: &Value,
+        integer_operation: &str,
+        float_operation: &str,
+    ) -> std::fmt::Result {
+        let context = self.context;
+        let operand_type = value_type(context, lhs.get_type()).unwrap();
+        self.push_value(lhs)?;
+        self.push_value(rhs)?;
+
+        if is_float(context, lhs.get_type()) {
+            emit!(self, "{operand_type}.{float_operation}")?;
+        }
+        else if integer_operation == "eq" || integer_operation == "ne" {
+            emit!(self, "{operand_type}.{integer_operation}")?;
+        }
+        else {
+            let sign = if is_signed_integer(context, lhs.get_type()) { "s" } else { "u" };
+            emit!(self, "{operand_type}.{integer_operation}_{sign}")?;
+        }
+        self.set_result(result)
+    }
+
+    /// Store `value` of type `value_type` at `offset` bytes past the address `pointer`.
+    fn fmt_store(&mut self, pointer: &Value, offset: u64, value: &Value, value_type: TypeHandle) -> std::fmt::Result {
+        let context = self.context;
+        if is_aggregate(context, value_type) {
+            self.push_value(pointer)?;
+            if offset != 0 {
+                emit!(self, "i32.const {offset}")?;
+                emit!(self, "i32.add")?;
+            }
+            self.push_value(value)?;
+            emit!(self, "i32.const {}", type_size(context, value_type))?;
+            emit!(self, "memory.copy")
+        }
+        else if super::value_type(context, value_type).is_some() {
+            self.push_value(pointer)?;
+            self.push_value(value)?;
+            if offset != 0 {
+                emit!(self, "{} offset={offset}", store_instruction(context, value_type))
+            }
+            else {
+                emit!(self, "{}", store_instruction(context, value_type))
+            }
+        }
+        else {
+            Ok(())
+        }
+    }
+
+    fn fmt_call(&mut self, result: Option<&LocalRegister>, callee: &Value, arguments: &[Value]) -> std::fmt::Result {
+        let context = self.context;
+        let TypeRepr::Function { signature } = callee.get_type().repr(context) else {
+            panic!("callee is not a function");
+        };
+        let returns_aggregate = is_aggregate(context, signature.return_type());
+
+        if returns_aggregate {
+            let result = result.expect("call returning an aggregate has no result");
+            self.push_value(&Value::Register(result.clone()))?;
+        }
+
+        let fixed_count = signature.parameter_types().len();
+        for argument in &arguments[..fixed_count] {
+            self.push_value(argument)?;
+        }
+        if signature.is_variadic() {
+            // Variadic arguments are written to a buffer in the stack frame
+            let (offsets, _) = varargs_layout(context, callee, arguments);
+            for (argument, offset) in arguments[fixed_count..].iter().zip(offsets) {
+                emit!(self, "local.get $%frame")?;
+                if is_aggregate(context, argument.get_type()) {
+                    emit!(self, "i32.const {}", self.frame.varargs_offset + offset)?;
+                    emit!(self, "i32.add")?;
+                    self.push_value(argument)?;
+                    emit!(self, "i32.const {}", type_size(context, argument.get_type()))?;
+                    emit!(self, "memory.copy")?;
+                }
+                else {
+                    self.push_value(argument)?;
+                    emit!(self, "{} offset={}", store_instruction(context, argument.get_type()), self.frame.varargs_offset + offset)?;
+                }
+            }
+            self.push_frame_address(self.frame.varargs_offset)?;
+        }
+
+        let direct_callee = match callee {
+            Value::Constant(Constant::Register(register)) => Some(register),
+            Value::BoundFunction { function_value, .. } => match function_value.as_ref() {
+                Value::Constant(Constant::Register(register)) => Some(register),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(register) = direct_callee {
+            emit!(self, "call ${}", IdentifierDisplay(register.identifier()))?;
+        }
+        else {
+            self.push_value(callee)?;
+            let mut signature_text = String::new();
+            fmt_signature(&mut signature_text, context, signature)?;
+            emit!(self, "call_indirect{signature_text}")?;
+        }
+
+        if let Some(result) = result.filter(|_| !returns_aggregate) {
+            if value_type(context, signature.return_type()).is_some() {
+                self.set_result(result)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn constant_index(index: &Value) -> usize {
+    match index {
+        Value::Constant(Constant::Integer(integer)) => integer.raw() as usize,
+        _ => panic!("member index must be an integer constant"),
+    }
+}
+
+/// Get the offset and type of member `index` of a tuple or structure type.
+fn member_offset(context: &GlobalContext, aggregate_type: TypeHandle, index: usize) -> (u64, TypeHandle) {
+    let offsets = context.type_registry().member_offsets(aggregate_type).expect("invalid aggregate type");
+    let member_type = match aggregate_type.repr(context) {
+        TypeRepr::Tuple { item_types } => item_types[index],
+        TypeRepr::Structure { members, .. } => members[index].member_type,
+        _ => unreachable!()
+    };
+    (offsets[index], member_type)
+}
+
+/// Get the offset and type of the member selected by `indices` in an aggregate, as used by
+/// `extractvalue` and `insertvalue` in LLVM.
+fn aggregate_path(context: &GlobalContext, aggregate_type: TypeHandle, indices: &[Value]) -> (u64, TypeHandle) {
+    let mut offset = 0;
+    let mut current_type = aggregate_type;
+    for index in indices {
+        let index = constant_index(index);
+        match *current_type.repr(context) {
+            TypeRepr::Array { item_type, .. } => {
+                offset += index as u64 * type_size(context, item_type);
+                current_type = item_type;
+            }
+            TypeRepr::Tuple { .. } | TypeRepr::Structure { .. } => {
+                let (member_offset, member_type) = member_offset(context, current_type, index);
+                offset += member_offset;
+                current_type = member_type;
+            }
+            _ => panic!("cannot index into type '{}'", current_type.path(context))
+        }
+    }
+    (offset, current_type)
+}
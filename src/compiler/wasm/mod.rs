@@ -0,0 +1,228 @@
+//! WebAssembly text format (`.wat`) backend.
+//!
+//! Each package becomes a module in the style of a dynamically linked side module: the linear
+//! memory, the function table, and the shadow stack pointer are imported from `env`, along with
+//! `__memory_base` and `__table_base` giving where this module's static data and table entries
+//! were placed. The sizes needed are given by a `dylink.0` custom section so they can be read
+//! before the module is instantiated; it is written as an annotation, which `wasm-tools parse`
+//! assembles into the section. Foreign functions and anything defined by other packages are
+//! imported from `env` by name.
+
+use std::fmt::Write;
+use crate::ir::CompilationUnit;
+use crate::sema::{FunctionSignature, GlobalContext, TypeHandle, TypeRepr};
+
+mod data;
+mod function;
+
+/// Writes an arbitrary identifier as a WebAssembly text format identifier, without the leading
+/// `$`. Bytes which cannot appear in an identifier are written as `^` followed by two hex digits.
+/// Since `%` is escaped as well, names containing `%` are free for internal use.
+pub struct IdentifierDisplay<T: AsRef<[u8]>>(pub T);
+
+impl<T: AsRef<[u8]>> std::fmt::Display for IdentifierDisplay<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for &ch in self.0.as_ref() {
+            if ch.is_ascii_alphanumeric() || b"!#$&'*+-./:<=>?@\\_`|~".contains(&ch) {
+                write!(f, "{}", ch as char)?;
+            }
+            else {
+                write!(f, "^{ch:02X}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct StringDisplay<T: AsRef<[u8]>>(pub T);
+
+impl<T: AsRef<[u8]>> std::fmt::Display for StringDisplay<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"")?;
+        for &ch in self.0.as_ref() {
+            if ch == b'"' || ch == b'\\' || !(ch == b' ' || ch.is_ascii_graphic()) {
+                write!(f, "\\{ch:02x}")?;
+            }
+            else {
+                write!(f, "{}", ch as char)?;
+            }
+        }
+        write!(f, "\"")
+    }
+}
+
+/// Get the WebAssembly value type used to represent values of `handle`, or `None` if the type
+/// has no values. Aggregates are represented by their address in linear memory.
+pub fn value_type(context: &GlobalContext, handle: TypeHandle) -> Option<&'static str> {
+    match *handle.repr(context) {
+        TypeRepr::Never | TypeRepr::Void => None,
+        TypeRepr::Boolean => Some("i32"),
+        TypeRepr::Integer { size: 1 | 2 | 4, .. } => Some("i32"),
+        TypeRepr::Integer { size: 8, .. } => Some("i64"),
+        TypeRepr::Integer { size, .. } => {
+            panic!("{size}-byte integers are not supported by the WebAssembly backend")
+        }
+        TypeRepr::Float32 => Some("f32"),
+        TypeRepr::Float64 => Some("f64"),
+        TypeRepr::Pointer { .. } | TypeRepr::Function { .. } => Some("i32"),
+        TypeRepr::Array { .. } | TypeRepr::Tuple { .. } | TypeRepr::Structure { .. } | TypeRepr::OpaqueStructure { .. } => Some("i32"),
+        TypeRepr::Unresolved | TypeRepr::Meta | TypeRepr::PointerSizedInteger { .. } => {
+            panic!("type '{}' cannot be represented in WebAssembly", handle.path(context))
+        }
+    }
+}
+
+/// Whether values of `handle` live in linear memory rather than in WebAssembly locals.
+pub fn is_aggregate(context: &GlobalContext, handle: TypeHandle) -> bool {
+    matches!(
+        handle.repr(context),
+        TypeRepr::Array { .. } | TypeRepr::Tuple { .. } | TypeRepr::Structure { .. } | TypeRepr::OpaqueStructure { .. }
+    )
+}
+
+fn type_size(context: &GlobalContext, handle: TypeHandle) -> u64 {
+    context.type_size(handle).unwrap_or(0)
+}
+
+fn type_alignment(context: &GlobalContext, handle: TypeHandle) -> u64 {
+    context.type_alignment(handle).unwrap_or(1).max(1)
+}
+
+/// Write the parameter and result types of a function with `signature`. Aggregates are returned
+/// through a hidden leading pointer parameter, and variadic arguments are passed in a buffer
+/// whose address is the last parameter.
+fn fmt_signature(out: &mut String, context: &GlobalContext, signature: &FunctionSignature) -> std::fmt::Result {
+    if is_aggregate(context, signature.return_type()) {
+        write!(out, " (param i32)")?;
+    }
+    for &parameter_type in signature.parameter_types() {
+        if let Some(parameter_type) = value_type(context, parameter_type) {
+            write!(out, " (param {parameter_type})")?;
+        }
+    }
+    if signature.is_variadic() {
+        write!(out, " (param i32)")?;
+    }
+    if let Some(return_type) = value_type(context, signature.return_type()).filter(|_| !is_aggregate(context, signature.return_type())) {
+        write!(out, " (result {return_type})")?;
+    }
+    Ok(())
+}
+
+fn function_signature<'a>(context: &'a GlobalContext, register: &crate::ir::value::GlobalRegister) -> &'a FunctionSignature {
+    let TypeRepr::Function { signature } = register.get_type().repr(context) else {
+        panic!("'{}' is not a function type", register.get_type().path(context));
+    };
+    signature
+}
+
+/// Get the comparison instruction suffix implementing an LLVM minimum/maximum intrinsic, if
+/// `identifier` names one.
+fn intrinsic_comparison(identifier: &[u8]) -> Option<&'static str> {
+    let name = identifier.strip_prefix(b"llvm.")?;
+    let operation = &name[..name.iter().position(|&ch| ch == b'.')?];
+    match operation {
+        b"smax" => Some("gt_s"),
+        b"umax" => Some("gt_u"),
+        b"smin" => Some("lt_s"),
+        b"umin" => Some("lt_u"),
+        _ => None
+    }
+}
+
+fn fmt_external_function(out: &mut String, context: &GlobalContext, function: &crate::ir::ExternalFunction) -> std::fmt::Result {
+    let identifier = function.register().identifier();
+    let signature = function_signature(context, function.register());
+
+    // LLVM intrinsics have nothing to import, so the common ones are defined here
+    if let Some(comparison) = intrinsic_comparison(identifier) {
+        let value_type = value_type(context, signature.return_type()).expect("invalid intrinsic signature");
+        write!(out, "  (func ${}", IdentifierDisplay(identifier))?;
+        fmt_signature(out, context, signature)?;
+        return writeln!(
+            out,
+            " local.get 0 local.get 1 local.get 0 local.get 1 {value_type}.{comparison} select)",
+        );
+    }
+
+    write!(out, "  (import \"env\" {} (func ${}", StringDisplay(identifier), IdentifierDisplay(identifier))?;
+    fmt_signature(out, context, signature)?;
+    writeln!(out, "))")
+}
+
+pub trait WatDisplay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result;
+
+    fn wat<'a>(&'a self, context: &'a GlobalContext) -> WatDisplayWrapper<'a, Self>
+    where
+        Self: Sized,
+    {
+        WatDisplayWrapper {
+            inner: self,
+            context,
+        }
+    }
+}
+
+pub struct WatDisplayWrapper<'a, T: WatDisplay> {
+    pub inner: &'a T,
+    pub context: &'a GlobalContext,
+}
+
+impl<'a, T: WatDisplay> std::fmt::Display for WatDisplayWrapper<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f, self.context)
+    }
+}
+
+impl WatDisplay for CompilationUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>, context: &GlobalContext) -> std::fmt::Result {
+        let mut static_data = data::StaticData::new(context, self);
+
+        // Functions are written first since they determine which constants and table entries
+        // the rest of the module needs
+        let mut functions = String::new();
+        for function in self.function_definitions() {
+            function::fmt_function_definition(&mut functions, context, &mut static_data, function)?;
+        }
+
+        writeln!(f, ";; Generated from '{}'", self.main_path().display())?;
+        writeln!(f, "(module")?;
+        static_data.fmt_memory_info(f)?;
+        writeln!(f, "  (import \"env\" \"memory\" (memory 1))")?;
+        writeln!(f, "  (import \"env\" \"__indirect_function_table\" (table 0 funcref))")?;
+        writeln!(f, "  (import \"env\" \"__stack_pointer\" (global $__stack_pointer (mut i32)))")?;
+        writeln!(f, "  (import \"env\" \"__memory_base\" (global $__memory_base i32))")?;
+        writeln!(f, "  (import \"env\" \"__table_base\" (global $__table_base i32))")?;
+
+        let mut declarations = String::new();
+        for variable in self.external_global_variables() {
+            // Imported variables are globals holding their address
+            let identifier = variable.register().identifier();
+            writeln!(
+                declarations,
+                "  (import \"env\" {} (global ${} (mut i32)))",
+                StringDisplay(identifier),
+                IdentifierDisplay(identifier),
+            )?;
+        }
+        // Imports must all come before any definitions
+        for function in self.external_functions() {
+            if intrinsic_comparison(function.register().identifier()).is_none() {
+                fmt_external_function(&mut declarations, context, function)?;
+            }
+        }
+        for function in self.external_functions() {
+            if intrinsic_comparison(function.register().identifier()).is_some() {
+                fmt_external_function(&mut declarations, context, function)?;
+            }
+        }
+        write!(f, "{declarations}")?;
+        writeln!(f)?;
+
+        static_data.fmt_declarations(f, self)?;
+        writeln!(f)?;
+        write!(f, "{functions}")?;
+        writeln!(f, ")")
+    }
+}
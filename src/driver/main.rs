@@ -1,19 +1,46 @@
 use std::time::Instant;
+use cupric::cli::{Command, Verbosity};
 
 fn main() {
     let start_time = Instant::now();
 
     let args = cupric::cli::parse_command_line_args();
+    if let Some(Command::Run(run_args)) = args.command() {
+        match cupric::cli::run(run_args) {
+            Err(error) => {
+                let (error, source_map) = *error;
+                println!("{}", run_args.color().paint(&error.to_string_with_context(&source_map), "31", "0"));
+                std::process::exit(1);
+            }
+            Ok(status) => std::process::exit(status),
+        }
+    }
+
+    if let Some(Command::Demangle(demangle_args)) = args.command() {
+        if let Err(error) = cupric::cli::demangle(demangle_args, &mut std::io::stdin().lock(), &mut std::io::stdout()) {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let color = args.color();
+    let quiet = args.verbosity() == Verbosity::Quiet;
+
     match cupric::cli::invoke(&args) {
         Err(error) => {
-            let (error, source_paths) = *error;
-            println!("\x1b[31m{}\x1b[0m", error.to_string_with_context(&source_paths));
+            let (error, source_map) = *error;
+            println!("{}", color.paint(&error.to_string_with_context(&source_map), "31", "0"));
         }
         Ok(..) => {
-            println!("\x1b[32mFinished\x1b[0m");
+            if !quiet {
+                println!("{}", color.paint("Finished", "32", "0"));
+            }
         }
     }
 
-    let time_taken_ms = start_time.elapsed().as_millis();
-    println!("\x1b[2mTime: {time_taken_ms} ms\x1b[22m");
+    if !quiet {
+        let time_taken_ms = start_time.elapsed().as_millis();
+        println!("{}", color.paint(&format!("Time: {time_taken_ms} ms"), "2", "22"));
+    }
 }
@@ -1,14 +1,333 @@
+// Each test crate only uses some of these helpers
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
 use clap::Parser;
+use cupric::cli::{Reporter, Verbosity};
+use cupric::ir::{FunctionDefinition, IRDisplay};
+use cupric::ir::instr::BasicBlock;
+use cupric::ir::value::{BlockLabel, GlobalRegister, IntegerType, IntegerValue, LocalRegister, Value};
+use cupric::package::MANIFEST_EXTENSION;
+use cupric::sema::{AbsolutePath, GlobalContext, SimplePath, TypeHandle};
+use cupric::target::TargetInfo;
+
+/// Set this environment variable to overwrite the committed snapshots with fresh output instead
+/// of comparing against them.
+const BLESS_VAR: &str = "CUPRIC_BLESS";
+
+/// Snapshots are always generated for the same target so they do not depend on the host.
+const SNAPSHOT_TARGET: &str = "x86_64-unknown-linux-gnu";
+
+/// Collects progress messages so they only need to be shown if compilation fails.
+#[derive(Default)]
+pub struct CaptureReporter {
+    pub messages: Vec<String>,
+}
+
+impl Reporter for CaptureReporter {
+    fn report(&mut self, _level: Verbosity, message: &str) {
+        self.messages.push(message.to_owned());
+    }
+}
+
+/// A failed compilation, which shows the progress messages and the error with its source context
+/// when unwrapped.
+pub struct CompileError {
+    pub error: cupric::Error,
+    messages: Vec<String>,
+    rendered: String,
+}
 
+impl std::fmt::Debug for CompileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for message in &self.messages {
+            writeln!(f, "{message}")?;
+        }
+        write!(f, "\x1b[31m{}\x1b[0m", self.rendered)
+    }
+}
+
+/// Get a path in the temporary directory for output named `name`, which is unique to this test
+/// process and doesn't exist yet.
+pub fn temp_out_dir(name: &str) -> PathBuf {
+    let out_dir = std::env::temp_dir().join(format!("cupric-{name}-{}", std::process::id()));
+    if out_dir.exists() {
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
+    out_dir
+}
+
+/// Compile the package at `package_path` with the additional arguments `extra_args`, writing the
+/// output into `out_dir` if given. Returns the progress messages reported while compiling.
+pub fn compile_package(package_path: impl AsRef<Path>, out_dir: Option<&Path>, extra_args: &[&str]) -> Result<Vec<String>, Box<CompileError>> {
+    let mut args = vec!["compiler".to_string(), package_path.as_ref().display().to_string()];
+    if let Some(out_dir) = out_dir {
+        args.extend(["--out-dir".to_string(), out_dir.display().to_string()]);
+    }
+    args.extend(extra_args.iter().map(|arg| arg.to_string()));
+
+    let args = cupric::cli::CompilerArgs::parse_from(args);
+    let mut reporter = CaptureReporter::default();
+    match cupric::cli::invoke_with_reporter(&args, &mut reporter) {
+        Ok(()) => Ok(reporter.messages),
+        Err(error) => {
+            let (error, source_map) = *error;
+            let rendered = error.to_string_with_context(&source_map);
+            Err(Box::new(CompileError {
+                error,
+                messages: reporter.messages,
+                rendered,
+            }))
+        }
+    }
+}
+
+/// Write a package named `package_name` into `root` with `source` as its main file, depending
+/// on each package in `dependencies`, which must also be in `root`.
+pub fn write_package(root: &Path, package_name: &str, kind: &str, dependencies: &[&str], source: &str) {
+    let mut manifest = format!("[package]\nname = \"{package_name}\"\nkind = \"{kind}\"\nmain_path = \"main.cupr\"\n");
+    for dependency in dependencies {
+        manifest.push_str(&format!("\n[dependency.{dependency}]\npath = \"../{dependency}\"\n"));
+    }
+    let package_path = root.join(package_name);
+    std::fs::create_dir_all(&package_path).unwrap();
+    std::fs::write(package_path.join("package.toml"), manifest).unwrap();
+    std::fs::write(package_path.join("main.cupr"), source).unwrap();
+}
+
+/// Compile the package `tests/packages/{package_name}`, then compare the LLVM IR produced for it
+/// and each of its dependencies against the committed snapshot in the `out` directory of the
+/// respective package.
 pub fn test_compile_package(package_name: &str) {
-    let args = cupric::cli::CompilerArgs::parse_from([
-        "compiler".to_string(),
-        format!("tests/packages/{package_name}"),
-    ]);
-
-    if let Err(error) = cupric::cli::invoke(&args) {
-        let (error, source_paths) = *error;
-        println!("\x1b[31m{}\x1b[0m", error.to_string_with_context(&source_paths));
-        panic!("compile command failed")
+    let out_dir = temp_out_dir(&format!("test-{package_name}"));
+    compile_package(format!("tests/packages/{package_name}"), Some(&out_dir), &["--target", SNAPSHOT_TARGET]).unwrap();
+
+    let mut output_paths: Vec<PathBuf> = std::fs::read_dir(&out_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        // Build manifests contain hashes of the sources, so aren't worth keeping snapshots of
+        .filter(|path| path.is_file() && path.extension().is_none_or(|extension| extension != MANIFEST_EXTENSION))
+        .collect();
+    output_paths.sort();
+
+    let mut mismatches = Vec::new();
+    for output_path in output_paths {
+        let output_name = output_path.file_stem().unwrap().to_string_lossy().into_owned();
+        let snapshot_dir = Path::new("tests/packages").join(&output_name).join("out");
+        let snapshot_path = snapshot_dir.join(output_path.file_name().unwrap());
+        let actual = normalize_output(&std::fs::read_to_string(&output_path).unwrap());
+
+        if let Some(diff) = check_snapshot(&snapshot_path, &actual) {
+            mismatches.push(format!("--- snapshot mismatch for '{}' ---\n{diff}", snapshot_path.display()));
+        }
+
+        // The interface headers of each package are compared as well
+        let headers_dir = out_dir.join("headers").join(&output_name);
+        let snapshot_headers_dir = snapshot_dir.join("headers");
+        let header_paths = relative_file_paths(&headers_dir);
+        for header_path in &header_paths {
+            let snapshot_path = snapshot_headers_dir.join(header_path);
+            let actual = std::fs::read_to_string(headers_dir.join(header_path)).unwrap();
+
+            if let Some(diff) = check_snapshot(&snapshot_path, &actual) {
+                mismatches.push(format!("--- snapshot mismatch for '{}' ---\n{diff}", snapshot_path.display()));
+            }
+        }
+        for snapshot_path in relative_file_paths(&snapshot_headers_dir) {
+            if !header_paths.contains(&snapshot_path) {
+                let snapshot_path = snapshot_headers_dir.join(snapshot_path);
+                if std::env::var_os(BLESS_VAR).is_some() {
+                    std::fs::remove_file(&snapshot_path).unwrap();
+                }
+                else {
+                    mismatches.push(format!("--- snapshot '{}' was not produced ---\n", snapshot_path.display()));
+                }
+            }
+        }
+    }
+
+    std::fs::remove_dir_all(&out_dir).unwrap();
+
+    if !mismatches.is_empty() {
+        for mismatch in &mismatches {
+            println!("{mismatch}");
+        }
+        panic!("{} snapshot(s) did not match (set {BLESS_VAR}=1 to update them)", mismatches.len())
+    }
+}
+
+/// Get the path of every file within `dir` and its subdirectories, relative to `dir`.
+fn relative_file_paths(dir: &Path) -> Vec<PathBuf> {
+    fn collect(dir: &Path, relative_dir: &Path, paths: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries {
+            let entry = entry.unwrap();
+            let relative_path = relative_dir.join(entry.file_name());
+            if entry.path().is_dir() {
+                collect(&entry.path(), &relative_path, paths);
+            }
+            else {
+                paths.push(relative_path);
+            }
+        }
+    }
+
+    let mut paths = Vec::new();
+    collect(dir, Path::new(""), &mut paths);
+    paths.sort();
+    paths
+}
+
+/// Remove the parts of the output which depend on where the repository is located.
+fn normalize_output(output: &str) -> String {
+    output
+        .lines()
+        .map(|line| {
+            if let (true, Some(index)) = (line.starts_with("source_filename = "), line.find("tests/packages/")) {
+                format!("source_filename = \"{}", &line[index + "tests/packages/".len() ..])
+            }
+            else {
+                line.to_owned()
+            }
+        })
+        .map(|line| line + "\n")
+        .collect()
+}
+
+/// Compare `actual` against the snapshot at `snapshot_path`, returning a diff if they differ. If
+/// blessing is enabled, the snapshot is overwritten instead.
+fn check_snapshot(snapshot_path: &Path, actual: &str) -> Option<String> {
+    if std::env::var_os(BLESS_VAR).is_some() {
+        std::fs::create_dir_all(snapshot_path.parent().unwrap()).unwrap();
+        std::fs::write(snapshot_path, actual).unwrap();
+        return None;
+    }
+
+    let expected = std::fs::read_to_string(snapshot_path).unwrap_or_default().replace("\r\n", "\n");
+    if expected == actual {
+        None
+    }
+    else {
+        Some(line_diff(&expected, actual))
+    }
+}
+
+/// Produce a diff between two texts showing changed lines with a few lines of context, where
+/// removed lines are prefixed with `-` and added lines with `+`.
+fn line_diff(expected: &str, actual: &str) -> String {
+    const CONTEXT_LINES: usize = 2;
+
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    // Longest common subsequence table, computed from the end of both texts
+    let mut lcs = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0 .. expected.len()).rev() {
+        for j in (0 .. actual.len()).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            }
+            else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    // Walk the table to produce a list of (prefix, line) edits
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            edits.push((' ', i + 1, expected[i]));
+            i += 1;
+            j += 1;
+        }
+        else if j < actual.len() && (i == expected.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            edits.push(('+', j + 1, actual[j]));
+            j += 1;
+        }
+        else {
+            edits.push(('-', i + 1, expected[i]));
+            i += 1;
+        }
+    }
+
+    // Only show changed lines and the lines surrounding them
+    let mut diff = String::new();
+    let mut last_shown = None;
+    for (index, &(prefix, line_number, line)) in edits.iter().enumerate() {
+        let near_change = edits[index.saturating_sub(CONTEXT_LINES) ..= (index + CONTEXT_LINES).min(edits.len() - 1)]
+            .iter()
+            .any(|&(prefix, ..)| prefix != ' ');
+        if !near_change {
+            continue;
+        }
+        if last_shown.is_some_and(|last_shown| last_shown + 1 != index) {
+            diff.push_str("   ...\n");
+        }
+        diff.push_str(&format!("{prefix}{line_number:>5} | {line}\n"));
+        last_shown = Some(index);
+    }
+    diff
+}
+
+/// Build the package `tests/packages/{package_name}` as an executable and run it, returning its
+/// standard output. Returns `None` if no external toolchain is available to build it.
+pub fn run_package_executable(package_name: &str) -> Option<String> {
+    let out_dir = temp_out_dir(&format!("exe-{package_name}"));
+    if let Err(error) = compile_package(format!("tests/packages/{package_name}"), Some(&out_dir), &["--emit=exe"]) {
+        if let cupric::ErrorKind::MissingToolchain { .. } = error.error.kind() {
+            println!("skipping: {}", error.error);
+            return None;
+        }
+        panic!("failed to build executable:\n{error:?}");
+    }
+
+    let executable_path = out_dir.join(package_name).with_extension(std::env::consts::EXE_EXTENSION);
+    let output = std::process::Command::new(&executable_path).output().unwrap();
+    std::fs::remove_dir_all(&out_dir).unwrap();
+    assert!(output.status.success(), "executable exited with {}", output.status);
+
+    Some(String::from_utf8(output.stdout).unwrap())
+}
+
+pub fn label(name: &str) -> BlockLabel {
+    BlockLabel::new(name.as_bytes().into())
+}
+
+pub fn register(name: &str, value_type: TypeHandle) -> LocalRegister {
+    LocalRegister::new(name.as_bytes().into(), value_type)
+}
+
+pub fn integer(value: i128) -> Value {
+    IntegerValue::new(IntegerType::I32, value).into()
+}
+
+/// Create a function `f(%a: i32) -> i32` with the given blocks.
+pub fn function(blocks: Vec<BasicBlock>) -> FunctionDefinition {
+    let mut function = FunctionDefinition::new(GlobalRegister::new(b"f".as_slice().into(), TypeHandle::I32), TypeHandle::I32, false);
+    function.add_parameter_register(register("a", TypeHandle::I32));
+    for block in blocks {
+        function.add_block(block);
+    }
+    function
+}
+
+/// Create a context for working with hand-built IR for the host target.
+pub fn ir_context() -> GlobalContext {
+    GlobalContext::new("tests/packages/hello", TargetInfo::host()).unwrap()
+}
+
+/// The path reported for `f` in verification errors.
+pub fn function_path() -> AbsolutePath {
+    AbsolutePath::from_root(SimplePath::new(vec!["test".into(), "f".into()]))
+}
+
+/// Verify `function`, panicking with the error and the function's IR if it is rejected.
+pub fn assert_valid(context: &GlobalContext, function: &FunctionDefinition) {
+    if let Err(error) = cupric::ir::verify_function(context, function, &function_path()) {
+        panic!("{error}\n{}", function.ir(context));
     }
 }
@@ -1,12 +1,14 @@
-source_filename = "\\\\?\\C:\\Users\\seane\\Projects\\compiler\\tests\\packages\\hello\\main.cupr"
+source_filename = "hello/main.cupr"
+target datalayout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-i128:128-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
 
-declare i32 @puts(i8*)
+declare i32 @puts(ptr) nounwind
 
 @.const.hello.0 = private unnamed_addr constant [13 x i8] c"Hello world!\00"
 
-define i32 @main() {
+define i32 @main() nounwind {
 .block.0:
-	%0 = call i32(i8*) @puts(i8* bitcast ([13 x i8]* @.const.hello.0 to i8*))
+	%0 = call i32(ptr) @puts(ptr bitcast (ptr @.const.hello.0 to ptr))
 	ret i32 0
 }
 
@@ -1,4 +1,6 @@
-source_filename = "\\\\?\\C:\\Users\\seane\\Projects\\compiler\\tests\\packages\\libc\\main.cupr"
+source_filename = "libc/main.cupr"
+target datalayout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-i128:128-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
 
 %"::libc::stdio::CFile" = type opaque
 
@@ -1,44 +1,46 @@
-source_filename = "\\\\?\\C:\\Users\\seane\\Projects\\compiler\\tests\\packages\\test_1\\main.cupr"
+source_filename = "test_1/main.cupr"
+target datalayout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-i128:128-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
 
-%"::std::string::Str" = type { i8*, i64 }
+%"::libc::stdio::CFile" = type opaque
 
-%"::std::string::String" = type { %"::std::string::MutStr", i64 }
+%"::std::string::Str" = type { ptr, i64 }
 
-%"::test_1::Student" = type { i8*, i32, [4 x i32] }
+%"::std::string::MutStr" = type { ptr, i64 }
 
-%"::test_1::Node" = type { i8*, %"::test_1::Node"* }
+%"::std::string::String" = type { %"::std::string::MutStr", i64 }
 
-%"::libc::stdio::CFile" = type opaque
+%"::test_1::Node" = type { ptr, ptr }
 
-%"::std::string::MutStr" = type { i8*, i64 }
+%"::test_1::Student" = type { ptr, i32, [4 x i32] }
 
-declare %"::libc::stdio::CFile"* @fopen(i8*, i8*)
+declare ptr @fopen(ptr, ptr) nounwind
 
-declare i32 @puts(i8*)
+declare i32 @puts(ptr) nounwind
 
-declare i8* @fgets(i8*, i32, %"::libc::stdio::CFile"*)
+declare ptr @fgets(ptr, i32, ptr) nounwind
 
-declare i32 @isdigit(i32)
+declare i32 @isdigit(i32) nounwind
 
-declare i64 @strlen(i8*)
+declare i64 @strlen(ptr nonnull readonly) nounwind
 
-declare i32 @fclose(%"::libc::stdio::CFile"*)
+declare i32 @fclose(ptr) nounwind
 
-declare i32 @printf(i8*, ...)
+declare i32 @printf(ptr, ...) nounwind
 
-declare i8* @malloc(i64)
+declare ptr @malloc(i64) nounwind
 
-declare void @free(i8*)
+declare void @free(ptr) nounwind
 
-declare %"::std::string::String" @"::std::string::String::new"()
+declare %"::std::string::String" @_CN3std6string6String3newE() nounwind
 
-declare void @"::std::string::String::push"(%"::std::string::String"*, i8)
+declare void @_CN3std6string6String4pushE(ptr, i8) nounwind
 
-declare void @"::std::string::String::insert"(%"::std::string::String"*, i64, i8)
+declare void @_CN3std6string6String6insertE(ptr, i64, i8) nounwind
 
-declare %"::std::string::Str" @"::std::string::String::as_str"(%"::std::string::String"*)
+declare %"::std::string::Str" @_CN3std6string6String6as_strE(ptr) nounwind
 
-declare void @"::std::string::String::del"(%"::std::string::String")
+declare void @_CN3std6string6String3delE(%"::std::string::String") nounwind
 
 @.const.test_1.0 = private unnamed_addr constant [17 x i8] c"test_1/day01.txt\00"
 
@@ -60,11 +62,11 @@ declare void @"::std::string::String::del"(%"::std::string::String")
 
 @.const.test_1.9 = private unnamed_addr constant [24 x i8] c"Grades: %u, %u, %u, %u\0A\00"
 
-@"::test_1::static_mut_var" = global i32 5
+@_CN6test_114static_mut_varE = internal global i32 5
 
 @.const.test_1.10 = private unnamed_addr constant [14 x i8] c"I am a string\00"
 
-@"::test_1::static_var" = constant i8* bitcast ([14 x i8]* @.const.test_1.10 to i8*)
+@_CN6test_110static_varE = internal constant ptr bitcast (ptr @.const.test_1.10 to ptr)
 
 @.const.test_1.11 = private unnamed_addr constant [8 x i8] c"Value 1\00"
 
@@ -76,361 +78,276 @@ declare void @"::std::string::String::del"(%"::std::string::String")
 
 @.const.test_1.15 = private unnamed_addr constant [22 x i8] c"i64::to_string: \22%s\22\0A\00"
 
-define i32 @"::test_1::fibonacci"(i32 %0) {
+define internal i32 @_CN6test_19fibonacciE(i32 %0) nounwind {
 .block.0:
-	%limit = alloca i32
-	store i32 %0, i32* %limit
-	%a = alloca i32
-	store i32 0, i32* %a
-	%b = alloca i32
-	store i32 1, i32* %b
 	br label %.block.1
 .block.1:
-	%1 = load i32, i32* %b
-	%2 = load i32, i32* %limit
-	%3 = icmp slt i32 %1, %2
-	br i1 %3, label %.block.2, label %.block.3
+	%a.0 = phi i32 [ 0, %.block.0 ], [ %b.0, %.block.2 ]
+	%b.0 = phi i32 [ 1, %.block.0 ], [ %2, %.block.2 ]
+	%temp.0 = phi i32 [ undef, %.block.0 ], [ %2, %.block.2 ]
+	%1 = icmp slt i32 %b.0, %0
+	br i1 %1, label %.block.2, label %.block.3
 .block.2:
-	%4 = load i32, i32* %a
-	%5 = load i32, i32* %b
-	%6 = add nsw i32 %4, %5
-	%temp = alloca i32
-	store i32 %6, i32* %temp
-	%7 = load i32, i32* %b
-	store i32 %7, i32* %a
-	%8 = load i32, i32* %temp
-	store i32 %8, i32* %b
+	%2 = add nsw i32 %a.0, %b.0
 	br label %.block.1
 .block.3:
-	%9 = load i32, i32* %a
-	ret i32 %9
+	ret i32 %a.0
 }
 
-define i32 @"::test_1::gcd"(i32 %0, i32 %1) {
+define internal i32 @_CN6test_13gcdE(i32 %0, i32 %1) nounwind {
 .block.0:
-	%a = alloca i32
-	store i32 %0, i32* %a
-	%b = alloca i32
-	store i32 %1, i32* %b
 	br label %.block.1
 .block.1:
-	%2 = load i32, i32* %b
-	%3 = icmp uge i32 %2, 1
-	br i1 %3, label %.block.2, label %.block.3
+	%a.0 = phi i32 [ %0, %.block.0 ], [ %b.0, %.block.2 ]
+	%b.0 = phi i32 [ %1, %.block.0 ], [ %3, %.block.2 ]
+	%temp.0 = phi i32 [ undef, %.block.0 ], [ %3, %.block.2 ]
+	%2 = icmp uge i32 %b.0, 1
+	br i1 %2, label %.block.2, label %.block.3
 .block.2:
-	%4 = load i32, i32* %a
-	%5 = load i32, i32* %b
-	%6 = urem i32 %4, %5
-	%temp = alloca i32
-	store i32 %6, i32* %temp
-	%7 = load i32, i32* %b
-	store i32 %7, i32* %a
-	%8 = load i32, i32* %temp
-	store i32 %8, i32* %b
+	%3 = urem i32 %a.0, %b.0
 	br label %.block.1
 .block.3:
-	%9 = load i32, i32* %a
-	ret i32 %9
+	ret i32 %a.0
 }
 
-define void @"::test_1::aoc_01_p1"() {
+define internal void @_CN6test_19aoc_01_p1E() nounwind {
 .block.0:
-	%0 = call %"::libc::stdio::CFile"*(i8*, i8*) @fopen(i8* bitcast ([17 x i8]* @.const.test_1.0 to i8*), i8* bitcast ([2 x i8]* @.const.test_1.1 to i8*))
-	%input = alloca %"::libc::stdio::CFile"*
-	store %"::libc::stdio::CFile"* %0, %"::libc::stdio::CFile"** %input
-	%1 = load %"::libc::stdio::CFile"*, %"::libc::stdio::CFile"** %input
-	%2 = icmp eq %"::libc::stdio::CFile"* %1, null
-	br i1 %2, label %.block.1, label %.block.2
+	%0 = call ptr(ptr, ptr) @fopen(ptr bitcast (ptr @.const.test_1.0 to ptr), ptr bitcast (ptr @.const.test_1.1 to ptr))
+	%1 = icmp eq ptr %0, null
+	br i1 %1, label %.block.1, label %.block.2
 .block.1:
-	%3 = call i32(i8*) @puts(i8* bitcast ([26 x i8]* @.const.test_1.2 to i8*))
+	%2 = call i32(ptr) @puts(ptr bitcast (ptr @.const.test_1.2 to ptr))
 	ret void
 .block.2:
-	%calibration_sum = alloca i32
-	store i32 0, i32* %calibration_sum
 	%line = alloca [100 x i8]
 	br label %.block.3
 .block.3:
-	%4 = bitcast [100 x i8]* %line to i8*
-	%5 = load %"::libc::stdio::CFile"*, %"::libc::stdio::CFile"** %input
-	%6 = call i8*(i8*, i32, %"::libc::stdio::CFile"*) @fgets(i8* %4, i32 100, %"::libc::stdio::CFile"* %5)
-	%7 = icmp ne i8* %6, null
-	br i1 %7, label %.block.4, label %.block.5
+	%calibration_sum.0 = phi i32 [ 0, %.block.2 ], [ %31, %.block.11 ]
+	%index.1 = phi i64 [ undef, %.block.2 ], [ %index.0, %.block.11 ]
+	%calibration_value.0 = phi i32 [ undef, %.block.2 ], [ %15, %.block.11 ]
+	%calibration_value-1.0 = phi i32 [ undef, %.block.2 ], [ %30, %.block.11 ]
+	%3 = bitcast ptr %line to ptr
+	%4 = call ptr(ptr, i32, ptr) @fgets(ptr %3, i32 100, ptr %0)
+	%5 = icmp ne ptr %4, null
+	br i1 %5, label %.block.4, label %.block.5
 .block.4:
-	%index = alloca i64
-	store i64 0, i64* %index
 	br label %.block.6
 .block.6:
-	%8 = load i64, i64* %index
-	%9 = getelementptr inbounds [100 x i8], [100 x i8]* %line, i32 0, i64 %8
-	%10 = load i8, i8* %9
-	%11 = zext i8 %10 to i32
-	%12 = call i32(i32) @isdigit(i32 %11)
-	%13 = icmp eq i32 %12, 0
-	br i1 %13, label %.block.7, label %.block.8
+	%index.2 = phi i64 [ 0, %.block.4 ], [ %11, %.block.7 ]
+	%6 = getelementptr inbounds [100 x i8], ptr %line, i32 0, i64 %index.2
+	%7 = load i8, ptr %6
+	%8 = zext i8 %7 to i32
+	%9 = call i32(i32) @isdigit(i32 %8)
+	%10 = icmp eq i32 %9, 0
+	br i1 %10, label %.block.7, label %.block.8
 .block.7:
-	%14 = load i64, i64* %index
-	%15 = add nuw i64 %14, 1
-	store i64 %15, i64* %index
+	%11 = add nuw i64 %index.2, 1
 	br label %.block.6
 .block.8:
-	%16 = load i64, i64* %index
-	%17 = getelementptr inbounds [100 x i8], [100 x i8]* %line, i32 0, i64 %16
-	%18 = load i8, i8* %17
-	%19 = sub nuw i8 %18, 48
-	%20 = zext i8 %19 to i32
-	%calibration_value = alloca i32
-	store i32 %20, i32* %calibration_value
-	%21 = bitcast [100 x i8]* %line to i8*
-	%22 = call i64(i8*) @strlen(i8* %21)
-	%23 = sub nuw i64 %22, 1
-	store i64 %23, i64* %index
+	%12 = getelementptr inbounds [100 x i8], ptr %line, i32 0, i64 %index.2
+	%13 = load i8, ptr %12
+	%14 = sub nuw i8 %13, 48
+	%15 = zext i8 %14 to i32
+	%16 = bitcast ptr %line to ptr
+	%17 = call i64(ptr) @strlen(ptr %16)
+	%18 = sub nuw i64 %17, 1
 	br label %.block.9
 .block.9:
-	%24 = load i64, i64* %index
-	%25 = getelementptr inbounds [100 x i8], [100 x i8]* %line, i32 0, i64 %24
-	%26 = load i8, i8* %25
-	%27 = zext i8 %26 to i32
-	%28 = call i32(i32) @isdigit(i32 %27)
-	%29 = icmp eq i32 %28, 0
-	br i1 %29, label %.block.10, label %.block.11
+	%index.0 = phi i64 [ %18, %.block.8 ], [ %24, %.block.10 ]
+	%19 = getelementptr inbounds [100 x i8], ptr %line, i32 0, i64 %index.0
+	%20 = load i8, ptr %19
+	%21 = zext i8 %20 to i32
+	%22 = call i32(i32) @isdigit(i32 %21)
+	%23 = icmp eq i32 %22, 0
+	br i1 %23, label %.block.10, label %.block.11
 .block.10:
-	%30 = load i64, i64* %index
-	%31 = sub nuw i64 %30, 1
-	store i64 %31, i64* %index
+	%24 = sub nuw i64 %index.0, 1
 	br label %.block.9
 .block.11:
-	%32 = load i32, i32* %calibration_value
-	%33 = mul nuw i32 %32, 10
-	%34 = load i64, i64* %index
-	%35 = getelementptr inbounds [100 x i8], [100 x i8]* %line, i32 0, i64 %34
-	%36 = load i8, i8* %35
-	%37 = sub nuw i8 %36, 48
-	%38 = zext i8 %37 to i32
-	%39 = add nuw i32 %33, %38
-	%calibration_value-1 = alloca i32
-	store i32 %39, i32* %calibration_value-1
-	%40 = load i32, i32* %calibration_value-1
-	%41 = load i32, i32* %calibration_sum
-	%42 = add nuw i32 %41, %40
-	store i32 %42, i32* %calibration_sum
+	%25 = mul nuw i32 %15, 10
+	%26 = getelementptr inbounds [100 x i8], ptr %line, i32 0, i64 %index.0
+	%27 = load i8, ptr %26
+	%28 = sub nuw i8 %27, 48
+	%29 = zext i8 %28 to i32
+	%30 = add nuw i32 %25, %29
+	%31 = add nuw i32 %calibration_sum.0, %30
 	br label %.block.3
 .block.5:
-	%43 = load %"::libc::stdio::CFile"*, %"::libc::stdio::CFile"** %input
-	%44 = call i32(%"::libc::stdio::CFile"*) @fclose(%"::libc::stdio::CFile"* %43)
-	%45 = load i32, i32* %calibration_sum
-	%46 = call i32(i8*, ...) @printf(i8* bitcast ([38 x i8]* @.const.test_1.3 to i8*), i32 %45)
+	%32 = call i32(ptr) @fclose(ptr %0)
+	%33 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_1.3 to ptr), i32 %calibration_sum.0)
 	ret void
 }
 
-define void @"::test_1::omg_linked_list"(i8** %0, i64 %1) {
+define internal void @_CN6test_115omg_linked_listE(ptr %0, i64 %1) nounwind {
 .block.0:
-	%values = alloca i8**
-	store i8** %0, i8*** %values
-	%value_count = alloca i64
-	store i64 %1, i64* %value_count
-	%head = alloca %"::test_1::Node"*
-	store %"::test_1::Node"* null, %"::test_1::Node"** %head
-	%index = alloca i64
-	store i64 0, i64* %index
 	br label %.block.1
 .block.1:
-	%2 = load i64, i64* %index
-	%3 = load i64, i64* %value_count
-	%4 = icmp ult i64 %2, %3
-	br i1 %4, label %.block.2, label %.block.3
+	%head.1 = phi ptr [ null, %.block.0 ], [ %4, %.block.2 ]
+	%index.0 = phi i64 [ 0, %.block.0 ], [ %11, %.block.2 ]
+	%node.0 = phi ptr [ undef, %.block.0 ], [ %4, %.block.2 ]
+	%2 = icmp ult i64 %index.0, %1
+	br i1 %2, label %.block.2, label %.block.3
 .block.2:
-	%5 = call i8*(i64) @malloc(i64 16)
-	%6 = bitcast i8* %5 to %"::test_1::Node"*
-	%node = alloca %"::test_1::Node"*
-	store %"::test_1::Node"* %6, %"::test_1::Node"** %node
-	%7 = load %"::test_1::Node"*, %"::test_1::Node"** %node
-	%8 = load i64, i64* %index
-	%9 = load i8**, i8*** %values
-	%10 = getelementptr inbounds i8*, i8** %9, i64 %8
-	%11 = load i8*, i8** %10
-	%12 = load %"::test_1::Node"*, %"::test_1::Node"** %head
-	%13 = alloca %"::test_1::Node"
-	%14 = getelementptr inbounds %"::test_1::Node", %"::test_1::Node"* %13, i32 0, i32 0
-	store i8* %11, i8** %14
-	%15 = getelementptr inbounds %"::test_1::Node", %"::test_1::Node"* %13, i32 0, i32 1
-	store %"::test_1::Node"* %12, %"::test_1::Node"** %15
-	%16 = load %"::test_1::Node", %"::test_1::Node"* %13
-	store %"::test_1::Node" %16, %"::test_1::Node"* %7
-	%17 = load %"::test_1::Node"*, %"::test_1::Node"** %node
-	store %"::test_1::Node"* %17, %"::test_1::Node"** %head
-	%18 = load i64, i64* %index
-	%19 = add nuw i64 %18, 1
-	store i64 %19, i64* %index
+	%3 = call ptr(i64) @malloc(i64 16)
+	%4 = bitcast ptr %3 to ptr
+	%5 = getelementptr inbounds ptr, ptr %0, i64 %index.0
+	%6 = load ptr, ptr %5
+	%7 = alloca %"::test_1::Node"
+	%8 = getelementptr inbounds %"::test_1::Node", ptr %7, i32 0, i32 0
+	store ptr %6, ptr %8
+	%9 = getelementptr inbounds %"::test_1::Node", ptr %7, i32 0, i32 1
+	store ptr %head.1, ptr %9
+	%10 = load %"::test_1::Node", ptr %7
+	store %"::test_1::Node" %10, ptr %4
+	%11 = add nuw i64 %index.0, 1
 	br label %.block.1
 .block.3:
-	%20 = call i32(i8*) @puts(i8* bitcast ([10 x i8]* @.const.test_1.4 to i8*))
+	%12 = call i32(ptr) @puts(ptr bitcast (ptr @.const.test_1.4 to ptr))
 	br label %.block.4
 .block.4:
-	%21 = load %"::test_1::Node"*, %"::test_1::Node"** %head
-	%22 = icmp ne %"::test_1::Node"* %21, null
-	br i1 %22, label %.block.5, label %.block.6
+	%head.0 = phi ptr [ %head.1, %.block.3 ], [ %18, %.block.5 ]
+	%node-1.0 = phi ptr [ undef, %.block.3 ], [ %head.0, %.block.5 ]
+	%13 = icmp ne ptr %head.0, null
+	br i1 %13, label %.block.5, label %.block.6
 .block.5:
-	%23 = load %"::test_1::Node"*, %"::test_1::Node"** %head
-	%node-1 = alloca %"::test_1::Node"*
-	store %"::test_1::Node"* %23, %"::test_1::Node"** %node-1
-	%24 = load %"::test_1::Node"*, %"::test_1::Node"** %node-1
-	%25 = getelementptr inbounds %"::test_1::Node", %"::test_1::Node"* %24, i32 0, i32 0
-	%26 = load i8*, i8** %25
-	%27 = call i32(i8*, ...) @printf(i8* bitcast ([4 x i8]* @.const.test_1.5 to i8*), i8* %26)
-	%28 = load %"::test_1::Node"*, %"::test_1::Node"** %node-1
-	%29 = getelementptr inbounds %"::test_1::Node", %"::test_1::Node"* %28, i32 0, i32 1
-	%30 = load %"::test_1::Node"*, %"::test_1::Node"** %29
-	store %"::test_1::Node"* %30, %"::test_1::Node"** %head
-	%31 = load %"::test_1::Node"*, %"::test_1::Node"** %node-1
-	%32 = bitcast %"::test_1::Node"* %31 to i8*
-	call void(i8*) @free(i8* %32)
+	%14 = getelementptr inbounds %"::test_1::Node", ptr %head.0, i32 0, i32 0
+	%15 = load ptr, ptr %14
+	%16 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_1.5 to ptr), ptr %15)
+	%17 = getelementptr inbounds %"::test_1::Node", ptr %head.0, i32 0, i32 1
+	%18 = load ptr, ptr %17
+	%19 = bitcast ptr %head.0 to ptr
+	call void(ptr) @free(ptr %19)
 	br label %.block.4
 .block.6:
 	ret void
 }
 
-define void @"::test_1::student_stuff"() {
+define internal void @_CN6test_113student_stuffE() nounwind {
 .block.0:
-	%joe_age = alloca i32
-	store i32 97, i32* %joe_age
-	%joe_calculus_grade_before_curve = alloca i32
-	store i32 47, i32* %joe_calculus_grade_before_curve
-	%0 = load i32, i32* %joe_age
-	%1 = load i32, i32* %joe_calculus_grade_before_curve
-	%2 = add nuw i32 %1, 15
-	%3 = alloca [4 x i32]
-	store [4 x i32] [ i32 80, i32 100, i32 92, i32 undef ], [4 x i32]* %3
-	%4 = getelementptr inbounds [4 x i32], [4 x i32]* %3, i32 0, i64 3
-	store i32 %2, i32* %4
-	%5 = load [4 x i32], [4 x i32]* %3
-	%6 = alloca %"::test_1::Student"
-	store %"::test_1::Student" { i8* bitcast ([9 x i8]* @.const.test_1.6 to i8*), i32 undef, [4 x i32] undef }, %"::test_1::Student"* %6
-	%7 = getelementptr inbounds %"::test_1::Student", %"::test_1::Student"* %6, i32 0, i32 1
-	store i32 %0, i32* %7
-	%8 = getelementptr inbounds %"::test_1::Student", %"::test_1::Student"* %6, i32 0, i32 2
-	store [4 x i32] %5, [4 x i32]* %8
-	%9 = load %"::test_1::Student", %"::test_1::Student"* %6
+	%0 = add nuw i32 47, 15
+	%1 = alloca [4 x i32]
+	store [4 x i32] [ i32 80, i32 100, i32 92, i32 undef ], ptr %1
+	%2 = getelementptr inbounds [4 x i32], ptr %1, i32 0, i64 3
+	store i32 %0, ptr %2
+	%3 = load [4 x i32], ptr %1
+	%4 = alloca %"::test_1::Student"
+	store %"::test_1::Student" { ptr bitcast (ptr @.const.test_1.6 to ptr), i32 undef, [4 x i32] undef }, ptr %4
+	%5 = getelementptr inbounds %"::test_1::Student", ptr %4, i32 0, i32 1
+	store i32 97, ptr %5
+	%6 = getelementptr inbounds %"::test_1::Student", ptr %4, i32 0, i32 2
+	store [4 x i32] %3, ptr %6
+	%7 = load %"::test_1::Student", ptr %4
 	%joe = alloca %"::test_1::Student"
-	store %"::test_1::Student" %9, %"::test_1::Student"* %joe
-	%10 = getelementptr inbounds %"::test_1::Student", %"::test_1::Student"* %joe, i32 0, i32 0
-	%11 = load i8*, i8** %10
-	%12 = call i32(i8*, ...) @printf(i8* bitcast ([10 x i8]* @.const.test_1.7 to i8*), i8* %11)
-	%13 = getelementptr inbounds %"::test_1::Student", %"::test_1::Student"* %joe, i32 0, i32 1
-	%14 = load i32, i32* %13
-	%15 = call i32(i8*, ...) @printf(i8* bitcast ([9 x i8]* @.const.test_1.8 to i8*), i32 %14)
-	%16 = getelementptr inbounds %"::test_1::Student", %"::test_1::Student"* %joe, i32 0, i32 2
-	%17 = getelementptr inbounds [4 x i32], [4 x i32]* %16, i32 0, i32 0
-	%18 = load i32, i32* %17
-	%19 = getelementptr inbounds %"::test_1::Student", %"::test_1::Student"* %joe, i32 0, i32 2
-	%20 = getelementptr inbounds [4 x i32], [4 x i32]* %19, i32 0, i32 1
-	%21 = load i32, i32* %20
-	%22 = getelementptr inbounds %"::test_1::Student", %"::test_1::Student"* %joe, i32 0, i32 2
-	%23 = getelementptr inbounds [4 x i32], [4 x i32]* %22, i32 0, i32 2
-	%24 = load i32, i32* %23
-	%25 = getelementptr inbounds %"::test_1::Student", %"::test_1::Student"* %joe, i32 0, i32 2
-	%26 = getelementptr inbounds [4 x i32], [4 x i32]* %25, i32 0, i32 3
-	%27 = load i32, i32* %26
-	%28 = call i32(i8*, ...) @printf(i8* bitcast ([24 x i8]* @.const.test_1.9 to i8*), i32 %18, i32 %21, i32 %24, i32 %27)
+	store %"::test_1::Student" %7, ptr %joe
+	%8 = getelementptr inbounds %"::test_1::Student", ptr %joe, i32 0, i32 0
+	%9 = load ptr, ptr %8
+	%10 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_1.7 to ptr), ptr %9)
+	%11 = getelementptr inbounds %"::test_1::Student", ptr %joe, i32 0, i32 1
+	%12 = load i32, ptr %11
+	%13 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_1.8 to ptr), i32 %12)
+	%14 = getelementptr inbounds %"::test_1::Student", ptr %joe, i32 0, i32 2
+	%15 = getelementptr inbounds [4 x i32], ptr %14, i32 0, i32 0
+	%16 = load i32, ptr %15
+	%17 = getelementptr inbounds %"::test_1::Student", ptr %joe, i32 0, i32 2
+	%18 = getelementptr inbounds [4 x i32], ptr %17, i32 0, i32 1
+	%19 = load i32, ptr %18
+	%20 = getelementptr inbounds %"::test_1::Student", ptr %joe, i32 0, i32 2
+	%21 = getelementptr inbounds [4 x i32], ptr %20, i32 0, i32 2
+	%22 = load i32, ptr %21
+	%23 = getelementptr inbounds %"::test_1::Student", ptr %joe, i32 0, i32 2
+	%24 = getelementptr inbounds [4 x i32], ptr %23, i32 0, i32 3
+	%25 = load i32, ptr %24
+	%26 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_1.9 to ptr), i32 %16, i32 %19, i32 %22, i32 %25)
 	ret void
 }
 
-define %"::std::string::String" @"<i64>::to_string"(i64 %0) {
+define internal %"::std::string::String" @_CN6test_1Xx9to_stringE(i64 %0) nounwind {
 .block.0:
-	%self = alloca i64
-	store i64 %0, i64* %self
-	%1 = call %"::std::string::String"() @"::std::string::String::new"()
+	%1 = call %"::std::string::String"() @_CN3std6string6String3newE()
 	%string = alloca %"::std::string::String"
-	store %"::std::string::String" %1, %"::std::string::String"* %string
-	%2 = load i64, i64* %self
-	%3 = icmp eq i64 %2, 0
-	br i1 %3, label %.block.1, label %.block.2
+	store %"::std::string::String" %1, ptr %string
+	%2 = icmp eq i64 %0, 0
+	br i1 %2, label %.block.1, label %.block.2
 .block.1:
-	call void(%"::std::string::String"*, i8) @"::std::string::String::push"(%"::std::string::String"* %string, i8 48)
+	call void(ptr, i8) @_CN3std6string6String4pushE(ptr %string, i8 48)
 	br label %.block.3
 .block.2:
-	%4 = load i64, i64* %self
-	%5 = icmp slt i64 %4, 0
-	%is_negative = alloca i1
-	store i1 %5, i1* %is_negative
-	%6 = load i1, i1* %is_negative
-	br i1 %6, label %.block.4, label %.block.5
+	%3 = icmp slt i64 %0, 0
+	br i1 %3, label %.block.4, label %.block.5
 .block.4:
-	%7 = load i64, i64* %self
-	%8 = sub nsw i64 0, %7
-	store i64 %8, i64* %self
+	%4 = sub nsw i64 0, %0
 	br label %.block.5
 .block.5:
+	%self.2 = phi i64 [ %0, %.block.2 ], [ %4, %.block.4 ]
 	br label %.block.6
 .block.6:
-	%9 = load i64, i64* %self
-	%10 = icmp ne i64 %9, 0
-	br i1 %10, label %.block.7, label %.block.8
+	%self.0 = phi i64 [ %self.2, %.block.5 ], [ %9, %.block.7 ]
+	%5 = icmp ne i64 %self.0, 0
+	br i1 %5, label %.block.7, label %.block.8
 .block.7:
-	%11 = load i64, i64* %self
-	%12 = srem i64 %11, 10
-	%13 = trunc i64 %12 to i8
-	%14 = add nuw i8 %13, 48
-	call void(%"::std::string::String"*, i64, i8) @"::std::string::String::insert"(%"::std::string::String"* %string, i64 0, i8 %14)
-	%15 = load i64, i64* %self
-	%16 = sdiv i64 %15, 10
-	store i64 %16, i64* %self
+	%6 = srem i64 %self.0, 10
+	%7 = trunc i64 %6 to i8
+	%8 = add nuw i8 %7, 48
+	call void(ptr, i64, i8) @_CN3std6string6String6insertE(ptr %string, i64 0, i8 %8)
+	%9 = sdiv i64 %self.0, 10
 	br label %.block.6
 .block.8:
-	%17 = load i1, i1* %is_negative
-	br i1 %17, label %.block.9, label %.block.10
+	br i1 %3, label %.block.9, label %.block.10
 .block.9:
-	call void(%"::std::string::String"*, i64, i8) @"::std::string::String::insert"(%"::std::string::String"* %string, i64 0, i8 45)
+	call void(ptr, i64, i8) @_CN3std6string6String6insertE(ptr %string, i64 0, i8 45)
 	br label %.block.10
 .block.10:
 	br label %.block.3
 .block.3:
-	%18 = load %"::std::string::String", %"::std::string::String"* %string
-	ret %"::std::string::String" %18
+	%self.1 = phi i64 [ %self.0, %.block.10 ], [ %0, %.block.1 ]
+	%is_negative.0 = phi i1 [ %3, %.block.10 ], [ undef, %.block.1 ]
+	%10 = load %"::std::string::String", ptr %string
+	ret %"::std::string::String" %10
 }
 
-define i32 @main() {
+define i32 @main() nounwind {
 .block.0:
-	call void() @"::test_1::aoc_01_p1"()
-	call void() @"::test_1::student_stuff"()
-	%values = alloca [4 x i8*]
-	store [4 x i8*] [ i8* bitcast ([8 x i8]* @.const.test_1.11 to i8*), i8* bitcast ([8 x i8]* @.const.test_1.12 to i8*), i8* bitcast ([8 x i8]* @.const.test_1.13 to i8*), i8* bitcast ([8 x i8]* @.const.test_1.14 to i8*) ], [4 x i8*]* %values
-	%0 = bitcast [4 x i8*]* %values to i8**
-	call void(i8**, i64) @"::test_1::omg_linked_list"(i8** %0, i64 4)
-	%1 = call %"::std::string::String"() @"::std::string::String::new"()
+	call void() @_CN6test_19aoc_01_p1E()
+	call void() @_CN6test_113student_stuffE()
+	%values = alloca [4 x ptr]
+	store [4 x ptr] [ ptr bitcast (ptr @.const.test_1.11 to ptr), ptr bitcast (ptr @.const.test_1.12 to ptr), ptr bitcast (ptr @.const.test_1.13 to ptr), ptr bitcast (ptr @.const.test_1.14 to ptr) ], ptr %values
+	%0 = bitcast ptr %values to ptr
+	call void(ptr, i64) @_CN6test_115omg_linked_listE(ptr %0, i64 4)
+	%1 = call %"::std::string::String"() @_CN3std6string6String3newE()
 	%string = alloca %"::std::string::String"
-	store %"::std::string::String" %1, %"::std::string::String"* %string
-	call void(%"::std::string::String"*, i8) @"::std::string::String::push"(%"::std::string::String"* %string, i8 72)
-	call void(%"::std::string::String"*, i8) @"::std::string::String::push"(%"::std::string::String"* %string, i8 101)
-	call void(%"::std::string::String"*, i8) @"::std::string::String::push"(%"::std::string::String"* %string, i8 108)
-	call void(%"::std::string::String"*, i8) @"::std::string::String::push"(%"::std::string::String"* %string, i8 108)
-	call void(%"::std::string::String"*, i8) @"::std::string::String::push"(%"::std::string::String"* %string, i8 111)
-	call void(%"::std::string::String"*, i8) @"::std::string::String::push"(%"::std::string::String"* %string, i8 32)
-	call void(%"::std::string::String"*, i8) @"::std::string::String::push"(%"::std::string::String"* %string, i8 119)
-	call void(%"::std::string::String"*, i8) @"::std::string::String::push"(%"::std::string::String"* %string, i8 111)
-	call void(%"::std::string::String"*, i8) @"::std::string::String::push"(%"::std::string::String"* %string, i8 114)
-	call void(%"::std::string::String"*, i8) @"::std::string::String::push"(%"::std::string::String"* %string, i8 108)
-	call void(%"::std::string::String"*, i8) @"::std::string::String::push"(%"::std::string::String"* %string, i8 100)
-	call void(%"::std::string::String"*, i8) @"::std::string::String::push"(%"::std::string::String"* %string, i8 33)
-	call void(%"::std::string::String"*, i8) @"::std::string::String::push"(%"::std::string::String"* %string, i8 0)
-	%2 = call %"::std::string::Str"(%"::std::string::String"*) @"::std::string::String::as_str"(%"::std::string::String"* %string)
+	store %"::std::string::String" %1, ptr %string
+	call void(ptr, i8) @_CN3std6string6String4pushE(ptr %string, i8 72)
+	call void(ptr, i8) @_CN3std6string6String4pushE(ptr %string, i8 101)
+	call void(ptr, i8) @_CN3std6string6String4pushE(ptr %string, i8 108)
+	call void(ptr, i8) @_CN3std6string6String4pushE(ptr %string, i8 108)
+	call void(ptr, i8) @_CN3std6string6String4pushE(ptr %string, i8 111)
+	call void(ptr, i8) @_CN3std6string6String4pushE(ptr %string, i8 32)
+	call void(ptr, i8) @_CN3std6string6String4pushE(ptr %string, i8 119)
+	call void(ptr, i8) @_CN3std6string6String4pushE(ptr %string, i8 111)
+	call void(ptr, i8) @_CN3std6string6String4pushE(ptr %string, i8 114)
+	call void(ptr, i8) @_CN3std6string6String4pushE(ptr %string, i8 108)
+	call void(ptr, i8) @_CN3std6string6String4pushE(ptr %string, i8 100)
+	call void(ptr, i8) @_CN3std6string6String4pushE(ptr %string, i8 33)
+	call void(ptr, i8) @_CN3std6string6String4pushE(ptr %string, i8 0)
+	%2 = call %"::std::string::Str"(ptr) @_CN3std6string6String6as_strE(ptr %string)
 	%str = alloca %"::std::string::Str"
-	store %"::std::string::Str" %2, %"::std::string::Str"* %str
-	%3 = getelementptr inbounds %"::std::string::Str", %"::std::string::Str"* %str, i32 0, i32 0
-	%4 = load i8*, i8** %3
-	%5 = call i32(i8*) @puts(i8* %4)
-	%6 = load %"::std::string::String", %"::std::string::String"* %string
-	call void(%"::std::string::String") @"::std::string::String::del"(%"::std::string::String" %6)
+	store %"::std::string::Str" %2, ptr %str
+	%3 = getelementptr inbounds %"::std::string::Str", ptr %str, i32 0, i32 0
+	%4 = load ptr, ptr %3
+	%5 = call i32(ptr) @puts(ptr %4)
+	%6 = load %"::std::string::String", ptr %string
+	call void(%"::std::string::String") @_CN3std6string6String3delE(%"::std::string::String" %6)
 	%7 = sub nsw i64 0, 12345
-	%8 = call %"::std::string::String"(i64) @"<i64>::to_string"(i64 %7)
+	%8 = call %"::std::string::String"(i64) @_CN6test_1Xx9to_stringE(i64 %7)
 	%number_string = alloca %"::std::string::String"
-	store %"::std::string::String" %8, %"::std::string::String"* %number_string
-	call void(%"::std::string::String"*, i8) @"::std::string::String::push"(%"::std::string::String"* %number_string, i8 0)
-	%9 = getelementptr inbounds %"::std::string::String", %"::std::string::String"* %number_string, i32 0, i32 0
-	%10 = getelementptr inbounds %"::std::string::MutStr", %"::std::string::MutStr"* %9, i32 0, i32 0
-	%11 = load i8*, i8** %10
-	%12 = call i32(i8*, ...) @printf(i8* bitcast ([22 x i8]* @.const.test_1.15 to i8*), i8* %11)
-	%13 = load %"::std::string::String", %"::std::string::String"* %number_string
-	call void(%"::std::string::String") @"::std::string::String::del"(%"::std::string::String" %13)
+	store %"::std::string::String" %8, ptr %number_string
+	call void(ptr, i8) @_CN3std6string6String4pushE(ptr %number_string, i8 0)
+	%9 = getelementptr inbounds %"::std::string::String", ptr %number_string, i32 0, i32 0
+	%10 = getelementptr inbounds %"::std::string::MutStr", ptr %9, i32 0, i32 0
+	%11 = load ptr, ptr %10
+	%12 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_1.15 to ptr), ptr %11)
+	%13 = load %"::std::string::String", ptr %number_string
+	call void(%"::std::string::String") @_CN3std6string6String3delE(%"::std::string::String" %13)
 	ret i32 0
 }
 
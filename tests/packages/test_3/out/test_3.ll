@@ -1,23 +1,16 @@
-source_filename = "\\\\?\\C:\\Users\\seane\\Projects\\compiler\\tests\\packages\\test_3\\main.cupr"
+source_filename = "test_3/main.cupr"
+target datalayout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-i128:128-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
 
-declare i32 @printf(i8*, ...)
+declare i32 @printf(ptr, ...) nounwind
 
 @.const.test_3.0 = private unnamed_addr constant [12 x i8] c"Result: %f\0A\00"
 
-define i32 @main() {
+define i32 @main() nounwind {
 .block.0:
-	%f1 = alloca float
-	store float 0x4008000000000000, float* %f1
-	%f2 = alloca float
-	store float 0x4018000000000000, float* %f2
-	%0 = load float, float* %f1
-	%1 = load float, float* %f2
-	%2 = fadd float %0, %1
-	%f3 = alloca float
-	store float %2, float* %f3
-	%3 = load float, float* %f3
-	%4 = fpext float %3 to double
-	%5 = call i32(i8*, ...) @printf(i8* bitcast ([12 x i8]* @.const.test_3.0 to i8*), double %4)
+	%0 = fadd float 0x4008000000000000, 0x4018000000000000
+	%1 = fpext float %0 to double
+	%2 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_3.0 to ptr), double %1)
 	ret i32 0
 }
 
@@ -0,0 +1,111 @@
+source_filename = "test_attributes/main.cupr"
+target datalayout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-i128:128-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+declare i32 @puts(ptr) nounwind
+
+declare void @exit(i32) noreturn nounwind
+
+declare i32 @printf(ptr, ...) nounwind
+
+declare i32 @putchar(i32)
+
+@.const.test_attributes.0 = private unnamed_addr constant [12 x i8] c"wrong total\00"
+
+@.const.test_attributes.1 = private unnamed_addr constant [10 x i8] c"total: %d\00"
+
+define fastcc i32 @scaled_sum(ptr nonnull readonly %0, i64 %1, i32 %2) nounwind {
+.block.0:
+	br label %.block.1
+.block.1:
+	%total.0 = phi i32 [ 0, %.block.0 ], [ %7, %.block.2 ]
+	%index.0 = phi i64 [ 0, %.block.0 ], [ %8, %.block.2 ]
+	%3 = icmp ult i64 %index.0, %1
+	br i1 %3, label %.block.2, label %.block.3
+.block.2:
+	%4 = getelementptr inbounds i32, ptr %0, i64 %index.0
+	%5 = load i32, ptr %4
+	%6 = mul nsw i32 %5, %2
+	%7 = add nsw i32 %total.0, %6
+	%8 = add nuw i64 %index.0, 1
+	br label %.block.1
+.block.3:
+	ret i32 %total.0
+}
+
+define internal void @_CN15test_attributes11copy_valuesE(ptr noalias nonnull %0, ptr noalias nonnull readonly %1, i64 %2) nounwind {
+.block.0:
+	br label %.block.1
+.block.1:
+	%index.0 = phi i64 [ 0, %.block.0 ], [ %7, %.block.2 ]
+	%3 = icmp ult i64 %index.0, %2
+	br i1 %3, label %.block.2, label %.block.3
+.block.2:
+	%4 = getelementptr inbounds i32, ptr %0, i64 %index.0
+	%5 = getelementptr inbounds i32, ptr %1, i64 %index.0
+	%6 = load i32, ptr %5
+	store i32 %6, ptr %4
+	%7 = add nuw i64 %index.0, 1
+	br label %.block.1
+.block.3:
+	ret void
+}
+
+define internal i32 @_CN15test_attributes8triangleE(i32 %0) nounwind {
+.block.0:
+	%1 = icmp eq i32 %0, 0
+	br i1 %1, label %.block.1, label %.block.2
+.block.1:
+	br label %.block.3
+.block.2:
+	%2 = sub nsw i32 %0, 1
+	%3 = call i32(i32) @_CN15test_attributes8triangleE(i32 %2)
+	%4 = add nsw i32 %0, %3
+	br label %.block.3
+.block.3:
+	%5 = phi i32 [ 0, %.block.1 ], [ %4, %.block.2 ]
+	ret i32 %5
+}
+
+define internal i32 @_CN15test_attributes5applyE(ptr %0, i32 %1) {
+.block.0:
+	%2 = call i32(i32) %0(i32 %1)
+	ret i32 %2
+}
+
+define internal void @_CN15test_attributes4failE(ptr %0) noreturn nounwind {
+.block.0:
+	%1 = call i32(ptr) @puts(ptr %0)
+	call void(i32) @exit(i32 1)
+	unreachable
+}
+
+define i32 @main() {
+.block.0:
+	%values = alloca [4 x i32]
+	store [4 x i32] [ i32 1, i32 2, i32 3, i32 4 ], ptr %values
+	%copied = alloca [4 x i32]
+	store [4 x i32] [ i32 0, i32 0, i32 0, i32 0 ], ptr %copied
+	%0 = bitcast ptr %copied to ptr
+	%1 = bitcast ptr %values to ptr
+	call void(ptr, ptr, i64) @_CN15test_attributes11copy_valuesE(ptr %0, ptr %1, i64 4)
+	%2 = bitcast ptr %copied to ptr
+	%3 = call fastcc i32(ptr, i64, i32) @scaled_sum(ptr %2, i64 4, i32 3)
+	%4 = icmp ne i32 %3, 30
+	br i1 %4, label %.block.2, label %.block.1
+.block.1:
+	%5 = call i32(ptr, i32) @_CN15test_attributes5applyE(ptr @_CN15test_attributes8triangleE, i32 4)
+	%6 = icmp ne i32 %5, 10
+	br label %.block.2
+.block.2:
+	%7 = phi i1 [ true, %.block.0 ], [ %6, %.block.1 ]
+	br i1 %7, label %.block.3, label %.block.4
+.block.3:
+	call void(ptr) @_CN15test_attributes4failE(ptr bitcast (ptr @.const.test_attributes.0 to ptr))
+	unreachable
+.block.4:
+	%8 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_attributes.1 to ptr), i32 %3)
+	%9 = call i32(i32) @putchar(i32 10)
+	ret i32 0
+}
+
@@ -0,0 +1,229 @@
+source_filename = "test_const_eval/main.cupr"
+target datalayout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-i128:128-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+%"::test_const_eval::Limits" = type { i32, i32 }
+
+%"::test_const_eval::Window" = type { [4 x i64] }
+
+declare i32 @printf(ptr, ...) nounwind
+
+@.const.test_const_eval.0 = private unnamed_addr constant [16 x i8] c"limits: %d..%d\0A\00"
+
+@.const.test_const_eval.1 = private unnamed_addr constant [18 x i8] c"window: %ld..%ld\0A\00"
+
+@.const.test_const_eval.2 = private unnamed_addr constant [19 x i8] c"squares: %u %u %u\0A\00"
+
+@.const.test_const_eval.3 = private unnamed_addr constant [20 x i8] c"fibonacci: %ld %ld\0A\00"
+
+@.const.test_const_eval.4 = private unnamed_addr constant [9 x i8] c"sum: %u\0A\00"
+
+@.const.test_const_eval.5 = private unnamed_addr constant [13 x i8] c"length: %zu\0A\00"
+
+@.const.test_const_eval.6 = private unnamed_addr constant [12 x i8] c"table: %ld\0A\00"
+
+@_CN15test_const_eval6tables12FIBONACCI_20E = internal constant i64 6765
+
+@_CN15test_const_eval7SQUARESE = internal constant [8 x i32] [ i32 0, i32 1, i32 4, i32 9, i32 16, i32 25, i32 36, i32 49 ]
+
+@_CN15test_const_eval6WINDOWE = internal constant %"::test_const_eval::Window" { [4 x i64] [ i64 55, i64 89, i64 144, i64 233 ] }
+
+@_CN15test_const_eval15FIBONACCI_TABLEE = internal constant [4 x i64] [ i64 55, i64 89, i64 144, i64 233 ]
+
+@.const.test_const_eval.7 = private unnamed_addr constant [14 x i8] c"Hello, world!\00"
+
+@_CN15test_const_eval15GREETING_LENGTHE = internal constant i64 13
+
+@_CN15test_const_eval6LIMITSE = internal constant %"::test_const_eval::Limits" { i32 5, i32 15 }
+
+@_CN15test_const_eval20DOUBLED_FIBONACCI_20E = internal constant i64 13530
+
+define internal i64 @_CN15test_const_eval13window_lengthE() nounwind {
+.block.0:
+	%0 = call i64(i64) @_CN15test_const_eval9fibonacciE(i64 4)
+	%1 = add nuw i64 %0, 1
+	ret i64 %1
+}
+
+define internal i64 @_CN15test_const_eval9fibonacciE(i64 %0) nounwind {
+.block.0:
+	%1 = icmp slt i64 %0, 2
+	br i1 %1, label %.block.1, label %.block.2
+.block.1:
+	br label %.block.3
+.block.2:
+	%2 = sub nsw i64 %0, 1
+	%3 = call i64(i64) @_CN15test_const_eval9fibonacciE(i64 %2)
+	%4 = sub nsw i64 %0, 2
+	%5 = call i64(i64) @_CN15test_const_eval9fibonacciE(i64 %4)
+	%6 = add nsw i64 %3, %5
+	br label %.block.3
+.block.3:
+	%7 = phi i64 [ %0, %.block.1 ], [ %6, %.block.2 ]
+	ret i64 %7
+}
+
+define internal [8 x i32] @_CN15test_const_eval7squaresE() nounwind {
+.block.0:
+	%table = alloca [8 x i32]
+	br label %.block.1
+.block.1:
+	%index.0 = phi i32 [ 0, %.block.0 ], [ %3, %.block.2 ]
+	%0 = icmp ult i32 %index.0, 8
+	br i1 %0, label %.block.2, label %.block.3
+.block.2:
+	%1 = getelementptr inbounds [8 x i32], ptr %table, i32 0, i32 %index.0
+	%2 = mul nuw i32 %index.0, %index.0
+	store i32 %2, ptr %1
+	%3 = add nuw i32 %index.0, 1
+	br label %.block.1
+.block.3:
+	%4 = load [8 x i32], ptr %table
+	ret [8 x i32] %4
+}
+
+define internal i64 @_CN15test_const_eval13string_lengthE(ptr %0) nounwind {
+.block.0:
+	br label %.block.1
+.block.1:
+	%length.0 = phi i64 [ 0, %.block.0 ], [ %4, %.block.2 ]
+	%1 = getelementptr inbounds i8, ptr %0, i64 %length.0
+	%2 = load i8, ptr %1
+	%3 = icmp ne i8 %2, 0
+	br i1 %3, label %.block.2, label %.block.3
+.block.2:
+	%4 = add nuw i64 %length.0, 1
+	br label %.block.1
+.block.3:
+	ret i64 %length.0
+}
+
+define internal %"::test_const_eval::Limits" @_CN15test_const_eval6limitsE(i32 %0, i32 %1) nounwind {
+.block.0:
+	%2 = sub nsw i32 %0, %1
+	%3 = add nsw i32 %0, %1
+	%4 = alloca %"::test_const_eval::Limits"
+	%5 = getelementptr inbounds %"::test_const_eval::Limits", ptr %4, i32 0, i32 0
+	store i32 %2, ptr %5
+	%6 = getelementptr inbounds %"::test_const_eval::Limits", ptr %4, i32 0, i32 1
+	store i32 %3, ptr %6
+	%7 = load %"::test_const_eval::Limits", ptr %4
+	ret %"::test_const_eval::Limits" %7
+}
+
+define internal void @_CN15test_const_eval12print_limitsE(%"::test_const_eval::Limits" %0) nounwind {
+.block.0:
+	%limits = alloca %"::test_const_eval::Limits"
+	store %"::test_const_eval::Limits" %0, ptr %limits
+	%1 = getelementptr inbounds %"::test_const_eval::Limits", ptr %limits, i32 0, i32 0
+	%2 = load i32, ptr %1
+	%3 = getelementptr inbounds %"::test_const_eval::Limits", ptr %limits, i32 0, i32 1
+	%4 = load i32, ptr %3
+	%5 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_const_eval.0 to ptr), i32 %2, i32 %4)
+	ret void
+}
+
+define internal i64 @_CN15test_const_eval12table_lengthE() nounwind {
+.block.0:
+	%0 = call i64(i64) @_CN15test_const_eval9fibonacciE(i64 6)
+	ret i64 %0
+}
+
+define internal i64 @_CN15test_const_eval20doubled_fibonacci_20E() nounwind {
+.block.0:
+	%0 = load i64, ptr @_CN15test_const_eval6tables12FIBONACCI_20E
+	%1 = mul nsw i64 %0, 2
+	ret i64 %1
+}
+
+define internal %"::test_const_eval::Window" @_CN15test_const_eval16fibonacci_windowE() nounwind {
+.block.0:
+	%0 = call [4 x i64]() @_CN15test_const_eval15fibonacci_tableE()
+	%1 = alloca %"::test_const_eval::Window"
+	%2 = getelementptr inbounds %"::test_const_eval::Window", ptr %1, i32 0, i32 0
+	store [4 x i64] %0, ptr %2
+	%3 = load %"::test_const_eval::Window", ptr %1
+	ret %"::test_const_eval::Window" %3
+}
+
+define internal [4 x i64] @_CN15test_const_eval15fibonacci_tableE() nounwind {
+.block.0:
+	%table = alloca [4 x i64]
+	br label %.block.1
+.block.1:
+	%index.0 = phi i64 [ 0, %.block.0 ], [ %5, %.block.2 ]
+	%0 = call i64() @_CN15test_const_eval13window_lengthE()
+	%1 = icmp ult i64 %index.0, %0
+	br i1 %1, label %.block.2, label %.block.3
+.block.2:
+	%2 = getelementptr inbounds [4 x i64], ptr %table, i32 0, i64 %index.0
+	%3 = add nsw i64 %index.0, 10
+	%4 = call i64(i64) @_CN15test_const_eval9fibonacciE(i64 %3)
+	store i64 %4, ptr %2
+	%5 = add nuw i64 %index.0, 1
+	br label %.block.1
+.block.3:
+	%6 = load [4 x i64], ptr %table
+	ret [4 x i64] %6
+}
+
+define internal void @_CN15test_const_eval12print_windowE(%"::test_const_eval::Window" %0) nounwind {
+.block.0:
+	%window = alloca %"::test_const_eval::Window"
+	store %"::test_const_eval::Window" %0, ptr %window
+	%1 = getelementptr inbounds %"::test_const_eval::Window", ptr %window, i32 0, i32 0
+	%2 = getelementptr inbounds [4 x i64], ptr %1, i32 0, i32 0
+	%3 = load i64, ptr %2
+	%4 = getelementptr inbounds %"::test_const_eval::Window", ptr %window, i32 0, i32 0
+	%5 = call i64() @_CN15test_const_eval13window_lengthE()
+	%6 = sub nuw i64 %5, 1
+	%7 = getelementptr inbounds [4 x i64], ptr %4, i32 0, i64 %6
+	%8 = load i64, ptr %7
+	%9 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_const_eval.1 to ptr), i64 %3, i64 %8)
+	ret void
+}
+
+define i32 @main() nounwind {
+.block.0:
+	%0 = load i32, ptr getelementptr inbounds ([8 x i32], ptr @_CN15test_const_eval7SQUARESE, i32 0, i32 2)
+	%1 = load i32, ptr getelementptr inbounds ([8 x i32], ptr @_CN15test_const_eval7SQUARESE, i32 0, i32 5)
+	%2 = load i32, ptr getelementptr inbounds ([8 x i32], ptr @_CN15test_const_eval7SQUARESE, i32 0, i32 7)
+	%3 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_const_eval.2 to ptr), i32 %0, i32 %1, i32 %2)
+	%4 = load i64, ptr @_CN15test_const_eval6tables12FIBONACCI_20E
+	%5 = load i64, ptr @_CN15test_const_eval20DOUBLED_FIBONACCI_20E
+	%6 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_const_eval.3 to ptr), i64 %4, i64 %5)
+	%7 = call i32() @_CN15test_const_eval14sum_of_squaresE()
+	%8 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_const_eval.4 to ptr), i32 %7)
+	%9 = load i64, ptr @_CN15test_const_eval15GREETING_LENGTHE
+	%10 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_const_eval.5 to ptr), i64 %9)
+	%11 = load %"::test_const_eval::Limits", ptr @_CN15test_const_eval6LIMITSE
+	call void(%"::test_const_eval::Limits") @_CN15test_const_eval12print_limitsE(%"::test_const_eval::Limits" %11)
+	%12 = load %"::test_const_eval::Window", ptr @_CN15test_const_eval6WINDOWE
+	call void(%"::test_const_eval::Window") @_CN15test_const_eval12print_windowE(%"::test_const_eval::Window" %12)
+	%13 = load i64, ptr getelementptr inbounds ([4 x i64], ptr @_CN15test_const_eval15FIBONACCI_TABLEE, i32 0, i32 2)
+	%14 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_const_eval.6 to ptr), i64 %13)
+	ret i32 0
+}
+
+define internal i32 @_CN15test_const_eval14sum_of_squaresE() nounwind {
+.block.0:
+	%0 = call [8 x i32]() @_CN15test_const_eval7squaresE()
+	%table = alloca [8 x i32]
+	store [8 x i32] %0, ptr %table
+	br label %.block.1
+.block.1:
+	%sum.0 = phi i32 [ 0, %.block.0 ], [ %5, %.block.2 ]
+	%index.0 = phi i64 [ 0, %.block.0 ], [ %6, %.block.2 ]
+	%1 = call i64() @_CN15test_const_eval12table_lengthE()
+	%2 = icmp ult i64 %index.0, %1
+	br i1 %2, label %.block.2, label %.block.3
+.block.2:
+	%3 = getelementptr inbounds [8 x i32], ptr %table, i32 0, i64 %index.0
+	%4 = load i32, ptr %3
+	%5 = add nuw i32 %sum.0, %4
+	%6 = add nuw i64 %index.0, 1
+	br label %.block.1
+.block.3:
+	ret i32 %sum.0
+}
+
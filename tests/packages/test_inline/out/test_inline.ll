@@ -0,0 +1,247 @@
+source_filename = "test_inline/main.cupr"
+target datalayout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-i128:128-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+declare i32 @printf(ptr, ...) nounwind
+
+@.const.test_inline.0 = private unnamed_addr constant [14 x i8] c"%s: negative\0A\00"
+
+@.const.test_inline.1 = private unnamed_addr constant [8 x i8] c"%s: %d\0A\00"
+
+@.const.test_inline.2 = private unnamed_addr constant [4 x i8] c"sum\00"
+
+@.const.test_inline.3 = private unnamed_addr constant [11 x i8] c"difference\00"
+
+@.const.test_inline.4 = private unnamed_addr constant [10 x i8] c"factorial\00"
+
+@.const.test_inline.5 = private unnamed_addr constant [5 x i8] c"even\00"
+
+@.const.test_inline.6 = private unnamed_addr constant [10 x i8] c"quadruple\00"
+
+define internal i32 @_CN11test_inline6doubleE(i32 %0) nounwind alwaysinline {
+.block.0:
+	%1 = add nsw i32 %0, %0
+	ret i32 %1
+}
+
+define internal i32 @_CN11test_inline6sum_toE(i32 %0) nounwind alwaysinline {
+.block.0:
+	br label %.block.1
+.block.1:
+	%total.0 = phi i32 [ 0, %.block.0 ], [ %2, %.block.2 ]
+	%i.0 = phi i32 [ 0, %.block.0 ], [ %3, %.block.2 ]
+	%1 = icmp sle i32 %i.0, %0
+	br i1 %1, label %.block.2, label %.block.3
+.block.2:
+	%2 = add nsw i32 %total.0, %i.0
+	%3 = add nsw i32 %i.0, 1
+	br label %.block.1
+.block.3:
+	ret i32 %total.0
+}
+
+define internal void @_CN11test_inline6reportE(ptr %0, i32 %1) nounwind alwaysinline {
+.block.0:
+	%2 = icmp slt i32 %1, 0
+	br i1 %2, label %.block.1, label %.block.2
+.block.1:
+	%3 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_inline.0 to ptr), ptr %0)
+	ret void
+.block.2:
+	%4 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_inline.1 to ptr), ptr %0, i32 %1)
+	ret void
+}
+
+define internal void @_CN11test_inline11store_sevenE(ptr %0) nounwind alwaysinline {
+.block.0:
+	store i32 7, ptr %0
+	ret void
+}
+
+define internal i32 @_CN11test_inline10plus_sevenE(i32 %0) nounwind alwaysinline {
+.block.0:
+	br label %.block.1
+.block.1:
+	br label %.block.2
+.block.2:
+	%1 = add nsw i32 7, %0
+	ret i32 %1
+}
+
+define internal i32 @_CN11test_inline9factorialE(i32 %0) nounwind alwaysinline {
+.block.0:
+	%1 = icmp sle i32 %0, 1
+	br i1 %1, label %.block.1, label %.block.2
+.block.1:
+	ret i32 1
+.block.2:
+	%2 = sub nsw i32 %0, 1
+	%3 = call i32(i32) @_CN11test_inline9factorialE(i32 %2)
+	%4 = mul nsw i32 %0, %3
+	ret i32 %4
+}
+
+define internal i1 @_CN11test_inline7is_evenE(i32 %0) nounwind alwaysinline {
+.block.0:
+	%1 = icmp eq i32 %0, 0
+	br i1 %1, label %.block.1, label %.block.2
+.block.1:
+	br label %.block.3
+.block.2:
+	%2 = sub nsw i32 %0, 1
+	%3 = call i1(i32) @_CN11test_inline6is_oddE(i32 %2)
+	br label %.block.3
+.block.3:
+	%4 = phi i1 [ true, %.block.1 ], [ %3, %.block.2 ]
+	ret i1 %4
+}
+
+define internal i1 @_CN11test_inline6is_oddE(i32 %0) nounwind alwaysinline {
+.block.0:
+	%1 = icmp eq i32 %0, 0
+	br i1 %1, label %.block.1, label %.block.2
+.block.1:
+	br label %.block.3
+.block.2:
+	%2 = sub nsw i32 %0, 1
+	%3 = call i1(i32) @_CN11test_inline7is_evenE(i32 %2)
+	br label %.block.3
+.block.3:
+	%4 = phi i1 [ false, %.block.1 ], [ %3, %.block.2 ]
+	ret i1 %4
+}
+
+define internal i32 @_CN11test_inline9quadrupleE(i32 %0) nounwind noinline {
+.block.0:
+	br label %.block.1
+.block.1:
+	%1 = add nsw i32 %0, %0
+	br label %.block.2
+.block.2:
+	%2 = phi i32 [ %1, %.block.1 ]
+	br label %.block.3
+.block.3:
+	%3 = add nsw i32 %2, %2
+	br label %.block.4
+.block.4:
+	%4 = phi i32 [ %3, %.block.3 ]
+	ret i32 %4
+}
+
+define i32 @main() nounwind {
+.block.0:
+	br label %.block.1
+.block.1:
+	%i.0 = phi i32 [ 0, %.block.0 ], [ %19, %.block.22 ]
+	%0 = icmp slt i32 %i.0, 3
+	br i1 %0, label %.block.2, label %.block.3
+.block.2:
+	br label %.block.4
+.block.4:
+	br label %.block.5
+.block.5:
+	%1 = phi i32 [ 0, %.block.4 ], [ %4, %.block.6 ]
+	%2 = phi i32 [ 0, %.block.4 ], [ %5, %.block.6 ]
+	%3 = icmp sle i32 %2, %i.0
+	br i1 %3, label %.block.6, label %.block.7
+.block.6:
+	%4 = add nsw i32 %1, %2
+	%5 = add nsw i32 %2, 1
+	br label %.block.5
+.block.7:
+	br label %.block.8
+.block.8:
+	%6 = phi i32 [ %1, %.block.7 ]
+	br label %.block.9
+.block.9:
+	%7 = add nsw i32 %i.0, %i.0
+	br label %.block.10
+.block.10:
+	%8 = phi i32 [ %7, %.block.9 ]
+	%9 = add nsw i32 %6, %8
+	br label %.block.11
+.block.11:
+	%10 = icmp slt i32 %9, 0
+	br i1 %10, label %.block.12, label %.block.13
+.block.12:
+	%11 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_inline.0 to ptr), ptr bitcast (ptr @.const.test_inline.2 to ptr))
+	br label %.block.14
+.block.13:
+	%12 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_inline.1 to ptr), ptr bitcast (ptr @.const.test_inline.2 to ptr), i32 %9)
+	br label %.block.14
+.block.14:
+	br label %.block.15
+.block.15:
+	br label %.block.16
+.block.16:
+	br label %.block.17
+.block.17:
+	%13 = add nsw i32 7, %i.0
+	br label %.block.18
+.block.18:
+	%14 = phi i32 [ %13, %.block.17 ]
+	%15 = sub nsw i32 0, %14
+	br label %.block.19
+.block.19:
+	%16 = icmp slt i32 %15, 0
+	br i1 %16, label %.block.20, label %.block.21
+.block.20:
+	%17 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_inline.0 to ptr), ptr bitcast (ptr @.const.test_inline.3 to ptr))
+	br label %.block.22
+.block.21:
+	%18 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_inline.1 to ptr), ptr bitcast (ptr @.const.test_inline.3 to ptr), i32 %15)
+	br label %.block.22
+.block.22:
+	%19 = add nsw i32 %i.0, 1
+	br label %.block.1
+.block.3:
+	%20 = call i32(i32) @_CN11test_inline9factorialE(i32 5)
+	br label %.block.23
+.block.23:
+	%21 = icmp slt i32 %20, 0
+	br i1 %21, label %.block.24, label %.block.25
+.block.24:
+	%22 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_inline.0 to ptr), ptr bitcast (ptr @.const.test_inline.4 to ptr))
+	br label %.block.26
+.block.25:
+	%23 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_inline.1 to ptr), ptr bitcast (ptr @.const.test_inline.4 to ptr), i32 %20)
+	br label %.block.26
+.block.26:
+	%24 = call i1(i32) @_CN11test_inline7is_evenE(i32 4)
+	%25 = zext i1 %24 to i32
+	br label %.block.27
+.block.27:
+	%26 = icmp slt i32 %25, 0
+	br i1 %26, label %.block.28, label %.block.29
+.block.28:
+	%27 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_inline.0 to ptr), ptr bitcast (ptr @.const.test_inline.5 to ptr))
+	br label %.block.30
+.block.29:
+	%28 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_inline.1 to ptr), ptr bitcast (ptr @.const.test_inline.5 to ptr), i32 %25)
+	br label %.block.30
+.block.30:
+	br label %.block.31
+.block.31:
+	br label %.block.32
+.block.32:
+	br label %.block.33
+.block.33:
+	%29 = add nsw i32 7, 1
+	br label %.block.34
+.block.34:
+	%30 = phi i32 [ %29, %.block.33 ]
+	%31 = call i32(i32) @_CN11test_inline9quadrupleE(i32 %30)
+	br label %.block.35
+.block.35:
+	%32 = icmp slt i32 %31, 0
+	br i1 %32, label %.block.36, label %.block.37
+.block.36:
+	%33 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_inline.0 to ptr), ptr bitcast (ptr @.const.test_inline.6 to ptr))
+	br label %.block.38
+.block.37:
+	%34 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_inline.1 to ptr), ptr bitcast (ptr @.const.test_inline.6 to ptr), i32 %31)
+	br label %.block.38
+.block.38:
+	ret i32 0
+}
+
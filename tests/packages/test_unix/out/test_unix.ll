@@ -1,12 +1,14 @@
-source_filename = "\\\\?\\C:\\Users\\seane\\Projects\\compiler\\tests\\packages\\test_unix\\main.cupr"
+source_filename = "test_unix/main.cupr"
+target datalayout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-i128:128-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
 
 declare i32 @getpid()
 
-declare i32 @printf(i8*, ...)
+declare i32 @printf(ptr, ...) nounwind
 
 declare i32 @fork()
 
-declare i32 @wait(i32*)
+declare i32 @wait(ptr)
 
 @.const.test_unix.0 = private unnamed_addr constant [32 x i8] c"1. I am the parent with PID %d\0A\00"
 
@@ -19,31 +21,21 @@ declare i32 @wait(i32*)
 define i32 @main() {
 .block.0:
 	%0 = call i32() @getpid()
-	%parent_pid = alloca i32
-	store i32 %0, i32* %parent_pid
-	%1 = load i32, i32* %parent_pid
-	%2 = call i32(i8*, ...) @printf(i8* bitcast ([32 x i8]* @.const.test_unix.0 to i8*), i32 %1)
-	%3 = call i32() @fork()
-	%child_pid = alloca i32
-	store i32 %3, i32* %child_pid
-	%4 = load i32, i32* %child_pid
-	%5 = icmp eq i32 %4, 0
-	br i1 %5, label %.block.1, label %.block.2
+	%1 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_unix.0 to ptr), i32 %0)
+	%2 = call i32() @fork()
+	%3 = icmp eq i32 %2, 0
+	br i1 %3, label %.block.1, label %.block.2
 .block.1:
-	%6 = call i32() @getpid()
-	%child_pid-1 = alloca i32
-	store i32 %6, i32* %child_pid-1
-	%7 = load i32, i32* %child_pid-1
-	%8 = call i32(i8*, ...) @printf(i8* bitcast ([31 x i8]* @.const.test_unix.1 to i8*), i32 %7)
-	%9 = load i32, i32* %parent_pid
-	%10 = call i32(i8*, ...) @printf(i8* bitcast ([25 x i8]* @.const.test_unix.2 to i8*), i32 %9)
+	%4 = call i32() @getpid()
+	%5 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_unix.1 to ptr), i32 %4)
+	%6 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_unix.2 to ptr), i32 %0)
 	br label %.block.3
 .block.2:
-	%11 = call i32(i32*) @wait(i32* null)
-	%12 = load i32, i32* %child_pid
-	%13 = call i32(i8*, ...) @printf(i8* bitcast ([24 x i8]* @.const.test_unix.3 to i8*), i32 %12)
+	%7 = call i32(ptr) @wait(ptr null)
+	%8 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_unix.3 to ptr), i32 %2)
 	br label %.block.3
 .block.3:
+	%child_pid-1.0 = phi i32 [ undef, %.block.2 ], [ %4, %.block.1 ]
 	ret i32 0
 }
 
@@ -0,0 +1,88 @@
+source_filename = "test_visibility/main.cupr"
+target datalayout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-i128:128-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-unknown-linux-gnu"
+
+%"::test_visibility::counter::Counter" = type { i32, i32 }
+
+declare i32 @printf(ptr, ...) nounwind
+
+@_CN15test_visibility7counter5LIMITE = internal constant i32 10
+
+@.const.test_visibility.0 = private unnamed_addr constant [22 x i8] c"count: %d (limit %d)\0A\00"
+
+define internal %"::test_visibility::counter::Counter" @_CN15test_visibility7counter7Counter3newE(i32 %0) nounwind {
+.block.0:
+	%1 = alloca %"::test_visibility::counter::Counter"
+	store %"::test_visibility::counter::Counter" { i32 undef, i32 0 }, ptr %1
+	%2 = getelementptr inbounds %"::test_visibility::counter::Counter", ptr %1, i32 0, i32 0
+	store i32 %0, ptr %2
+	%3 = load %"::test_visibility::counter::Counter", ptr %1
+	ret %"::test_visibility::counter::Counter" %3
+}
+
+define internal void @_CN15test_visibility7counter7Counter7advanceE(ptr %0) nounwind {
+.block.0:
+	%1 = getelementptr inbounds %"::test_visibility::counter::Counter", ptr %0, i32 0, i32 1
+	%2 = getelementptr inbounds %"::test_visibility::counter::Counter", ptr %0, i32 0, i32 1
+	%3 = load i32, ptr %2
+	%4 = getelementptr inbounds %"::test_visibility::counter::Counter", ptr %0, i32 0, i32 0
+	%5 = load i32, ptr %4
+	%6 = add nsw i32 %3, %5
+	%7 = call i32(i32) @_CN15test_visibility7counter5clampE(i32 %6)
+	store i32 %7, ptr %1
+	ret void
+}
+
+define internal i32 @_CN15test_visibility7counter7Counter5countE(ptr %0) nounwind {
+.block.0:
+	%1 = getelementptr inbounds %"::test_visibility::counter::Counter", ptr %0, i32 0, i32 1
+	%2 = load i32, ptr %1
+	ret i32 %2
+}
+
+define internal i32 @_CN15test_visibility7counter5clampE(i32 %0) nounwind {
+.block.0:
+	%1 = load i32, ptr @_CN15test_visibility7counter5LIMITE
+	%2 = icmp sgt i32 %0, %1
+	br i1 %2, label %.block.1, label %.block.2
+.block.1:
+	%3 = load i32, ptr @_CN15test_visibility7counter5LIMITE
+	br label %.block.3
+.block.2:
+	br label %.block.3
+.block.3:
+	%4 = phi i32 [ %3, %.block.1 ], [ %0, %.block.2 ]
+	ret i32 %4
+}
+
+define internal void @_CN15test_visibility7counter6report5printE(ptr %0) nounwind {
+.block.0:
+	%1 = getelementptr inbounds %"::test_visibility::counter::Counter", ptr %0, i32 0, i32 1
+	%2 = load i32, ptr %1
+	%3 = load i32, ptr @_CN15test_visibility7counter5LIMITE
+	%4 = call i32(ptr, ...) @printf(ptr bitcast (ptr @.const.test_visibility.0 to ptr), i32 %2, i32 %3)
+	ret void
+}
+
+define internal void @_CN15test_visibility3runE(i32 %0) nounwind {
+.block.0:
+	%1 = call %"::test_visibility::counter::Counter"(i32) @_CN15test_visibility7counter7Counter3newE(i32 %0)
+	%counter = alloca %"::test_visibility::counter::Counter"
+	store %"::test_visibility::counter::Counter" %1, ptr %counter
+	call void(ptr) @_CN15test_visibility7counter7Counter7advanceE(ptr %counter)
+	call void(ptr) @_CN15test_visibility7counter7Counter7advanceE(ptr %counter)
+	%2 = getelementptr inbounds %"::test_visibility::counter::Counter", ptr %counter, i32 0, i32 0
+	%3 = call i32(ptr) @_CN15test_visibility7counter7Counter5countE(ptr %counter)
+	store i32 %3, ptr %2
+	call void(ptr) @_CN15test_visibility7counter7Counter7advanceE(ptr %counter)
+	call void(ptr) @_CN15test_visibility7counter6report5printE(ptr %counter)
+	ret void
+}
+
+define i32 @main() nounwind {
+.block.0:
+	call void(i32) @_CN15test_visibility3runE(i32 2)
+	call void(i32) @_CN15test_visibility3runE(i32 3)
+	ret i32 0
+}
+
@@ -29,3 +29,44 @@ fn test_unix() {
 fn test_collections() {
     common::test_compile_package("test_collections");
 }
+
+#[test]
+fn test_const_eval() {
+    common::test_compile_package("test_const_eval");
+}
+
+#[test]
+fn test_inline() {
+    common::test_compile_package("test_inline");
+}
+
+#[test]
+fn hello_executable() {
+    if let Some(output) = common::run_package_executable("hello") {
+        assert_eq!(output, "Hello world!\n");
+    }
+}
+
+#[test]
+fn test_attributes() {
+    common::test_compile_package("test_attributes");
+}
+
+#[test]
+fn attributes_executable() {
+    if let Some(output) = common::run_package_executable("test_attributes") {
+        assert_eq!(output, "total: 30\n");
+    }
+}
+
+#[test]
+fn test_visibility() {
+    common::test_compile_package("test_visibility");
+}
+
+#[test]
+fn visibility_executable() {
+    if let Some(output) = common::run_package_executable("test_visibility") {
+        assert_eq!(output, "count: 8 (limit 10)\ncount: 10 (limit 10)\n");
+    }
+}
@@ -0,0 +1,117 @@
+//! Checks the C source emitted with `--backend c`.
+
+mod common;
+
+use std::path::Path;
+use std::process::Command;
+
+fn compile_with_c_backend(package_name: &str, out_dir: &Path, extra_args: &[&str]) {
+    let mut args = vec!["--backend", "c"];
+    args.extend(extra_args);
+    common::compile_package(format!("tests/packages/{package_name}"), Some(out_dir), &args).unwrap();
+}
+
+fn is_cc_available() -> bool {
+    Command::new("cc").arg("--version").output().is_ok_and(|output| output.status.success())
+}
+
+#[test]
+fn c_backend_output() {
+    let out_dir = common::temp_out_dir("c-backend");
+    compile_with_c_backend("test_collections", &out_dir, &[]);
+
+    let output = std::fs::read_to_string(out_dir.join("test_collections.c")).unwrap();
+    assert!(output.contains("#include <stdint.h>"));
+    assert!(output.contains("typedef struct cupric_S"));
+    assert!(output.contains("goto b"));
+    assert!(!output.contains("<ERROR"), "invalid C output:\n{output}");
+    assert!(out_dir.join("std.c").exists());
+    assert!(!out_dir.join("test_collections.ll").exists());
+
+    // Have the C compiler check the output if it is available
+    if is_cc_available() {
+        for entry in std::fs::read_dir(&out_dir).unwrap() {
+            let source_path = entry.unwrap().path();
+            if source_path.is_dir() {
+                continue;
+            }
+            let cc_output = Command::new("cc")
+                .args(["-std=c99", "-fsyntax-only", "-Werror=implicit-function-declaration"])
+                .arg(&source_path)
+                .output()
+                .unwrap();
+            assert!(
+                cc_output.status.success(),
+                "cc rejected '{}':\n{}",
+                source_path.display(),
+                String::from_utf8_lossy(&cc_output.stderr),
+            );
+        }
+    }
+
+    std::fs::remove_dir_all(&out_dir).unwrap();
+}
+
+#[test]
+fn c_backend_executable() {
+    if !is_cc_available() {
+        return;
+    }
+
+    let out_dir = common::temp_out_dir("c-backend-exe");
+    compile_with_c_backend("test_collections", &out_dir, &["--emit", "exe"]);
+
+    let executable_path = out_dir.join("test_collections").with_extension(std::env::consts::EXE_EXTENSION);
+    let output = Command::new(&executable_path).output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("push: 1\npush: 2\npush: 3\npush: 4\npush: 5\npop: 5\n"), "unexpected output:\n{stdout}");
+
+    std::fs::remove_dir_all(&out_dir).unwrap();
+}
+
+#[test]
+fn c_backend_signed_overflow_wraps() {
+    let root = common::temp_out_dir("c-backend-overflow");
+    let source = r#"foreign function printf(format: *[u8], ..) -> i32;
+
+noinline function add(a: i32, b: i32) -> i32 { a + b }
+noinline function subtract(a: i32, b: i32) -> i32 { a - b }
+noinline function multiply(a: i32, b: i32) -> i32 { a * b }
+noinline function shift_left(a: i32, b: i32) -> i32 { a << b }
+noinline function negate(a: i32) -> i32 { -a }
+
+foreign function main() -> i32 {
+    let max: i32 = 2147483647;
+    let min = -max - 1;
+    printf("%d %d %d %d %d\n", add(max, 1), subtract(min, 1), multiply(max, 2), shift_left(-1, 4), negate(min));
+    0
+}
+"#;
+    common::write_package(&root, "overflow", "exe", &[], source);
+    let out_dir = root.join("out");
+    common::compile_package(root.join("overflow"), Some(&out_dir), &["--backend", "c"]).unwrap();
+
+    // Signed arithmetic is done on unsigned values and converted back
+    let output = std::fs::read_to_string(out_dir.join("overflow.c")).unwrap();
+    assert!(!output.lines().any(|line| line.contains("(int32_t)(") && !line.contains("uint64_t")), "signed arithmetic in C:\n{output}");
+
+    // With `-ftrapv`, any signed overflow left in the output aborts the program
+    if is_cc_available() {
+        let executable_path = out_dir.join("overflow").with_extension(std::env::consts::EXE_EXTENSION);
+        let cc_output = Command::new("cc")
+            .args(["-std=c99", "-ftrapv", "-o"])
+            .arg(&executable_path)
+            .arg(out_dir.join("overflow.c"))
+            .output()
+            .unwrap();
+        assert!(cc_output.status.success(), "cc rejected output:\n{}", String::from_utf8_lossy(&cc_output.stderr));
+
+        let output = Command::new(&executable_path).output().unwrap();
+        assert!(output.status.success(), "executable exited with {}", output.status);
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "-2147483648 2147483647 -2 -16 -2147483648\n");
+    }
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,52 @@
+//! Checks the textual dump of the compiler's own IR written with `--emit cupric-ir`.
+
+mod common;
+
+use std::path::Path;
+
+fn compile_to_cupric_ir(package_name: &str, out_dir: &Path) -> String {
+    let package_path = format!("tests/packages/{package_name}");
+    common::compile_package(package_path, Some(out_dir), &["--emit", "cupric-ir", "--target", "x86_64-unknown-linux-gnu"]).unwrap();
+
+    std::fs::read_to_string(out_dir.join(format!("{package_name}.cir"))).unwrap()
+}
+
+#[test]
+fn cupric_ir_output() {
+    let out_dir = common::temp_out_dir("ir-dump");
+    let output = compile_to_cupric_ir("test_collections", &out_dir);
+
+    assert!(output.contains("type ::test_collections::LinkedList\n"));
+    assert!(output.contains("external function @printf: function(*[u8], ..) -> i32\n"));
+    assert!(output.contains("function @_CN16test_collectionsXi3cmpE(%0: *i32, %1: *i32) -> i32 {\n.block.0:\n"));
+    assert!(output.contains("    %2: i32 = Load *i32 %0\n"));
+    assert!(output.contains("    %4: bool = CompareLessThan i32 %2, i32 %3\n"));
+    assert!(output.contains("    ConditionalBranch bool %4, .block.1, .block.2\n"));
+    assert!(output.contains("    %tree: *mut ::test_collections::AVLTree = StackAllocate\n"));
+    assert!(output.contains(" = Phi [i32 1 from .block.4], [i32 0 from .block.5]\n"));
+    assert!(out_dir.join("std.cir").exists());
+    assert!(!out_dir.join("test_collections.ll").exists());
+
+    std::fs::remove_dir_all(&out_dir).unwrap();
+}
+
+#[test]
+fn cupric_ir_inlines_functions() {
+    let out_dir = common::temp_out_dir("ir-inline");
+    let output = compile_to_cupric_ir("test_inline", &out_dir);
+
+    assert!(output.contains("inline function @_CN11test_inline6doubleE(%0: i32) -> i32 {\n"));
+    assert!(output.contains("noinline function @_CN11test_inline9quadrupleE(%0: i32) -> i32 {\n"));
+    for function in ["double", "sum_to", "report", "store_seven", "plus_seven"] {
+        let callee = format!("@_CN11test_inline{}{function}E(", function.len());
+        assert!(!output.lines().any(|line| line.contains(" Call ") && line.contains(&callee)), "'{function}' was not inlined");
+    }
+    // Recursive functions and functions marked `noinline` are still called
+    assert!(output.contains(" = Call function(i32) -> i32 @_CN11test_inline9factorialE(i32 5)\n"));
+    assert!(output.contains(" = Call function(i32) -> bool @_CN11test_inline7is_evenE(i32 4)\n"));
+    assert!(output.contains(" = Call function(i32) -> i32 @_CN11test_inline9quadrupleE("));
+    // Stack allocations made by inlined functions are promoted in the caller
+    assert!(!output.contains("StackAllocate"));
+
+    std::fs::remove_dir_all(&out_dir).unwrap();
+}
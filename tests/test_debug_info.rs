@@ -0,0 +1,56 @@
+//! Checks the debug info metadata emitted with `-g`.
+
+mod common;
+
+use std::path::Path;
+
+/// Compile `package_name` with debug info and return its LLVM IR. The IR is also compiled to an
+/// object file, which has LLVM verify the metadata, unless no external toolchain is available.
+fn compile_with_debug_info(package_name: &str, out_dir: &Path) -> String {
+    let package_path = format!("tests/packages/{package_name}");
+    let args = ["-g", "--target", "x86_64-unknown-linux-gnu"];
+    let result = common::compile_package(&package_path, Some(out_dir), &[&args[..], &["--emit", "obj"]].concat());
+    match result {
+        Ok(_) => assert!(out_dir.join(format!("{package_name}.o")).exists()),
+        Err(error) if matches!(error.error.kind(), cupric::ErrorKind::MissingToolchain { .. }) => {
+            println!("skipping object file: {}", error.error);
+            common::compile_package(&package_path, Some(out_dir), &args).unwrap();
+        }
+        Err(error) => panic!("failed to compile with debug info:\n{error:?}"),
+    }
+
+    std::fs::read_to_string(out_dir.join(format!("{package_name}.ll"))).unwrap()
+}
+
+#[test]
+fn debug_info_metadata() {
+    let out_dir = common::temp_out_dir("debug-info");
+    let output = compile_with_debug_info("test_collections", &out_dir);
+
+    assert!(output.contains("!llvm.dbg.cu = !{!0}"));
+    assert!(output.contains("distinct !DICompileUnit("));
+    assert!(output.contains("!DIFile(filename: \"main.cupr\""));
+
+    // Every function definition has a subprogram and every instruction a location
+    let mut in_function = false;
+    for line in output.lines() {
+        if line.starts_with("define ") {
+            assert!(line.contains(" !dbg !"), "function without subprogram: {line}");
+            in_function = true;
+        }
+        else if line == "}" {
+            in_function = false;
+        }
+        else if in_function && line.starts_with('\t') && !line.contains(" = phi ") {
+            assert!(line.contains(", !dbg !"), "instruction without location: {line}");
+        }
+    }
+
+    // Parameters and let bindings are declared as variables
+    assert!(output.contains("call void @llvm.dbg.declare(metadata ptr %self, metadata !"));
+    assert!(output.contains("!DILocalVariable(name: \"self\", arg: 1, "));
+    assert!(output.contains("!DILocalVariable(name: \"new_node\", scope: "));
+    assert!(output.contains("distinct !DICompositeType(tag: DW_TAG_structure_type, name: \"test_collections::LinkedList\""));
+
+    std::fs::remove_dir_all(&out_dir).unwrap();
+}
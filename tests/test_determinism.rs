@@ -0,0 +1,55 @@
+//! Compiles every package under `tests/packages` twice and checks that the output is identical
+//! byte-for-byte.
+
+mod common;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+fn compile_into(package_path: &Path, out_dir: &Path) -> BTreeMap<PathBuf, Vec<u8>> {
+    common::compile_package(package_path, Some(out_dir), &[]).unwrap();
+
+    std::fs::read_dir(out_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_file())
+        .map(|path| {
+            let content = std::fs::read(&path).unwrap();
+            (path.strip_prefix(out_dir).unwrap().to_owned(), content)
+        })
+        .collect()
+}
+
+#[test]
+fn output_is_deterministic() {
+    let mut package_paths: Vec<PathBuf> = std::fs::read_dir("tests/packages")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.join("package.toml").is_file())
+        .collect();
+    package_paths.sort();
+    assert!(!package_paths.is_empty());
+
+    let temp_dir = common::temp_out_dir("determinism");
+    for package_path in &package_paths {
+        let package_name = package_path.file_name().unwrap().to_string_lossy();
+        let first = compile_into(package_path, &temp_dir.join(format!("{package_name}-1")));
+        let second = compile_into(package_path, &temp_dir.join(format!("{package_name}-2")));
+
+        assert!(!first.is_empty(), "no output for package '{package_name}'");
+        assert_eq!(
+            first.keys().collect::<Vec<_>>(),
+            second.keys().collect::<Vec<_>>(),
+            "package '{package_name}' produced different output files",
+        );
+        for (file_name, content) in &first {
+            assert!(
+                *content == second[file_name],
+                "package '{package_name}' produced different bytes for '{}' between runs",
+                file_name.display(),
+            );
+        }
+    }
+
+    std::fs::remove_dir_all(&temp_dir).unwrap();
+}
@@ -0,0 +1,173 @@
+//! Checks that packages are loaded from their interface headers when nothing recorded in their
+//! build manifest has changed, and compiled from source again when something has.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+
+fn files_in(dir: &Path) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            paths.extend(files_in(&path));
+        }
+        else {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// Copy the sources of the package `tests/packages/{package_name}` into `root`.
+fn copy_package(package_name: &str, root: &Path) {
+    let source_dir = Path::new("tests/packages").join(package_name);
+    for source_path in files_in(&source_dir) {
+        let relative_path = source_path.strip_prefix(&source_dir).unwrap();
+        if relative_path.starts_with("out") {
+            continue;
+        }
+        let destination_path = root.join(package_name).join(relative_path);
+        std::fs::create_dir_all(destination_path.parent().unwrap()).unwrap();
+        std::fs::copy(&source_path, &destination_path).unwrap();
+    }
+}
+
+/// The message reported when a package calls into a dependency at compile time which was loaded
+/// from its interface headers, so everything is compiled again.
+const RESTART_MESSAGE: &str = "is needed at compile time, building every package from source...";
+
+/// Compile the package at `package_path` with the additional arguments `extra_args`, returning
+/// the names of the packages which were loaded from their interface headers.
+fn compile(package_path: &Path, extra_args: &[&str]) -> Vec<String> {
+    interface_packages(&common::compile_package(package_path, None, extra_args).unwrap())
+}
+
+/// Get the names of the packages which were loaded from their interface headers from the status
+/// `messages` of a compilation.
+fn interface_packages(messages: &[String]) -> Vec<String> {
+    let mut current_package = None;
+    let mut interface_packages = Vec::new();
+    for message in messages {
+        if let Some(name) = message.strip_prefix("--- Compiling package '").and_then(|rest| rest.strip_suffix("' ---")) {
+            current_package = Some(name.to_owned());
+        }
+        else if message.starts_with("Loading interface from ") {
+            interface_packages.push(current_package.clone().unwrap());
+        }
+        else if message.ends_with(RESTART_MESSAGE) {
+            // Everything is compiled again, so only the last attempt counts
+            interface_packages.clear();
+        }
+    }
+    interface_packages
+}
+
+#[test]
+fn unchanged_packages_load_from_interface() {
+    let root = common::temp_out_dir("interface");
+    for package_name in ["libc", "std", "test_1"] {
+        copy_package(package_name, &root);
+    }
+    let package_path = root.join("test_1");
+    let output_path = package_path.join("out/test_1.ll");
+    let libc_source_path = root.join("libc/main.cupr");
+    let append_to_libc = |text: &str| {
+        let mut libc_source = std::fs::read_to_string(&libc_source_path).unwrap();
+        libc_source.push_str(text);
+        std::fs::write(&libc_source_path, libc_source).unwrap();
+    };
+
+    assert_eq!(compile(&package_path, &[]), Vec::<String>::new());
+    assert!(root.join("libc/out/headers/stdio/module.cuprh").is_file());
+    assert!(root.join("std/out/headers/string/module.cuprh").is_file());
+    assert!(root.join("std/out/std.manifest").is_file());
+    let first_output = std::fs::read_to_string(&output_path).unwrap();
+
+    assert_eq!(compile(&package_path, &[]), ["libc", "std", "test_1"]);
+    assert_eq!(std::fs::read_to_string(&output_path).unwrap(), first_output);
+
+    // Changing the sources of libc without affecting its interface only recompiles libc
+    append_to_libc("\n// A comment\n");
+    assert_eq!(compile(&package_path, &[]), ["std", "test_1"]);
+
+    // Changing the interface of libc also recompiles every package depending on it
+    append_to_libc("\npub foreign function abs(value: i32) -> i32;\n");
+    assert_eq!(compile(&package_path, &[]), Vec::<String>::new());
+    let libc_header = std::fs::read_to_string(root.join("libc/out/headers/module.cuprh")).unwrap();
+    assert!(libc_header.contains("pub foreign function abs(value: i32) -> i32;\n"), "{libc_header}");
+
+    // Changing options which affect the output recompiles everything
+    assert_eq!(compile(&package_path, &["-g"]), Vec::<String>::new());
+    assert_eq!(compile(&package_path, &["-g"]), ["libc", "std", "test_1"]);
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn interface_declares_without_defining() {
+    let root = common::temp_out_dir("interface-text");
+    for package_name in ["libc", "test_const_eval"] {
+        copy_package(package_name, &root);
+    }
+
+    compile(&root.join("test_const_eval"), &[]);
+    let header = std::fs::read_to_string(root.join("test_const_eval/out/headers/module.cuprh")).unwrap();
+    let tables_header = std::fs::read_to_string(root.join("test_const_eval/out/headers/tables/module.cuprh")).unwrap();
+
+    assert!(header.contains("function fibonacci(n: i64) -> i64;\n"), "{header}");
+    assert!(header.contains("struct Limits {\n    min: i32,\n    max: i32,\n}\n"), "{header}");
+    assert!(header.contains("let LIMITS: ::test_const_eval::Limits;\n"), "{header}");
+    assert!(header.contains("module tables;\n"), "{header}");
+    assert_eq!(tables_header, "pub let FIBONACCI_20: i64;\n");
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn constant_calls_into_unchanged_dependencies() {
+    let root = common::temp_out_dir("interface-calls");
+    common::write_package(&root, "cdep", "lib", &[], "pub function seven() -> i32 {\n    7\n}\n");
+    let exe_source = "let X: i32 = cdep::seven();\n\nforeign function main() -> i32 {\n    X\n}\n";
+    common::write_package(&root, "cexe", "exe", &["cdep"], exe_source);
+    let package_path = root.join("cexe");
+    let output_path = package_path.join("out/cexe.ll");
+
+    assert_eq!(compile(&package_path, &[]), Vec::<String>::new());
+    let clean_output = std::fs::read_to_string(&output_path).unwrap();
+
+    // Rebuilding only the executable still needs the body of `seven`. The manifest of cexe says
+    // so, so cdep is built from source without compiling anything twice
+    std::fs::write(package_path.join("main.cupr"), format!("{exe_source}// A comment\n")).unwrap();
+    let messages = common::compile_package(&package_path, None, &[]).unwrap();
+    assert_eq!(interface_packages(&messages), Vec::<String>::new());
+    assert!(messages.contains(&"Package 'cdep' is needed at compile time, building it from source...".to_owned()), "{messages:#?}");
+    assert!(!messages.iter().any(|message| message.ends_with(RESTART_MESSAGE)), "{messages:#?}");
+    assert_eq!(messages.iter().filter(|message| message.starts_with("--- Compiling package ")).count(), 2);
+    assert_eq!(std::fs::read_to_string(&output_path).unwrap(), clean_output);
+    assert_eq!(compile(&package_path, &[]), ["cdep", "cexe"]);
+
+    // Changing the result of `seven` leaves the header of cdep alone, but the value of `X` was
+    // computed from its body, so cexe is compiled again instead of reusing the old value
+    common::write_package(&root, "cdep", "lib", &[], "pub function seven() -> i32 {\n    8\n}\n");
+    assert_eq!(compile(&package_path, &[]), Vec::<String>::new());
+    let changed_output = std::fs::read_to_string(&output_path).unwrap();
+    assert_ne!(changed_output, clean_output);
+    assert_eq!(compile(&package_path, &[]), ["cdep", "cexe"]);
+
+    // Without a manifest for cexe, the call into cdep is only found while compiling, so
+    // everything is compiled again once
+    std::fs::remove_dir_all(root.join("cexe/out")).unwrap();
+    let messages = common::compile_package(&package_path, None, &[]).unwrap();
+    assert_eq!(interface_packages(&messages), Vec::<String>::new());
+    assert_eq!(messages.iter().filter(|message| message.ends_with(RESTART_MESSAGE)).count(), 1, "{messages:#?}");
+    assert_eq!(std::fs::read_to_string(&output_path).unwrap(), changed_output);
+    assert_eq!(compile(&package_path, &[]), ["cdep", "cexe"]);
+
+    std::fs::remove_dir_all(root.join("cexe/out")).unwrap();
+    std::fs::remove_dir_all(root.join("cdep/out")).unwrap();
+    assert_eq!(compile(&package_path, &[]), Vec::<String>::new());
+    assert_eq!(std::fs::read_to_string(&output_path).unwrap(), changed_output);
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,110 @@
+//! Runs the test packages with the built-in interpreter and checks what they print.
+
+use clap::Parser;
+use cupric::cli::{RunArgs, SilentReporter};
+
+/// Run the package `tests/packages/{package_name}` with `extra_args`, returning its exit status
+/// and everything it printed.
+fn run_package(package_name: &str, extra_args: &[&str]) -> Result<(i32, String), Box<(cupric::Error, cupric::package::SourceMap)>> {
+    let package_path = format!("tests/packages/{package_name}");
+    let args = RunArgs::parse_from(["run", &package_path].iter().chain(extra_args));
+    let mut output = Vec::new();
+    let status = cupric::cli::run_with_reporter(&args, &mut SilentReporter, &mut output)?;
+    Ok((status, String::from_utf8(output).unwrap()))
+}
+
+fn run_successfully(package_name: &str, extra_args: &[&str]) -> String {
+    match run_package(package_name, extra_args) {
+        Ok((0, output)) => output,
+        Ok((status, output)) => panic!("'{package_name}' exited with status {status}:\n{output}"),
+        Err(error) => {
+            let (error, source_map) = *error;
+            panic!("failed to run '{package_name}':\n{}", error.to_string_with_context(&source_map));
+        }
+    }
+}
+
+#[test]
+fn interpreter_runs_hello() {
+    assert_eq!(run_successfully("hello", &[]), "Hello world!\n");
+}
+
+#[test]
+fn interpreter_runs_tuples() {
+    assert_eq!(run_successfully("test_2", &[]), "(4, 8)\n");
+}
+
+#[test]
+fn interpreter_runs_float_arithmetic() {
+    assert_eq!(run_successfully("test_3", &[]), "Result: 9.000000\n");
+}
+
+#[test]
+fn interpreter_runs_collections() {
+    let output = run_successfully("test_collections", &[]);
+    assert_eq!(output.lines().count(), 30, "{output}");
+    assert!(output.contains("((( 1 ) 2 ( 3 )) 5 (( 6 ) 8 ( 9 )))\n"), "{output}");
+    assert!(output.ends_with("heapsort: [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]\n"), "{output}");
+}
+
+#[test]
+fn interpreter_runs_constants_evaluated_at_compile_time() {
+    assert_eq!(
+        run_successfully("test_const_eval", &[]),
+        "squares: 4 25 49\nfibonacci: 6765 13530\nsum: 140\nlength: 13\nlimits: 5..15\nwindow: 55..233\ntable: 144\n",
+    );
+}
+
+#[test]
+fn interpreter_runs_inlined_functions() {
+    assert_eq!(
+        run_successfully("test_inline", &[]),
+        "sum: 0\ndifference: negative\nsum: 3\ndifference: negative\nsum: 7\ndifference: negative\n\
+         factorial: 120\neven: 1\nquadruple: 32\n",
+    );
+}
+
+#[test]
+fn interpreter_runs_attributes() {
+    assert_eq!(run_successfully("test_attributes", &[]), "total: 30\n");
+}
+
+#[test]
+fn interpreter_runs_visibility() {
+    assert_eq!(run_successfully("test_visibility", &[]), "count: 8 (limit 10)\ncount: 10 (limit 10)\n");
+}
+
+#[test]
+fn interpreter_matches_across_targets_and_optimization() {
+    let expected = run_successfully("test_collections", &[]);
+    assert_eq!(run_successfully("test_collections", &["-O1"]), expected);
+    assert_eq!(run_successfully("test_collections", &["--target", "wasm32-unknown-unknown"]), expected);
+}
+
+#[test]
+fn interpreter_handles_missing_file() {
+    // The input file is found relative to `tests/packages`, so opening it fails here
+    let output = run_successfully("test_1", &[]);
+    assert!(output.starts_with("unable to open input file\nName: Joe Mama\nAge: 97\n"), "{output}");
+    assert!(output.ends_with("i64::to_string: \"-12345\"\n"), "{output}");
+}
+
+#[test]
+fn interpreter_reports_unavailable_foreign_function() {
+    let Err(error) = run_package("test_unix", &[]) else {
+        panic!("expected 'test_unix' to fail");
+    };
+    let (error, source_map) = *error;
+    assert_eq!(error.kind().name(), "RuntimeError");
+    let message = error.to_string_with_context(&source_map);
+    assert!(message.contains("foreign function 'getpid' is not available in the interpreter"), "{message}");
+    assert!(error.span().is_some(), "{message}");
+}
+
+#[test]
+fn interpreter_rejects_library_package() {
+    let Err(error) = run_package("std", &[]) else {
+        panic!("expected 'std' to be rejected");
+    };
+    assert_eq!(error.0.kind().name(), "NonExecutablePackage");
+}
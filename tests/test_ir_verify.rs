@@ -0,0 +1,143 @@
+//! Checks that the IR verifier catches malformed functions.
+
+mod common;
+
+use cupric::ir::FunctionDefinition;
+use cupric::ir::instr::{BasicBlock, Instruction, PhiInstruction, TerminatorInstruction};
+use cupric::ir::value::{IntegerType, IntegerValue};
+use cupric::sema::TypeHandle;
+use common::{function, function_path, integer, ir_context, label, register};
+
+/// Verify `function`, returning the error message if it is rejected.
+fn verify(function: &FunctionDefinition) -> Option<String> {
+    let context = ir_context();
+    cupric::ir::verify_function(&context, function, &function_path()).err().map(|error| {
+        assert_eq!(error.kind().name(), "InvalidIR");
+        error.to_string()
+    })
+}
+
+#[test]
+fn verify_accepts_valid_function() {
+    let mut entry = BasicBlock::new(label("entry"));
+    entry.add_instruction(Instruction::CompareLessThan {
+        result: register("condition", TypeHandle::BOOL),
+        lhs: register("a", TypeHandle::I32).into(),
+        rhs: integer(0),
+    }, None);
+    entry.set_terminator(TerminatorInstruction::ConditionalBranch {
+        condition: register("condition", TypeHandle::BOOL).into(),
+        consequent_label: label("negative"),
+        alternative_label: label("tail"),
+    }, None);
+
+    let mut negative = BasicBlock::new(label("negative"));
+    negative.add_instruction(Instruction::Negate {
+        result: register("negated", TypeHandle::I32),
+        operand: register("a", TypeHandle::I32).into(),
+    }, None);
+    negative.set_terminator(TerminatorInstruction::Branch { to_label: label("tail") }, None);
+
+    let mut tail = BasicBlock::new(label("tail"));
+    tail.add_phi(PhiInstruction {
+        result: register("result", TypeHandle::I32),
+        inputs: [
+            (register("a", TypeHandle::I32).into(), label("entry")),
+            (register("negated", TypeHandle::I32).into(), label("negative")),
+        ].into(),
+    });
+    tail.set_terminator(TerminatorInstruction::Return { value: register("result", TypeHandle::I32).into() }, None);
+
+    assert_eq!(verify(&function(vec![entry, negative, tail])), None);
+}
+
+#[test]
+fn verify_rejects_missing_terminator() {
+    let entry = BasicBlock::new(label("entry"));
+    let message = verify(&function(vec![entry])).expect("unterminated block should be rejected");
+    assert!(message.contains("'::test::f'"), "{message}");
+    assert!(message.contains("block 'entry' has no terminator"), "{message}");
+}
+
+#[test]
+fn verify_rejects_phi_from_non_predecessor() {
+    let mut entry = BasicBlock::new(label("entry"));
+    entry.set_terminator(TerminatorInstruction::Branch { to_label: label("tail") }, None);
+
+    let mut other = BasicBlock::new(label("other"));
+    other.set_terminator(TerminatorInstruction::Unreachable, None);
+
+    let mut tail = BasicBlock::new(label("tail"));
+    tail.add_phi(PhiInstruction {
+        result: register("result", TypeHandle::I32),
+        inputs: [
+            (integer(1), label("entry")),
+            (integer(2), label("other")),
+        ].into(),
+    });
+    tail.set_terminator(TerminatorInstruction::Return { value: register("result", TypeHandle::I32).into() }, None);
+
+    let message = verify(&function(vec![entry, other, tail])).expect("phi input from a non-predecessor should be rejected");
+    assert!(message.contains("which is not a predecessor"), "{message}");
+}
+
+#[test]
+fn verify_rejects_use_before_definition() {
+    let mut entry = BasicBlock::new(label("entry"));
+    entry.add_instruction(Instruction::Add {
+        result: register("sum", TypeHandle::I32),
+        lhs: register("later", TypeHandle::I32).into(),
+        rhs: integer(1),
+    }, None);
+    entry.add_instruction(Instruction::Add {
+        result: register("later", TypeHandle::I32),
+        lhs: register("a", TypeHandle::I32).into(),
+        rhs: integer(1),
+    }, None);
+    entry.set_terminator(TerminatorInstruction::Return { value: register("sum", TypeHandle::I32).into() }, None);
+
+    let message = verify(&function(vec![entry])).expect("use before definition should be rejected");
+    assert!(message.contains("register %later is used before it is defined"), "{message}");
+}
+
+#[test]
+fn verify_rejects_mismatched_operand_types() {
+    let mut entry = BasicBlock::new(label("entry"));
+    entry.add_instruction(Instruction::Add {
+        result: register("sum", TypeHandle::I32),
+        lhs: register("a", TypeHandle::I32).into(),
+        rhs: IntegerValue::new(IntegerType::U8, 1).into(),
+    }, None);
+    entry.set_terminator(TerminatorInstruction::Return { value: register("sum", TypeHandle::I32).into() }, None);
+
+    let message = verify(&function(vec![entry])).expect("mismatched operand types should be rejected");
+    assert!(message.contains("right operand has type 'u8', expected 'i32'"), "{message}");
+}
+
+#[test]
+fn verify_accepts_registers_used_with_equivalent_types() {
+    // Conversions like `i32 as u32` only change the type a register is used with
+    let mut entry = BasicBlock::new(label("entry"));
+    entry.add_instruction(Instruction::Add {
+        result: register("sum", TypeHandle::U32),
+        lhs: register("a", TypeHandle::U32).into(),
+        rhs: IntegerValue::new(IntegerType::U32, 1).into(),
+    }, None);
+    entry.set_terminator(TerminatorInstruction::Return { value: register("sum", TypeHandle::I32).into() }, None);
+
+    assert_eq!(verify(&function(vec![entry])), None);
+}
+
+#[test]
+fn verify_rejects_registers_used_with_different_sizes() {
+    let mut entry = BasicBlock::new(label("entry"));
+    entry.add_instruction(Instruction::Add {
+        result: register("sum", TypeHandle::U8),
+        lhs: register("a", TypeHandle::U8).into(),
+        rhs: IntegerValue::new(IntegerType::U8, 1).into(),
+    }, None);
+    entry.set_terminator(TerminatorInstruction::Return { value: register("sum", TypeHandle::I32).into() }, None);
+
+    let message = verify(&function(vec![entry])).expect("register used with a different size should be rejected");
+    assert!(message.contains("register %a is used with type 'u8' but defined with type 'i32'"), "{message}");
+}
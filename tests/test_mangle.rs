@@ -0,0 +1,108 @@
+//! Checks symbol name mangling and the `demangle` subcommand.
+
+use clap::Parser;
+use cupric::cli::{Command, CompilerArgs};
+use cupric::sema::{AbsolutePath, PathBaseType, PointerSemantics, PrimitiveType, SimplePath, demangle, demangle_text, mangle_path};
+
+fn simple_path(segments: &[&str]) -> SimplePath {
+    SimplePath::new(segments.iter().map(|&segment| segment.into()).collect())
+}
+
+fn primitive(name: &str) -> AbsolutePath {
+    let primitive_type = PrimitiveType::from_name(name).unwrap();
+    AbsolutePath::at_base_type(Box::new(PathBaseType::Primitive(primitive_type)))
+}
+
+fn run_demangle(arguments: &[&str], input: &str) -> String {
+    let args = CompilerArgs::parse_from(["driver", "demangle"].iter().chain(arguments));
+    let Some(Command::Demangle(demangle_args)) = args.command() else {
+        panic!("expected the demangle subcommand");
+    };
+
+    let mut output = Vec::new();
+    cupric::cli::demangle(demangle_args, &mut input.as_bytes(), &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn mangles_module_paths() {
+    let path = AbsolutePath::from_root(simple_path(&["std", "string", "String", "push"]));
+    let mangled = mangle_path("std", &path);
+
+    assert_eq!(mangled, "_CN3std6string6String4pushE");
+    assert_eq!(demangle(&mangled).as_deref(), Some("std::string::String::push"));
+}
+
+#[test]
+fn mangles_implementations_of_base_types() {
+    let string_type = AbsolutePath::from_root(simple_path(&["std", "string", "String"]));
+    let cases = [
+        (primitive("i64"), "_CN6test_1Xx9to_stringE", "test_1::<i64>::to_string"),
+        (
+            AbsolutePath::at_base_type(Box::new(PathBaseType::Pointer {
+                pointee_type: string_type,
+                semantics: PointerSemantics::Mutable,
+            })),
+            "_CN6test_1XMN3std6string6StringE9to_stringE",
+            "test_1::<*mut std::string::String>::to_string",
+        ),
+        (
+            AbsolutePath::at_base_type(Box::new(PathBaseType::Array {
+                item_type: AbsolutePath::at_base_type(Box::new(PathBaseType::Tuple {
+                    item_types: Box::new([primitive("u8"), primitive("bool")]),
+                })),
+                length: Some(4),
+            })),
+            "_CN6test_1XA4_ThbE9to_stringE",
+            "test_1::<[(u8, bool); 4]>::to_string",
+        ),
+        (
+            AbsolutePath::at_base_type(Box::new(PathBaseType::Function {
+                parameter_types: Box::new([primitive("usize")]),
+                is_variadic: true,
+                return_type: primitive("void"),
+            })),
+            "_CN6test_1XFvmVE9to_stringE",
+            "test_1::<function(usize, ..) -> void>::to_string",
+        ),
+    ];
+
+    for (self_type, expected_mangled, expected_demangled) in cases {
+        let mangled = mangle_path("test_1", &self_type.child("to_string"));
+        assert_eq!(mangled, expected_mangled);
+        assert_eq!(demangle(&mangled).as_deref(), Some(expected_demangled));
+    }
+}
+
+#[test]
+fn rejects_invalid_symbols() {
+    for symbol in ["main", "_CN3stdE3", "_CN9stdE", "_CNXqE", "_CN3std"] {
+        assert_eq!(demangle(symbol), None, "'{symbol}' should not demangle");
+    }
+}
+
+#[test]
+fn demangles_generic_arguments() {
+    assert_eq!(demangle("_CN3std3Vec3VecIiPhE3newE").as_deref(), Some("std::Vec::Vec<i32, *u8>::new"));
+}
+
+#[test]
+fn demangles_symbols_within_text() {
+    let text = "define void @_CN3stdXh4swapE(ptr %0) ; calls @_CN3std6string6String4pushE, not x_CN1aE";
+    assert_eq!(
+        demangle_text(text),
+        "define void @std::<u8>::swap(ptr %0) ; calls @std::string::String::push, not x_CN1aE",
+    );
+}
+
+#[test]
+fn demangle_subcommand() {
+    assert_eq!(
+        run_demangle(&["_CN6test_1Xx9to_stringE", "printf"], ""),
+        "test_1::<i64>::to_string\nprintf\n",
+    );
+    assert_eq!(
+        run_demangle(&[], "0000000000001139 t _CN6test_13gcdE\n                 U printf\n"),
+        "0000000000001139 t test_1::gcd\n                 U printf\n",
+    );
+}
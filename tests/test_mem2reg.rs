@@ -0,0 +1,106 @@
+//! Checks that stack allocations used only through loads and stores are promoted to registers.
+
+mod common;
+
+use cupric::ir::{FunctionDefinition, IRDisplay};
+use cupric::ir::instr::{BasicBlock, Instruction, TerminatorInstruction};
+use cupric::ir::value::LocalRegister;
+use cupric::sema::{ConversionOperation, GlobalContext, PointerSemantics, TypeHandle};
+use common::{assert_valid, function, function_path, integer, ir_context, label, register};
+
+/// Create a function `f(%a: i32) -> i32` which stores `%a` into the stack slot `%x`, overwrites
+/// it with `-%a` when `%a` is negative, and returns the contents of `%x`. `escape` is given the
+/// slot and may add instructions which use it in other ways.
+fn absolute_value(context: &mut GlobalContext, escape: impl FnOnce(&mut BasicBlock, LocalRegister)) -> FunctionDefinition {
+    let pointer_type = context.get_pointer_type(TypeHandle::I32, PointerSemantics::Mutable);
+    let slot = register("x", pointer_type);
+
+    let mut entry = BasicBlock::new(label("entry"));
+    entry.add_instruction(Instruction::StackAllocate { result: slot.clone() }, None);
+    entry.add_instruction(Instruction::Store {
+        value: register("a", TypeHandle::I32).into(),
+        pointer: slot.clone().into(),
+    }, None);
+    escape(&mut entry, slot.clone());
+    entry.add_instruction(Instruction::CompareLessThan {
+        result: register("condition", TypeHandle::BOOL),
+        lhs: register("a", TypeHandle::I32).into(),
+        rhs: integer(0),
+    }, None);
+    entry.set_terminator(TerminatorInstruction::ConditionalBranch {
+        condition: register("condition", TypeHandle::BOOL).into(),
+        consequent_label: label("negative"),
+        alternative_label: label("tail"),
+    }, None);
+
+    let mut negative = BasicBlock::new(label("negative"));
+    negative.add_instruction(Instruction::Negate {
+        result: register("negated", TypeHandle::I32),
+        operand: register("a", TypeHandle::I32).into(),
+    }, None);
+    negative.add_instruction(Instruction::Store {
+        value: register("negated", TypeHandle::I32).into(),
+        pointer: slot.clone().into(),
+    }, None);
+    negative.set_terminator(TerminatorInstruction::Branch { to_label: label("tail") }, None);
+
+    let mut tail = BasicBlock::new(label("tail"));
+    tail.add_instruction(Instruction::Load {
+        result: register("result", TypeHandle::I32),
+        pointer: slot.into(),
+    }, None);
+    tail.set_terminator(TerminatorInstruction::Return { value: register("result", TypeHandle::I32).into() }, None);
+
+    function(vec![entry, negative, tail])
+}
+
+/// Promote the stack allocations of `function`, check that the result is still valid, and return
+/// it as text.
+fn promote(context: &GlobalContext, function: &mut FunctionDefinition) -> String {
+    cupric::ir::promote_allocas(context, function);
+    assert_valid(context, function);
+    function.ir(context).to_string()
+}
+
+#[test]
+fn mem2reg_promotes_local_variable() {
+    let mut context = ir_context();
+    let mut function = absolute_value(&mut context, |_, _| {});
+    let output = promote(&context, &mut function);
+
+    assert!(!output.contains("StackAllocate"), "{output}");
+    assert!(!output.contains("Load"), "{output}");
+    assert!(!output.contains("Store"), "{output}");
+    assert!(output.contains("%x.0: i32 = Phi [i32 %a from entry], [i32 %negated from negative]\n"), "{output}");
+    assert!(output.contains("Return i32 %x.0\n"), "{output}");
+}
+
+#[test]
+fn mem2reg_keeps_escaping_allocation() {
+    let mut context = ir_context();
+    let mut function = absolute_value(&mut context, |block, slot| {
+        block.add_instruction(Instruction::Convert {
+            operation: ConversionOperation::PointerToInteger,
+            result: register("address", TypeHandle::USIZE),
+            value: slot.into(),
+        }, None);
+    });
+    let output = promote(&context, &mut function);
+
+    assert!(output.contains("%x: *mut i32 = StackAllocate\n"), "{output}");
+    assert!(output.contains("%result: i32 = Load *mut i32 %x\n"), "{output}");
+    assert!(!output.contains("Phi"), "{output}");
+}
+
+#[test]
+fn mem2reg_leaves_invalid_control_flow() {
+    let mut context = ir_context();
+    let mut function = absolute_value(&mut context, |_, _| {});
+    function.blocks_mut()[1].set_terminator(TerminatorInstruction::Branch { to_label: label("missing") }, None);
+    let before = function.ir(&context).to_string();
+    cupric::ir::promote_allocas(&context, &mut function);
+
+    assert_eq!(function.ir(&context).to_string(), before);
+    let error = cupric::ir::verify_function(&context, &function, &function_path()).unwrap_err();
+    assert_eq!(error.kind().name(), "InvalidIR");
+}
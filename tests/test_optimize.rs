@@ -0,0 +1,178 @@
+//! Checks constant folding and dead code elimination on hand-built IR.
+
+mod common;
+
+use cupric::ir::{FunctionDefinition, IRDisplay, OptimizationLevel};
+use cupric::ir::instr::{BasicBlock, Instruction, PhiInstruction, TerminatorInstruction};
+use cupric::ir::value::{IntegerType, IntegerValue};
+use cupric::sema::TypeHandle;
+use common::{assert_valid, function, integer, ir_context, label, register};
+
+/// Optimize `function` at `level`, check that the result is still valid, and return it as text.
+fn optimize(mut function: FunctionDefinition, level: OptimizationLevel) -> String {
+    let context = ir_context();
+    cupric::ir::optimize_function(&context, &mut function, level);
+    assert_valid(&context, &function);
+    function.ir(&context).to_string()
+}
+
+/// `f` computes `1 + 2`, branches on whether the sum is less than 5, and returns either the sum
+/// or `%a * 2` through a phi.
+fn constant_branch() -> FunctionDefinition {
+    let mut entry = BasicBlock::new(label("entry"));
+    entry.add_instruction(Instruction::Add {
+        result: register("sum", TypeHandle::I32),
+        lhs: integer(1),
+        rhs: integer(2),
+    }, None);
+    entry.add_instruction(Instruction::CompareLessThan {
+        result: register("condition", TypeHandle::BOOL),
+        lhs: register("sum", TypeHandle::I32).into(),
+        rhs: integer(5),
+    }, None);
+    entry.set_terminator(TerminatorInstruction::ConditionalBranch {
+        condition: register("condition", TypeHandle::BOOL).into(),
+        consequent_label: label("small"),
+        alternative_label: label("large"),
+    }, None);
+
+    let mut small = BasicBlock::new(label("small"));
+    small.set_terminator(TerminatorInstruction::Branch { to_label: label("tail") }, None);
+
+    let mut large = BasicBlock::new(label("large"));
+    large.add_instruction(Instruction::Multiply {
+        result: register("doubled", TypeHandle::I32),
+        lhs: register("a", TypeHandle::I32).into(),
+        rhs: integer(2),
+    }, None);
+    large.set_terminator(TerminatorInstruction::Branch { to_label: label("tail") }, None);
+
+    let mut tail = BasicBlock::new(label("tail"));
+    tail.add_phi(PhiInstruction {
+        result: register("result", TypeHandle::I32),
+        inputs: [
+            (register("sum", TypeHandle::I32).into(), label("small")),
+            (register("doubled", TypeHandle::I32).into(), label("large")),
+        ].into(),
+    });
+    tail.set_terminator(TerminatorInstruction::Return { value: register("result", TypeHandle::I32).into() }, None);
+
+    function(vec![entry, small, large, tail])
+}
+
+#[test]
+fn optimize_folds_constant_branch() {
+    let output = optimize(constant_branch(), OptimizationLevel::Basic);
+
+    assert!(!output.contains("Add"), "{output}");
+    assert!(!output.contains("CompareLessThan"), "{output}");
+    assert!(!output.contains("Phi"), "{output}");
+    assert!(!output.contains("large"), "{output}");
+    assert!(output.contains("entry:\n    Branch small\n"), "{output}");
+    assert!(output.contains("    Return i32 3\n"), "{output}");
+}
+
+#[test]
+fn optimize_level_zero_changes_nothing() {
+    let output = optimize(constant_branch(), OptimizationLevel::None);
+
+    assert!(output.contains("%sum: i32 = Add i32 1, i32 2\n"), "{output}");
+    assert!(output.contains("ConditionalBranch bool %condition, small, large\n"), "{output}");
+    assert!(output.contains("%doubled: i32 = Multiply i32 %a, i32 2\n"), "{output}");
+}
+
+#[test]
+fn optimize_keeps_undefined_operations() {
+    let mut entry = BasicBlock::new(label("entry"));
+    entry.add_instruction(Instruction::Divide {
+        result: register("quotient", TypeHandle::I32),
+        lhs: integer(1),
+        rhs: integer(0),
+    }, None);
+    entry.add_instruction(Instruction::Divide {
+        result: register("overflow", TypeHandle::I32),
+        lhs: integer(i32::MIN.into()),
+        rhs: integer(-1),
+    }, None);
+    entry.add_instruction(Instruction::ShiftLeft {
+        result: register("shifted", TypeHandle::I32),
+        lhs: integer(1),
+        rhs: integer(32),
+    }, None);
+    entry.add_instruction(Instruction::Add {
+        result: register("partial", TypeHandle::I32),
+        lhs: register("quotient", TypeHandle::I32).into(),
+        rhs: register("overflow", TypeHandle::I32).into(),
+    }, None);
+    entry.add_instruction(Instruction::Add {
+        result: register("sum", TypeHandle::I32),
+        lhs: register("partial", TypeHandle::I32).into(),
+        rhs: register("shifted", TypeHandle::I32).into(),
+    }, None);
+    entry.set_terminator(TerminatorInstruction::Return { value: register("sum", TypeHandle::I32).into() }, None);
+
+    let output = optimize(function(vec![entry]), OptimizationLevel::Basic);
+    assert!(output.contains("%quotient: i32 = Divide i32 1, i32 0\n"), "{output}");
+    assert!(output.contains("%overflow: i32 = Divide i32 -2147483648, i32 -1\n"), "{output}");
+    assert!(output.contains("%shifted: i32 = ShiftLeft i32 1, i32 32\n"), "{output}");
+}
+
+#[test]
+fn optimize_wraps_integer_arithmetic() {
+    let mut entry = BasicBlock::new(label("entry"));
+    entry.add_instruction(Instruction::Add {
+        result: register("sum", TypeHandle::U8),
+        lhs: IntegerValue::new(IntegerType::U8, 200).into(),
+        rhs: IntegerValue::new(IntegerType::U8, 100).into(),
+    }, None);
+    entry.add_instruction(Instruction::Subtract {
+        result: register("difference", TypeHandle::U8),
+        lhs: register("sum", TypeHandle::U8).into(),
+        rhs: IntegerValue::new(IntegerType::U8, 50).into(),
+    }, None);
+    entry.add_instruction(Instruction::Convert {
+        operation: cupric::sema::ConversionOperation::SignExtend,
+        result: register("extended", TypeHandle::I32),
+        value: register("difference", TypeHandle::U8).into(),
+    }, None);
+    entry.set_terminator(TerminatorInstruction::Return { value: register("extended", TypeHandle::I32).into() }, None);
+
+    let output = optimize(function(vec![entry]), OptimizationLevel::Basic);
+    // 200 + 100 wraps to 44, 44 - 50 wraps to 250, and 250 is -6 when sign-extended
+    assert!(output.contains("    Return i32 -6\n"), "{output}");
+}
+
+#[test]
+fn optimize_removes_unused_instructions() {
+    let mut entry = BasicBlock::new(label("entry"));
+    entry.add_instruction(Instruction::Multiply {
+        result: register("unused", TypeHandle::I32),
+        lhs: register("a", TypeHandle::I32).into(),
+        rhs: integer(3),
+    }, None);
+    entry.add_instruction(Instruction::Add {
+        result: register("also_unused", TypeHandle::I32),
+        lhs: register("unused", TypeHandle::I32).into(),
+        rhs: integer(1),
+    }, None);
+    entry.set_terminator(TerminatorInstruction::Return { value: register("a", TypeHandle::I32).into() }, None);
+
+    let output = optimize(function(vec![entry]), OptimizationLevel::Basic);
+    assert!(!output.contains("Multiply"), "{output}");
+    assert!(!output.contains("Add"), "{output}");
+    assert!(output.contains("entry:\n    Return i32 %a\n"), "{output}");
+}
+
+#[test]
+fn optimize_flag_folds_package() {
+    let out_dir = common::temp_out_dir("optimize");
+    let extra_args = ["-O1", "--emit", "cupric-ir", "--target", "x86_64-unknown-linux-gnu"];
+    common::compile_package("tests/packages/test_collections", Some(&out_dir), &extra_args).unwrap();
+    let output = std::fs::read_to_string(out_dir.join("test_collections.cir")).unwrap();
+
+    // `-1` is generated as the negation of `1`, which is folded into the phi using it
+    assert!(!output.contains("Negate i32 1\n"), "{output}");
+    assert!(output.contains(" = Phi [i32 -1 from .block.1], "), "{output}");
+
+    std::fs::remove_dir_all(&out_dir).unwrap();
+}
@@ -0,0 +1,63 @@
+//! Checks the pass statistics reported for each package with `--time-passes`.
+
+mod common;
+
+/// Parse the statistics reported for a package into its name and each of its `(name, value)`
+/// rows, with times in milliseconds.
+fn parse_statistics(message: &str) -> (&str, Vec<(&str, f64)>) {
+    let mut lines = message.lines();
+    let package_name = lines.next()
+        .and_then(|line| line.strip_prefix("--- Pass statistics for package '"))
+        .and_then(|rest| rest.strip_suffix("' ---"))
+        .unwrap_or_else(|| panic!("unexpected statistics header:\n{message}"));
+    let rows = lines
+        .map(|line| {
+            let line = line.trim_end_matches(" ms");
+            let (name, value) = line.trim().rsplit_once(' ').unwrap();
+            (name.trim(), value.parse().unwrap_or_else(|_| panic!("malformed statistics row '{line}'")))
+        })
+        .collect();
+    (package_name, rows)
+}
+
+#[test]
+fn time_passes_reports_each_package() {
+    let out_dir = common::temp_out_dir("time-passes");
+    let messages = common::compile_package("tests/packages/hello", Some(&out_dir), &["--time-passes"]).unwrap();
+
+    let statistics: Vec<_> = messages.iter()
+        .filter(|message| message.starts_with("--- Pass statistics"))
+        .map(|message| parse_statistics(message))
+        .collect();
+    let package_names: Vec<_> = statistics.iter().map(|(name, _)| *name).collect();
+    assert_eq!(package_names, ["libc", "hello"]);
+
+    for (package_name, rows) in &statistics {
+        let row_names: Vec<_> = rows.iter().map(|(name, _)| *name).collect();
+        assert_eq!(row_names, [
+            "scanning", "parsing/outline", "fill", "type layout", "generation", "LLVM writing", "total",
+            "tokens", "AST nodes", "types", "symbols", "functions", "IR instructions",
+        ]);
+
+        let value = |name: &str| rows.iter().find(|(row_name, _)| *row_name == name).unwrap().1;
+        let phase_time: f64 = rows[..6].iter().map(|(_, value)| value).sum();
+        assert!((value("total") - phase_time).abs() < 0.01, "{package_name}: {rows:?}");
+        assert!(value("scanning") > 0.0, "{package_name}: {rows:?}");
+        assert!(value("tokens") > 0.0, "{package_name}: {rows:?}");
+        assert!(value("AST nodes") > 0.0, "{package_name}: {rows:?}");
+    }
+    let (_, hello_rows) = &statistics[1];
+    assert!(hello_rows.contains(&("tokens", 17.0)), "{hello_rows:?}");
+    assert!(hello_rows.contains(&("functions", 1.0)), "{hello_rows:?}");
+
+    std::fs::remove_dir_all(&out_dir).unwrap();
+}
+
+#[test]
+fn statistics_only_reported_with_time_passes() {
+    let out_dir = common::temp_out_dir("no-time-passes");
+    let messages = common::compile_package("tests/packages/hello", Some(&out_dir), &[]).unwrap();
+    assert!(!messages.iter().any(|message| message.starts_with("--- Pass statistics")), "{messages:#?}");
+
+    std::fs::remove_dir_all(&out_dir).unwrap();
+}
@@ -0,0 +1,48 @@
+//! Checks that objects and executables are built for the target given with `--target`.
+
+mod common;
+
+const CROSS_TARGET: &str = "aarch64-unknown-linux-gnu";
+
+/// The ELF machine number for AArch64.
+const EM_AARCH64: u16 = 183;
+
+fn compile_for_target(out_dir: &std::path::Path, extra_args: &[&str]) -> Result<Vec<String>, Box<common::CompileError>> {
+    let mut args = vec!["--target", CROSS_TARGET];
+    args.extend(extra_args);
+    common::compile_package("tests/packages/hello", Some(out_dir), &args)
+}
+
+#[test]
+fn llc_compiles_objects_for_target() {
+    let out_dir = common::temp_out_dir("toolchain-llc-target");
+    if let Err(error) = compile_for_target(&out_dir, &["--toolchain", "llc", "--emit", "obj"]) {
+        if let cupric::ErrorKind::MissingToolchain { .. } = error.error.kind() {
+            println!("skipping: {}", error.error);
+            return;
+        }
+        panic!("failed to compile object:\n{error:?}");
+    }
+
+    // The machine field of the ELF header follows the 16 identification bytes and the file type
+    let object = std::fs::read(out_dir.join("hello.o")).unwrap();
+    assert_eq!(&object[.. 4], b"\x7fELF");
+    assert_eq!(u16::from_le_bytes([object[18], object[19]]), EM_AARCH64);
+
+    std::fs::remove_dir_all(&out_dir).unwrap();
+}
+
+#[test]
+fn cc_rejects_other_targets() {
+    let out_dir = common::temp_out_dir("toolchain-cc-target");
+    let error = compile_for_target(&out_dir, &["--backend", "c", "--emit", "obj"])
+        .expect_err("compiling C for another target with cc should fail");
+    assert_eq!(error.error.kind().name(), "UnsupportedToolchainTarget");
+
+    let error = compile_for_target(&out_dir, &["--toolchain", "llc", "--emit", "exe"])
+        .expect_err("linking for another target with cc should fail");
+    assert!(
+        matches!(error.error.kind().name(), "UnsupportedToolchainTarget" | "MissingToolchain"),
+        "{error:?}",
+    );
+}
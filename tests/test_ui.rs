@@ -0,0 +1,206 @@
+//! Expected-diagnostic tests. Each directory under `tests/ui` is a package which should fail to
+//! compile. Source lines which should produce an error are annotated with a comment of the form
+//! `// ~ERROR <kind>`, where `<kind>` is the name of an `ErrorKind` variant. An annotation on a
+//! line by itself expects an error which has no source location. Lines which the error should
+//! point out with a secondary label are annotated with `~LABEL` in a comment.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+use clap::Parser;
+use cupric::cli::SilentReporter;
+
+const ANNOTATION: &str = "// ~ERROR ";
+const LABEL_ANNOTATION: &str = "~LABEL";
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct Diagnostic {
+    location: Option<(PathBuf, usize)>,
+    kind: String,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.location {
+            Some((path, line)) => write!(f, "{} at '{}' line {line}", self.kind, path.display()),
+            None => write!(f, "{} with no location", self.kind),
+        }
+    }
+}
+
+fn collect_expected_diagnostics(dir: &Path, expected: &mut Vec<Diagnostic>, expected_labels: &mut Vec<(PathBuf, usize)>) {
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            collect_expected_diagnostics(&path, expected, expected_labels);
+        }
+        else if path.extension().is_some_and(|extension| extension == "cupr") {
+            let path = path.canonicalize().unwrap();
+            let text = std::fs::read_to_string(&path).unwrap();
+            for (line_index, line) in text.lines().enumerate() {
+                if line.split_once("//").is_some_and(|(_, comment)| comment.contains(LABEL_ANNOTATION)) {
+                    expected_labels.push((path.clone(), line_index + 1));
+                }
+                let Some(annotation_index) = line.find(ANNOTATION) else {
+                    continue;
+                };
+                let kind = line[annotation_index + ANNOTATION.len() ..]
+                    .split_whitespace()
+                    .next()
+                    .expect("missing error kind in annotation");
+                let location = if line[.. annotation_index].trim().is_empty() {
+                    None
+                }
+                else {
+                    Some((path.clone(), line_index + 1))
+                };
+                expected.push(Diagnostic {
+                    location,
+                    kind: kind.to_owned(),
+                });
+            }
+        }
+    }
+}
+
+fn test_ui_case(case_name: &str) {
+    let case_path = Path::new("tests/ui").join(case_name);
+    let mut expected = Vec::new();
+    let mut expected_labels = Vec::new();
+    collect_expected_diagnostics(&case_path, &mut expected, &mut expected_labels);
+    assert!(!expected.is_empty(), "UI test '{case_name}' has no '{ANNOTATION}' annotations");
+
+    // Dependencies of a case may compile successfully, so output is kept out of the source tree
+    let out_dir = common::temp_out_dir(&format!("ui-{case_name}"));
+    let args = cupric::cli::CompilerArgs::parse_from([
+        "compiler".to_string(),
+        case_path.display().to_string(),
+        "--out-dir".to_string(),
+        out_dir.display().to_string(),
+    ]);
+    let mut produced = Vec::new();
+    let mut produced_labels = Vec::new();
+    let mut rendered = String::new();
+    if let Err(error) = cupric::cli::invoke_with_reporter(&args, &mut SilentReporter) {
+        let (error, source_map) = *error;
+        let line_of = |span: cupric::Span| {
+            let file = source_map.file(span.source_id);
+            let (line, _) = file.line_column(span.start_index);
+            (file.path().canonicalize().unwrap(), line)
+        };
+        produced.push(Diagnostic {
+            location: error.span().map(line_of),
+            kind: error.kind().name().to_owned(),
+        });
+        produced_labels.extend(error.labels().iter().map(|label| line_of(label.span)));
+        rendered = error.to_string_with_context(&source_map);
+    }
+    if out_dir.exists() {
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
+
+    expected.sort();
+    produced.sort();
+    expected_labels.sort();
+    produced_labels.sort();
+    if expected != produced || expected_labels != produced_labels {
+        let list = |diagnostics: &[Diagnostic]| {
+            diagnostics.iter().map(|diagnostic| format!("\n    {diagnostic}")).collect::<String>()
+        };
+        let list_labels = |labels: &[(PathBuf, usize)]| {
+            labels.iter().map(|(path, line)| format!("\n    label at '{}' line {line}", path.display())).collect::<String>()
+        };
+        panic!(
+            "UI test '{case_name}' produced unexpected diagnostics\n  expected:{}{}\n  produced:{}{}\n\n{rendered}",
+            list(&expected),
+            list_labels(&expected_labels),
+            list(&produced),
+            list_labels(&produced_labels),
+        );
+    }
+}
+
+#[test]
+fn ui_global_symbol_conflict() {
+    test_ui_case("global_symbol_conflict");
+}
+
+#[test]
+fn ui_incompatible_types() {
+    test_ui_case("incompatible_types");
+}
+
+#[test]
+fn ui_recursive_type() {
+    test_ui_case("recursive_type");
+}
+
+#[test]
+fn ui_return_type_mismatch() {
+    test_ui_case("return_type_mismatch");
+}
+
+#[test]
+fn ui_undefined_symbol() {
+    test_ui_case("undefined_symbol");
+}
+
+#[test]
+fn ui_unclosed_string() {
+    test_ui_case("unclosed_string");
+}
+
+#[test]
+fn ui_expected_return_value() {
+    test_ui_case("expected_return_value");
+}
+
+#[test]
+fn ui_constant_foreign_call() {
+    test_ui_case("constant_foreign_call");
+}
+
+#[test]
+fn ui_constant_pointer_result() {
+    test_ui_case("constant_pointer_result");
+}
+
+#[test]
+fn ui_unknown_calling_convention() {
+    test_ui_case("unknown_calling_convention");
+}
+
+#[test]
+fn ui_non_pointer_parameter_attribute() {
+    test_ui_case("non_pointer_parameter_attribute");
+}
+
+#[test]
+fn ui_private_symbol() {
+    test_ui_case("private_symbol");
+}
+
+#[test]
+fn ui_private_member() {
+    test_ui_case("private_member");
+}
+
+#[test]
+fn ui_dependency_symbol_conflict() {
+    test_ui_case("dependency_symbol_conflict");
+}
+
+#[test]
+fn ui_constant_cycle() {
+    test_ui_case("constant_cycle");
+}
+
+#[test]
+fn ui_array_length_cycle() {
+    test_ui_case("array_length_cycle");
+}
@@ -0,0 +1,255 @@
+//! Checks the WebAssembly text emitted for `wasm32` targets.
+
+mod common;
+
+use std::path::Path;
+use std::process::Command;
+
+const WASM_TARGET: &str = "wasm32-unknown-unknown";
+
+fn compile_for_wasm(package_name: &str, out_dir: &Path, extra_args: &[&str]) -> Result<Vec<String>, Box<common::CompileError>> {
+    common::compile_package(format!("tests/packages/{package_name}"), Some(out_dir), extra_args)
+}
+
+/// A node of the S-expressions making up WebAssembly text.
+#[derive(Debug)]
+enum Expression {
+    List(Vec<Expression>),
+    Atom(String),
+    String(Vec<u8>),
+}
+
+impl Expression {
+    fn atom(&self) -> Option<&str> {
+        match self {
+            Self::Atom(atom) => Some(atom),
+            _ => None,
+        }
+    }
+
+    fn list(&self) -> Option<&[Expression]> {
+        match self {
+            Self::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// The leading keyword of a list, such as `func` for `(func ...)`.
+    fn keyword(&self) -> Option<&str> {
+        self.list()?.first()?.atom()
+    }
+}
+
+/// Parse WebAssembly text into its top-level S-expressions, skipping comments. Panics if the text
+/// is malformed.
+fn parse_wat(text: &str) -> Vec<Expression> {
+    let bytes = text.as_bytes();
+    let mut stack = vec![Vec::new()];
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b';' if bytes.get(index + 1) == Some(&b';') => {
+                while index < bytes.len() && bytes[index] != b'\n' {
+                    index += 1;
+                }
+            }
+            b'(' if bytes.get(index + 1) == Some(&b';') => {
+                let end = text[index ..].find(";)").expect("unterminated block comment");
+                index += end + 2;
+            }
+            b'(' => {
+                stack.push(Vec::new());
+                index += 1;
+            }
+            b')' => {
+                let items = stack.pop().unwrap();
+                stack.last_mut().expect("unbalanced ')'").push(Expression::List(items));
+                index += 1;
+            }
+            b'"' => {
+                let mut string = Vec::new();
+                index += 1;
+                while bytes[index] != b'"' {
+                    if bytes[index] == b'\\' {
+                        let hex = &text[index + 1 .. index + 3];
+                        string.push(u8::from_str_radix(hex, 16).unwrap_or_else(|_| panic!("bad escape '\\{hex}'")));
+                        index += 3;
+                    }
+                    else {
+                        string.push(bytes[index]);
+                        index += 1;
+                    }
+                }
+                stack.last_mut().unwrap().push(Expression::String(string));
+                index += 1;
+            }
+            ch if ch.is_ascii_whitespace() => {
+                index += 1;
+            }
+            _ => {
+                let start = index;
+                while index < bytes.len() && !bytes[index].is_ascii_whitespace() && !b"()\"".contains(&bytes[index]) {
+                    index += 1;
+                }
+                stack.last_mut().unwrap().push(Expression::Atom(text[start .. index].to_string()));
+            }
+        }
+    }
+    assert_eq!(stack.len(), 1, "unbalanced '('");
+    stack.pop().unwrap()
+}
+
+/// Check the structure of a module written by the WebAssembly backend without any external tools:
+/// the layout of its fields, the sizes in its `dylink.0` section, that every name used is defined
+/// exactly once, and that blocks are closed within each function.
+fn check_module_structure(text: &str) {
+    let expressions = parse_wat(text);
+    let [module] = expressions.as_slice() else {
+        panic!("expected a single module, got {} expressions", expressions.len());
+    };
+    assert_eq!(module.keyword(), Some("module"));
+    let fields = &module.list().unwrap()[1 ..];
+
+    // The sizes to reserve come first, as the dylink.0 section must
+    let memory_info = fields[0].list().expect("expected a dylink.0 section");
+    assert_eq!(fields[0].keyword(), Some("@dylink.0"));
+    let mem_info = memory_info[1].list().unwrap();
+    assert_eq!(mem_info[0].atom(), Some("mem-info"));
+    let size_and_alignment = |expression: &Expression, keyword: &str| -> (u64, u32) {
+        assert_eq!(expression.keyword(), Some(keyword));
+        let items = expression.list().unwrap();
+        (items[1].atom().unwrap().parse().unwrap(), items[2].atom().unwrap().parse().unwrap())
+    };
+    let (memory_size, memory_alignment) = size_and_alignment(&mem_info[1], "memory");
+    let (table_size, _) = size_and_alignment(&mem_info[2], "table");
+    assert_eq!(memory_size % (1 << memory_alignment), 0);
+
+    let mut functions = Vec::new();
+    let mut globals = Vec::new();
+    let mut data_size = 0;
+    let mut table_entries = Vec::new();
+    let mut seen_definition = false;
+    for field in &fields[1 ..] {
+        let items = field.list().expect("expected a module field");
+        match field.keyword() {
+            Some("import") => {
+                assert!(!seen_definition, "import after a definition: {field:?}");
+                let kind = &items[3];
+                let name = kind.list().unwrap()[1].atom().unwrap().to_string();
+                match kind.keyword() {
+                    Some("func") => functions.push(name),
+                    Some("global") => globals.push(name),
+                    _ => {}
+                }
+            }
+            Some("func") => {
+                seen_definition = true;
+                functions.push(items[1].atom().unwrap().to_string());
+            }
+            Some("global") => {
+                seen_definition = true;
+                if let Some(name) = items[1].atom() {
+                    globals.push(name.to_string());
+                }
+            }
+            Some("data") => {
+                seen_definition = true;
+                for item in &items[2 ..] {
+                    if let Expression::String(bytes) = item {
+                        data_size += bytes.len() as u64;
+                    }
+                }
+            }
+            Some("elem") => {
+                seen_definition = true;
+                assert_eq!(items[2].atom(), Some("func"));
+                table_entries.extend(items[3 ..].iter().map(|item| item.atom().unwrap().to_string()));
+            }
+            Some("start") => {}
+            keyword => panic!("unexpected module field {keyword:?}"),
+        }
+    }
+    assert!(data_size <= memory_size, "{data_size} bytes of data exceed the reserved {memory_size}");
+    assert_eq!(table_entries.len() as u64, table_size);
+
+    for names in [&functions, &globals] {
+        let mut sorted = names.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted.len(), names.len(), "duplicate definitions in {names:?}");
+    }
+
+    // Check references made by each function, including the start function and table entries
+    let check_function = |name: &str| assert!(functions.iter().any(|function| function == name), "undefined function '{name}'");
+    table_entries.iter().for_each(|name| check_function(name));
+    for field in &fields[1 ..] {
+        let items = field.list().unwrap();
+        match field.keyword() {
+            Some("start") => check_function(items[1].atom().unwrap()),
+            Some("func") => {
+                let mut depth = 0;
+                let mut atoms = items.iter().filter_map(Expression::atom);
+                while let Some(atom) = atoms.next() {
+                    match atom {
+                        "block" | "loop" | "if" => depth += 1,
+                        "end" => depth -= 1,
+                        "call" => check_function(atoms.next().unwrap()),
+                        "global.get" | "global.set" => {
+                            let name = atoms.next().unwrap();
+                            assert!(globals.iter().any(|global| global == name), "undefined global '{name}'");
+                        }
+                        _ => {}
+                    }
+                    assert!(depth >= 0, "unexpected 'end' in {}", items[1].atom().unwrap());
+                }
+                assert_eq!(depth, 0, "unclosed block in {}", items[1].atom().unwrap());
+            }
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn wasm_backend_output() {
+    let out_dir = common::temp_out_dir("wasm-backend");
+    compile_for_wasm("test_collections", &out_dir, &["--target", WASM_TARGET]).unwrap();
+
+    let output = std::fs::read_to_string(out_dir.join("test_collections.wat")).unwrap();
+    check_module_structure(&output);
+    assert!(output.contains("(import \"env\" \"memory\" (memory 1))"));
+    assert!(output.contains("(import \"env\" \"__stack_pointer\" (global $__stack_pointer (mut i32)))"));
+    assert!(output.contains("(import \"env\" \"printf\" (func $printf (param i32) (param i32) (result i32)))"));
+    assert!(output.contains("(import \"env\" \"malloc\" (func $malloc (param i32) (result i32)))"));
+    assert!(output.contains("(func $main (export \"main\") (result i32)"));
+    assert!(output.contains("(@dylink.0 (mem-info (memory 224 4) (table 2 0)))"));
+    assert!(output.contains("(start $%init)"));
+    assert!(output.contains("br $%dispatch"));
+    assert!(!out_dir.join("test_collections.ll").exists());
+    check_module_structure(&std::fs::read_to_string(out_dir.join("std.wat")).unwrap());
+
+    // Have the WebAssembly Binary Toolkit check the output if it is available
+    let wasm_path = out_dir.join("test_collections.wasm");
+    let wat2wasm_result = Command::new("wat2wasm")
+        .arg("--enable-annotations")
+        .arg(out_dir.join("test_collections.wat"))
+        .arg("-o")
+        .arg(&wasm_path)
+        .output();
+    if let Ok(wat2wasm_output) = wat2wasm_result {
+        assert!(wat2wasm_output.status.success(), "wat2wasm rejected output:\n{}", String::from_utf8_lossy(&wat2wasm_output.stderr));
+    }
+
+    std::fs::remove_dir_all(&out_dir).unwrap();
+}
+
+#[test]
+fn wasm_backend_requires_wasm_target() {
+    let out_dir = common::temp_out_dir("wasm-backend-target");
+    let error = compile_for_wasm("hello", &out_dir, &["--backend", "wasm", "--target", "x86_64-unknown-linux-gnu"])
+        .expect_err("compiling for x86_64 with the wasm backend should fail");
+    assert_eq!(error.error.kind().name(), "UnsupportedBackendTarget");
+
+    let error = compile_for_wasm("hello", &out_dir, &["--target", WASM_TARGET, "--emit", "exe"])
+        .expect_err("linking an executable with the wasm backend should fail");
+    assert_eq!(error.error.kind().name(), "UnsupportedBackendOutput");
+}